@@ -0,0 +1,185 @@
+// ambient_module.rs
+use rodio::{OutputStream, Sink, Source};
+use std::f32::consts::TAU;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::i18n::tr;
+
+const SAMPLE_RATE: u32 = 44100;
+
+// Фоновые звуки синтезируются на лету, поэтому в assets ничего класть не нужно
+#[derive(Clone, Copy, PartialEq)]
+pub enum AmbientSound {
+    Rain,
+    WhiteNoise,
+    BrownNoise,
+    Cafe,
+}
+
+impl AmbientSound {
+    pub const ALL: [AmbientSound; 4] = [
+        AmbientSound::Rain,
+        AmbientSound::WhiteNoise,
+        AmbientSound::BrownNoise,
+        AmbientSound::Cafe,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            AmbientSound::Rain => tr!("Дождь"),
+            AmbientSound::WhiteNoise => tr!("Белый шум"),
+            AmbientSound::BrownNoise => tr!("Коричневый шум"),
+            AmbientSound::Cafe => tr!("Кафе"),
+        }
+    }
+}
+
+// Свой поток и свой sink: громкость и пауза музыки фоновый звук не трогают
+#[derive(Default)]
+pub struct AmbientModule {
+    pub current: Option<AmbientSound>,
+    sink: Option<Sink>,
+    _stream: Option<OutputStream>,
+}
+
+impl AmbientModule {
+    pub fn play(&mut self, sound: AmbientSound, volume: f32) -> Result<(), String> {
+        self.stop();
+        let (stream, handle) = OutputStream::try_default().map_err(|e| tr!("Ошибка звукового устройства: {}", e))?;
+        let sink = Sink::try_new(&handle).map_err(|e| tr!("Ошибка звукового устройства: {}", e))?;
+        sink.append(AmbientSource::new(sound));
+        sink.set_volume(volume);
+        sink.play();
+
+        self.sink = Some(sink);
+        self._stream = Some(stream);
+        self.current = Some(sound);
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(sink) = self.sink.take() {
+            sink.stop();
+        }
+        self._stream = None;
+        self.current = None;
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        if let Some(sink) = &self.sink {
+            sink.set_volume(volume);
+        }
+    }
+}
+
+// Бесконечный моно-источник; все звуки строятся из шума и простых фильтров
+struct AmbientSource {
+    sound: AmbientSound,
+    seed: u32,
+    time: u32,     // Номер сэмпла
+    lowpass: f32,  // Состояние однополюсного фильтра
+    brown: f32,    // Проинтегрированный шум
+    drop: f32,     // Громкость текущей капли (дождь)
+    murmur: f32,   // Громкость голосов, медленно блуждает (кафе)
+    clink: f32,    // Громкость звона посуды (кафе)
+    clink_freq: f32,
+}
+
+impl AmbientSource {
+    fn new(sound: AmbientSound) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(1);
+        Self {
+            sound,
+            seed: seed | 1,
+            time: 0,
+            lowpass: 0.0,
+            brown: 0.0,
+            drop: 0.0,
+            murmur: 0.5,
+            clink: 0.0,
+            clink_freq: 3000.0,
+        }
+    }
+
+    // xorshift: качество случайности для шума не важно, важна скорость
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32
+    }
+
+    fn white(&mut self) -> f32 {
+        self.random() * 2.0 - 1.0
+    }
+
+    fn brown_step(&mut self) -> f32 {
+        let white = self.white();
+        self.brown = (self.brown + 0.02 * white) / 1.02;
+        self.brown * 3.5
+    }
+
+    fn next_sample(&mut self) -> f32 {
+        self.time = self.time.wrapping_add(1);
+        match self.sound {
+            AmbientSound::WhiteNoise => self.white() * 0.3,
+            AmbientSound::BrownNoise => self.brown_step(),
+            AmbientSound::Rain => {
+                // Ровный шелест плюс отдельные капли — короткие затухающие всплески шума
+                let white = self.white();
+                self.lowpass += 0.3 * (white - self.lowpass);
+                if self.random() < 0.0008 {
+                    self.drop = self.random() * 0.6;
+                }
+                self.drop *= 0.995;
+                self.lowpass * 0.35 + (white - self.lowpass) * self.drop
+            }
+            AmbientSound::Cafe => {
+                // Гул голосов: низкий шум, громкость которого плавно гуляет
+                let voices = self.brown_step();
+                self.murmur = (self.murmur + (self.random() - 0.5) * 0.002).clamp(0.3, 1.0);
+                let seconds = self.time as f32 / SAMPLE_RATE as f32;
+                let swell = 0.8 + 0.2 * (TAU * 0.15 * seconds).sin();
+
+                // Иногда звенит посуда
+                if self.clink < 0.001 && self.random() < 0.00004 {
+                    self.clink = 0.1 + self.random() * 0.15;
+                    self.clink_freq = 2000.0 + self.random() * 2500.0;
+                }
+                self.clink *= 0.9997;
+                let clink = (TAU * self.clink_freq * seconds).sin() * self.clink;
+
+                voices * self.murmur * swell + clink
+            }
+        }
+    }
+}
+
+impl Iterator for AmbientSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.next_sample().clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for AmbientSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,231 @@
+// archive_module.rs
+use chrono::{Datelike, Timelike};
+use eframe::egui;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+// Откуда взят текст документа, открытого из архива
+#[derive(Clone)]
+pub enum ArchiveKind {
+    Gzip,
+    Zip(String), // Имя записи внутри zip
+}
+
+#[derive(Default)]
+pub struct ArchiveModule {
+    current: Option<(PathBuf, ArchiveKind)>,
+    picker: Option<(PathBuf, Vec<String>)>, // Архив, из которого выбирают запись
+}
+
+pub enum ArchiveResult {
+    None,
+    Open(PathBuf, String),
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "gz" || e == "zip")
+}
+
+fn archive_error(e: impl std::fmt::Display) -> String {
+    tr!("Ошибка чтения архива: {}", e)
+}
+
+fn compress_error(e: impl std::fmt::Display) -> String {
+    tr!("Ошибка сжатия: {}", e)
+}
+
+fn read_gzip(path: &Path) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    MultiGzDecoder::new(File::open(path).map_err(archive_error)?)
+        .read_to_end(&mut bytes)
+        .map_err(archive_error)?;
+    Ok(bytes)
+}
+
+fn open_zip(path: &Path) -> Result<zip::ZipArchive<File>, String> {
+    zip::ZipArchive::new(File::open(path).map_err(archive_error)?).map_err(archive_error)
+}
+
+// Файлы внутри zip, без папок
+pub fn zip_entries(path: &Path) -> Result<Vec<String>, String> {
+    let archive = open_zip(path)?;
+    let mut entries: Vec<String> = archive.file_names().filter(|name| !name.ends_with('/')).map(str::to_string).collect();
+    entries.sort();
+    Ok(entries)
+}
+
+fn read_zip_entry(path: &Path, entry: &str) -> Result<Vec<u8>, String> {
+    let mut archive = open_zip(path)?;
+    let mut file = archive.by_name(entry).map_err(archive_error)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(archive_error)?;
+    Ok(bytes)
+}
+
+// Время записи внутри zip: без него zip ставит 1980 год
+fn zip_options() -> zip::write::FileOptions {
+    let now = chrono::Local::now().naive_local();
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    match zip::DateTime::from_date_and_time(
+        now.year() as u16,
+        now.month() as u8,
+        now.day() as u8,
+        now.hour() as u8,
+        now.minute() as u8,
+        now.second() as u8,
+    ) {
+        Ok(time) => options.last_modified_time(time),
+        Err(_) => options,
+    }
+}
+
+// Новое содержимое zip: запись заменяется, остальные копируются без пересжатия
+pub fn replace_zip_entry(source: impl Read + Seek, entry: &str, contents: &[u8]) -> Result<Vec<u8>, String> {
+    let mut archive = zip::ZipArchive::new(source).map_err(archive_error)?;
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for index in 0..archive.len() {
+        let file = archive.by_index_raw(index).map_err(archive_error)?;
+        if file.name() == entry {
+            drop(file);
+            writer.start_file(entry, zip_options()).map_err(compress_error)?;
+            writer.write_all(contents).map_err(compress_error)?;
+        } else {
+            writer.raw_copy_file(file).map_err(compress_error)?;
+        }
+    }
+    Ok(writer.finish().map_err(compress_error)?.into_inner())
+}
+
+// Байты документа из архива, еще не декодированные
+pub fn read_archive(path: &Path, kind: &ArchiveKind) -> Result<Vec<u8>, String> {
+    match kind {
+        ArchiveKind::Gzip => read_gzip(path),
+        ArchiveKind::Zip(entry) => read_zip_entry(path, entry),
+    }
+}
+
+// Что записать на диск вместо архива, чтобы в нем оказался новый текст
+pub fn compress_archive(path: &Path, kind: &ArchiveKind, contents: &[u8]) -> Result<Vec<u8>, String> {
+    match kind {
+        ArchiveKind::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(contents).map_err(compress_error)?;
+            encoder.finish().map_err(compress_error)
+        }
+        ArchiveKind::Zip(entry) => {
+            // Архив мог удалить кто-то другой — тогда собираем новый из одной записи
+            if fs::metadata(path).is_err() {
+                let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+                writer.start_file(entry.as_str(), zip_options()).map_err(compress_error)?;
+                writer.write_all(contents).map_err(compress_error)?;
+                return Ok(writer.finish().map_err(compress_error)?.into_inner());
+            }
+            replace_zip_entry(File::open(path).map_err(archive_error)?, entry, contents)
+        }
+    }
+}
+
+impl ArchiveModule {
+    // Чем является открытый файл; None — обычный файл
+    pub fn kind_for(&self, path: &Path) -> Option<&ArchiveKind> {
+        self.current.as_ref().filter(|(current, _)| current == path).map(|(_, kind)| kind)
+    }
+
+    pub fn set_current(&mut self, path: &Path, kind: ArchiveKind) {
+        self.current = Some((path.to_path_buf(), kind));
+    }
+
+    pub fn show_picker(&mut self, path: &Path, entries: Vec<String>) {
+        self.picker = Some((path.to_path_buf(), entries));
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> ArchiveResult {
+        let mut result = ArchiveResult::None;
+        let Some((path, entries)) = &self.picker else {
+            return result;
+        };
+
+        let mut open = true;
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        egui::Window::new(tr!("Открыть из архива {}", name))
+            .id(egui::Id::new("archive_picker"))
+            .open(&mut open)
+            .default_size([360.0, 300.0])
+            .show(ctx, |ui| {
+                if entries.is_empty() {
+                    ui.label(tr!("В архиве нет файлов"));
+                    return;
+                }
+                egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+                    for entry in entries {
+                        if ui.selectable_label(false, entry).clicked() {
+                            result = ArchiveResult::Open(path.clone(), entry.clone());
+                        }
+                    }
+                });
+            });
+
+        if !open || matches!(result, ArchiveResult::Open(..)) {
+            self.picker = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zip_with(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in entries {
+            writer.start_file(*name, zip_options()).unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn entry_text(zip: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(zip)).unwrap();
+        let mut text = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut text).unwrap();
+        text
+    }
+
+    #[test]
+    fn replacing_entry_keeps_the_rest_of_zip() {
+        let original = zip_with(&[("notes.txt", "старый текст"), ("images/readme.md", "не трогать")]);
+        let replaced = replace_zip_entry(Cursor::new(&original), "notes.txt", "новый текст".as_bytes()).unwrap();
+        assert_eq!(entry_text(&replaced, "notes.txt"), "новый текст");
+        assert_eq!(entry_text(&replaced, "images/readme.md"), "не трогать");
+        // Остальные записи копируются как есть, с тем же сжатым содержимым
+        let mut before = zip::ZipArchive::new(Cursor::new(&original)).unwrap();
+        let mut after = zip::ZipArchive::new(Cursor::new(&replaced)).unwrap();
+        let (before, after) = (before.by_name("images/readme.md").unwrap(), after.by_name("images/readme.md").unwrap());
+        assert_eq!((before.crc32(), before.compressed_size()), (after.crc32(), after.compressed_size()));
+    }
+
+    #[test]
+    fn compressed_archives_read_back() {
+        let dir = std::env::temp_dir().join(format!("gte-archive-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let gzip = dir.join("log.txt.gz");
+        fs::write(&gzip, compress_archive(&gzip, &ArchiveKind::Gzip, "строка лога".as_bytes()).unwrap()).unwrap();
+        assert_eq!(read_archive(&gzip, &ArchiveKind::Gzip).unwrap(), "строка лога".as_bytes());
+
+        let zip = dir.join("notes.zip");
+        fs::write(&zip, zip_with(&[("a.txt", "один"), ("b.txt", "два")])).unwrap();
+        let kind = ArchiveKind::Zip("a.txt".to_string());
+        fs::write(&zip, compress_archive(&zip, &kind, "один и еще".as_bytes()).unwrap()).unwrap();
+        assert_eq!(read_archive(&zip, &kind).unwrap(), "один и еще".as_bytes());
+        assert_eq!(read_archive(&zip, &ArchiveKind::Zip("b.txt".to_string())).unwrap(), "два".as_bytes());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+// atomic_write.rs
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Сколько имен временного файла перебирать, если прошлые записи оставили свои после сбоя
+const TEMP_ATTEMPTS: u32 = 100;
+
+// Путь резервной копии: рядом с файлом, с тильдой в конце имени
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push("~");
+    path.with_file_name(name)
+}
+
+// Свободный временный файл рядом с целевым
+fn create_temp(path: &Path) -> io::Result<(PathBuf, File)> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut attempt = 0;
+    loop {
+        let temp_path = path.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), attempt));
+        match OpenOptions::new().write(true).create_new(true).open(&temp_path) {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt + 1 < TEMP_ATTEMPTS => attempt += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Пишет во временный файл в той же папке и переименовывает его поверх целевого.
+// Переименование внутри одной файловой системы атомарно: при сбое на диске остается либо старый файл, либо новый.
+// Ссылку не заменяем обычным файлом — пишем туда, куда она ведет
+pub fn write_atomically(path: &Path, contents: &[u8], backup: bool) -> io::Result<()> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = path.as_path();
+    let original = fs::metadata(path).ok();
+    let (temp_path, mut file) = create_temp(path)?;
+
+    let written = (|| {
+        file.write_all(contents)?;
+        file.sync_all()?;
+        // Новый файл создается с правами по умолчанию — возвращаем те, что были у старого
+        if let Some(metadata) = &original {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        if backup && original.is_some() {
+            fs::copy(path, backup_path(path))?;
+        }
+        fs::rename(&temp_path, path)
+    })();
+
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gte-atomic-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn replaces_contents_and_keeps_backup() {
+        let dir = scratch_dir("backup");
+        let path = dir.join("note.txt");
+        fs::write(&path, "старое").unwrap();
+        write_atomically(&path, "новое".as_bytes(), true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "новое");
+        assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "старое");
+        // Временных файлов не остается
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stale_temp_file_does_not_block_saving() {
+        let dir = scratch_dir("stale");
+        let path = dir.join("note.txt");
+        let stale = dir.join(format!(".note.txt.{}.0.tmp", std::process::id()));
+        fs::write(&stale, "после сбоя").unwrap();
+        write_atomically(&path, b"text", false).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"text");
+        assert_eq!(fs::read_to_string(&stale).unwrap(), "после сбоя");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_through_symlink() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = scratch_dir("symlink");
+        let target = dir.join("real.txt");
+        let link = dir.join("link.txt");
+        fs::write(&target, b"old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        write_atomically(&link, b"new", false).unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read(&target).unwrap(), b"new");
+        assert_eq!(fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o640);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+// auto_pair.rs
+use crate::auto_replace_module::opens_quote;
+use crate::config::Config;
+
+const PAIRS: [(char, char); 5] = [('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('«', '»')];
+
+// Правка после набранного символа: символы start..end заменить на text, затем выделить anchor..cursor
+pub struct PairEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub anchor: usize,
+    pub cursor: usize,
+}
+
+fn closing_for(open: char) -> Option<char> {
+    PAIRS.iter().find(|(o, _)| *o == open).map(|(_, c)| *c)
+}
+
+fn is_closing(c: char) -> bool {
+    PAIRS.iter().any(|(_, close)| *close == c)
+}
+
+// Символ, который на самом деле попадет в текст: с «умными кавычками» " становится « или »
+fn effective_char(typed: char, previous: Option<char>, config: &Config) -> char {
+    if typed == '"' && config.smart_quotes {
+        if opens_quote(previous) {
+            '«'
+        } else {
+            '»'
+        }
+    } else {
+        typed
+    }
+}
+
+// None — символ обычный, его вставит текстовое поле
+pub fn on_typed(
+    typed: char,
+    text: &[char],
+    cursor: usize,
+    selection: Option<(usize, usize)>,
+    config: &Config,
+) -> Option<PairEdit> {
+    if let Some((start, end)) = selection {
+        // Выделение оборачиваем и оставляем выделенным
+        let previous = start.checked_sub(1).map(|i| text[i]);
+        let open = effective_char(typed, previous, config);
+        let open = if open == '»' { '«' } else { open };
+        let close = closing_for(open)?;
+        let inner: String = text[start..end].iter().collect();
+        return Some(PairEdit {
+            start,
+            end,
+            text: format!("{}{}{}", open, inner, close),
+            anchor: start + 1,
+            cursor: end + 1,
+        });
+    }
+
+    let previous = cursor.checked_sub(1).map(|i| text[i]);
+    let next = text.get(cursor).copied();
+    let c = effective_char(typed, previous, config);
+
+    // Закрывающий символ поверх уже вставленного — просто перешагиваем
+    if is_closing(c) && next == Some(c) {
+        return Some(PairEdit {
+            start: cursor,
+            end: cursor,
+            text: String::new(),
+            anchor: cursor + 1,
+            cursor: cursor + 1,
+        });
+    }
+
+    let close = closing_for(c)?;
+    // Перед словом пару не добавляем: скобку часто ставят перед уже написанным текстом
+    if next.is_some_and(|n| !n.is_whitespace() && !is_closing(n) && !",.;:".contains(n)) {
+        return None;
+    }
+    // Прямая кавычка сразу после буквы — скорее закрывающая или дюймы
+    if c == '"' && previous.is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    Some(PairEdit {
+        start: cursor,
+        end: cursor,
+        text: format!("{}{}", c, close),
+        anchor: cursor + 1,
+        cursor: cursor + 1,
+    })
+}
+
+// Backspace внутри пустой пары удаляет обе половины: «(|)» → «|»
+pub fn on_backspace(text: &[char], cursor: usize) -> Option<(usize, usize)> {
+    let previous = *text.get(cursor.checked_sub(1)?)?;
+    let next = *text.get(cursor)?;
+    (closing_for(previous) == Some(next)).then_some((cursor - 1, cursor + 1))
+}
@@ -0,0 +1,205 @@
+// auto_replace_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+// Правило «что набрано → чем заменить». Если правило кончается буквой, замена
+// срабатывает после следующего пробела или знака препинания, чтобы не задеть длинные слова
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct ReplaceRule {
+    pub from: String,
+    pub to: String,
+}
+
+pub fn default_rules() -> Vec<ReplaceRule> {
+    [("->", "→"), ("<-", "←"), ("--", "—"), ("...", "…"), ("(c)", "©"), ("+-", "±")]
+        .into_iter()
+        .map(|(from, to)| ReplaceRule { from: from.to_string(), to: to.to_string() })
+        .collect()
+}
+
+// Замена символов start..end на text
+pub struct Replacement {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+// Последняя замена: Backspace сразу после нее возвращает набранное
+struct LastReplacement {
+    start: usize,
+    end: usize,
+    original: String,
+}
+
+#[derive(Default)]
+pub struct AutoReplaceModule {
+    pub show_window: bool,
+    last: Option<LastReplacement>,
+}
+
+#[derive(PartialEq)]
+pub enum AutoReplaceResult {
+    None,
+    ConfigChanged,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Открывающая кавычка — в начале текста, после пробела или открывающей скобки
+pub fn opens_quote(previous: Option<char>) -> bool {
+    previous.is_none_or(|c| c.is_whitespace() || "([{«„—–-".contains(c))
+}
+
+impl AutoReplaceModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+    }
+
+    // Вызывается, когда пользователь только что набрал символ перед cursor
+    pub fn on_typed(&mut self, text: &str, cursor: usize, config: &Config) -> Option<Replacement> {
+        self.last = None;
+        let before: Vec<char> = text.chars().take(cursor).collect();
+        let &typed = before.last()?;
+
+        if config.smart_quotes && typed == '"' {
+            let previous = before.len().checked_sub(2).map(|i| before[i]);
+            let quote = if opens_quote(previous) { "«" } else { "»" };
+            return Some(self.replace(cursor - 1, cursor, quote, "\""));
+        }
+
+        // Длинные правила раньше: «-->» не должно сработать как «--»
+        let mut rules: Vec<&ReplaceRule> = config.replace_rules.iter().filter(|r| !r.from.is_empty()).collect();
+        rules.sort_by_key(|r| std::cmp::Reverse(r.from.chars().count()));
+
+        for rule in rules {
+            let from: Vec<char> = rule.from.chars().collect();
+            let ends_with_word = from.last().copied().is_some_and(is_word_char);
+            // Правило на слово ждет разделителя, он остается после замены
+            let (end, separator) = if ends_with_word {
+                if is_word_char(typed) {
+                    continue;
+                }
+                (before.len() - 1, typed.to_string())
+            } else {
+                (before.len(), String::new())
+            };
+            let Some(start) = end.checked_sub(from.len()) else {
+                continue;
+            };
+            if before[start..end] != from[..] {
+                continue;
+            }
+            if is_word_char(from[0]) && start > 0 && is_word_char(before[start - 1]) {
+                continue;
+            }
+
+            let original = format!("{}{}", rule.from, separator);
+            return Some(self.replace(start, cursor, &format!("{}{}", rule.to, separator), &original));
+        }
+        None
+    }
+
+    fn replace(&mut self, start: usize, end: usize, text: &str, original: &str) -> Replacement {
+        self.last = Some(LastReplacement {
+            start,
+            end: start + text.chars().count(),
+            original: original.to_string(),
+        });
+        Replacement { start, end, text: text.to_string() }
+    }
+
+    // Backspace сразу после замены возвращает набранный текст; любое другое действие
+    // делает замену окончательной. Вызывается до текстового поля, чтобы забрать Backspace
+    pub fn handle_keys(&mut self, ctx: &egui::Context, cursor: usize) -> Option<Replacement> {
+        self.last.as_ref()?;
+        let (backspace, other) = ctx.input_mut(|i| {
+            let other = i.events.iter().any(|e| {
+                matches!(
+                    e,
+                    egui::Event::Key { pressed: true, .. } | egui::Event::Text(_) | egui::Event::PointerButton { .. }
+                )
+            });
+            (i.consume_key(egui::Modifiers::NONE, egui::Key::Backspace), other)
+        });
+        if !backspace && !other {
+            return None;
+        }
+
+        let last = self.last.take()?;
+        (backspace && cursor == last.end).then_some(Replacement {
+            start: last.start,
+            end: last.end,
+            text: last.original,
+        })
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, config: &mut Config) -> AutoReplaceResult {
+        if !self.show_window {
+            return AutoReplaceResult::None;
+        }
+
+        let mut changed = false;
+        let mut open = self.show_window;
+
+        egui::Window::new(tr!("Автозамена"))
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                changed |= ui.checkbox(&mut config.auto_replace, tr!("Заменять при наборе")).changed();
+                changed |= ui
+                    .checkbox(&mut config.smart_quotes, tr!("Кавычки-елочки «» вместо \"\""))
+                    .changed();
+                ui.label(tr!("Backspace сразу после замены возвращает набранное."));
+                ui.separator();
+
+                let mut remove = None;
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("auto_replace_grid").num_columns(3).striped(true).show(ui, |ui| {
+                        ui.label(tr!("Набрано"));
+                        ui.label(tr!("Заменить на"));
+                        ui.end_row();
+
+                        for (index, rule) in config.replace_rules.iter_mut().enumerate() {
+                            changed |= ui.add(egui::TextEdit::singleline(&mut rule.from).desired_width(100.0)).changed();
+                            changed |= ui.add(egui::TextEdit::singleline(&mut rule.to).desired_width(180.0)).changed();
+                            if ui.small_button("✖").on_hover_text(tr!("Удалить")).clicked() {
+                                remove = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                if let Some(index) = remove {
+                    config.replace_rules.remove(index);
+                    changed = true;
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Добавить")).clicked() {
+                        config.replace_rules.push(ReplaceRule::default());
+                        changed = true;
+                    }
+                    if ui.button(tr!("По умолчанию")).clicked() {
+                        config.replace_rules = default_rules();
+                        changed = true;
+                    }
+                });
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+
+        if changed {
+            AutoReplaceResult::ConfigChanged
+        } else {
+            AutoReplaceResult::None
+        }
+    }
+}
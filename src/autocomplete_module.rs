@@ -0,0 +1,173 @@
+// autocomplete_module.rs
+use eframe::egui;
+use std::collections::HashMap;
+
+// Автоматически предлагаем варианты, когда набрано столько символов слова
+pub const AUTO_TRIGGER_LENGTH: usize = 3;
+const MAX_ITEMS: usize = 10;
+
+#[derive(Default)]
+pub struct AutocompleteModule {
+    pub active: bool,
+    prefix_start: usize, // Начало дописываемого слова (в символах)
+    prefix: String,
+    items: Vec<String>,
+    selected: usize,
+}
+
+pub enum AutocompleteResult {
+    None,
+    // Заменить символы prefix_start..cursor на слово
+    Accept { start: usize, word: String },
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Нечеткое совпадение: буквы образца идут в слове по порядку, возможно с пропусками.
+// Меньше — лучше; начало слова и слитные совпадения ценятся выше
+fn fuzzy_score(pattern: &[char], word: &str) -> Option<usize> {
+    let lower: Vec<char> = word.to_lowercase().chars().collect();
+    if lower.len() <= pattern.len() {
+        return None;
+    }
+    if lower.starts_with(pattern) {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut position = 0;
+    for (index, &c) in pattern.iter().enumerate() {
+        let found = lower[position..].iter().position(|&w| w == c)?;
+        // Первая буква должна совпасть с началом слова, иначе вариантов слишком много
+        if index == 0 && found != 0 {
+            return None;
+        }
+        score += found * 2 + 1;
+        position += found + 1;
+    }
+    Some(score)
+}
+
+impl AutocompleteModule {
+    pub fn close(&mut self) {
+        self.active = false;
+        self.items.clear();
+    }
+
+    // Слово, которое дописывается слева от курсора
+    fn prefix_at(text: &str, cursor: usize) -> (usize, String) {
+        let before: Vec<char> = text.chars().take(cursor).collect();
+        let start = before
+            .iter()
+            .rposition(|&c| !is_word_char(c))
+            .map_or(0, |i| i + 1);
+        (start, before[start..].iter().collect())
+    }
+
+    // typed — в этом кадре набран символ; forced — вызвано вручную (Ctrl + Пробел)
+    pub fn update(&mut self, text: &str, cursor: usize, typed: bool, forced: bool, automatic: bool) {
+        let (start, prefix) = Self::prefix_at(text, cursor);
+        let triggered = forced || (typed && automatic && prefix.chars().count() >= AUTO_TRIGGER_LENGTH);
+
+        if !self.active && !triggered {
+            return;
+        }
+        // Курсор ушел из слова — список больше не нужен
+        if !forced && (prefix.is_empty() || (self.active && start != self.prefix_start)) {
+            self.close();
+            return;
+        }
+        if self.active && !triggered && prefix == self.prefix && start == self.prefix_start {
+            return;
+        }
+
+        self.prefix_start = start;
+        self.prefix = prefix;
+        self.items = self.collect_items(text);
+        self.selected = 0;
+        self.active = !self.items.is_empty();
+    }
+
+    fn collect_items(&self, text: &str) -> Vec<String> {
+        let pattern: Vec<char> = self.prefix.to_lowercase().chars().collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for word in text.split(|c: char| !is_word_char(c)) {
+            if !word.is_empty() && word != self.prefix {
+                *counts.entry(word).or_insert(0) += 1;
+            }
+        }
+
+        let mut scored: Vec<(usize, usize, &str)> = counts
+            .into_iter()
+            .filter_map(|(word, count)| fuzzy_score(&pattern, word).map(|score| (score, count, word)))
+            .collect();
+        // Лучшее совпадение, потом чаще встречающееся, потом по алфавиту
+        scored.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(b.2)));
+        scored.into_iter().take(MAX_ITEMS).map(|(_, _, word)| word.to_string()).collect()
+    }
+
+    // Клавиши списка забираем до текстового поля, чтобы Enter не вставил перевод строки
+    pub fn handle_keys(&mut self, ctx: &egui::Context) -> AutocompleteResult {
+        if !self.active {
+            return AutocompleteResult::None;
+        }
+        let (up, down, accept, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)
+                    || i.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+
+        if escape {
+            self.close();
+        } else if up {
+            self.selected = self.selected.checked_sub(1).unwrap_or(self.items.len() - 1);
+        } else if down {
+            self.selected = (self.selected + 1) % self.items.len();
+        } else if accept {
+            return self.accept(self.selected);
+        }
+        AutocompleteResult::None
+    }
+
+    fn accept(&mut self, index: usize) -> AutocompleteResult {
+        let Some(word) = self.items.get(index).cloned() else {
+            return AutocompleteResult::None;
+        };
+        let start = self.prefix_start;
+        self.close();
+        AutocompleteResult::Accept { start, word }
+    }
+
+    // Список под курсором; position — левый нижний угол курсора на экране
+    pub fn show_popup(&mut self, ctx: &egui::Context, position: egui::Pos2) -> AutocompleteResult {
+        if !self.active {
+            return AutocompleteResult::None;
+        }
+
+        let mut clicked = None;
+        egui::Area::new(egui::Id::new("autocomplete_popup"))
+            .fixed_pos(position)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(160.0);
+                    for (index, item) in self.items.iter().enumerate() {
+                        if ui.selectable_label(index == self.selected, item).clicked() {
+                            clicked = Some(index);
+                        }
+                    }
+                });
+            });
+
+        match clicked {
+            Some(index) => self.accept(index),
+            None => AutocompleteResult::None,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+// bookmarks_module.rs
+use eframe::egui;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::config_dir;
+use crate::i18n::tr;
+
+const BOOKMARKS_FILE: &str = "bookmarks.txt";
+
+#[derive(Default)]
+pub struct BookmarksModule {
+    pub show_panel: bool,
+    // Номера строк (с нуля) для каждого файла. Несохраненный документ хранится под пустым путем
+    bookmarks: HashMap<PathBuf, BTreeSet<usize>>,
+    loaded: bool,
+}
+
+pub enum BookmarksResult {
+    None,
+    GoToLine(usize),
+}
+
+impl BookmarksModule {
+    fn storage_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(BOOKMARKS_FILE))
+    }
+
+    fn key(file: Option<&Path>) -> PathBuf {
+        file.map(Path::to_path_buf).unwrap_or_default()
+    }
+
+    // Формат файла: одна строка на документ, "путь\tномер,номер,..."
+    pub fn load(&mut self) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+
+        let Some(path) = Self::storage_path() else {
+            return;
+        };
+        let Ok(content) = fs::read_to_string(path) else {
+            return;
+        };
+
+        for line in content.lines() {
+            if let Some((file, numbers)) = line.rsplit_once('\t') {
+                let lines: BTreeSet<usize> = numbers
+                    .split(',')
+                    .filter_map(|n| n.trim().parse().ok())
+                    .collect();
+                if !lines.is_empty() {
+                    self.bookmarks.insert(PathBuf::from(file), lines);
+                }
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = Self::storage_path() else {
+            return Ok(());
+        };
+
+        let mut content = String::new();
+        for (file, lines) in &self.bookmarks {
+            if file.as_os_str().is_empty() || lines.is_empty() {
+                continue;
+            }
+            let numbers: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+            content.push_str(&format!("{}\t{}\n", file.display(), numbers.join(",")));
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| tr!("Ошибка сохранения закладок: {}", e))?;
+        }
+        fs::write(&path, content).map_err(|e| tr!("Ошибка сохранения закладок: {}", e))
+    }
+
+    pub fn lines(&self, file: Option<&Path>) -> Vec<usize> {
+        self.bookmarks
+            .get(&Self::key(file))
+            .map(|lines| lines.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn toggle(&mut self, file: Option<&Path>, line: usize) -> Result<(), String> {
+        let lines = self.bookmarks.entry(Self::key(file)).or_default();
+        if !lines.remove(&line) {
+            lines.insert(line);
+        }
+        self.save()
+    }
+
+    // Несохраненный документ получил имя — переносим его закладки
+    pub fn rename_file(&mut self, from: Option<&Path>, to: &Path) -> Result<(), String> {
+        if let Some(lines) = self.bookmarks.remove(&Self::key(from)) {
+            self.bookmarks.insert(to.to_path_buf(), lines);
+            return self.save();
+        }
+        Ok(())
+    }
+
+    pub fn clear_unnamed(&mut self) {
+        self.bookmarks.remove(&PathBuf::new());
+    }
+
+    // После вставки или удаления строк сдвигаем закладки ниже места правки
+    pub fn shift_lines(&mut self, file: Option<&Path>, edit_line: usize, delta: isize) {
+        let Some(lines) = self.bookmarks.get_mut(&Self::key(file)) else {
+            return;
+        };
+
+        let shifted: BTreeSet<usize> = lines
+            .iter()
+            .filter_map(|&line| {
+                if line <= edit_line {
+                    Some(line)
+                } else {
+                    let moved = line as isize + delta;
+                    // Строка с закладкой была удалена вместе с правкой
+                    (moved > edit_line as isize).then_some(moved as usize)
+                }
+            })
+            .collect();
+        *lines = shifted;
+    }
+
+    pub fn persist(&self) -> Result<(), String> {
+        self.save()
+    }
+
+    pub fn next(&self, file: Option<&Path>, current_line: usize) -> Option<usize> {
+        let lines = self.bookmarks.get(&Self::key(file))?;
+        lines
+            .range(current_line + 1..)
+            .next()
+            .or_else(|| lines.iter().next())
+            .copied()
+    }
+
+    pub fn previous(&self, file: Option<&Path>, current_line: usize) -> Option<usize> {
+        let lines = self.bookmarks.get(&Self::key(file))?;
+        lines
+            .range(..current_line)
+            .next_back()
+            .or_else(|| lines.iter().next_back())
+            .copied()
+    }
+
+    pub fn show_panel(&mut self, ctx: &egui::Context, file: Option<&Path>, text: &str) -> BookmarksResult {
+        if !self.show_panel {
+            return BookmarksResult::None;
+        }
+
+        let mut result = BookmarksResult::None;
+        let mut open = self.show_panel;
+        let lines = self.lines(file);
+
+        egui::Window::new(tr!("Закладки"))
+            .open(&mut open)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                if lines.is_empty() {
+                    ui.label(tr!("Закладок нет. Ctrl+B — поставить закладку на текущей строке"));
+                    return;
+                }
+
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    let text_lines: Vec<&str> = text.split('\n').collect();
+                    for line in &lines {
+                        let preview: String = text_lines
+                            .get(*line)
+                            .map(|l| l.trim().chars().take(60).collect())
+                            .unwrap_or_default();
+                        if ui.selectable_label(false, tr!("Строка {}: {}", line + 1, preview)).clicked() {
+                            result = BookmarksResult::GoToLine(*line);
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.label(tr!("F2 / Shift+F2 — следующая / предыдущая закладка"));
+            });
+
+        if !open {
+            self.show_panel = false;
+        }
+
+        result
+    }
+}
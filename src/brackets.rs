@@ -0,0 +1,95 @@
+// brackets.rs
+
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+// Дальше не ищем: подсветка считается каждый кадр, а парная скобка за сотней тысяч символов не нужна
+const MAX_DISTANCE: usize = 100_000;
+
+// Скобка у курсора — сразу после него или, если там не скобка, сразу перед ним
+pub struct BracketPair {
+    pub at: usize,
+    pub matching: usize,
+}
+
+fn bracket_kind(c: char) -> Option<(char, char, bool)> {
+    BRACKETS.iter().find_map(|&(open, close)| {
+        if c == open {
+            Some((open, close, true))
+        } else if c == close {
+            Some((open, close, false))
+        } else {
+            None
+        }
+    })
+}
+
+// Сколько символов от скобки до парной; chars идут от скобки наружу
+fn distance_to_matching(chars: impl Iterator<Item = char>, open: char, close: char, forward: bool) -> Option<usize> {
+    let mut depth = 0usize;
+    for (distance, c) in chars.take(MAX_DISTANCE).enumerate() {
+        if c == open || c == close {
+            if (c == open) == forward {
+                depth += 1;
+            } else if depth == 0 {
+                return Some(distance + 1);
+            } else {
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+// Текст не собираем в массив символов: идем по строке от курсора, не дальше MAX_DISTANCE
+fn find_matching(text: &str, byte: usize, index: usize) -> Option<usize> {
+    let c = text[byte..].chars().next()?;
+    let (open, close, forward) = bracket_kind(c)?;
+    if forward {
+        distance_to_matching(text[byte + c.len_utf8()..].chars(), open, close, true).map(|d| index + d)
+    } else {
+        distance_to_matching(text[..byte].chars().rev(), open, close, false).map(|d| index - d)
+    }
+}
+
+// cursor — номер символа, как у курсора egui
+pub fn matching_pair(text: &str, cursor: usize) -> Option<BracketPair> {
+    let byte = match cursor.checked_sub(1) {
+        Some(before) => text.char_indices().nth(before).map(|(i, c)| i + c.len_utf8())?,
+        None => 0,
+    };
+    let after = Some((byte, cursor));
+    let before = text[..byte].chars().next_back().map(|c| (byte - c.len_utf8(), cursor - 1));
+    [after, before]
+        .into_iter()
+        .flatten()
+        .find(|&(byte, _)| text[byte..].chars().next().and_then(bracket_kind).is_some())
+        .and_then(|(byte, at)| find_matching(text, byte, at).map(|matching| BracketPair { at, matching }))
+}
+
+// Куда поставить курсор при переходе: с той же стороны от парной скобки, с какой он стоял от исходной
+pub fn jump_target(text: &str, cursor: usize) -> Option<usize> {
+    let pair = matching_pair(text, cursor)?;
+    Some(if pair.at == cursor {
+        pair.matching
+    } else {
+        pair.matching + 1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Номера символов, а не байтов: кириллица перед скобками занимает по два байта
+    #[test]
+    fn matches_brackets_by_char_index() {
+        let text = "ф(а[б]в)г";
+        let pair = matching_pair(text, 1).unwrap();
+        assert_eq!((pair.at, pair.matching), (1, 7));
+        let pair = matching_pair(text, 6).unwrap();
+        assert_eq!((pair.at, pair.matching), (5, 3));
+        assert_eq!(jump_target(text, 8), Some(2));
+        assert!(matching_pair(text, 0).is_none());
+        assert!(matching_pair(text, 20).is_none());
+    }
+}
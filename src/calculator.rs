@@ -0,0 +1,237 @@
+// calculator.rs
+use crate::i18n::tr;
+
+// Разбор арифметики рекурсивным спуском: сумма → произведение → знак → степень → число или скобки
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    args_depth: usize, // Внутри аргументов функции запятая разделяет аргументы, а не дробь
+    decimal_comma: bool,
+}
+
+// Число с процентом остается «сырым», пока не ясно, к чему он относится: 200 + 10% = 220
+struct Value {
+    number: f64,
+    percent: bool,
+}
+
+impl Value {
+    fn plain(number: f64) -> Self {
+        Value { number, percent: false }
+    }
+
+    fn resolved(&self) -> f64 {
+        if self.percent {
+            self.number / 100.0
+        } else {
+            self.number
+        }
+    }
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> String {
+        tr!("Ошибка в выражении: {}", message)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, expected: &[char]) -> Option<char> {
+        let c = self.peek().filter(|c| expected.contains(c))?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn sum(&mut self) -> Result<Value, String> {
+        let mut left = self.product()?;
+        while let Some(op) = self.eat(&['+', '-', '−']) {
+            let right = self.product()?;
+            let base = left.resolved();
+            let operand = if right.percent {
+                base * right.number / 100.0
+            } else {
+                right.number
+            };
+            left = Value::plain(if op == '+' { base + operand } else { base - operand });
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Value, String> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.eat(&['*', '×', '·', '/', '÷', ':']) {
+            let right = self.unary()?.resolved();
+            let base = left.resolved();
+            left = Value::plain(match op {
+                '*' | '×' | '·' => base * right,
+                _ if right == 0.0 => return Err(self.error(tr!("деление на ноль"))),
+                _ => base / right,
+            });
+        }
+        Ok(left)
+    }
+
+    // Минус слабее степени: -2^2 = -4
+    fn unary(&mut self) -> Result<Value, String> {
+        if self.eat(&['-', '−']).is_some() {
+            let value = self.unary()?;
+            return Ok(Value {
+                number: -value.number,
+                ..value
+            });
+        }
+        if self.eat(&['+']).is_some() {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    // Степень правоассоциативна: 2^3^2 = 2^9
+    fn power(&mut self) -> Result<Value, String> {
+        let mut base = self.atom()?;
+        if self.eat(&['%']).is_some() {
+            base.percent = true;
+        }
+        if self.eat(&['^']).is_some() {
+            let exponent = self.unary()?.resolved();
+            return Ok(Value::plain(base.resolved().powf(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.sum()?;
+                if self.eat(&[')']).is_none() {
+                    return Err(self.error(tr!("не хватает «)»")));
+                }
+                Ok(Value::plain(value.resolved()))
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_alphabetic() => self.function(),
+            Some(c) => Err(self.error(&tr!("неожиданный символ «{}»", c))),
+            None => Err(self.error(tr!("выражение оборвалось"))),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.pos;
+        let mut text = String::new();
+        while let Some(&c) = self.chars.get(self.pos) {
+            let next_is_digit = self.chars.get(self.pos + 1).is_some_and(char::is_ascii_digit);
+            if c.is_ascii_digit() || c == '.' {
+                text.push(c);
+            } else if c == ',' && self.args_depth == 0 && next_is_digit {
+                text.push('.');
+                self.decimal_comma = true;
+            } else if matches!(c, 'e' | 'E') && next_is_digit {
+                text.push(c);
+            } else {
+                break;
+            }
+            self.pos += 1;
+        }
+        text.parse::<f64>().map(Value::plain).map_err(|_| {
+            let written: String = self.chars[start..self.pos].iter().collect();
+            self.error(&tr!("неверное число «{}»", written))
+        })
+    }
+
+    fn function(&mut self) -> Result<Value, String> {
+        let mut name = String::new();
+        while let Some(&c) = self.chars.get(self.pos).filter(|c| c.is_alphanumeric()) {
+            name.push(c);
+            self.pos += 1;
+        }
+        let name = name.to_lowercase();
+        match name.as_str() {
+            "pi" | "пи" => return Ok(Value::plain(std::f64::consts::PI)),
+            "e" => return Ok(Value::plain(std::f64::consts::E)),
+            _ => {}
+        }
+
+        if self.eat(&['(']).is_none() {
+            return Err(self.error(&tr!("неизвестное имя «{}»", name)));
+        }
+        self.args_depth += 1;
+        let mut args = vec![self.sum()?.resolved()];
+        while self.eat(&[',', ';']).is_some() {
+            args.push(self.sum()?.resolved());
+        }
+        self.args_depth -= 1;
+        if self.eat(&[')']).is_none() {
+            return Err(self.error(tr!("не хватает «)»")));
+        }
+
+        let one = |f: fn(f64) -> f64| match args.as_slice() {
+            [x] => Ok(f(*x)),
+            _ => Err(self.error(&tr!("{} принимает один аргумент", name))),
+        };
+        let result = match name.as_str() {
+            "sqrt" | "корень" => one(f64::sqrt)?,
+            "abs" => one(f64::abs)?,
+            "round" => match args.as_slice() {
+                [x] => x.round(),
+                // round(x; 2) — округление до знаков после запятой
+                [x, digits] => {
+                    let scale = 10f64.powi(*digits as i32);
+                    (x * scale).round() / scale
+                }
+                _ => return Err(self.error(&tr!("{} принимает один аргумент", name))),
+            },
+            "floor" => one(f64::floor)?,
+            "ceil" => one(f64::ceil)?,
+            "sin" => one(f64::sin)?,
+            "cos" => one(f64::cos)?,
+            "tan" | "tg" => one(f64::tan)?,
+            "ln" => one(f64::ln)?,
+            "log" | "lg" => one(f64::log10)?,
+            "exp" => one(f64::exp)?,
+            "min" => args.iter().copied().fold(f64::INFINITY, f64::min),
+            "max" => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            _ => return Err(self.error(&tr!("неизвестная функция «{}»", name))),
+        };
+        Ok(Value::plain(result))
+    }
+}
+
+// Результат и то, писал ли пользователь дроби через запятую — ответ записываем так же
+pub fn evaluate(expression: &str) -> Result<(f64, bool), String> {
+    let mut parser = Parser {
+        chars: expression.chars().collect(),
+        pos: 0,
+        args_depth: 0,
+        decimal_comma: false,
+    };
+    let value = parser.sum()?.resolved();
+    if parser.peek().is_some() {
+        let c = parser.chars[parser.pos];
+        return Err(parser.error(&tr!("неожиданный символ «{}»", c)));
+    }
+    if !value.is_finite() {
+        return Err(parser.error(tr!("результат не является числом")));
+    }
+    Ok((value, parser.decimal_comma))
+}
+
+// Без хвоста из погрешности: 0.1 + 0.2 = 0.3, а не 0.30000000000000004
+pub fn format_number(value: f64, decimal_comma: bool) -> String {
+    let mut text = format!("{:.10}", value);
+    if text.contains('.') {
+        text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    if text == "-0" {
+        text = "0".to_string();
+    }
+    if decimal_comma {
+        text = text.replace('.', ",");
+    }
+    text
+}
@@ -0,0 +1,536 @@
+// command_palette_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+use crate::quick_open_module::fuzzy_score;
+
+const MAX_RECENT_COMMANDS: usize = 5;
+
+// Все команды редактора. Имена в snake_case — по ним команды можно назначать клавишам в настройках
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Command {
+    NewFile,
+    NewFromTemplate,
+    OpenJournal,
+    OpenFile,
+    OpenFolder,
+    OpenWordDocument,
+    QuickOpen,
+    Save,
+    SaveAs,
+    SaveSelectionAs,
+    CompareWithSaved,
+    Settings,
+    Quit,
+    Find,
+    FindInFiles,
+    RegexReplace,
+    ToggleBookmark,
+    ReflowParagraph,
+    Evaluate,
+    EvaluateReplace,
+    InsertDate,
+    Symbols,
+    InsertPlaceholder,
+    PastePlain,
+    InsertFile,
+    JumpToBracket,
+    SelectNextOccurrence,
+    SkipOccurrence,
+    DuplicateLines,
+    MoveLinesUp,
+    MoveLinesDown,
+    DeleteLines,
+    ToggleComments,
+    IndentToSpaces,
+    IndentToTabs,
+    ToggleFileTree,
+    ToggleBookmarksPanel,
+    ToggleOutline,
+    ToggleMinimap,
+    ToggleWelcome,
+    TogglePlaylist,
+    TogglePlaybackBar,
+    TogglePomodoro,
+    ToggleParagraphStats,
+    SplitVertical,
+    SplitHorizontal,
+    OpenInSplit,
+    CloseSplit,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    ThemeEditor,
+    Statistics,
+    WordFrequency,
+    WritingLog,
+    DocumentGoal,
+    AutoReplace,
+    Snippets,
+    CompareFiles,
+    ToggleDuplicates,
+    FormatDocument,
+    ToggleSpellCheck,
+    PlayPause,
+    PreviousTrack,
+    NextTrack,
+    StopMusic,
+    ToggleMute,
+    ChooseMusicFile,
+    ChooseMusicFolder,
+    BuiltinMusic,
+}
+
+impl Command {
+    pub const ALL: [Command; 70] = [
+        Command::NewFile,
+        Command::NewFromTemplate,
+        Command::OpenJournal,
+        Command::OpenFile,
+        Command::OpenFolder,
+        Command::OpenWordDocument,
+        Command::QuickOpen,
+        Command::Save,
+        Command::SaveAs,
+        Command::SaveSelectionAs,
+        Command::CompareWithSaved,
+        Command::Settings,
+        Command::Quit,
+        Command::Find,
+        Command::FindInFiles,
+        Command::RegexReplace,
+        Command::ToggleBookmark,
+        Command::ReflowParagraph,
+        Command::Evaluate,
+        Command::EvaluateReplace,
+        Command::InsertDate,
+        Command::Symbols,
+        Command::InsertPlaceholder,
+        Command::PastePlain,
+        Command::InsertFile,
+        Command::JumpToBracket,
+        Command::SelectNextOccurrence,
+        Command::SkipOccurrence,
+        Command::DuplicateLines,
+        Command::MoveLinesUp,
+        Command::MoveLinesDown,
+        Command::DeleteLines,
+        Command::ToggleComments,
+        Command::IndentToSpaces,
+        Command::IndentToTabs,
+        Command::ToggleFileTree,
+        Command::ToggleBookmarksPanel,
+        Command::ToggleOutline,
+        Command::ToggleMinimap,
+        Command::ToggleWelcome,
+        Command::TogglePlaylist,
+        Command::TogglePlaybackBar,
+        Command::TogglePomodoro,
+        Command::ToggleParagraphStats,
+        Command::SplitVertical,
+        Command::SplitHorizontal,
+        Command::OpenInSplit,
+        Command::CloseSplit,
+        Command::ZoomIn,
+        Command::ZoomOut,
+        Command::ZoomReset,
+        Command::ThemeEditor,
+        Command::Statistics,
+        Command::WordFrequency,
+        Command::WritingLog,
+        Command::DocumentGoal,
+        Command::AutoReplace,
+        Command::Snippets,
+        Command::CompareFiles,
+        Command::ToggleDuplicates,
+        Command::FormatDocument,
+        Command::ToggleSpellCheck,
+        Command::PlayPause,
+        Command::PreviousTrack,
+        Command::NextTrack,
+        Command::StopMusic,
+        Command::ToggleMute,
+        Command::ChooseMusicFile,
+        Command::ChooseMusicFolder,
+        Command::BuiltinMusic,
+    ];
+
+    // Раздел меню, в котором живет команда
+    pub fn category(self) -> &'static str {
+        match self {
+            Command::NewFile
+            | Command::NewFromTemplate
+            | Command::OpenJournal
+            | Command::OpenFile
+            | Command::OpenFolder
+            | Command::OpenWordDocument
+            | Command::QuickOpen
+            | Command::Save
+            | Command::SaveAs
+            | Command::SaveSelectionAs
+            | Command::CompareWithSaved
+            | Command::Settings
+            | Command::Quit => tr!("Файл"),
+            Command::Find
+            | Command::FindInFiles
+            | Command::RegexReplace
+            | Command::ToggleBookmark
+            | Command::ReflowParagraph
+            | Command::Evaluate
+            | Command::EvaluateReplace
+            | Command::InsertDate
+            | Command::Symbols
+            | Command::InsertPlaceholder
+            | Command::PastePlain
+            | Command::InsertFile
+            | Command::JumpToBracket
+            | Command::SelectNextOccurrence
+            | Command::SkipOccurrence
+            | Command::DuplicateLines
+            | Command::MoveLinesUp
+            | Command::MoveLinesDown
+            | Command::DeleteLines
+            | Command::ToggleComments
+            | Command::IndentToSpaces
+            | Command::IndentToTabs => tr!("Редактировать"),
+            Command::ToggleFileTree
+            | Command::ToggleBookmarksPanel
+            | Command::ToggleOutline
+            | Command::ToggleMinimap
+            | Command::ToggleWelcome
+            | Command::TogglePlaylist
+            | Command::TogglePlaybackBar
+            | Command::TogglePomodoro
+            | Command::ToggleParagraphStats
+            | Command::SplitVertical
+            | Command::SplitHorizontal
+            | Command::OpenInSplit
+            | Command::CloseSplit
+            | Command::ZoomIn
+            | Command::ZoomOut
+            | Command::ZoomReset
+            | Command::ThemeEditor => tr!("Вид"),
+            Command::Statistics
+            | Command::WordFrequency
+            | Command::WritingLog
+            | Command::DocumentGoal
+            | Command::AutoReplace
+            | Command::Snippets
+            | Command::CompareFiles
+            | Command::ToggleDuplicates
+            | Command::FormatDocument
+            | Command::ToggleSpellCheck => tr!("Инструменты"),
+            Command::PlayPause
+            | Command::PreviousTrack
+            | Command::NextTrack
+            | Command::StopMusic
+            | Command::ToggleMute
+            | Command::ChooseMusicFile
+            | Command::ChooseMusicFolder
+            | Command::BuiltinMusic => tr!("Музыка"),
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Command::NewFile => tr!("Новый"),
+            Command::NewFromTemplate => tr!("Шаблоны документов..."),
+            Command::OpenJournal => tr!("Новая запись в журнале"),
+            Command::OpenFile => tr!("Открыть"),
+            Command::OpenFolder => tr!("Открыть папку..."),
+            Command::OpenWordDocument => tr!("Открыть документ Word..."),
+            Command::QuickOpen => tr!("Быстрое открытие"),
+            Command::Save => tr!("Сохранить"),
+            Command::SaveAs => tr!("Сохранить как"),
+            Command::SaveSelectionAs => tr!("Сохранить выделенное как..."),
+            Command::CompareWithSaved => tr!("Сравнить с сохраненным"),
+            Command::Settings => tr!("Настройки"),
+            Command::Quit => tr!("Выйти"),
+            Command::Find => tr!("Найти"),
+            Command::FindInFiles => tr!("Найти в файлах"),
+            Command::RegexReplace => tr!("Замена по регулярному выражению..."),
+            Command::ToggleBookmark => tr!("Закладка"),
+            Command::ReflowParagraph => tr!("Переформатировать абзац"),
+            Command::Evaluate => tr!("Вычислить выражение"),
+            Command::EvaluateReplace => tr!("Заменить выражение результатом"),
+            Command::InsertDate => tr!("Вставить дату/время"),
+            Command::Symbols => tr!("Таблица символов..."),
+            Command::InsertPlaceholder => tr!("Текст-заполнитель..."),
+            Command::PastePlain => tr!("Вставить как обычный текст"),
+            Command::InsertFile => tr!("Вставить файл..."),
+            Command::JumpToBracket => tr!("К парной скобке"),
+            Command::SelectNextOccurrence => tr!("Выделить следующее вхождение"),
+            Command::SkipOccurrence => tr!("Пропустить вхождение"),
+            Command::DuplicateLines => tr!("Дублировать строку"),
+            Command::MoveLinesUp => tr!("Переместить строку вверх"),
+            Command::MoveLinesDown => tr!("Переместить строку вниз"),
+            Command::DeleteLines => tr!("Удалить строку"),
+            Command::ToggleComments => tr!("Закомментировать строки"),
+            Command::IndentToSpaces => tr!("Преобразовать отступы в пробелы"),
+            Command::IndentToTabs => tr!("Преобразовать отступы в табуляцию"),
+            Command::ToggleFileTree => tr!("Панель файлов"),
+            Command::ToggleBookmarksPanel => tr!("Закладки"),
+            Command::ToggleOutline => tr!("Структура документа"),
+            Command::ToggleMinimap => tr!("Миникарта"),
+            Command::ToggleWelcome => tr!("Начальная страница"),
+            Command::TogglePlaylist => tr!("Плейлист"),
+            Command::TogglePlaybackBar => tr!("Панель воспроизведения"),
+            Command::TogglePomodoro => tr!("Таймер помидоро"),
+            Command::ToggleParagraphStats => tr!("Статистика абзацев"),
+            Command::SplitVertical => tr!("Разделить рядом"),
+            Command::SplitHorizontal => tr!("Разделить одна над другой"),
+            Command::OpenInSplit => tr!("Открыть файл во второй панели..."),
+            Command::CloseSplit => tr!("Закрыть разделение"),
+            Command::ZoomIn => tr!("Увеличить"),
+            Command::ZoomOut => tr!("Уменьшить"),
+            Command::ZoomReset => tr!("Исходный масштаб"),
+            Command::ThemeEditor => tr!("Редактор тем..."),
+            Command::Statistics => tr!("Статистика..."),
+            Command::WordFrequency => tr!("Частота слов..."),
+            Command::WritingLog => tr!("Журнал письма..."),
+            Command::DocumentGoal => tr!("Цель по словам..."),
+            Command::AutoReplace => tr!("Автозамена..."),
+            Command::Snippets => tr!("Сниппеты..."),
+            Command::CompareFiles => tr!("Сравнить файлы..."),
+            Command::ToggleDuplicates => tr!("Повторяющиеся строки"),
+            Command::FormatDocument => tr!("Форматировать JSON/XML..."),
+            Command::ToggleSpellCheck => tr!("Проверка орфографии"),
+            Command::PlayPause => tr!("Играть / пауза"),
+            Command::PreviousTrack => tr!("Предыдущий трек"),
+            Command::NextTrack => tr!("Следующий трек"),
+            Command::StopMusic => tr!("Остановить"),
+            Command::ToggleMute => tr!("Выключить / включить звук"),
+            Command::ChooseMusicFile => tr!("Выбрать файл..."),
+            Command::ChooseMusicFolder => tr!("Выбрать папку..."),
+            Command::BuiltinMusic => tr!("Встроенная музыка"),
+        }
+    }
+
+    // Сочетание клавиш, если оно есть, — для подсказки в списке
+    pub fn shortcut(self) -> Option<&'static str> {
+        match self {
+            Command::QuickOpen => Some("Ctrl + P"),
+            Command::Find => Some("Ctrl + F"),
+            Command::FindInFiles => Some("Ctrl + Shift + F"),
+            Command::ToggleBookmark => Some("Ctrl + B"),
+            Command::ReflowParagraph => Some("Alt + Q"),
+            Command::Evaluate => Some("Ctrl + Shift + E"),
+            Command::EvaluateReplace => Some("Ctrl + Shift + R"),
+            Command::InsertDate => Some("F5"),
+            Command::PastePlain => Some("Ctrl + Shift + V"),
+            Command::JumpToBracket => Some("Ctrl + M"),
+            Command::SelectNextOccurrence => Some("Ctrl + Shift + D"),
+            Command::SkipOccurrence => Some("Alt + Shift + D"),
+            Command::DuplicateLines => Some("Ctrl + D"),
+            Command::MoveLinesUp => Some("Alt + ↑"),
+            Command::MoveLinesDown => Some("Alt + ↓"),
+            Command::DeleteLines => Some("Ctrl + Shift + K"),
+            Command::ToggleComments => Some("Ctrl + /"),
+            Command::ZoomIn => Some("Ctrl + +"),
+            Command::ZoomOut => Some("Ctrl + -"),
+            Command::ZoomReset => Some("Ctrl + 0"),
+            Command::PlayPause => Some("F8"),
+            Command::PreviousTrack => Some("F7"),
+            Command::NextTrack => Some("F9"),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> String {
+        format!("{}: {}", self.category(), self.title())
+    }
+}
+
+// Строка палитры: встроенная команда или команда плагина (индекс в списке плагинов)
+#[derive(Clone, Copy, PartialEq)]
+enum PaletteItem {
+    Command(Command),
+    Plugin(usize),
+}
+
+#[derive(Default)]
+pub struct CommandPaletteModule {
+    pub show: bool,
+    pub query: String,
+    filtered: Vec<PaletteItem>,
+    selected: usize,
+    focus_query: bool,
+    recent: Vec<PaletteItem>, // Последние выполненные — первыми, пока запрос пуст
+    plugins: Vec<String>,     // Названия команд плагинов
+}
+
+pub enum CommandPaletteResult {
+    None,
+    Run(Command),
+    RunPlugin(usize),
+}
+
+impl CommandPaletteModule {
+    pub fn open(&mut self) {
+        self.show = true;
+        self.query.clear();
+        self.focus_query = true;
+        self.update_filter();
+    }
+
+    pub fn close(&mut self) {
+        self.show = false;
+        self.filtered.clear();
+    }
+
+    // Плагины перезагрузили — старые индексы больше не годятся
+    pub fn set_plugin_commands(&mut self, titles: Vec<String>) {
+        self.plugins = titles;
+        self.recent.retain(|item| matches!(item, PaletteItem::Command(_)));
+    }
+
+    fn items(&self) -> impl Iterator<Item = PaletteItem> + '_ {
+        Command::ALL
+            .iter()
+            .map(|&command| PaletteItem::Command(command))
+            .chain((0..self.plugins.len()).map(PaletteItem::Plugin))
+    }
+
+    fn label(&self, item: PaletteItem) -> String {
+        match item {
+            PaletteItem::Command(command) => command.label(),
+            PaletteItem::Plugin(index) => format!("{}: {}", tr!("Плагины"), self.plugins[index]),
+        }
+    }
+
+    // Ctrl + Shift + P нужно проверить раньше, чем Ctrl + P быстрого открытия
+    pub fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let shortcut = egui::Modifiers::COMMAND | egui::Modifiers::SHIFT;
+        if ctx.input_mut(|i| i.consume_key(shortcut, egui::Key::P)) {
+            if self.show {
+                self.close();
+            } else {
+                self.open();
+            }
+        }
+    }
+
+    fn update_filter(&mut self) {
+        if self.query.is_empty() {
+            let rest: Vec<PaletteItem> = self.items().filter(|item| !self.recent.contains(item)).collect();
+            self.filtered = self.recent.clone();
+            self.filtered.extend(rest);
+        } else {
+            let mut scored: Vec<(PaletteItem, i32)> = self
+                .items()
+                .filter_map(|item| fuzzy_score(&self.label(item), &self.query).map(|score| (item, score)))
+                .collect();
+            scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+            self.filtered = scored.into_iter().map(|(item, _)| item).collect();
+        }
+        self.selected = 0;
+    }
+
+    fn run(&mut self, item: PaletteItem) -> CommandPaletteResult {
+        self.recent.retain(|&i| i != item);
+        self.recent.insert(0, item);
+        self.recent.truncate(MAX_RECENT_COMMANDS);
+        match item {
+            PaletteItem::Command(command) => CommandPaletteResult::Run(command),
+            PaletteItem::Plugin(index) => CommandPaletteResult::RunPlugin(index),
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> CommandPaletteResult {
+        if !self.show {
+            return CommandPaletteResult::None;
+        }
+
+        let mut result = CommandPaletteResult::None;
+
+        // Клавиши забираем до того, как их получит поле ввода
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+
+        if up && self.selected > 0 {
+            self.selected -= 1;
+        }
+        if down && self.selected + 1 < self.filtered.len() {
+            self.selected += 1;
+        }
+        if enter {
+            if let Some(&item) = self.filtered.get(self.selected) {
+                result = self.run(item);
+            }
+        }
+
+        egui::Window::new(tr!("Палитра команд"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .fixed_size([450.0, 0.0])
+            .show(ctx, |ui| {
+                let query_id = ui.make_persistent_id("command_palette_query");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text(tr!("Команда..."))
+                        .desired_width(f32::INFINITY)
+                        .id(query_id),
+                );
+                if self.focus_query {
+                    ui.memory_mut(|mem| mem.request_focus(query_id));
+                    self.focus_query = false;
+                }
+                if response.changed() {
+                    self.update_filter();
+                }
+
+                ui.separator();
+
+                if self.filtered.is_empty() {
+                    ui.label(tr!("Ничего не найдено"));
+                }
+
+                let mut clicked = None;
+                egui::ScrollArea::vertical().max_height(350.0).show(ui, |ui| {
+                    for (row, &item) in self.filtered.iter().enumerate() {
+                        let selected = row == self.selected;
+                        let label = ui
+                            .horizontal(|ui| {
+                                let label = ui.selectable_label(selected, self.label(item));
+                                let shortcut = match item {
+                                    PaletteItem::Command(command) => command.shortcut(),
+                                    PaletteItem::Plugin(_) => None,
+                                };
+                                if let Some(shortcut) = shortcut {
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        ui.weak(shortcut);
+                                    });
+                                }
+                                label
+                            })
+                            .inner;
+                        if selected && (up || down) {
+                            label.scroll_to_me(None);
+                        }
+                        if label.clicked() {
+                            clicked = Some(item);
+                        }
+                    }
+                });
+                if let Some(item) = clicked {
+                    result = self.run(item);
+                }
+            });
+
+        if escape || !matches!(result, CommandPaletteResult::None) {
+            self.close();
+        }
+
+        result
+    }
+}
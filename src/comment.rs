@@ -0,0 +1,155 @@
+// comment.rs
+use std::path::Path;
+
+use crate::line_ops::{line_start, lines_span, LineEdit};
+
+// Как закомментировать строку: префиксом или обернуть целиком, как в HTML
+#[derive(Clone, Copy)]
+pub struct CommentStyle {
+    pub open: &'static str,
+    pub close: &'static str,
+}
+
+const fn line(prefix: &'static str) -> CommentStyle {
+    CommentStyle {
+        open: prefix,
+        close: "",
+    }
+}
+
+const fn block(open: &'static str, close: &'static str) -> CommentStyle {
+    CommentStyle { open, close }
+}
+
+const STYLES: [(&[&str], CommentStyle); 8] = [
+    (
+        &[
+            "rs", "c", "h", "cpp", "hpp", "cc", "cs", "java", "js", "jsx", "ts", "tsx", "go", "kt", "swift", "scss",
+            "php", "dart", "rhai", "jsonc",
+        ],
+        line("//"),
+    ),
+    (
+        &[
+            "py",
+            "sh",
+            "bash",
+            "zsh",
+            "rb",
+            "pl",
+            "toml",
+            "yaml",
+            "yml",
+            "conf",
+            "r",
+            "ps1",
+            "cmake",
+            "gitignore",
+        ],
+        line("#"),
+    ),
+    (&["ini", "asm", "lisp", "el", "scm", "clj"], line(";")),
+    (&["sql", "lua", "hs"], line("--")),
+    (&["tex", "erl"], line("%")),
+    (&["bat", "cmd"], line("REM")),
+    (
+        &["html", "htm", "xml", "svg", "md", "vue", "xaml"],
+        block("<!--", "-->"),
+    ),
+    (&["css"], block("/*", "*/")),
+];
+
+pub fn comment_style(path: &Path) -> Option<CommentStyle> {
+    // У «.gitignore» и подобных имя целиком считается расширением
+    let extension = path
+        .extension()
+        .or_else(|| path.file_name().filter(|name| name.to_string_lossy().starts_with('.')))?
+        .to_string_lossy()
+        .trim_start_matches('.')
+        .to_lowercase();
+    STYLES
+        .iter()
+        .find(|(extensions, _)| extensions.contains(&extension.as_str()))
+        .map(|(_, style)| *style)
+}
+
+fn is_commented(line: &str, style: CommentStyle) -> bool {
+    let body = line.trim();
+    body.starts_with(style.open) && body.ends_with(style.close)
+}
+
+// Снять комментарий: префикс с одним пробелом после него и окончание с пробелом перед ним
+fn uncomment(line: &str, style: CommentStyle) -> (String, usize, usize) {
+    let indent = line.len() - line.trim_start().len();
+    let mut body = &line.trim_start()[style.open.len()..];
+    let removed = style.open.chars().count() + usize::from(body.starts_with(' '));
+    body = body.strip_prefix(' ').unwrap_or(body);
+    if !style.close.is_empty() {
+        body = body.trim_end();
+        body = body.strip_suffix(style.close).unwrap_or(body);
+        body = body.strip_suffix(' ').unwrap_or(body);
+    }
+    let column = line[..indent].chars().count();
+    (format!("{}{}", &line[..indent], body), column, removed)
+}
+
+// Ctrl + /: если все непустые строки уже закомментированы — снять комментарии, иначе закомментировать.
+// Префикс ставится на общий отступ блока, чтобы комментарии стояли ровно
+pub fn toggle_comments(text: &[char], anchor: usize, cursor: usize, style: CommentStyle) -> LineEdit {
+    let (start, end) = lines_span(text, anchor, cursor);
+    let block: String = text[start..end].iter().collect();
+    let lines: Vec<&str> = block.split('\n').collect();
+    let filled = || lines.iter().filter(|line| !line.trim().is_empty());
+
+    let uncommenting = filled().count() > 0 && filled().all(|line| is_commented(line, style));
+    let indent = filled()
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+
+    // Сдвиг курсора считаем только для его строки: без выделения строка одна
+    let cursor_column = cursor - line_start(text, cursor);
+    let mut new_cursor_column = cursor_column;
+    let mut result = Vec::with_capacity(lines.len());
+    for line in &lines {
+        if line.trim().is_empty() {
+            result.push(line.to_string());
+            continue;
+        }
+        if uncommenting {
+            let (plain, column, removed) = uncomment(line, style);
+            if cursor_column >= column + removed {
+                new_cursor_column = cursor_column - removed;
+            } else if cursor_column > column {
+                new_cursor_column = column;
+            }
+            result.push(plain);
+        } else {
+            let split = line.char_indices().nth(indent).map_or(line.len(), |(i, _)| i);
+            let close = if style.close.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", style.close)
+            };
+            if cursor_column >= indent {
+                new_cursor_column = cursor_column + style.open.chars().count() + 1;
+            }
+            result.push(format!("{}{} {}{}", &line[..split], style.open, &line[split..], close));
+        }
+    }
+
+    let replacement = result.join("\n");
+    let (anchor, cursor) = if anchor != cursor {
+        (start, start + replacement.chars().count())
+    } else {
+        let position = line_start(text, cursor) + new_cursor_column;
+        (position, position)
+    };
+    LineEdit {
+        start,
+        end,
+        text: replacement,
+        anchor,
+        cursor,
+    }
+}
@@ -0,0 +1,108 @@
+// compare_module.rs
+use eframe::egui;
+use std::path::PathBuf;
+
+use crate::i18n::tr;
+
+// Откуда взять текст для одной из сторон сравнения
+#[derive(Clone, PartialEq)]
+pub enum CompareSource {
+    Document,              // Текст в редакторе, вместе с несохраненными правками
+    SplitPane,             // Файл, открытый во второй панели
+    File(Option<PathBuf>), // Файл с диска; None — еще не выбран
+}
+
+pub struct CompareModule {
+    pub show_window: bool,
+    left: CompareSource,
+    right: CompareSource,
+}
+
+impl Default for CompareModule {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            left: CompareSource::Document,
+            right: CompareSource::File(None),
+        }
+    }
+}
+
+pub enum CompareResult {
+    None,
+    Compare(CompareSource, CompareSource),
+}
+
+impl CompareModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+    }
+
+    // split_file — файл во второй панели, если она показывает отдельный файл
+    pub fn show_window(&mut self, ctx: &egui::Context, split_file: Option<&str>) -> CompareResult {
+        let mut result = CompareResult::None;
+        if !self.show_window {
+            return result;
+        }
+        if split_file.is_none() {
+            for side in [&mut self.left, &mut self.right] {
+                if *side == CompareSource::SplitPane {
+                    *side = CompareSource::Document;
+                }
+            }
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Сравнить файлы"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("compare_sources").num_columns(2).spacing([8.0, 8.0]).show(ui, |ui| {
+                    ui.label(tr!("Исходный:"));
+                    Self::source_picker(ui, &mut self.left, split_file);
+                    ui.end_row();
+                    ui.label(tr!("Измененный:"));
+                    Self::source_picker(ui, &mut self.right, split_file);
+                    ui.end_row();
+                });
+                ui.separator();
+
+                let ready = [&self.left, &self.right].iter().all(|side| **side != CompareSource::File(None));
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(ready, egui::Button::new(tr!("Сравнить"))).clicked() {
+                        result = CompareResult::Compare(self.left.clone(), self.right.clone());
+                    }
+                    if ui.button(tr!("⇄ Поменять местами")).clicked() {
+                        std::mem::swap(&mut self.left, &mut self.right);
+                    }
+                });
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+        result
+    }
+
+    fn source_picker(ui: &mut egui::Ui, source: &mut CompareSource, split_file: Option<&str>) {
+        ui.vertical(|ui| {
+            ui.radio_value(source, CompareSource::Document, tr!("Текущий документ"));
+            if let Some(name) = split_file {
+                ui.radio_value(source, CompareSource::SplitPane, tr!("Вторая панель: {}", name));
+            }
+            ui.horizontal(|ui| {
+                let is_file = matches!(source, CompareSource::File(_));
+                let chosen = match source {
+                    CompareSource::File(Some(path)) => path.display().to_string(),
+                    _ => tr!("Файл...").to_string(),
+                };
+                let clicked = ui.radio(is_file, chosen).clicked();
+                if ui.button(tr!("Обзор...")).clicked() || (clicked && !is_file) {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        *source = CompareSource::File(Some(path));
+                    }
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,264 @@
+// config.rs
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::auto_replace_module::{default_rules, ReplaceRule};
+use crate::date_module::default_date_formats;
+use crate::docx_import::DocxImport;
+use crate::document_goal_module::DocumentGoal;
+use crate::format_module::FormatKind;
+use crate::i18n::tr;
+use crate::sequence_module::SequencePadding;
+use crate::snippet_module::{default_snippets, Snippet};
+use crate::statistics_module::{CharacterCount, WordCount};
+use crate::status_bar_module::{default_status_bar, normalize, StatusBarItem};
+use crate::theme_module::{Palette, DARK_THEME, LIGHT_THEME};
+use crate::translit::TranslitScheme;
+
+const CONFIG_FILE: &str = "config.toml";
+const MAX_RECENT_FILES: usize = 10;
+
+// Кодировки, которые предлагаются в настройках для файлов не в UTF-8
+pub const ENCODINGS: [&str; 4] = ["windows-1251", "KOI8-R", "IBM866", "UTF-8"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Language {
+    #[serde(rename = "ru")]
+    Russian,
+    #[serde(rename = "en")]
+    English,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum FontFamily {
+    Monospace,
+    Proportional,
+    Custom, // Шрифт из файла custom_font
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TypingSoundSet {
+    Mechanical,
+    Typewriter,
+    Soft,
+}
+
+// Положение и размер окна в логических точках
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct WindowGeometry {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub maximized: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub font_family: FontFamily,
+    pub font_size: f32,
+    pub custom_font: Option<PathBuf>, // .ttf/.otf, например с хорошей кириллицей
+    pub theme: String, // "dark", "light" или название темы из custom_themes
+    pub tab_width: usize,
+    pub insert_spaces: bool, // Tab вставляет пробелы до следующей позиции табуляции
+    pub highlight_current_line: bool,
+    pub show_ruler: bool,
+    pub ruler_column: usize,
+    pub reflow_column: usize, // Ширина строки для «Переформатировать абзац»
+    pub paste_collapse_blank_lines: bool, // «Вставить как обычный текст»: не больше одной пустой строки подряд
+    pub paste_reflow: bool,               // «Вставить как обычный текст»: переносить по ширине абзаца
+    pub autosave: bool,
+    pub autosave_interval_secs: u64,
+    pub backup_on_save: bool, // Перед сохранением копировать прежнее содержимое в «имя~»
+    pub recompress_archives: bool, // Текст из .gz и .zip сохранять обратно в архив
+    pub docx_import: DocxImport,
+    pub music_volume: f32,
+    pub music_muted: bool,
+    pub music_folder: Option<PathBuf>, // None — встроенная музыка из assets
+    pub music_file: Option<PathBuf>,   // Один выбранный трек; если задан, папка не используется
+    pub music_shuffle: bool,
+    pub music_repeat: bool,
+    pub ambient_volume: f32, // Фоновые звуки (дождь, шум) — отдельно от музыки
+    pub typing_sounds: bool,
+    pub typing_sound_set: TypingSoundSet,
+    pub typing_volume: f32,
+    pub pomodoro_focus_minutes: u32,
+    pub pomodoro_break_minutes: u32,
+    pub pomodoro_music: bool, // Включать музыку на время работы и ставить на паузу в перерыв
+    pub reading_wpm: u32,     // Скорость чтения для оценки времени, слов в минуту
+    pub daily_word_goal: u32, // 0 — без цели
+    pub document_goals: Vec<DocumentGoal>,
+    pub status_bar: Vec<StatusBarItem>, // Порядок и видимость элементов строки состояния
+    pub status_characters: CharacterCount, // Какие символы считать в строке состояния
+    pub status_words: WordCount,           // И какие слова
+    pub spell_check: bool,
+    pub paragraph_stats: bool, // Подписывать в конце абзаца число слов и предложений
+    pub autocomplete: bool, // Показывать подсказки сами после нескольких букв; Ctrl + Пробел работает всегда
+    pub language: Language,
+    pub default_encoding: String,
+    pub tui_mouse: bool, // Мышь в консольном режиме; в части терминалов мешает выделять текст их средствами
+    pub custom_themes: Vec<Palette>,
+    pub snippets: Vec<Snippet>,
+    pub auto_replace: bool,
+    pub smart_quotes: bool, // "" при наборе становятся «»
+    pub auto_pair: bool,    // Закрывающая скобка или кавычка добавляется сама
+    pub replace_rules: Vec<ReplaceRule>,
+    pub command_timeout_secs: u64, // Сколько ждать внешнюю команду из «Выполнить команду…»
+    pub keep_history: bool,        // Сохранять копию файла в «Историю версий» при каждом сохранении
+    pub format_kind: FormatKind,
+    pub format_indent: usize, // Пробелов на уровень в «Форматировать JSON/XML»
+    pub format_use_tabs: bool,
+    pub join_separator: String, // «Преобразовать → Объединить строки через»
+    pub translit_scheme: TranslitScheme,
+    pub sequence_start: i64,
+    pub sequence_step: i64,
+    pub sequence_padding: SequencePadding,
+    pub sequence_separator: String, // Между номером и текстом строки
+    pub sequence_skip_empty: bool,
+    pub date_formats: Vec<String>, // strftime; %B и %A — по-русски
+    pub date_format: String,       // Для F5 и {{date}}
+    pub author: String,            // Для {{author}} в шаблонах
+    pub journal_path: String,      // strftime; от домашней папки, если путь относительный
+    pub journal_heading: String,   // strftime; заголовок каждой новой записи
+    pub journal_append_only: bool, // Прежние записи журнала не правятся
+    pub recent_symbols: String,    // Из «Таблицы символов», последний — первым
+    pub show_welcome: bool,
+    pub recent_files: Vec<PathBuf>, // Последний открытый — первым
+    pub window: Option<WindowGeometry>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            font_family: FontFamily::Monospace,
+            font_size: 16.0,
+            custom_font: None,
+            theme: DARK_THEME.to_string(),
+            tab_width: 4,
+            insert_spaces: false,
+            highlight_current_line: true,
+            show_ruler: false,
+            ruler_column: 80,
+            reflow_column: 72,
+            paste_collapse_blank_lines: true,
+            paste_reflow: false,
+            autosave: false,
+            autosave_interval_secs: 60,
+            backup_on_save: false,
+            recompress_archives: true,
+            docx_import: DocxImport::default(),
+            music_volume: 0.5,
+            music_muted: false,
+            music_folder: None,
+            music_file: None,
+            music_shuffle: false,
+            music_repeat: true,
+            ambient_volume: 0.3,
+            typing_sounds: false,
+            typing_sound_set: TypingSoundSet::Mechanical,
+            typing_volume: 0.5,
+            pomodoro_focus_minutes: 25,
+            pomodoro_break_minutes: 5,
+            pomodoro_music: true,
+            reading_wpm: 180,
+            daily_word_goal: 500,
+            document_goals: Vec::new(),
+            status_bar: default_status_bar(),
+            status_characters: CharacterCount::WithSpaces,
+            status_words: WordCount::AllTokens,
+            spell_check: true,
+            paragraph_stats: false,
+            autocomplete: true,
+            language: Language::Russian,
+            default_encoding: "windows-1251".to_string(),
+            tui_mouse: true,
+            custom_themes: Vec::new(),
+            snippets: default_snippets(),
+            auto_replace: true,
+            smart_quotes: true,
+            auto_pair: true,
+            replace_rules: default_rules(),
+            command_timeout_secs: 10,
+            keep_history: true,
+            format_kind: FormatKind::Auto,
+            format_indent: 2,
+            format_use_tabs: false,
+            join_separator: " ".to_string(),
+            translit_scheme: TranslitScheme::Gost,
+            sequence_start: 1,
+            sequence_step: 1,
+            sequence_padding: SequencePadding::None,
+            sequence_separator: ". ".to_string(),
+            sequence_skip_empty: false,
+            date_formats: default_date_formats(),
+            date_format: "%d.%m.%Y".to_string(),
+            author: String::new(),
+            journal_path: "journal/%Y-%m-%d.txt".to_string(),
+            journal_heading: "## %H:%M".to_string(),
+            journal_append_only: true,
+            recent_symbols: String::new(),
+            show_welcome: true,
+            recent_files: Vec::new(),
+            window: None,
+        }
+    }
+}
+
+// Папка настроек редактора: ~/.config/gte, %APPDATA%\gte и т.п.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("gte"))
+}
+
+impl Config {
+    // Отсутствующий или поврежденный файл не мешает запуску: берем значения по умолчанию
+    pub fn load() -> Self {
+        config_dir()
+            .and_then(|dir| fs::read_to_string(dir.join(CONFIG_FILE)).ok())
+            .and_then(|content| toml::from_str(&content).ok())
+            .map(|mut config: Config| {
+                normalize(&mut config.status_bar);
+                config
+            })
+            .unwrap_or_default()
+    }
+
+    // Неизвестное название темы (например, удаленной вручную из файла) — темная тема
+    pub fn palette(&self) -> Palette {
+        match self.theme.as_str() {
+            LIGHT_THEME => Palette::light(),
+            DARK_THEME => Palette::dark(),
+            name => self
+                .custom_themes
+                .iter()
+                .find(|p| p.name == name)
+                .cloned()
+                .unwrap_or_else(Palette::dark),
+        }
+    }
+
+    // Откуда брать музыку: выбранный файл, папка или None — встроенная музыка
+    pub fn music_source(&self) -> Option<&Path> {
+        self.music_file.as_deref().or(self.music_folder.as_deref())
+    }
+
+    pub fn add_recent_file(&mut self, path: &Path) {
+        self.recent_files.retain(|p| p != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(dir) = config_dir() else {
+            return Ok(());
+        };
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| tr!("Ошибка сохранения настроек: {}", e))?;
+        fs::create_dir_all(&dir).map_err(|e| tr!("Ошибка сохранения настроек: {}", e))?;
+        fs::write(dir.join(CONFIG_FILE), content).map_err(|e| tr!("Ошибка сохранения настроек: {}", e))
+    }
+}
@@ -0,0 +1,207 @@
+// crypto_module.rs
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+pub const ENCRYPTED_EXTENSION: &str = "gte";
+// Формат файла: метка, соль для argon2, nonce для AES-GCM, затем шифротекст
+const MAGIC: &[u8; 4] = b"GTE1";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == ENCRYPTED_EXTENSION)
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>, String> {
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| tr!("Ошибка шифрования: {}", e))?;
+    Ok(key)
+}
+
+pub fn encrypt(text: &str, password: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = Aes256Gcm::new(&derive_key(password, &salt)?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, text.as_bytes())
+        .map_err(|e| tr!("Ошибка шифрования: {}", e))?;
+
+    let mut bytes = Vec::with_capacity(MAGIC.len() + SALT_LENGTH + NONCE_LENGTH + encrypted.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&salt);
+    bytes.extend_from_slice(&nonce);
+    bytes.extend_from_slice(&encrypted);
+    Ok(bytes)
+}
+
+pub fn decrypt(bytes: &[u8], password: &str) -> Result<String, String> {
+    let header = MAGIC.len() + SALT_LENGTH + NONCE_LENGTH;
+    if bytes.len() < header || !bytes.starts_with(MAGIC) {
+        return Err(tr!("Файл не является зашифрованным документом").to_string());
+    }
+    let salt = &bytes[MAGIC.len()..MAGIC.len() + SALT_LENGTH];
+    let nonce = Nonce::from_slice(&bytes[MAGIC.len() + SALT_LENGTH..header]);
+    let cipher = Aes256Gcm::new(&derive_key(password, salt)?);
+    // GCM проверяет целостность: неверный пароль и порча файла выглядят одинаково
+    let decrypted = cipher
+        .decrypt(nonce, &bytes[header..])
+        .map_err(|_| tr!("Неверный пароль или файл поврежден").to_string())?;
+    String::from_utf8(decrypted).map_err(|_| tr!("Неверный пароль или файл поврежден").to_string())
+}
+
+#[derive(PartialEq)]
+enum PromptPurpose {
+    Open,
+    Save,
+}
+
+struct PasswordPrompt {
+    path: PathBuf,
+    purpose: PromptPurpose,
+    password: String,
+    confirm: String,
+    error: Option<String>,
+    focus: bool,
+}
+
+#[derive(Default)]
+pub struct CryptoModule {
+    current: Option<(PathBuf, String)>, // Открытый зашифрованный файл и его пароль — для повторного сохранения
+    prompt: Option<PasswordPrompt>,
+}
+
+pub enum CryptoResult {
+    None,
+    Open(PathBuf, String),
+    Save(PathBuf, String),
+}
+
+impl CryptoModule {
+    pub fn password_for(&self, path: &Path) -> Option<&str> {
+        self.current.as_ref().filter(|(current, _)| current == path).map(|(_, password)| password.as_str())
+    }
+
+    pub fn set_current(&mut self, path: &Path, password: String) {
+        self.current = Some((path.to_path_buf(), password));
+        self.prompt = None;
+    }
+
+    fn show_prompt(&mut self, path: &Path, purpose: PromptPurpose) {
+        self.prompt = Some(PasswordPrompt {
+            path: path.to_path_buf(),
+            purpose,
+            password: String::new(),
+            confirm: String::new(),
+            error: None,
+            focus: true,
+        });
+    }
+
+    pub fn ask_to_open(&mut self, path: &Path) {
+        self.show_prompt(path, PromptPurpose::Open);
+    }
+
+    pub fn ask_to_save(&mut self, path: &Path) {
+        self.show_prompt(path, PromptPurpose::Save);
+    }
+
+    // Ошибку (например, неверный пароль) показываем в том же окне, чтобы можно было ввести пароль снова
+    pub fn set_error(&mut self, error: String) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.error = Some(error);
+            prompt.password.clear();
+            prompt.focus = true;
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> CryptoResult {
+        let mut result = CryptoResult::None;
+        let Some(prompt) = &mut self.prompt else {
+            return result;
+        };
+
+        let mut open = true;
+        let title = match prompt.purpose {
+            PromptPurpose::Open => tr!("Открыть зашифрованный файл"),
+            PromptPurpose::Save => tr!("Сохранить зашифрованным"),
+        };
+        let name = prompt.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        egui::Window::new(title)
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(name);
+                let saving = prompt.purpose == PromptPurpose::Save;
+                let mut submit = false;
+
+                egui::Grid::new("password_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr!("Пароль:"));
+                    let response = ui.add(egui::TextEdit::singleline(&mut prompt.password).password(true));
+                    if std::mem::take(&mut prompt.focus) {
+                        response.request_focus();
+                    }
+                    submit |= !saving && response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    ui.end_row();
+                    if saving {
+                        ui.label(tr!("Повторите пароль:"));
+                        let response = ui.add(egui::TextEdit::singleline(&mut prompt.confirm).password(true));
+                        submit |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        ui.end_row();
+                    }
+                });
+
+                if saving {
+                    ui.label(tr!("Забытый пароль восстановить нельзя."));
+                }
+                if let Some(error) = &prompt.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+                }
+
+                let button = if saving { tr!("Сохранить") } else { tr!("Открыть") };
+                submit |= ui.add_enabled(!prompt.password.is_empty(), egui::Button::new(button)).clicked();
+                if submit && !prompt.password.is_empty() {
+                    if saving && prompt.password != prompt.confirm {
+                        prompt.error = Some(tr!("Пароли не совпадают").to_string());
+                    } else if saving {
+                        result = CryptoResult::Save(prompt.path.clone(), prompt.password.clone());
+                    } else {
+                        result = CryptoResult::Open(prompt.path.clone(), prompt.password.clone());
+                    }
+                }
+            });
+
+        if !open {
+            self.prompt = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_text_round_trips() {
+        let bytes = encrypt("Секретная запись", "пароль").unwrap();
+        assert!(bytes.starts_with(MAGIC));
+        assert_eq!(decrypt(&bytes, "пароль").unwrap(), "Секретная запись");
+        assert!(decrypt(&bytes, "не тот").is_err());
+        // Испорченный байт шифротекста GCM тоже не пропускает
+        let mut damaged = bytes.clone();
+        *damaged.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&damaged, "пароль").is_err());
+        assert!(decrypt(b"plain text", "пароль").is_err());
+    }
+}
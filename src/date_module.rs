@@ -0,0 +1,204 @@
+// date_module.rs
+use chrono::{Datelike, Local};
+use eframe::egui;
+
+use crate::config::{Config, Language};
+use crate::i18n::tr;
+
+// Месяцы в родительном падеже: «16 октября 2026»
+const MONTHS_GENITIVE: [&str; 12] = [
+    "января",
+    "февраля",
+    "марта",
+    "апреля",
+    "мая",
+    "июня",
+    "июля",
+    "августа",
+    "сентября",
+    "октября",
+    "ноября",
+    "декабря",
+];
+const MONTHS: [&str; 12] = [
+    "январь",
+    "февраль",
+    "март",
+    "апрель",
+    "май",
+    "июнь",
+    "июль",
+    "август",
+    "сентябрь",
+    "октябрь",
+    "ноябрь",
+    "декабрь",
+];
+const MONTHS_SHORT: [&str; 12] = [
+    "янв", "фев", "мар", "апр", "мая", "июн", "июл", "авг", "сен", "окт", "ноя", "дек",
+];
+const WEEKDAYS: [&str; 7] = [
+    "понедельник",
+    "вторник",
+    "среда",
+    "четверг",
+    "пятница",
+    "суббота",
+    "воскресенье",
+];
+const WEEKDAYS_SHORT: [&str; 7] = ["пн", "вт", "ср", "чт", "пт", "сб", "вс"];
+
+pub fn default_date_formats() -> Vec<String> {
+    [
+        "%d.%m.%Y",
+        "%-d %B %Y",
+        "%A, %-d %B %Y",
+        "%d.%m.%Y %H:%M",
+        "%H:%M",
+        "%Y-%m-%d",
+    ]
+    .iter()
+    .map(|f| f.to_string())
+    .collect()
+}
+
+// strftime, но названия месяцев и дней недели — по-русски, если интерфейс на русском.
+// %B — «октября», %L — «октябрь», %b — «окт», %A — «пятница», %a — «пт»
+pub fn format_date(format: &str, language: Language) -> String {
+    let now = Local::now();
+    let mut prepared = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            prepared.push(c);
+            continue;
+        }
+        let Some(spec) = chars.next() else {
+            prepared.push_str("%%");
+            break;
+        };
+        let month = now.month0() as usize;
+        let weekday = now.weekday().num_days_from_monday() as usize;
+        let russian = match spec {
+            'B' => MONTHS_GENITIVE[month],
+            'L' => MONTHS[month],
+            'b' | 'h' => MONTHS_SHORT[month],
+            'A' => WEEKDAYS[weekday],
+            'a' => WEEKDAYS_SHORT[weekday],
+            _ => "",
+        };
+        if language == Language::Russian && !russian.is_empty() {
+            prepared.push_str(russian);
+        } else if spec == 'L' {
+            prepared.push_str("%B");
+        } else {
+            prepared.push('%');
+            prepared.push(spec);
+        }
+    }
+
+    // Неизвестный спецификатор chrono не вернет ошибкой, а уронит форматирование
+    let items: Vec<_> = chrono::format::StrftimeItems::new(&prepared).collect();
+    if items.iter().any(|item| matches!(item, chrono::format::Item::Error)) {
+        return format.to_string();
+    }
+    now.format_with_items(items.into_iter()).to_string()
+}
+
+#[derive(Default)]
+pub struct DateModule {
+    pub show_window: bool,
+}
+
+pub enum DateResult {
+    None,
+    ConfigChanged,
+    Insert(String),
+}
+
+impl DateModule {
+    // Содержимое подменю «Вставить дату/время»: каждый формат показан готовой датой
+    pub fn show_menu(&mut self, ui: &mut egui::Ui, config: &Config) -> DateResult {
+        let mut result = DateResult::None;
+        for format in &config.date_formats {
+            let text = format_date(format, config.language);
+            let label = if *format == config.date_format {
+                format!("{}  (F5)", text)
+            } else {
+                text.clone()
+            };
+            if ui.button(label).clicked() {
+                result = DateResult::Insert(text);
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui.button(tr!("Форматы даты...")).clicked() {
+            self.show_window = true;
+            ui.close_menu();
+        }
+        result
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, config: &mut Config) -> DateResult {
+        if !self.show_window {
+            return DateResult::None;
+        }
+
+        let before = config.clone();
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Форматы даты"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(tr!("Отмеченный формат вставляется по F5 и подставляется вместо {{date}} в сниппетах"));
+                ui.separator();
+
+                let mut remove = None;
+                egui::Grid::new("date_formats_grid").num_columns(4).show(ui, |ui| {
+                    for (index, format) in config.date_formats.iter_mut().enumerate() {
+                        let selected = *format == config.date_format;
+                        if ui.radio(selected, "").clicked() {
+                            config.date_format = format.clone();
+                        }
+                        let response = ui.add(egui::TextEdit::singleline(format).desired_width(140.0));
+                        // Формат по умолчанию хранится строкой — правим его вместе с записью в списке
+                        if response.changed() && selected {
+                            config.date_format = format.clone();
+                        }
+                        ui.label(format_date(format, config.language));
+                        if ui.small_button("✖").on_hover_text(tr!("Удалить")).clicked() {
+                            remove = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(index) = remove {
+                    config.date_formats.remove(index);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Добавить")).clicked() {
+                        config.date_formats.push("%d.%m.%Y".to_string());
+                    }
+                    if ui.button(tr!("По умолчанию")).clicked() {
+                        config.date_formats = default_date_formats();
+                        config.date_format = config.date_formats[0].clone();
+                    }
+                });
+                ui.separator();
+                ui.small(tr!(
+                    "%d — день, %m — месяц, %Y — год, %H:%M — время, %B — «октября», %L — «октябрь», %b — «окт», %A — день недели, %a — «пт»"
+                ));
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+        if *config != before {
+            DateResult::ConfigChanged
+        } else {
+            DateResult::None
+        }
+    }
+}
@@ -0,0 +1,216 @@
+// diff_module.rs
+use eframe::egui;
+use similar::{ChangeTag, DiffOp, TextDiff};
+
+use crate::i18n::tr;
+
+// Сколько неизмененных строк оставлять вокруг правок в режиме «Только изменения»
+const CONTEXT_LINES: usize = 3;
+const INSERT_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(40, 110, 40, 90);
+const DELETE_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(130, 40, 40, 90);
+
+// Состояние строки нового текста относительно старого — для отметок на полях
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LineStatus {
+    Added,
+    Modified,
+    Deleted, // Перед этой строкой были удалены строки
+}
+
+pub fn line_statuses(old: &str, new: &str) -> Vec<(usize, LineStatus)> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut statuses = Vec::new();
+    for op in diff.ops() {
+        match *op {
+            DiffOp::Equal { .. } => {}
+            DiffOp::Insert { new_index, new_len, .. } => {
+                statuses.extend((new_index..new_index + new_len).map(|line| (line, LineStatus::Added)));
+            }
+            DiffOp::Delete { new_index, .. } => statuses.push((new_index, LineStatus::Deleted)),
+            DiffOp::Replace { new_index, new_len, .. } => {
+                statuses.extend((new_index..new_index + new_len).map(|line| (line, LineStatus::Modified)));
+            }
+        }
+    }
+    statuses
+}
+
+// Строка построчного сравнения; номера строк с нуля
+pub struct DiffLine {
+    pub tag: ChangeTag,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            tag: change.tag(),
+            old_line: change.old_index(),
+            new_line: change.new_index(),
+            text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+        })
+        .collect()
+}
+
+// Что показывать в списке: строку сравнения или свернутый кусок без изменений
+#[derive(Clone, Copy)]
+enum Row {
+    Line(usize),
+    Skipped(usize), // Сколько строк свернуто
+}
+
+#[derive(Default)]
+pub struct DiffModule {
+    pub show_window: bool,
+    title: String,
+    old_label: String,
+    new_label: String,
+    lines: Vec<DiffLine>,
+    rows: Vec<Row>,
+    changes: Vec<usize>, // Индексы в rows, с которых начинаются блоки правок
+    current: usize,
+    changes_only: bool,
+    scroll_to_current: bool,
+}
+
+impl DiffModule {
+    // old_label и new_label — подписи сравниваемых сторон («На диске», «В редакторе»)
+    pub fn open(&mut self, title: &str, old_label: &str, new_label: &str, old: &str, new: &str) {
+        self.show_window = true;
+        self.title = title.to_string();
+        self.old_label = old_label.to_string();
+        self.new_label = new_label.to_string();
+        self.lines = diff_lines(old, new);
+        self.current = 0;
+        self.rebuild_rows();
+        self.scroll_to_current = !self.changes.is_empty();
+    }
+
+    fn rebuild_rows(&mut self) {
+        self.rows.clear();
+        self.changes.clear();
+
+        let changed: Vec<bool> = self.lines.iter().map(|l| l.tag != ChangeTag::Equal).collect();
+        let near_change = |index: usize| {
+            let from = index.saturating_sub(CONTEXT_LINES);
+            let to = (index + CONTEXT_LINES + 1).min(changed.len());
+            changed[from..to].iter().any(|&c| c)
+        };
+
+        let mut skipped = 0;
+        for index in 0..self.lines.len() {
+            if self.changes_only && !near_change(index) {
+                skipped += 1;
+                continue;
+            }
+            if skipped > 0 {
+                self.rows.push(Row::Skipped(skipped));
+                skipped = 0;
+            }
+            if changed[index] && (index == 0 || !changed[index - 1]) {
+                self.changes.push(self.rows.len());
+            }
+            self.rows.push(Row::Line(index));
+        }
+        if skipped > 0 {
+            self.rows.push(Row::Skipped(skipped));
+        }
+        self.current = self.current.min(self.changes.len().saturating_sub(1));
+    }
+
+    fn go_to_change(&mut self, forward: bool) {
+        if self.changes.is_empty() {
+            return;
+        }
+        let count = self.changes.len();
+        self.current = if forward { (self.current + 1) % count } else { (self.current + count - 1) % count };
+        self.scroll_to_current = true;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) {
+        if !self.show_window {
+            return;
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(self.title.clone())
+            .id(egui::Id::new("diff_window"))
+            .open(&mut open)
+            .default_size([700.0, 500.0])
+            .show(ctx, |ui| {
+                let inserted = self.lines.iter().filter(|l| l.tag == ChangeTag::Insert).count();
+                let deleted = self.lines.iter().filter(|l| l.tag == ChangeTag::Delete).count();
+
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::from_rgb(100, 200, 100), format!("+{}", inserted));
+                    ui.colored_label(egui::Color32::from_rgb(220, 100, 100), format!("−{}", deleted));
+                    ui.label(format!("{} → {}", self.old_label, self.new_label));
+                    ui.separator();
+                    if ui.checkbox(&mut self.changes_only, tr!("Только изменения")).changed() {
+                        self.rebuild_rows();
+                        self.scroll_to_current = true;
+                    }
+                    ui.separator();
+                    let has_changes = !self.changes.is_empty();
+                    if ui.add_enabled(has_changes, egui::Button::new(tr!("⏶ Предыдущее"))).clicked() {
+                        self.go_to_change(false);
+                    }
+                    if ui.add_enabled(has_changes, egui::Button::new(tr!("⏷ Следующее"))).clicked() {
+                        self.go_to_change(true);
+                    }
+                    if has_changes {
+                        ui.label(tr!("{} из {}", self.current + 1, self.changes.len()));
+                    }
+                });
+                ui.separator();
+
+                if self.changes.is_empty() {
+                    ui.label(tr!("Различий нет"));
+                    return;
+                }
+                self.show_rows(ui);
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+    }
+
+    fn show_rows(&mut self, ui: &mut egui::Ui) {
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let spacing = ui.spacing().item_spacing.y;
+        let mut scroll_area = egui::ScrollArea::both().auto_shrink([false; 2]);
+        if std::mem::take(&mut self.scroll_to_current) {
+            let row = self.changes.get(self.current).copied().unwrap_or(0);
+            let offset = (row as f32 * (row_height + spacing) - ui.available_height() / 3.0).max(0.0);
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+
+        let number = |n: Option<usize>| n.map(|n| format!("{:>5}", n + 1)).unwrap_or_else(|| " ".repeat(5));
+        scroll_area.show_rows(ui, row_height, self.rows.len(), |ui, range| {
+            for row in &self.rows[range] {
+                match *row {
+                    Row::Skipped(count) => {
+                        ui.weak(tr!("⋯ без изменений: {} строк", count));
+                    }
+                    Row::Line(index) => {
+                        let line = &self.lines[index];
+                        let (sign, fill) = match line.tag {
+                            ChangeTag::Equal => (" ", egui::Color32::TRANSPARENT),
+                            ChangeTag::Insert => ("+", INSERT_COLOR),
+                            ChangeTag::Delete => ("-", DELETE_COLOR),
+                        };
+                        let text = format!("{} {} {} {}", number(line.old_line), number(line.new_line), sign, line.text);
+                        egui::Frame::none().fill(fill).show(ui, |ui| {
+                            ui.set_min_width(ui.available_width());
+                            ui.add(egui::Label::new(egui::RichText::new(text).monospace()).wrap(false));
+                        });
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,102 @@
+// document_goal_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+// Сколько слов должно быть в документе; хранится в настройках по пути файла
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DocumentGoal {
+    pub path: PathBuf,
+    pub words: usize,
+}
+
+pub fn goal_for(goals: &[DocumentGoal], path: Option<&Path>) -> Option<usize> {
+    let path = path?;
+    goals.iter().find(|goal| goal.path == path).map(|goal| goal.words)
+}
+
+// Цель переезжает вместе с документом при «Сохранить как»
+pub fn rename_goal(goals: &mut [DocumentGoal], from: Option<&Path>, to: &Path) -> bool {
+    let Some(from) = from else {
+        return false;
+    };
+    match goals.iter_mut().find(|goal| goal.path == from) {
+        Some(goal) => {
+            goal.path = to.to_path_buf();
+            true
+        }
+        None => false,
+    }
+}
+
+#[derive(Default)]
+pub struct DocumentGoalModule {
+    pub show_window: bool,
+    words: usize, // Цель в поле ввода, пока не нажали «Задать»
+}
+
+#[derive(PartialEq)]
+pub enum DocumentGoalResult {
+    None,
+    ConfigChanged,
+}
+
+impl DocumentGoalModule {
+    pub fn open(&mut self, path: Option<&Path>, config: &Config) {
+        self.show_window = true;
+        self.words = goal_for(&config.document_goals, path).unwrap_or(1000);
+    }
+
+    pub fn show_window(
+        &mut self,
+        ctx: &egui::Context,
+        path: Option<&Path>,
+        words: usize,
+        config: &mut Config,
+    ) -> DocumentGoalResult {
+        if !self.show_window {
+            return DocumentGoalResult::None;
+        }
+
+        let mut result = DocumentGoalResult::None;
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Цель по словам"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let Some(path) = path else {
+                    ui.label(tr!("Цель запоминается для файла — сначала сохраните документ"));
+                    return;
+                };
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                ui.label(name);
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Слов в документе:"));
+                    ui.add(egui::DragValue::new(&mut self.words).clamp_range(1..=1_000_000).speed(10));
+                });
+                ui.label(tr!("Сейчас: {}", words));
+
+                let current = goal_for(&config.document_goals, Some(path));
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Задать")).clicked() {
+                        config.document_goals.retain(|goal| goal.path != path);
+                        config.document_goals.push(DocumentGoal { path: path.to_path_buf(), words: self.words });
+                        result = DocumentGoalResult::ConfigChanged;
+                    }
+                    if ui.add_enabled(current.is_some(), egui::Button::new(tr!("Убрать цель"))).clicked() {
+                        config.document_goals.retain(|goal| goal.path != path);
+                        result = DocumentGoalResult::ConfigChanged;
+                    }
+                });
+            });
+
+        if !open || result == DocumentGoalResult::ConfigChanged {
+            self.show_window = false;
+        }
+        result
+    }
+}
@@ -0,0 +1,479 @@
+// docx_import.rs
+use docx_rs::{
+    Bold, CommentChild, DocumentChild, Docx, Drawing, DrawingData, FooterChild, HeaderChild, Italic, Paragraph,
+    ParagraphChild, Run, RunChild, Table, TableCellContent, TableChild, TableRowChild,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+// Как переносить документ Word в текст редактора
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct DocxImport {
+    pub ask: bool,      // Перед каждым открытием .docx показывать окно с этими параметрами
+    pub markdown: bool, // Заголовки, выделение и списки — разметкой Markdown, а не простым текстом
+    pub headings: bool, // И в простом тексте заголовки — строками «# …»: по ним строится структура документа
+    pub tables: DocxTables,
+    pub headers: bool,        // Текст колонтитулов — в конце документа
+    pub footnotes: bool,      // Ссылки на сноски — [^n] в тексте, сами сноски — в конце
+    pub comments: bool,       // Примечания рецензентов — цитатой после абзаца, к которому они относятся
+    pub extract_images: bool, // Картинки из документа — файлами в папку рядом с ним
+}
+
+impl Default for DocxImport {
+    fn default() -> Self {
+        Self {
+            ask: false,
+            markdown: false,
+            headings: true,
+            tables: DocxTables::Tabs,
+            headers: false,
+            footnotes: false,
+            comments: false,
+            extract_images: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum DocxTables {
+    #[default]
+    Tabs, // Строка таблицы — строка текста, ячейки через табуляцию: удобно вставлять в электронные таблицы
+    Markdown,
+}
+
+// Абзац или таблица документа (номер в document.children) и их место в тексте редактора, в байтах
+pub struct Block {
+    pub child: usize,
+    pub range: Range<usize>,
+}
+
+pub fn extract_text(bytes: &[u8], options: &DocxImport) -> Result<String, Box<dyn std::error::Error>> {
+    let docx = docx_rs::read_docx(bytes)?;
+    Ok(extract(&docx, options).0)
+}
+
+// Места блоков есть только у простого текста без примечаний, сносок и колонтитулов:
+// лишь такой текст можно разобрать обратно по абзацам при сохранении в .docx
+pub fn extract(docx: &Docx, options: &DocxImport) -> (String, Option<Vec<Block>>) {
+    let importer = Importer { docx, options };
+    let mut blocks = Vec::new();
+
+    let mut text = if options.markdown {
+        importer.markdown()
+    } else {
+        let mut text = String::new();
+        for (index, child) in docx.document.children.iter().enumerate() {
+            let start = text.len();
+            importer.extract_plain(child, &mut text);
+            let range = match child {
+                DocumentChild::Paragraph(_) => start..text.len(),
+                // Пустые строки вокруг таблицы к ней не относятся
+                DocumentChild::Table(_) => start + 1..text.len() - 2,
+                _ => continue,
+            };
+            blocks.push(Block { child: index, range });
+        }
+        text
+    };
+    if options.footnotes {
+        importer.append_footnotes(&mut text);
+    }
+    if options.headers {
+        importer.append_headers(&mut text);
+    }
+
+    let editable = !options.markdown && !options.headers && !options.footnotes && !options.comments;
+    let offset = text.len() - text.trim_start().len();
+    let text = text.trim().to_string();
+    let shift = |position: usize| position.saturating_sub(offset).min(text.len());
+    let blocks = editable.then(|| {
+        blocks
+            .into_iter()
+            .map(|block| Block {
+                child: block.child,
+                range: shift(block.range.start)..shift(block.range.end),
+            })
+            .collect()
+    });
+    (text, blocks)
+}
+
+// Папка для картинок документа: «отчет.docx» → «отчет_images»
+fn images_folder(document: &Path) -> PathBuf {
+    let stem = document.file_stem().unwrap_or_default().to_string_lossy();
+    document.with_file_name(format!("{}_images", stem))
+}
+
+// Сохраняет картинки под теми же именами, что в заглушках; возвращает их число
+pub fn save_images(bytes: &[u8], document: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let docx = docx_rs::read_docx(bytes)?;
+    if docx.images.is_empty() {
+        return Ok(0);
+    }
+    let folder = images_folder(document);
+    fs::create_dir_all(&folder)?;
+    for (_, path, image, _) in &docx.images {
+        fs::write(folder.join(file_name(path)), &image.0)?;
+    }
+    Ok(docx.images.len())
+}
+
+// Картинки лежат в архиве как «media/image1.png»
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+// «[изображение: image1.png]»; надписи (TextBox) пропускаем
+pub fn image_placeholder(docx: &Docx, drawing: &Drawing) -> String {
+    let Some(DrawingData::Pic(pic)) = &drawing.data else {
+        return String::new();
+    };
+    let name = docx
+        .images
+        .iter()
+        .find(|(id, ..)| *id == pic.id)
+        .map_or(pic.id.as_str(), |(_, path, ..)| file_name(path));
+    tr!("[изображение: {}]", name)
+}
+
+struct Importer<'a> {
+    docx: &'a Docx,
+    options: &'a DocxImport,
+}
+
+impl Importer<'_> {
+    // === Простой текст ===
+
+    fn extract_plain(&self, document: &DocumentChild, text: &mut String) {
+        match document {
+            DocumentChild::Paragraph(para) => {
+                if let Some(level) = heading_level(para).filter(|_| self.options.headings) {
+                    text.push_str(&"#".repeat(level));
+                    text.push(' ');
+                }
+                // Пробелы между словами Word хранит в самих прогонах: склеиваем их как есть
+                let mut runs = Vec::new();
+                collect_runs(&para.children, &mut runs);
+                for run in runs {
+                    text.push_str(&self.run_text(run));
+                }
+                text.push('\n');
+                for comment in self.comments(para) {
+                    text.push_str(&comment);
+                    text.push('\n');
+                }
+            }
+            DocumentChild::Table(table) => {
+                text.push('\n');
+                text.push_str(&self.table_text(table));
+                text.push_str("\n\n");
+            }
+            _ => {}
+        }
+    }
+
+    // === Markdown ===
+
+    // Абзацы разделяются пустой строкой, пункты одного списка идут подряд
+    fn markdown(&self) -> String {
+        let mut text = String::new();
+        let mut previous_list = false;
+        for child in &self.docx.document.children {
+            let (block, list) = match child {
+                DocumentChild::Paragraph(para) => {
+                    let mut block = self.paragraph_markdown(para);
+                    let comments = self.comments(para);
+                    if block.trim().is_empty() && comments.is_empty() {
+                        continue;
+                    }
+                    for comment in comments {
+                        block.push_str("\n\n");
+                        block.push_str(&comment);
+                    }
+                    (block, list_level(para).is_some())
+                }
+                DocumentChild::Table(table) => (self.table_text(table), false),
+                _ => continue,
+            };
+            if !text.is_empty() {
+                text.push_str(if list && previous_list { "\n" } else { "\n\n" });
+            }
+            text.push_str(&block);
+            previous_list = list;
+        }
+        text
+    }
+
+    fn paragraph_markdown(&self, para: &Paragraph) -> String {
+        let mut runs = Vec::new();
+        collect_runs(&para.children, &mut runs);
+        let body = self.runs_markdown(&runs);
+        if let Some(level) = heading_level(para) {
+            format!("{} {}", "#".repeat(level), body.replace('\n', " "))
+        } else if let Some(level) = list_level(para) {
+            format!("{}- {}", "  ".repeat(level), body)
+        } else {
+            body
+        }
+    }
+
+    fn run_text(&self, run: &Run) -> String {
+        let mut text = String::new();
+        for child in &run.children {
+            match child {
+                RunChild::Text(t) => text.push_str(&t.text),
+                RunChild::Break(_) => text.push('\n'),
+                RunChild::Tab(_) => text.push('\t'),
+                RunChild::Drawing(drawing) => text.push_str(&self.image_placeholder(drawing)),
+                RunChild::FootnoteReference(reference) if self.options.footnotes => {
+                    text.push_str(&format!("[^{}]", reference.id));
+                }
+                _ => {}
+            }
+        }
+        text
+    }
+
+    // Соседние прогоны с одинаковым оформлением склеиваются, иначе слово, разбитое Word на
+    // несколько прогонов, получило бы лишние звездочки посередине
+    fn runs_markdown(&self, runs: &[&Run]) -> String {
+        let mut spans: Vec<(String, bool, bool)> = Vec::new();
+        for run in runs {
+            let bold = run.run_property.bold.as_ref().is_some_and(|b| *b == Bold::new());
+            let italic = run.run_property.italic.as_ref().is_some_and(|i| *i == Italic::new());
+            let text = self.run_text(run);
+            match spans.last_mut() {
+                Some(last) if last.1 == bold && last.2 == italic => last.0.push_str(&text),
+                _ => spans.push((text, bold, italic)),
+            }
+        }
+        spans
+            .iter()
+            .map(|(text, bold, italic)| {
+                let marker = match (bold, italic) {
+                    (true, true) => "***",
+                    (true, false) => "**",
+                    (false, true) => "*",
+                    (false, false) => "",
+                };
+                emphasize(text, marker)
+            })
+            .collect()
+    }
+
+    // Абзац одной строкой — для ячеек, сносок, примечаний и колонтитулов.
+    // С выделением, если весь документ переносится в Markdown
+    fn inline_text(&self, para: &Paragraph) -> String {
+        let mut runs = Vec::new();
+        collect_runs(&para.children, &mut runs);
+        let text: String = if self.options.markdown {
+            self.runs_markdown(&runs)
+        } else {
+            runs.iter().map(|run| self.run_text(run)).collect()
+        };
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    // === Таблицы ===
+
+    // Текст ячейки одной строкой: абзацы через пробел, вложенные таблицы — текстом их ячеек
+    fn cell_text(&self, children: &[TableCellContent]) -> String {
+        let mut parts = Vec::new();
+        for child in children {
+            match child {
+                TableCellContent::Paragraph(para) => parts.push(self.inline_text(para)),
+                TableCellContent::Table(table) => parts.push(self.table_inline(table)),
+                _ => {}
+            }
+        }
+        parts.retain(|part| !part.is_empty());
+        parts.join(" ")
+    }
+
+    fn table_rows(&self, table: &Table) -> Vec<Vec<String>> {
+        table
+            .rows
+            .iter()
+            .map(|TableChild::TableRow(row)| {
+                row.cells
+                    .iter()
+                    .map(|TableRowChild::TableCell(cell)| self.cell_text(&cell.children))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn table_text(&self, table: &Table) -> String {
+        let rows = self.table_rows(table);
+        match self.options.tables {
+            DocxTables::Tabs => rows.iter().map(|row| row.join("\t")).collect::<Vec<_>>().join("\n"),
+            DocxTables::Markdown => table_markdown(&rows),
+        }
+    }
+
+    // Таблица внутри ячейки, примечания или колонтитула — все ячейки через пробел
+    fn table_inline(&self, table: &Table) -> String {
+        let mut cells = self.table_rows(table).concat();
+        cells.retain(|cell| !cell.is_empty());
+        cells.join(" ")
+    }
+
+    // === Картинки ===
+
+    fn image_placeholder(&self, drawing: &Drawing) -> String {
+        image_placeholder(self.docx, drawing)
+    }
+
+    // === Колонтитулы, сноски, примечания ===
+
+    // Примечания, начатые в этом абзаце, — цитатами «> Примечание (автор, дата): текст»
+    fn comments(&self, para: &Paragraph) -> Vec<String> {
+        if !self.options.comments {
+            return Vec::new();
+        }
+        para.children
+            .iter()
+            .filter_map(|child| match child {
+                ParagraphChild::CommentStart(start) => Some(start.id),
+                _ => None,
+            })
+            .filter_map(|id| self.docx.comments.comments.iter().find(|comment| comment.id == id))
+            .map(|comment| {
+                let body: Vec<String> = comment
+                    .children
+                    .iter()
+                    .map(|child| match child {
+                        CommentChild::Paragraph(para) => self.inline_text(para),
+                        CommentChild::Table(table) => self.table_inline(table),
+                    })
+                    .filter(|part| !part.is_empty())
+                    .collect();
+                // Дата в документе — «2024-03-01T10:15:00Z», время не нужно
+                let date = comment.date.split('T').next().unwrap_or_default();
+                let who: Vec<&str> = [comment.author.as_str(), date]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                tr!("> Примечание ({}): {}", who.join(", "), body.join(" "))
+            })
+            .collect()
+    }
+
+    // Служебные сноски-разделители пустые и пропускаются
+    fn append_footnotes(&self, text: &mut String) {
+        let notes: Vec<String> = self
+            .docx
+            .footnotes
+            .footnotes
+            .iter()
+            .filter_map(|footnote| {
+                let body: Vec<String> = footnote.content.iter().map(|para| self.inline_text(para)).collect();
+                let body = body.join(" ");
+                (!body.trim().is_empty()).then(|| format!("[^{}]: {}", footnote.id, body.trim()))
+            })
+            .collect();
+        if !notes.is_empty() {
+            text.push_str("\n\n");
+            text.push_str(&notes.join("\n"));
+        }
+    }
+
+    fn append_headers(&self, text: &mut String) {
+        let section = &self.docx.document.section_property;
+        let header: Vec<String> = section
+            .header
+            .iter()
+            .flat_map(|(_, header)| &header.children)
+            .map(|child| match child {
+                HeaderChild::Paragraph(para) => self.inline_text(para),
+                HeaderChild::Table(table) => self.table_inline(table),
+            })
+            .collect();
+        let footer: Vec<String> = section
+            .footer
+            .iter()
+            .flat_map(|(_, footer)| &footer.children)
+            .map(|child| match child {
+                FooterChild::Paragraph(para) => self.inline_text(para),
+                FooterChild::Table(table) => self.table_inline(table),
+            })
+            .collect();
+        for (title, mut lines) in [
+            (tr!("Верхний колонтитул:"), header),
+            (tr!("Нижний колонтитул:"), footer),
+        ] {
+            lines.retain(|line| !line.is_empty());
+            if !lines.is_empty() {
+                text.push_str(&format!("\n\n{}\n{}", title, lines.join("\n")));
+            }
+        }
+    }
+}
+
+// Идентификаторы встроенных стилей заголовков: Heading1 … Heading6 в английском Word,
+// 1 … 6 в русском, Заголовок1 … в документах из других редакторов. «Название» (Title) — первый уровень
+pub fn heading_level(para: &Paragraph) -> Option<usize> {
+    let style = para.property.style.as_ref()?.val.as_str();
+    if style.eq_ignore_ascii_case("Title") {
+        return Some(1);
+    }
+    let number = ["Heading", "heading", "Заголовок", "заголовок"]
+        .iter()
+        .find_map(|prefix| style.strip_prefix(prefix))
+        .unwrap_or(style);
+    let level: usize = number.trim().parse().ok()?;
+    (1..=6).contains(&level).then_some(level)
+}
+
+// Уровень вложенности пункта списка; нумерация с id 0 в Word означает «без списка»
+fn list_level(para: &Paragraph) -> Option<usize> {
+    let numbering = para.property.numbering_property.as_ref()?;
+    if numbering.id.as_ref().is_some_and(|id| id.id == 0) {
+        return None;
+    }
+    Some(numbering.level.as_ref().map_or(0, |level| level.val))
+}
+
+// Текст ссылок тоже нужен: его прогоны лежат внутри Hyperlink
+fn collect_runs<'a>(children: &'a [ParagraphChild], runs: &mut Vec<&'a Run>) {
+    for child in children {
+        match child {
+            ParagraphChild::Run(run) => runs.push(run),
+            ParagraphChild::Hyperlink(link) => collect_runs(&link.children, runs),
+            _ => {}
+        }
+    }
+}
+
+// Пробелы по краям остаются снаружи звездочек: «** слово**» Markdown не распознает
+fn emphasize(text: &str, marker: &str) -> String {
+    let inner = text.trim();
+    if marker.is_empty() || inner.is_empty() {
+        return text.to_string();
+    }
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    format!("{}{}{}{}{}", leading, marker, inner, marker, trailing)
+}
+
+// Первая строка таблицы Word становится заголовком: без него Markdown таблицу не признает
+fn table_markdown(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| cells.get(i).map_or(String::new(), |cell| cell.replace('|', "\\|")))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let mut lines = vec![line(&rows[0]), line(&vec!["---".to_string(); columns])];
+    lines.extend(rows[1..].iter().map(|row| line(row)));
+    lines.join("\n")
+}
@@ -0,0 +1,100 @@
+// docx_module.rs
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+use crate::docx_import::{DocxImport, DocxTables};
+use crate::docx_roundtrip::DocxDocument;
+use crate::i18n::tr;
+
+// Окно параметров импорта перед открытием .docx; выбранное запоминается в настройках
+#[derive(Default)]
+pub struct DocxModule {
+    path: Option<PathBuf>,
+    options: DocxImport,
+    current: Option<(PathBuf, DocxDocument)>, // Открытый .docx — для сохранения обратно с прежним оформлением
+}
+
+pub enum DocxResult {
+    None,
+    Open(PathBuf, DocxImport),
+}
+
+// Те же флажки показываются в настройках
+pub fn options_ui(ui: &mut egui::Ui, options: &mut DocxImport) {
+    ui.vertical(|ui| {
+        ui.checkbox(&mut options.markdown, tr!("открывать с разметкой Markdown"))
+            .on_hover_text(tr!("Заголовки, полужирный и курсив, списки сохраняют структуру"));
+        ui.add_enabled(
+            !options.markdown,
+            egui::Checkbox::new(&mut options.headings, tr!("заголовки отмечать знаками #")),
+        )
+        .on_hover_text(tr!("По ним строится структура документа (Вид → Структура документа)"));
+        ui.horizontal(|ui| {
+            ui.label(tr!("Таблицы:"));
+            ui.selectable_value(&mut options.tables, DocxTables::Tabs, tr!("через табуляцию"));
+            ui.selectable_value(&mut options.tables, DocxTables::Markdown, "Markdown");
+        });
+        ui.checkbox(&mut options.headers, tr!("колонтитулы в конце текста"));
+        ui.checkbox(&mut options.footnotes, tr!("сноски: [^1] в тексте, пояснения в конце"));
+        ui.checkbox(
+            &mut options.comments,
+            tr!("примечания рецензентов цитатами после абзаца"),
+        );
+        ui.checkbox(
+            &mut options.extract_images,
+            tr!("сохранять картинки в папку рядом с документом"),
+        )
+        .on_hover_text(tr!("В тексте на их месте — [изображение: имя файла]"));
+        ui.checkbox(&mut options.ask, tr!("спрашивать при каждом открытии .docx"));
+    });
+}
+
+impl DocxModule {
+    pub fn document_for(&mut self, path: &Path) -> Option<&mut DocxDocument> {
+        self.current
+            .as_mut()
+            .filter(|(current, _)| current == path)
+            .map(|(_, document)| document)
+    }
+
+    pub fn set_current(&mut self, path: &Path, document: Option<DocxDocument>) {
+        self.current = document.map(|document| (path.to_path_buf(), document));
+    }
+
+    pub fn ask_to_open(&mut self, path: &Path, options: &DocxImport) {
+        self.path = Some(path.to_path_buf());
+        self.options = options.clone();
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> DocxResult {
+        let mut result = DocxResult::None;
+        let Some(path) = &self.path else {
+            return result;
+        };
+
+        let mut open = true;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        egui::Window::new(tr!("Открыть документ Word"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(name);
+                ui.separator();
+                options_ui(ui, &mut self.options);
+                ui.separator();
+                if ui.button(tr!("Открыть")).clicked() {
+                    result = DocxResult::Open(path.clone(), self.options.clone());
+                }
+            });
+
+        if !open || matches!(result, DocxResult::Open(..)) {
+            self.path = None;
+        }
+        result
+    }
+}
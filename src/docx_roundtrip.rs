@@ -0,0 +1,565 @@
+// docx_roundtrip.rs
+use docx_rs::{
+    BuildXML, DocumentChild, Docx, Paragraph, ParagraphChild, Run, RunChild, RunProperty, Table, TableCellContent,
+    TableChild, TableRowChild,
+};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use similar::{DiffOp, TextDiff};
+use std::io::{Cursor, Read};
+use std::ops::Range;
+
+use crate::archive_module::replace_zip_entry;
+use crate::docx_import::{extract, heading_level, image_placeholder, Block, DocxImport, DocxTables};
+use crate::i18n::tr;
+
+const DOCUMENT_XML: &str = "word/document.xml";
+const W14_NAMESPACE: &str = "http://schemas.microsoft.com/office/word/2010/wordml";
+
+// Разобранный .docx рядом с текстом редактора. При сохранении в word/document.xml заменяются только абзацы,
+// строки которых правили; остальные байты документа и все прочие части архива остаются как были.
+// Заново через docx-rs проходят лишь измененные абзацы: то, чего он не знает, терялось бы при полной пересборке
+pub struct DocxDocument {
+    bytes: Vec<u8>, // Файл, каким его прочитали или записали последний раз
+    docx: Docx,
+    options: DocxImport,
+    text: String, // Текст, каким его показали при открытии или после последнего сохранения
+    blocks: Vec<Block>,
+}
+
+// Новые строки блока; changed — строки блока правили, и его нужно собрать заново
+#[derive(Default, Clone)]
+struct Edited {
+    changed: bool,
+    lines: Vec<String>,
+}
+
+// Что встает на место абзаца или таблицы: он сам, как лежал в файле, или заново собранный элемент
+enum Piece {
+    Original,
+    New(DocumentChild),
+}
+
+// Новое содержимое для каждого блока и абзацы в конец тела документа, если блоков нет
+struct Patch {
+    blocks: Vec<Vec<Piece>>,
+    tail: Vec<DocumentChild>,
+}
+
+// Абзацы и таблицы прямо в <w:body> и место, куда дописывать новые абзацы: перед <w:sectPr> или </w:body>
+struct BodyLayout {
+    elements: Vec<Range<usize>>,
+    end: usize,
+}
+
+impl DocxDocument {
+    // Документ None — текст с разметкой Markdown или с добавками, обратно по абзацам его не разобрать
+    pub fn open(
+        bytes: &[u8],
+        options: &DocxImport,
+    ) -> Result<(String, Option<DocxDocument>), Box<dyn std::error::Error>> {
+        let docx = docx_rs::read_docx(bytes)?;
+        let (text, blocks) = extract(&docx, options);
+        let document = blocks.map(|blocks| DocxDocument {
+            bytes: bytes.to_vec(),
+            docx,
+            options: options.clone(),
+            text: text.clone(),
+            blocks,
+        });
+        Ok((text, document))
+    }
+
+    pub fn save(&mut self, text: &str) -> Result<Vec<u8>, String> {
+        let old_lines: Vec<&str> = self.text.split('\n').collect();
+        let new_lines: Vec<&str> = text.split('\n').collect();
+
+        // Строка принадлежит блоку, в котором начинается; пустые строки вокруг таблиц ничьи.
+        // preceding — сколько блоков начинается не позже строки: после них встают вставленные абзацы
+        let mut owners = Vec::with_capacity(old_lines.len());
+        let mut preceding = Vec::with_capacity(old_lines.len());
+        let mut old_by_block = vec![Vec::new(); self.blocks.len()];
+        let mut start = 0;
+        for line in &old_lines {
+            let index = self.blocks.partition_point(|block| block.range.end <= start);
+            let owner = self
+                .blocks
+                .get(index)
+                .filter(|block| block.range.contains(&start))
+                .map(|_| index);
+            if let Some(owner) = owner {
+                old_by_block[owner].push(*line);
+            }
+            owners.push(owner);
+            preceding.push(self.blocks.partition_point(|block| block.range.start <= start));
+            start += line.len() + 1;
+        }
+
+        let mut edited = vec![Edited::default(); self.blocks.len()];
+        // inserted[0] — абзацы перед первым блоком, inserted[i + 1] — после блока i
+        let mut inserted: Vec<Vec<String>> = vec![Vec::new(); self.blocks.len() + 1];
+        let is_paragraph = |block: usize| {
+            matches!(
+                self.docx.document.children[self.blocks[block].child],
+                DocumentChild::Paragraph(_)
+            )
+        };
+        // Вставка перед старой строкой old_index: продолжение абзаца выше или новые абзацы после блока
+        let mut insert = |old_index: usize, lines: &[&str], edited: &mut Vec<Edited>| {
+            let previous = old_index.checked_sub(1);
+            let lines = lines.iter().map(|line| line.to_string());
+            match previous
+                .and_then(|line| owners[line])
+                .filter(|&block| is_paragraph(block))
+            {
+                Some(block) => {
+                    edited[block].changed = true;
+                    edited[block].lines.extend(lines);
+                }
+                None => inserted[previous.map_or(0, |line| preceding[line])].extend(lines),
+            }
+        };
+
+        for op in TextDiff::from_slices(&old_lines, &new_lines).ops() {
+            match *op {
+                DiffOp::Equal {
+                    old_index,
+                    new_index,
+                    len,
+                } => {
+                    for k in 0..len {
+                        if let Some(block) = owners[old_index + k] {
+                            edited[block].lines.push(new_lines[new_index + k].to_string());
+                        }
+                    }
+                }
+                DiffOp::Delete { old_index, old_len, .. } => {
+                    for block in owners[old_index..old_index + old_len].iter().flatten() {
+                        edited[*block].changed = true;
+                    }
+                }
+                DiffOp::Insert {
+                    old_index,
+                    new_index,
+                    new_len,
+                } => {
+                    insert(old_index, &new_lines[new_index..new_index + new_len], &mut edited);
+                }
+                DiffOp::Replace {
+                    old_index,
+                    old_len,
+                    new_index,
+                    new_len,
+                } => {
+                    let replaced = &owners[old_index..old_index + old_len];
+                    for block in replaced.iter().flatten() {
+                        edited[*block].changed = true;
+                    }
+                    let lines = &new_lines[new_index..new_index + new_len];
+                    // Новый текст достается первому затронутому абзацу (или таблице, если правили ее строки)
+                    match replaced.iter().flatten().next() {
+                        Some(&block) => edited[block].lines.extend(lines.iter().map(|line| line.to_string())),
+                        None => insert(old_index, lines, &mut edited),
+                    }
+                }
+            }
+        }
+
+        let patch = self.rebuild(&edited, &inserted, &old_by_block)?;
+        let xml = self.patch_document_xml(patch)?;
+        let bytes = replace_zip_entry(Cursor::new(&self.bytes), DOCUMENT_XML, &xml)
+            .map_err(|e| tr!("Ошибка записи DOCX файла: {}", e))?;
+
+        // Следующее сохранение сравниваем уже с записанным документом
+        if let Ok((_, Some(document))) = DocxDocument::open(&bytes, &self.options) {
+            *self = document;
+        }
+        Ok(bytes)
+    }
+
+    // Новый word/document.xml: байты исходного файла, в которые вставлены только пересобранные элементы
+    fn patch_document_xml(&self, patch: Patch) -> Result<Vec<u8>, String> {
+        let mut xml = Vec::new();
+        zip::ZipArchive::new(Cursor::new(&self.bytes))
+            .and_then(|mut archive| archive.by_name(DOCUMENT_XML)?.read_to_end(&mut xml).map_err(Into::into))
+            .map_err(|e| tr!("Ошибка чтения DOCX файла: {}", e))?;
+        let layout = body_layout(&xml)?;
+
+        // k-й абзац или таблица тела в XML — k-й Paragraph или Table в document.children
+        let mut elements = layout.elements.into_iter();
+        let ranges: Vec<Option<Range<usize>>> = self
+            .docx
+            .document
+            .children
+            .iter()
+            .map(|child| match child {
+                DocumentChild::Paragraph(_) | DocumentChild::Table(_) => elements.next(),
+                _ => None,
+            })
+            .collect();
+        let mismatch =
+            || tr!("Не удалось сопоставить абзацы документа с файлом — сохраните его под другим именем").to_string();
+        if elements.next().is_some() {
+            return Err(mismatch());
+        }
+
+        let mut edits = Vec::new();
+        for (block, pieces) in self.blocks.iter().zip(patch.blocks) {
+            if matches!(pieces.as_slice(), [Piece::Original]) {
+                continue;
+            }
+            let range = ranges[block.child].clone().ok_or_else(mismatch)?;
+            let mut replacement = Vec::new();
+            for piece in pieces {
+                match piece {
+                    Piece::Original => replacement.extend_from_slice(&xml[range.clone()]),
+                    Piece::New(child) => replacement.extend(build_child(&child)),
+                }
+            }
+            edits.push((range, replacement));
+        }
+        if !patch.tail.is_empty() {
+            edits.push((layout.end..layout.end, patch.tail.iter().flat_map(build_child).collect()));
+        }
+        if edits.is_empty() {
+            return Ok(xml);
+        }
+        Ok(declare_w14(splice(&xml, edits)))
+    }
+
+    fn rebuild(
+        &self,
+        edited: &[Edited],
+        inserted: &[Vec<String>],
+        old_by_block: &[Vec<&str>],
+    ) -> Result<Patch, String> {
+        let plain = |lines: &[String], template: Option<&Paragraph>| -> Vec<DocumentChild> {
+            // Новые абзацы берут оформление абзаца выше, если это не заголовок
+            let template = template.filter(|para| heading_level(para).is_none());
+            lines
+                .iter()
+                .map(|line| DocumentChild::Paragraph(Box::new(new_paragraph(template, line.trim_end()))))
+                .collect()
+        };
+        let paragraph_at = |block: usize| match &self.docx.document.children[self.blocks[block].child] {
+            DocumentChild::Paragraph(para) => Some(para.as_ref()),
+            _ => None,
+        };
+
+        if self.blocks.is_empty() {
+            return Ok(Patch { blocks: Vec::new(), tail: plain(&inserted[0], None) });
+        }
+        let mut blocks = Vec::with_capacity(self.blocks.len());
+        for (block, edit) in edited.iter().enumerate() {
+            let mut pieces: Vec<Piece> = Vec::new();
+            if block == 0 {
+                pieces.extend(plain(&inserted[0], None).into_iter().map(Piece::New));
+            }
+            match &self.docx.document.children[self.blocks[block].child] {
+                _ if !edit.changed => pieces.push(Piece::Original),
+                DocumentChild::Paragraph(para) => pieces.extend(
+                    self.rewrite_paragraph(para, &edit.lines)
+                        .into_iter()
+                        .map(|para| Piece::New(DocumentChild::Paragraph(Box::new(para)))),
+                ),
+                DocumentChild::Table(table) => pieces.push(Piece::New(DocumentChild::Table(Box::new(
+                    self.patch_table(table, &old_by_block[block], &edit.lines)?,
+                )))),
+                _ => pieces.push(Piece::Original),
+            }
+            pieces.extend(plain(&inserted[block + 1], paragraph_at(block)).into_iter().map(Piece::New));
+            blocks.push(pieces);
+        }
+        Ok(Patch { blocks, tail: Vec::new() })
+    }
+
+    // Каждая строка — отдельный абзац: Enter внутри абзаца делит его на два, как в Word.
+    // Картинка остается в той строке, где осталась ее заглушка «[изображение: …]»
+    fn rewrite_paragraph(&self, para: &Paragraph, lines: &[String]) -> Vec<Paragraph> {
+        let heading = heading_level(para).filter(|_| self.options.headings);
+        let mut drawings: Vec<(String, &ParagraphChild)> = para
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                ParagraphChild::Run(run) => run.children.iter().find_map(|run_child| match run_child {
+                    RunChild::Drawing(drawing) => Some((image_placeholder(&self.docx, drawing), child)),
+                    _ => None,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut text = line.as_str();
+                if let Some(level) = heading.filter(|_| index == 0) {
+                    let marker = format!("{} ", "#".repeat(level));
+                    text = text.strip_prefix(&marker).unwrap_or(text);
+                }
+                let mut text = text.to_string();
+                let mut images = Vec::new();
+                drawings.retain(|(placeholder, child)| {
+                    if !placeholder.is_empty() && text.contains(placeholder.as_str()) {
+                        text = text.replacen(placeholder.as_str(), "", 1);
+                        images.push((*child).clone());
+                        false
+                    } else {
+                        true
+                    }
+                });
+                // Продолжение заголовка на новой строке — уже обычный текст
+                let template = (index == 0 || heading.is_none()).then_some(para);
+                let mut paragraph = new_paragraph(template, text.trim_end());
+                paragraph.children.extend(images);
+                paragraph
+            })
+            .collect()
+    }
+
+    // Таблицу можно править только внутри ячеек: число строк и ячеек должно остаться прежним
+    fn patch_table(&self, table: &Table, old: &[&str], new: &[String]) -> Result<Table, String> {
+        let rows = |lines: Vec<&str>| -> Vec<Vec<String>> {
+            match self.options.tables {
+                DocxTables::Tabs => lines
+                    .iter()
+                    .map(|line| line.split('\t').map(str::to_string).collect())
+                    .collect(),
+                // Вторая строка Markdown-таблицы — разделитель под заголовком
+                DocxTables::Markdown => lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| *index != 1)
+                    .map(|(_, line)| markdown_cells(line))
+                    .collect(),
+            }
+        };
+        let old = rows(old.to_vec());
+        let new = rows(new.iter().map(String::as_str).collect());
+        let same_shape = old.len() == new.len()
+            && table.rows.len() == new.len()
+            && table
+                .rows
+                .iter()
+                .zip(&new)
+                .all(|(TableChild::TableRow(row), cells)| row.cells.len() == cells.len());
+        if !same_shape {
+            return Err(
+                tr!("В таблицах документа Word можно менять только текст ячеек, но не число строк и столбцов")
+                    .to_string(),
+            );
+        }
+
+        let mut table = table.clone();
+        for ((TableChild::TableRow(row), old), new) in table.rows.iter_mut().zip(&old).zip(&new) {
+            for ((TableRowChild::TableCell(cell), old), new) in row.cells.iter_mut().zip(old).zip(new) {
+                if old.trim() == new.trim() {
+                    continue;
+                }
+                let template = cell.children.iter().find_map(|content| match content {
+                    TableCellContent::Paragraph(para) => Some(para.clone()),
+                    _ => None,
+                });
+                let paragraph = new_paragraph(template.as_ref(), new.trim());
+                cell.children = vec![TableCellContent::Paragraph(paragraph)];
+            }
+        }
+        Ok(table)
+    }
+}
+
+fn build_child(child: &DocumentChild) -> Vec<u8> {
+    match child {
+        DocumentChild::Paragraph(para) => para.build(),
+        DocumentChild::Table(table) => table.build(),
+        _ => Vec::new(),
+    }
+}
+
+fn body_layout(xml: &[u8]) -> Result<BodyLayout, String> {
+    let error = |e: &dyn std::fmt::Display| tr!("Ошибка чтения DOCX файла: {}", e);
+    let mut reader = Reader::from_reader(xml);
+    let mut elements = Vec::new();
+    let mut depth = 0;
+    let mut body_depth = None;
+    let mut element_start = 0;
+    loop {
+        let before = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| error(&e))?;
+        let after = reader.buffer_position() as usize;
+        let in_body = body_depth.is_some_and(|body| depth == body + 1);
+        match event {
+            Event::Start(start) => {
+                let name = start.local_name();
+                if body_depth.is_none() && name.as_ref() == b"body" {
+                    body_depth = Some(depth);
+                } else if in_body && name.as_ref() == b"sectPr" {
+                    return Ok(BodyLayout { elements, end: before });
+                } else if in_body {
+                    element_start = before;
+                }
+                depth += 1;
+            }
+            Event::End(end) => {
+                depth -= 1;
+                let in_body = body_depth.is_some_and(|body| depth == body + 1);
+                let name = end.local_name();
+                if body_depth == Some(depth) {
+                    return Ok(BodyLayout { elements, end: before });
+                } else if in_body && matches!(name.as_ref(), b"p" | b"tbl") {
+                    elements.push(element_start..after);
+                }
+            }
+            Event::Empty(empty) if in_body => match empty.local_name().as_ref() {
+                b"p" | b"tbl" => elements.push(before..after),
+                b"sectPr" => return Ok(BodyLayout { elements, end: before }),
+                _ => {}
+            },
+            Event::Eof => return Err(error(&"w:body")),
+            _ => {}
+        }
+    }
+}
+
+// Замена непересекающихся участков, идущих по порядку
+fn splice(xml: &[u8], edits: Vec<(Range<usize>, Vec<u8>)>) -> Vec<u8> {
+    let mut result = Vec::with_capacity(xml.len());
+    let mut copied = 0;
+    for (range, replacement) in edits {
+        result.extend_from_slice(&xml[copied..range.start]);
+        result.extend(replacement);
+        copied = range.end;
+    }
+    result.extend_from_slice(&xml[copied..]);
+    result
+}
+
+// docx-rs помечает абзацы атрибутом w14:paraId; в старых файлах это пространство имен не объявлено
+fn declare_w14(mut xml: Vec<u8>) -> Vec<u8> {
+    let declared = xml.windows(b"xmlns:w14=".len()).any(|window| window == b"xmlns:w14=");
+    let root = xml.windows(b"<w:document".len()).position(|window| window == b"<w:document");
+    if let Some(root) = root.filter(|_| !declared) {
+        let at = root + b"<w:document".len();
+        let declaration = format!(" xmlns:w14=\"{}\"", W14_NAMESPACE);
+        xml.splice(at..at, declaration.into_bytes());
+    }
+    xml
+}
+
+fn first_run_property(para: &Paragraph) -> Option<&RunProperty> {
+    para.children.iter().find_map(|child| match child {
+        ParagraphChild::Run(run) if run.children.iter().any(|c| matches!(c, RunChild::Text(_))) => {
+            Some(&run.run_property)
+        }
+        _ => None,
+    })
+}
+
+// Абзац с одним прогоном текста; оформление абзаца и первого прогона — от образца
+fn new_paragraph(template: Option<&Paragraph>, text: &str) -> Paragraph {
+    let mut run = Run::new();
+    for (index, part) in text.split('\t').enumerate() {
+        if index > 0 {
+            run = run.add_tab();
+        }
+        run = run.add_text(part);
+    }
+    let mut paragraph = Paragraph::new();
+    if let Some(template) = template {
+        paragraph.property = template.property.clone();
+        paragraph.has_numbering = template.has_numbering;
+        if let Some(property) = first_run_property(template) {
+            run.run_property = property.clone();
+        }
+    }
+    paragraph.add_run(run)
+}
+
+// «| a | b \| c |» → ["a", "b | c"]
+fn markdown_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line
+        .strip_suffix('|')
+        .filter(|rest| !rest.ends_with('\\'))
+        .unwrap_or(line);
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push('|');
+                chars.next();
+            }
+            '|' => cells.push(String::new()),
+            _ => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const DOCUMENT: &str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>"#,
+        r#"<w:p><w:pPr><w:pStyle w:val="Quote"/></w:pPr><w:r><w:t>Первый</w:t></w:r>"#,
+        r#"<w:customXml w:element="x"/></w:p>"#,
+        r#"<w:p><w:fldSimple w:instr="PAGE"><w:r><w:t>Второй</w:t></w:r></w:fldSimple></w:p>"#,
+        r#"<w:p/>"#,
+        r#"<w:sectPr><w:pgSz w:w="11906" w:h="16838"/></w:sectPr></w:body></w:document>"#,
+    );
+    const COMMENTS: &[u8] = br#"<w:comments><w:comment w:id="0"><w:p/></w:comment></w:comments>"#;
+
+    fn docx(document: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+        writer.start_file(DOCUMENT_XML, options).unwrap();
+        writer.write_all(document.as_bytes()).unwrap();
+        writer.start_file("word/comments.xml", options).unwrap();
+        writer.write_all(COMMENTS).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn entry(bytes: &[u8], name: &str) -> Vec<u8> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut contents = Vec::new();
+        archive.by_name(name).unwrap().read_to_end(&mut contents).unwrap();
+        contents
+    }
+
+    #[test]
+    fn body_layout_finds_top_level_paragraphs() {
+        let layout = body_layout(DOCUMENT.as_bytes()).unwrap();
+        let elements: Vec<&str> = layout.elements.iter().map(|range| &DOCUMENT[range.clone()]).collect();
+        assert_eq!(elements.len(), 3);
+        assert!(elements[0].starts_with("<w:p><w:pPr>") && elements[0].ends_with("</w:p>"));
+        assert!(elements[1].contains("fldSimple"));
+        assert_eq!(elements[2], "<w:p/>");
+        assert!(DOCUMENT[layout.end..].starts_with("<w:sectPr>"));
+    }
+
+    // Правка второго абзаца не трогает байты первого и остальных частей архива
+    #[test]
+    fn untouched_paragraph_survives_byte_for_byte() {
+        let original = docx(DOCUMENT);
+        let layout = body_layout(DOCUMENT.as_bytes()).unwrap();
+        let first = &DOCUMENT[layout.elements[0].clone()];
+        let new_paragraph = r#"<w:p w14:paraId="1"><w:r><w:t>Новый</w:t></w:r></w:p>"#.as_bytes().to_vec();
+        let xml = declare_w14(splice(DOCUMENT.as_bytes(), vec![(layout.elements[1].clone(), new_paragraph)]));
+        let saved = replace_zip_entry(Cursor::new(&original), DOCUMENT_XML, &xml).unwrap();
+
+        let document = String::from_utf8(entry(&saved, DOCUMENT_XML)).unwrap();
+        let layout = body_layout(document.as_bytes()).unwrap();
+        assert_eq!(&document[layout.elements[0].clone()], first);
+        assert!(document[layout.elements[1].clone()].contains("Новый"));
+        assert!(!document.contains("Второй"));
+        assert!(document.contains(&format!("xmlns:w14=\"{}\"", W14_NAMESPACE)));
+        assert!(document.ends_with("</w:sectPr></w:body></w:document>"));
+        assert_eq!(entry(&saved, "word/comments.xml"), COMMENTS);
+    }
+}
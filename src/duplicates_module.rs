@@ -0,0 +1,141 @@
+// duplicates_module.rs
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+
+use crate::i18n::tr;
+
+// Строка, встретившаяся в тексте не один раз, и номера всех ее строк (с нуля)
+pub struct DuplicateGroup {
+    pub text: String,
+    pub lines: Vec<usize>,
+}
+
+// Без учета пробелов строки сравниваются без отступов и с одним пробелом между словами
+fn line_key(line: &str, ignore_whitespace: bool) -> String {
+    if ignore_whitespace {
+        line.split_whitespace().collect::<Vec<_>>().join(" ")
+    } else {
+        line.trim_end_matches('\r').to_string()
+    }
+}
+
+// Пустые строки повторами не считаются; чаще встречающиеся — первыми
+pub fn find_duplicates(text: &str, ignore_whitespace: bool) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (line, content) in text.split('\n').enumerate() {
+        if content.trim().is_empty() {
+            continue;
+        }
+        let key = line_key(content, ignore_whitespace);
+        match index.get(&key) {
+            Some(&group) => groups[group].lines.push(line),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(DuplicateGroup { text: content.trim().to_string(), lines: vec![line] });
+            }
+        }
+    }
+    groups.retain(|group| group.lines.len() > 1);
+    groups.sort_by(|a, b| b.lines.len().cmp(&a.lines.len()).then(a.lines[0].cmp(&b.lines[0])));
+    groups
+}
+
+// Оставляет первое вхождение каждой строки; пустые строки не трогает
+pub fn remove_duplicates(text: &str, ignore_whitespace: bool) -> String {
+    let mut seen = HashSet::new();
+    text.split('\n')
+        .filter(|line| line.trim().is_empty() || seen.insert(line_key(line, ignore_whitespace)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Default)]
+pub struct DuplicatesModule {
+    pub show_panel: bool,
+    ignore_whitespace: bool,
+    groups: Vec<DuplicateGroup>,
+    selected: Option<usize>,
+    next_occurrence: usize, // Какое вхождение выбранной строки показать следующим щелчком
+}
+
+pub enum DuplicatesResult {
+    None,
+    GoToLine(usize),
+    RemoveDuplicates(bool), // Без учета пробелов
+}
+
+impl DuplicatesModule {
+    // Строки для подсветки: повторы и отдельно — выбранная в панели группа
+    pub fn highlighted_lines(&self) -> Vec<(usize, bool)> {
+        if !self.show_panel {
+            return Vec::new();
+        }
+        self.groups
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| group.lines.iter().map(move |&line| (line, self.selected == Some(index))))
+            .collect()
+    }
+
+    pub fn show_panel(&mut self, ctx: &egui::Context, text: &str) -> DuplicatesResult {
+        if !self.show_panel {
+            self.groups.clear();
+            return DuplicatesResult::None;
+        }
+
+        let mut result = DuplicatesResult::None;
+        let mut open = self.show_panel;
+        self.groups = find_duplicates(text, self.ignore_whitespace);
+        if self.selected.is_some_and(|index| index >= self.groups.len()) {
+            self.selected = None;
+        }
+
+        egui::Window::new(tr!("Повторяющиеся строки"))
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                if ui.checkbox(&mut self.ignore_whitespace, tr!("без учета пробелов")).changed() {
+                    self.selected = None;
+                }
+                if self.groups.is_empty() {
+                    ui.label(tr!("Повторов нет"));
+                    return;
+                }
+
+                let extra: usize = self.groups.iter().map(|group| group.lines.len() - 1).sum();
+                ui.label(tr!("Строк с повторами: {}, лишних копий: {}", self.groups.len(), extra));
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for (index, group) in self.groups.iter().enumerate() {
+                        let title = format!("×{}  {}", group.lines.len(), group.text);
+                        let selected = self.selected == Some(index);
+                        let response = ui
+                            .selectable_label(selected, title)
+                            .on_hover_text(tr!("Щелчок — к следующему вхождению"));
+                        if response.clicked() {
+                            let occurrence = if selected { self.next_occurrence % group.lines.len() } else { 0 };
+                            self.selected = Some(index);
+                            self.next_occurrence = occurrence + 1;
+                            result = DuplicatesResult::GoToLine(group.lines[occurrence]);
+                        }
+                    }
+                });
+                ui.separator();
+                if ui
+                    .button(tr!("Удалить повторы"))
+                    .on_hover_text(tr!("Остается первое вхождение каждой строки"))
+                    .clicked()
+                {
+                    result = DuplicatesResult::RemoveDuplicates(self.ignore_whitespace);
+                    self.selected = None;
+                }
+            });
+
+        if !open {
+            self.show_panel = false;
+        }
+
+        result
+    }
+}
@@ -1,390 +1,1552 @@
 use std::fs;
 use std::io::{self, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use base64::Engine;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
-    execute, style,
-    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyCode,
+        KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    cursor::{Hide, MoveTo, Show},
+    execute, queue,
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
     QueueableCommand,
 };
+use crate::config::Config;
+use crate::i18n::{self, tr};
+use crate::search_module::unescape_pattern;
+use crate::theme_module::Palette;
+use syntect::highlighting::{HighlightState, Highlighter, RangedHighlightIterator, Theme, ThemeSet};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Цвета встроенных тем: стандартные цвета терминала, которые есть везде
+const SEARCH_PROMPT_COLOR: Color = Color::Yellow;
+const MATCH_BG: Color = Color::Yellow;
+const MATCH_FG: Color = Color::Black;
+const CURRENT_MATCH_BG: Color = Color::Red;
+const CURRENT_MATCH_FG: Color = Color::White;
+
+// На сколько строк прокручивает один щелчок колеса
+const MOUSE_SCROLL_LINES: isize = 3;
+
+const HELP_MESSAGE: &str = "F1 = help, Ctrl-Q = quit, Ctrl-S = save, Ctrl-F = search, Ctrl-R = replace, Ctrl-Z = undo";
+
+const CTRL_SHIFT: KeyModifiers = KeyModifiers::CONTROL.union(KeyModifiers::SHIFT);
+
+// Клавиши обычного режима. По этой же таблице строится справка (F1), так что она не расходится с делом.
+// Клавиши движения с Shift дополнительно продлевают выделение
+const KEYMAP: &[(KeyCode, KeyModifiers, Action)] = &[
+    (KeyCode::F(1), KeyModifiers::NONE, Action::Help),
+    (KeyCode::Char('h'), KeyModifiers::CONTROL, Action::Help),
+    (KeyCode::Char('q'), KeyModifiers::CONTROL, Action::Quit),
+    (KeyCode::Char('s'), KeyModifiers::CONTROL, Action::Save),
+    // Без расширенного режима клавиатуры терминал присылает Ctrl + Shift + S как Ctrl + S, поэтому есть и F12
+    (KeyCode::Char('s'), CTRL_SHIFT, Action::SaveAs),
+    (KeyCode::F(12), KeyModifiers::NONE, Action::SaveAs),
+    (KeyCode::Char('o'), KeyModifiers::CONTROL, Action::Open),
+    (KeyCode::Char('n'), KeyModifiers::CONTROL, Action::NewBuffer),
+    (KeyCode::Char('b'), KeyModifiers::CONTROL, Action::BufferList),
+    // Ctrl + Tab доходит не через все терминалы, поэтому есть и Ctrl + PageDown / PageUp
+    (KeyCode::Tab, KeyModifiers::CONTROL, Action::NextBuffer),
+    (KeyCode::PageDown, KeyModifiers::CONTROL, Action::NextBuffer),
+    (KeyCode::BackTab, KeyModifiers::CONTROL, Action::PreviousBuffer),
+    (KeyCode::PageUp, KeyModifiers::CONTROL, Action::PreviousBuffer),
+    (KeyCode::Char('g'), KeyModifiers::CONTROL, Action::GoToLine),
+    (KeyCode::Char('f'), KeyModifiers::CONTROL, Action::Find),
+    (KeyCode::Char('r'), KeyModifiers::CONTROL, Action::Replace),
+    (KeyCode::F(3), KeyModifiers::NONE, Action::FindNext),
+    (KeyCode::F(3), KeyModifiers::SHIFT, Action::FindPrevious),
+    (KeyCode::Esc, KeyModifiers::NONE, Action::ClearHighlights),
+    (KeyCode::Char('z'), KeyModifiers::CONTROL, Action::Undo),
+    (KeyCode::Char('c'), KeyModifiers::CONTROL, Action::Copy),
+    (KeyCode::Char('x'), KeyModifiers::CONTROL, Action::Cut),
+    (KeyCode::Char('v'), KeyModifiers::CONTROL, Action::Paste),
+    (KeyCode::Char('w'), KeyModifiers::CONTROL, Action::SoftWrap),
+    (KeyCode::Char('l'), KeyModifiers::CONTROL, Action::LineNumbers),
+    (KeyCode::Left, KeyModifiers::NONE, Action::Left),
+    (KeyCode::Right, KeyModifiers::NONE, Action::Right),
+    (KeyCode::Up, KeyModifiers::NONE, Action::Up),
+    (KeyCode::Down, KeyModifiers::NONE, Action::Down),
+    (KeyCode::Up, KeyModifiers::CONTROL, Action::Up),
+    (KeyCode::Down, KeyModifiers::CONTROL, Action::Down),
+    (KeyCode::Left, KeyModifiers::CONTROL, Action::WordLeft),
+    (KeyCode::Right, KeyModifiers::CONTROL, Action::WordRight),
+    (KeyCode::Home, KeyModifiers::NONE, Action::LineStart),
+    (KeyCode::End, KeyModifiers::NONE, Action::LineEnd),
+    (KeyCode::Home, KeyModifiers::CONTROL, Action::DocumentStart),
+    (KeyCode::End, KeyModifiers::CONTROL, Action::DocumentEnd),
+    (KeyCode::PageUp, KeyModifiers::NONE, Action::PageUp),
+    (KeyCode::PageDown, KeyModifiers::NONE, Action::PageDown),
+];
 
 pub struct Editor {
     content: Vec<String>,
     cursor_position: CursorPosition,
     should_quit: bool,
     filename: Option<String>,
+    modified: bool, // Есть несохраненные изменения
     status_message: String,
     scroll_offset: usize,
+    horizontal_offset: usize, // Сколько столбцов длинных строк ушло за левый край экрана
+    soft_wrap: bool,          // Переносить длинные строки на следующие строки экрана вместо прокрутки
+    line_numbers: LineNumbers,
+    prompt: Option<Prompt>,   // Ввод имени файла в строке состояния
+    pending: Option<PendingAction>, // Что сделать после ответа на «Сохранить изменения?»
     terminal_size: (u16, u16),
+    last_frame: Vec<Vec<u8>>, // Что сейчас на экране: с ним сравниваем новый кадр
     search_mode: bool,          // Режим поиска
     search_query: String,       // Текст для поиска
-    search_matches: Vec<Match>, // Найденные совпадения
+    search_matches: Vec<Match>, // Найденные совпадения; остаются подсвеченными и после выхода из поиска
+    matches_stale: bool,        // Текст правили после поиска — совпадения надо найти заново
     current_match: usize,       // Текущее выделенное совпадение
+    replace_mode: bool,         // Режим поиска с заменой
+    replace_query: String,      // Текст для замены
+    editing_replacement: bool,  // Ввод идет в поле замены, а не в поле поиска
+    undo_stack: Vec<UndoEntry>, // Снимки текста перед заменами
+    colors: TuiColors,
+    syntax: Option<SyntaxHighlight>, // Подсветка синтаксиса, если расширение файла знакомо
+    selection_anchor: Option<CursorPosition>, // Где начали выделение с Shift; конец — курсор
+    mouse: bool,                              // Включать захват мыши (настройка tui_mouse)
+    drag_start: Option<CursorPosition>,       // Где нажали кнопку мыши: отсюда выделяем при перетаскивании
+    clipboard: String,                        // Последнее скопированное, если системный буфер недоступен
+    buffers: Vec<Buffer>,       // Все открытые файлы; место активного пустует, он сам — в полях выше
+    active_buffer: usize,
+    buffer_list: Option<usize>, // Открыт список буферов; число — выбранная в нем строка
+    help_scroll: Option<usize>, // Открыта справка; число — сколько ее строк ушло за верх экрана
 }
 
+// Открытый файл со своим курсором, прокруткой, поиском и историей отмены.
+// Пока буфер активен, все это лежит прямо в полях Editor, а при переключении переезжает сюда
 #[derive(Default)]
+struct Buffer {
+    content: Vec<String>,
+    cursor_position: CursorPosition,
+    filename: Option<String>,
+    modified: bool,
+    scroll_offset: usize,
+    horizontal_offset: usize,
+    search_query: String,
+    search_matches: Vec<Match>,
+    current_match: usize,
+    undo_stack: Vec<UndoEntry>,
+    selection_anchor: Option<CursorPosition>,
+    syntax: Option<SyntaxHighlight>,
+}
+
+impl Buffer {
+    fn empty() -> Self {
+        Self { content: vec![String::new()], ..Default::default() }
+    }
+}
+
+// Цвета подсветки поиска; пользовательская тема из настроек GUI задает их в RGB
+struct TuiColors {
+    prompt: Color,
+    match_bg: Color,
+    match_fg: Color,
+    current_bg: Color,
+    current_fg: Color,
+    dark: bool, // Тема подсветки синтаксиса под темный или светлый фон
+}
+
+impl TuiColors {
+    fn from_palette(palette: &Palette) -> Self {
+        if palette.is_builtin() {
+            return Self {
+                prompt: SEARCH_PROMPT_COLOR,
+                match_bg: MATCH_BG,
+                match_fg: MATCH_FG,
+                current_bg: CURRENT_MATCH_BG,
+                current_fg: CURRENT_MATCH_FG,
+                dark: palette.dark,
+            };
+        }
+
+        let rgb = |[r, g, b]: [u8; 3]| Color::Rgb { r, g, b };
+        let [mr, mg, mb, _] = palette.search_match;
+        let [cr, cg, cb, _] = palette.current_match;
+        Self {
+            prompt: rgb([mr, mg, mb]),
+            match_bg: rgb([mr, mg, mb]),
+            match_fg: rgb(palette.background),
+            current_bg: rgb([cr, cg, cb]),
+            current_fg: rgb(palette.background),
+            dark: palette.dark,
+        }
+    }
+}
+
+// x — байтовое смещение в строке, всегда на границе графемы:
+// так строку можно резать срезами, а смещения совпадают с найденными при поиске
+#[derive(Default, Clone, Copy, PartialEq)]
 pub struct CursorPosition {
     pub x: usize,
     pub y: usize,
 }
 
+// Снимок буфера для отмены: одна запись на одну операцию замены
+struct UndoEntry {
+    content: Vec<String>,
+    cursor_x: usize,
+    cursor_y: usize,
+}
+
+// Номера строк слева; в относительном режиме у текущей строки — ее номер, у остальных — расстояние до нее
+#[derive(Clone, Copy, PartialEq)]
+enum LineNumbers {
+    Off,
+    Absolute,
+    Relative,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+    Help,
+    Quit,
+    Save,
+    SaveAs,
+    Open,
+    NewBuffer,
+    BufferList,
+    NextBuffer,
+    PreviousBuffer,
+    GoToLine,
+    Find,
+    Replace,
+    FindNext,
+    FindPrevious,
+    ClearHighlights,
+    Undo,
+    Copy,
+    Cut,
+    Paste,
+    SoftWrap,
+    LineNumbers,
+    Left,
+    Right,
+    Up,
+    Down,
+    WordLeft,
+    WordRight,
+    LineStart,
+    LineEnd,
+    DocumentStart,
+    DocumentEnd,
+    PageUp,
+    PageDown,
+}
+
+impl Action {
+    fn title(self) -> &'static str {
+        match self {
+            Action::Help => tr!("Show this help"),
+            Action::Quit => tr!("Quit"),
+            Action::Save => tr!("Save"),
+            Action::SaveAs => tr!("Save as"),
+            Action::Open => tr!("Open file"),
+            Action::NewBuffer => tr!("New buffer"),
+            Action::BufferList => tr!("List of buffers"),
+            Action::NextBuffer => tr!("Next buffer"),
+            Action::PreviousBuffer => tr!("Previous buffer"),
+            Action::GoToLine => tr!("Go to line"),
+            Action::Find => tr!("Find"),
+            Action::Replace => tr!("Find and replace"),
+            Action::FindNext => tr!("Next match"),
+            Action::FindPrevious => tr!("Previous match"),
+            Action::ClearHighlights => tr!("Clear selection, then search highlights"),
+            Action::Undo => tr!("Undo the last change"),
+            Action::Copy => tr!("Copy"),
+            Action::Cut => tr!("Cut"),
+            Action::Paste => tr!("Paste"),
+            Action::SoftWrap => tr!("Toggle soft wrap"),
+            Action::LineNumbers => tr!("Line numbers: off, absolute, relative"),
+            Action::Left => tr!("Character left"),
+            Action::Right => tr!("Character right"),
+            Action::Up => tr!("Line up"),
+            Action::Down => tr!("Line down"),
+            Action::WordLeft => tr!("Word left"),
+            Action::WordRight => tr!("Word right"),
+            Action::LineStart => tr!("Start of line"),
+            Action::LineEnd => tr!("End of line"),
+            Action::DocumentStart => tr!("Start of document"),
+            Action::DocumentEnd => tr!("End of document"),
+            Action::PageUp => tr!("Page up"),
+            Action::PageDown => tr!("Page down"),
+        }
+    }
+
+    fn is_movement(self) -> bool {
+        matches!(
+            self,
+            Action::Left
+                | Action::Right
+                | Action::Up
+                | Action::Down
+                | Action::WordLeft
+                | Action::WordRight
+                | Action::LineStart
+                | Action::LineEnd
+                | Action::DocumentStart
+                | Action::DocumentEnd
+                | Action::PageUp
+                | Action::PageDown
+        )
+    }
+}
+
+// Строки экрана с управляющими последовательностями цвета и место курсора (None — курсор не виден)
+struct Frame {
+    rows: Vec<Vec<u8>>,
+    cursor: Option<(usize, usize)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PromptKind {
+    SaveAs,
+    Open,
+    SaveChanges, // Вопрос перед действием, которое выбросит несохраненный текст
+    GoToLine,
+}
+
+enum PendingAction {
+    Quit,
+    Close, // Закрыть активный буфер
+}
+
+struct Prompt {
+    kind: PromptKind,
+    input: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Highlight {
+    Match,
+    CurrentMatch,
+    Selection,
+}
+
+// Совпадение может занимать несколько строк: от (line, start) до (end_line, end)
 #[derive(Clone)]
 struct Match {
     line: usize,
     start: usize,
+    end_line: usize,
     end: usize,
 }
 
 impl Editor {
     pub fn new() -> Self {
+        let config = Config::load();
+        i18n::set_language(config.language);
         let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
         Self {
             content: vec![String::new()],
             cursor_position: CursorPosition::default(),
             should_quit: false,
             filename: None,
-            status_message: String::from("Help: Ctrl-Q = quit, Ctrl-S = save, Ctrl-F = search"),
+            modified: false,
+            status_message: tr!(HELP_MESSAGE).to_string(),
             scroll_offset: 0,
+            horizontal_offset: 0,
+            soft_wrap: false,
+            line_numbers: LineNumbers::Off,
+            prompt: None,
+            pending: None,
             terminal_size: (width, height),
+            last_frame: Vec::new(),
             search_mode: false,
             search_query: String::new(),
             search_matches: Vec::new(),
+            matches_stale: false,
             current_match: 0,
+            replace_mode: false,
+            replace_query: String::new(),
+            editing_replacement: false,
+            undo_stack: Vec::new(),
+            colors: TuiColors::from_palette(&config.palette()),
+            syntax: None,
+            selection_anchor: None,
+            mouse: config.tui_mouse,
+            drag_start: None,
+            clipboard: String::new(),
+            buffers: vec![Buffer::default()],
+            active_buffer: 0,
+            buffer_list: None,
+            help_scroll: None,
         }
     }
 
     pub fn run(&mut self) -> Result<(), io::Error> {
+        // Терминал возвращаем в обычный режим при любом выходе: по ошибке, панике или Ctrl-Q.
+        // Хук срабатывает до вывода сообщения о панике, иначе оно осталось бы на альтернативном экране
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            default_hook(info);
+        }));
+        let _guard = TerminalGuard;
+
         enable_raw_mode()?;
-        execute!(io::stdout(), EnterAlternateScreen)?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+        if self.mouse {
+            execute!(io::stdout(), EnableMouseCapture)?;
+        }
 
         while !self.should_quit {
-            self.update_terminal_size();
             self.refresh_screen()?;
             self.process_keypress()?;
         }
-
-        disable_raw_mode()?;
-        execute!(io::stdout(), LeaveAlternateScreen)?;
         Ok(())
     }
 
-    fn update_terminal_size(&mut self) {
-        if let Ok((width, height)) = crossterm::terminal::size() {
-            self.terminal_size = (width, height);
+    // Размер приходит событием от терминала, опрашивать его на каждом нажатии не нужно
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), io::Error> {
+        self.terminal_size = (width, height);
+        // Терминал мог переложить старый текст по-своему — следующий кадр рисуем целиком
+        self.last_frame.clear();
+        execute!(io::stdout(), Clear(ClearType::All))?;
+
+        // Если окно выросло, подтягиваем конец текста к низу экрана, чтобы снизу не оставалось пустоты
+        if !self.soft_wrap {
+            let max_offset = self.content.len().saturating_sub(self.visible_lines());
+            self.scroll_offset = self.scroll_offset.min(max_offset);
         }
+        // Курсор, оказавшийся за краем, вернет в видимую часть update_scroll
+        self.update_scroll();
+        Ok(())
+    }
+
+    // Строки текста на экране: все, кроме строк поиска и состояния
+    fn visible_lines(&self) -> usize {
+        (self.terminal_size.1 as usize).saturating_sub(2).max(1)
     }
 
+    // Кадр собираем целиком в памяти и сравниваем с прошлым: переписываем только изменившиеся строки экрана.
+    // Полная очистка на каждое нажатие заметно мерцает, особенно по ssh
     fn refresh_screen(&mut self) -> Result<(), io::Error> {
+        if self.matches_stale {
+            self.refresh_matches();
+        }
         self.update_scroll();
+        let last_visible = self.scroll_offset + self.visible_lines();
+        if let Some(syntax) = &mut self.syntax {
+            syntax.update(&self.content, last_visible);
+        }
+        let frame = self.render_frame()?;
 
-        execute!(
-            io::stdout(),
-            crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
-            crossterm::cursor::MoveTo(0, 0)
-        )?;
-        
-        // Показываем только видимые строки с учетом прокрутки
-        let visible_lines = (self.terminal_size.1 - 2) as usize; // -2 для статусных строк
-        let end_line = (self.scroll_offset + visible_lines).min(self.content.len());
-        
-        for (line_index, line) in self.content[self.scroll_offset..end_line].iter().enumerate() {
-            let absolute_line = line_index + self.scroll_offset;
-            
-            if self.search_mode && !self.search_query.is_empty() {
-                // В режиме поиска выделяем совпадения
-                self.print_line_with_highlights(absolute_line, line)?;
-            } else {
-                println!("{}\r", line);
+        let mut stdout = io::stdout();
+        stdout.queue(Hide)?;
+        for (row, line) in frame.rows.iter().enumerate() {
+            if self.last_frame.get(row) == Some(line) {
+                continue;
             }
+            stdout.queue(MoveTo(0, row as u16))?;
+            stdout.queue(Clear(ClearType::CurrentLine))?;
+            stdout.write_all(line)?;
         }
+        if let Some((x, y)) = frame.cursor {
+            stdout.queue(MoveTo(x as u16, y as u16))?;
+            stdout.queue(Show)?;
+        }
+        stdout.flush()?;
+        self.last_frame = frame.rows;
+        Ok(())
+    }
 
-        // Перемещаем курсор с учетом прокрутки
-        let cursor_y = self.cursor_position.y.saturating_sub(self.scroll_offset);
-        if cursor_y < visible_lines {
-            execute!(
-                io::stdout(),
-                crossterm::cursor::MoveTo(
-                    self.cursor_position.x as u16,
-                    cursor_y as u16
-                )
-            )?;
+    fn render_frame(&self) -> Result<Frame, io::Error> {
+        let visible_lines = self.visible_lines();
+        let mut frame = Vec::with_capacity(visible_lines + 2);
+
+        // Показываем только видимые строки с учетом прокрутки; справка и список буферов рисуются вместо текста
+        let lines = if self.overlay_shown() { &[][..] } else { &self.content[..] };
+        self.print_help(&mut frame);
+        if self.help_scroll.is_none() {
+            self.print_buffer_list(&mut frame)?;
+        }
+        for (line_index, line) in lines.iter().enumerate().skip(self.scroll_offset) {
+            if frame.len() >= visible_lines {
+                break;
+            }
+            if self.soft_wrap {
+                self.print_wrapped_line(&mut frame, line_index, line, visible_lines)?;
+            } else {
+                let mut row = Vec::new();
+                self.print_line(&mut row, line_index, line)?;
+                frame.push(row);
+            }
         }
+        frame.resize(visible_lines, Vec::new());
 
         // Строка поиска (если активен режим поиска)
+        let mut search_row = Vec::new();
         if self.search_mode {
-            let search_prompt = format!("Search: {}", self.search_query);
+            let search_prompt = if self.replace_mode {
+                // Активное поле помечаем курсором "_"
+                let (search_marker, replace_marker) = if self.editing_replacement { ("", "_") } else { ("_", "") };
+                tr!(
+                    "Search: {}{} | Replace: {}{}",
+                    self.search_query, search_marker, self.replace_query, replace_marker
+                )
+            } else {
+                tr!("Search: {}", self.search_query)
+            };
             let search_info = if !self.search_matches.is_empty() {
-                format!(" [{} matches, current: {}]", self.search_matches.len(), self.current_match + 1)
+                tr!(" [{} matches, current: {}]", self.search_matches.len(), self.current_match + 1)
             } else if !self.search_query.is_empty() {
-                " [no matches]".to_string()
+                tr!(" [no matches]").to_string()
             } else {
                 String::new()
             };
             
             let full_search_line = format!("{}{}", search_prompt, search_info);
-            let search_line = if full_search_line.len() > self.terminal_size.0 as usize {
-                format!("{}...", &full_search_line[..self.terminal_size.0 as usize - 3])
-            } else {
-                full_search_line
-            };
+            let search_line = Self::truncate_to_width(full_search_line, self.terminal_size.0 as usize);
             
-            execute!(
-                io::stdout(),
-                crossterm::cursor::MoveTo(0, (self.terminal_size.1 - 2) as u16),
-                crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
-                SetForegroundColor(Color::Yellow),
+            queue!(
+                search_row,
+                SetForegroundColor(self.colors.prompt),
                 Print(search_line),
                 SetForegroundColor(Color::Reset)
             )?;
         }
+        frame.push(search_row);
 
-        // Статусная строка
-        let status = format!(
-            "{} | Line: {}/{}, Col: {} | Scroll: {} | {}",
-            self.filename.as_deref().unwrap_or("[No Name]"),
+        // Статусная строка; несохраненный файл помечаем, как в vim
+        let status = tr!(
+            "{}{}{} | Line: {}/{}, Col: {} | Scroll: {} | {}",
+            self.buffer_number(),
+            display_name(self.filename.as_deref()),
+            if self.modified { " [+]" } else { "" },
             self.cursor_position.y + 1,
             self.content.len(),
-            self.cursor_position.x + 1,
+            self.current_line()[..self.cursor_position.x].graphemes(true).count() + 1,
             self.scroll_offset + 1,
             self.status_message
         );
-        let status = if status.len() > self.terminal_size.0 as usize {
-            format!("{}...", &status[..self.terminal_size.0 as usize - 3])
+        // Пока вводят имя файла, строка состояния отдана под него
+        let status = match &self.prompt {
+            Some(prompt) => format!("{}{}", prompt.label(display_name(self.filename.as_deref())), prompt.input),
+            None => status,
+        };
+        let status = Self::truncate_to_width(status, self.terminal_size.0 as usize);
+        frame.push(status.clone().into_bytes());
+
+        // Столбец на экране — ширина текста до курсора: кириллица занимает одну клетку, иероглифы — две
+        let (cursor_x, cursor_y) = self.cursor_screen_position();
+        let cursor = if self.prompt.is_some() {
+            Some((status.width(), visible_lines + 1))
+        } else if self.overlay_shown() {
+            None
+        } else if cursor_y < visible_lines {
+            Some((cursor_x, cursor_y))
         } else {
-            status
+            None
         };
-        
-        execute!(
-            io::stdout(),
-            crossterm::cursor::MoveTo(0, (self.terminal_size.1 - 1) as u16),
-            crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
-            Print(status)
-        )?;
-
-        io::stdout().flush()?;
+        Ok(Frame { rows: frame, cursor })
+    }
+
+    fn overlay_shown(&self) -> bool {
+        self.help_scroll.is_some() || self.buffer_list.is_some()
+    }
+
+    // Номер буфера в строке состояния, когда их несколько
+    fn buffer_number(&self) -> String {
+        match self.buffers.len() {
+            1 => String::new(),
+            count => format!("[{}/{}] ", self.active_buffer + 1, count),
+        }
+    }
+
+    // Строки списка буферов, выбранная — в инверсии
+    fn print_buffer_list(&self, frame: &mut Vec<Vec<u8>>) -> Result<(), io::Error> {
+        let Some(selected) = self.buffer_list else {
+            return Ok(());
+        };
+        let width = self.terminal_size.0 as usize;
+        for index in 0..self.buffers.len().min(self.visible_lines()) {
+            let (filename, modified) = if index == self.active_buffer {
+                (self.filename.as_deref(), self.modified)
+            } else {
+                (self.buffers[index].filename.as_deref(), self.buffers[index].modified)
+            };
+            let marker = if index == self.active_buffer { '*' } else { ' ' };
+            let title = format!(
+                "{}{:>3}  {}{}",
+                marker,
+                index + 1,
+                display_name(filename),
+                if modified { " [+]" } else { "" }
+            );
+            let mut row = Vec::new();
+            if index == selected {
+                row.queue(SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(row, Print(Self::truncate_to_width(title, width)), SetAttribute(Attribute::Reset))?;
+            frame.push(row);
+        }
         Ok(())
     }
 
-    fn print_line_with_highlights(&self, line_num: usize, line: &str) -> Result<(), io::Error> {
-        let mut stdout = io::stdout();
-        let mut last_pos = 0;
-        
-        // Получаем все совпадения для этой строки
-        let line_matches: Vec<&Match> = self.search_matches
+    // Обрезаем по ширине на экране, а не по байтам: в переведенных строках есть кириллица
+    fn truncate_to_width(line: String, width: usize) -> String {
+        if line.width() <= width {
+            return line;
+        }
+        let mut truncated = line[..byte_at_column(&line, width.saturating_sub(3))].to_string();
+        truncated.push_str("...");
+        truncated
+    }
+
+    // Участки строки, которые надо выделить: выделение, а где его нет — найденное последним поиском.
+    // Многострочные совпадения и выделение обрезаем по границам строки
+    fn line_highlights(&self, line_num: usize, line: &str) -> Vec<(usize, usize, Highlight)> {
+        if let Some((start, end)) = self.selection_range() {
+            if start.y <= line_num && line_num <= end.y {
+                let from = if start.y == line_num { start.x } else { 0 };
+                let to = if end.y == line_num { end.x } else { line.len() };
+                return vec![(from, to, Highlight::Selection)];
+            }
+        }
+        self.search_matches
             .iter()
-            .filter(|m| m.line == line_num)
-            .collect();
-        
-        if line_matches.is_empty() {
-            // Если нет совпадений, просто печатаем строку
-            stdout.queue(Print(line))?;
-            stdout.queue(Print("\r\n"))?;
-        } else {
-            // Печатаем строку с выделением совпадений
-            for mat in line_matches {
-                // Текст до совпадения
-                if mat.start > last_pos {
-                    stdout.queue(Print(&line[last_pos..mat.start]))?;
-                }
-                
-                // Выделенное совпадение
-                let is_current = self.current_match < self.search_matches.len() && 
-                               self.search_matches[self.current_match].line == line_num &&
-                               self.search_matches[self.current_match].start == mat.start;
-                
-                if is_current {
-                    // Текущее совпадение выделяем другим цветом
-                    stdout.queue(SetBackgroundColor(Color::Red))?;
-                    stdout.queue(SetForegroundColor(Color::White))?;
+            .enumerate()
+            .filter(|(_, m)| m.line <= line_num && m.end_line >= line_num)
+            .map(|(index, m)| {
+                let start = if m.line == line_num { m.start } else { 0 };
+                let end = if m.end_line == line_num { m.end } else { line.len() };
+                if index == self.current_match {
+                    (start, end, Highlight::CurrentMatch)
                 } else {
-                    stdout.queue(SetBackgroundColor(Color::Yellow))?;
-                    stdout.queue(SetForegroundColor(Color::Black))?;
+                    (start, end, Highlight::Match)
                 }
-                
-                stdout.queue(Print(&line[mat.start..mat.end]))?;
-                stdout.queue(SetBackgroundColor(Color::Reset))?;
-                stdout.queue(SetForegroundColor(Color::Reset))?;
-                
-                last_pos = mat.end;
+            })
+            .collect()
+    }
+
+    fn set_highlight(&self, row: &mut Vec<u8>, highlight: Highlight) -> Result<(), io::Error> {
+        match highlight {
+            Highlight::Match => queue!(
+                row,
+                SetBackgroundColor(self.colors.match_bg),
+                SetForegroundColor(self.colors.match_fg)
+            ),
+            Highlight::CurrentMatch => queue!(
+                row,
+                SetBackgroundColor(self.colors.current_bg),
+                SetForegroundColor(self.colors.current_fg)
+            ),
+            Highlight::Selection => queue!(row, SetAttribute(Attribute::Reverse)),
+        }
+    }
+
+    // Печатает видимую по горизонтали часть строки. Если текст уходит за край экрана,
+    // в крайней клетке вместо него стоит «<» или «>»
+    fn print_line(&self, row: &mut Vec<u8>, line_num: usize, line: &str) -> Result<(), io::Error> {
+        self.print_gutter(row, line_num, true)?;
+        let width = self.text_width();
+        let line_width = line.width();
+        let left_marker = self.horizontal_offset > 0 && line_width > self.horizontal_offset;
+        let right_marker = line_width > self.horizontal_offset + width;
+        let from = self.horizontal_offset + left_marker as usize;
+        let to = self.horizontal_offset + width - right_marker as usize;
+        let (start, end) = column_range(line, from, to);
+
+        if left_marker {
+            queue!(row, SetForegroundColor(self.colors.prompt), Print('<'), SetForegroundColor(Color::Reset))?;
+        }
+        // Широкий символ, разрезанный краем экрана, заменяем пробелом
+        let shown_from = line[..start].width();
+        row.queue(Print(" ".repeat(shown_from.saturating_sub(from))))?;
+
+        self.print_span(row, line_num, line, start, end)?;
+        let shown_to = line[..end].width();
+        if !right_marker && shown_to < to && end == line.len() {
+            self.print_selected_newline(row, line_num)?;
+        }
+        if right_marker {
+            queue!(
+                row,
+                Print(" ".repeat(to.saturating_sub(shown_to))),
+                SetForegroundColor(self.colors.prompt),
+                Print('>'),
+                SetForegroundColor(Color::Reset)
+            )?;
+        }
+        Ok(())
+    }
+
+    // Часть строки от start до end с подсветкой, обрезанной по ее границам
+    fn print_span(
+        &self,
+        row: &mut Vec<u8>,
+        line_num: usize,
+        line: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(), io::Error> {
+        let mut position = start;
+        for (span_start, span_end, highlight) in self.line_highlights(line_num, line) {
+            let span_start = span_start.clamp(position, end);
+            let span_end = span_end.clamp(span_start, end);
+            if span_start == span_end {
+                continue;
             }
-            
-            // Текст после последнего совпадения
-            if last_pos < line.len() {
-                stdout.queue(Print(&line[last_pos..]))?;
+            self.print_colored(row, line_num, line, position, span_start)?;
+            self.set_highlight(row, highlight)?;
+            // Найденное рисуем цветами поиска, а выделение — инверсией поверх цветов синтаксиса
+            if highlight == Highlight::Selection {
+                self.print_colored(row, line_num, line, span_start, span_end)?;
+            } else {
+                row.queue(Print(&line[span_start..span_end]))?;
             }
-            
-            stdout.queue(Print("\r\n"))?;
+            row.queue(SetAttribute(Attribute::Reset))?;
+            position = span_end;
+        }
+        self.print_colored(row, line_num, line, position, end)
+    }
+
+    // Текст с цветами подсветки синтаксиса; без нее — как есть
+    fn print_colored(
+        &self,
+        row: &mut Vec<u8>,
+        line_num: usize,
+        line: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(), io::Error> {
+        if start >= end {
+            return Ok(());
+        }
+        let spans = self.syntax.as_ref().map_or(&[][..], |syntax| syntax.spans(line_num, line));
+        let mut position = start;
+        for &(span_start, span_end, color) in spans {
+            let (from, to) = (span_start.max(position), span_end.min(end));
+            if from >= to {
+                continue;
+            }
+            queue!(row, Print(&line[position..from]), SetForegroundColor(color), Print(&line[from..to]))?;
+            position = to;
+        }
+        if !spans.is_empty() {
+            row.queue(SetForegroundColor(Color::Reset))?;
+        }
+        row.queue(Print(&line[position..end]))?;
+        Ok(())
+    }
+
+    // Выделенный перевод строки показываем пробелом в инверсии
+    fn print_selected_newline(&self, row: &mut Vec<u8>, line_num: usize) -> Result<(), io::Error> {
+        let selected = self
+            .selection_range()
+            .is_some_and(|(first, last)| first.y <= line_num && line_num < last.y);
+        if selected && !self.search_mode {
+            self.set_highlight(row, Highlight::Selection)?;
+            queue!(row, Print(' '), SetAttribute(Attribute::Reset))?;
+        }
+        Ok(())
+    }
+
+    // В режиме переноса строка занимает несколько строк экрана; кадр не растет больше max_rows
+    fn print_wrapped_line(
+        &self,
+        frame: &mut Vec<Vec<u8>>,
+        line_num: usize,
+        line: &str,
+        max_rows: usize,
+    ) -> Result<(), io::Error> {
+        let rows = wrap_rows(line, self.wrap_width());
+        for (index, &(start, end)) in rows.iter().enumerate().take(max_rows.saturating_sub(frame.len())) {
+            let mut row = Vec::new();
+            self.print_gutter(&mut row, line_num, index == 0)?;
+            self.print_span(&mut row, line_num, line, start, end)?;
+            if end == line.len() {
+                self.print_selected_newline(&mut row, line_num)?;
+            }
+            frame.push(row);
         }
-        
-        stdout.flush()?;
         Ok(())
     }
 
+    // Номер строки, выровненный вправо; на продолжениях перенесенной строки — пусто
+    fn print_gutter(&self, row: &mut Vec<u8>, line_num: usize, first_row: bool) -> Result<(), io::Error> {
+        let width = self.gutter_width();
+        if width == 0 {
+            return Ok(());
+        }
+        let current = self.cursor_position.y;
+        let number = match self.line_numbers {
+            _ if !first_row => String::new(),
+            LineNumbers::Relative if line_num != current => line_num.abs_diff(current).to_string(),
+            _ => (line_num + 1).to_string(),
+        };
+        queue!(
+            row,
+            SetForegroundColor(Color::DarkGrey),
+            Print(format!("{:>1$} ", number, width - 1)),
+            SetForegroundColor(Color::Reset)
+        )
+    }
+
+    // Цифры самого большого номера и пробел после них
+    fn gutter_width(&self) -> usize {
+        match self.line_numbers {
+            LineNumbers::Off => 0,
+            _ => self.content.len().to_string().len() + 1,
+        }
+    }
+
+    fn text_width(&self) -> usize {
+        (self.terminal_size.0 as usize).saturating_sub(self.gutter_width()).max(1)
+    }
+
+    // Последний столбец оставляем под курсор в конце строки
+    fn wrap_width(&self) -> usize {
+        self.text_width().saturating_sub(1).max(1)
+    }
+
+    fn cycle_line_numbers(&mut self) {
+        self.line_numbers = match self.line_numbers {
+            LineNumbers::Off => LineNumbers::Absolute,
+            LineNumbers::Absolute => LineNumbers::Relative,
+            LineNumbers::Relative => LineNumbers::Off,
+        };
+        self.status_message = match self.line_numbers {
+            LineNumbers::Off => tr!("Line numbers off"),
+            LineNumbers::Absolute => tr!("Line numbers on"),
+            LineNumbers::Relative => tr!("Relative line numbers"),
+        }
+        .to_string();
+    }
+
+    // Столбец и строка курсора на экране с учетом прокрутки или переноса
+    fn cursor_screen_position(&self) -> (usize, usize) {
+        let line = self.current_line();
+        let x = self.cursor_position.x;
+        let gutter = self.gutter_width();
+        if !self.soft_wrap {
+            let y = self.cursor_position.y.saturating_sub(self.scroll_offset);
+            return (gutter + line[..x].width().saturating_sub(self.horizontal_offset), y);
+        }
+        let rows = wrap_rows(line, self.wrap_width());
+        let row = row_of(&rows, x);
+        let above: usize = self.content[self.scroll_offset.min(self.cursor_position.y)..self.cursor_position.y]
+            .iter()
+            .map(|line| wrap_rows(line, self.wrap_width()).len())
+            .sum();
+        (gutter + line[rows[row].0..x].width(), above + row)
+    }
+
+    fn toggle_soft_wrap(&mut self) {
+        self.soft_wrap = !self.soft_wrap;
+        self.horizontal_offset = 0;
+        self.status_message = if self.soft_wrap {
+            tr!("Soft wrap on").to_string()
+        } else {
+            tr!("Soft wrap off").to_string()
+        };
+    }
+
     fn update_scroll(&mut self) {
-        let visible_lines = (self.terminal_size.1 - 2) as usize;
+        let visible_lines = self.visible_lines();
         
         if self.cursor_position.y >= self.scroll_offset + visible_lines {
             self.scroll_offset = self.cursor_position.y - visible_lines + 1;
         } else if self.cursor_position.y < self.scroll_offset {
             self.scroll_offset = self.cursor_position.y;
         }
+
+        // С переносом строки прокручиваем, пока строка экрана с курсором не станет видна
+        if self.soft_wrap {
+            while self.scroll_offset < self.cursor_position.y && self.cursor_screen_position().1 >= visible_lines {
+                self.scroll_offset += 1;
+            }
+            return;
+        }
+
+        // По горизонтали держим курсор не ближе одной клетки к краю: там может стоять «<» или «>».
+        // В поле шириной в одну клетку такого запаса нет — там курсор стоит в самой клетке
+        let width = self.text_width();
+        let column = self.current_line()[..self.cursor_position.x].width();
+        if column < self.horizontal_offset + 1 {
+            self.horizontal_offset = column.saturating_sub(1);
+        } else if column + 2 > self.horizontal_offset + width {
+            self.horizontal_offset = if width >= 2 { column + 2 - width } else { column };
+        }
     }
 
     fn process_keypress(&mut self) -> Result<(), io::Error> {
-        if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
-            if self.search_mode {
-                self.process_search_keypress(code, modifiers)?;
-            } else {
-                self.process_normal_keypress(code, modifiers)?;
+        let event = event::read()?;
+        // Отпускание кнопки могло не дойти (например, пока был открыт поиск), а текст с тех пор изменился
+        if matches!(event, Event::Key(_) | Event::Paste(_)) {
+            self.drag_start = None;
+        }
+        match event {
+            // В Windows терминал присылает и отпускание клавиши — иначе каждое нажатие сработало бы дважды
+            Event::Key(KeyEvent { code, modifiers, kind, .. }) if kind != KeyEventKind::Release => {
+                if self.prompt.is_some() {
+                    self.process_prompt_keypress(code);
+                } else if self.help_scroll.is_some() {
+                    self.process_help_keypress(code);
+                } else if self.buffer_list.is_some() {
+                    self.process_buffer_list_keypress(code);
+                } else if self.search_mode {
+                    self.process_search_keypress(code, modifiers)?;
+                } else {
+                    self.process_normal_keypress(code, modifiers)?;
+                }
             }
+            // Вставка средствами терминала (в том числе по ssh) приходит целиком
+            Event::Paste(text) if self.prompt.is_some() => {
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.input.push_str(text.trim_end_matches(['\r', '\n']));
+                }
+            }
+            Event::Paste(text) if !self.search_mode && !self.overlay_shown() => self.paste_text(&text),
+            Event::Mouse(mouse) if !self.search_mode && self.prompt.is_none() && !self.overlay_shown() => {
+                self.process_mouse(mouse)
+            }
+            Event::Resize(width, height) => self.resize(width, height)?,
+            _ => {}
         }
         Ok(())
     }
 
     fn process_normal_keypress(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<(), io::Error> {
-        match (code, modifiers) {
-            (KeyCode::Char('q'), KeyModifiers::CONTROL) => {
-                self.should_quit = true;
-            }
-            (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
-                self.save_file()?;
+        if let Some(action) = action_for(code, modifiers) {
+            self.perform(action)?;
+            // Выделение остается после копирования, чтобы его можно было сразу вырезать или заменить
+            if action != Action::Copy {
+                self.selection_anchor = None;
             }
-            (KeyCode::Char('f'), KeyModifiers::CONTROL) => {
-                self.enter_search_mode();
-            }
-            (KeyCode::PageUp, _) => {
-                self.scroll_page_up();
+            return Ok(());
+        }
+        // Клавиша движения с Shift продлевает выделение
+        let movement = action_for(code, modifiers - KeyModifiers::SHIFT).filter(|a| a.is_movement());
+        if let Some(action) = movement.filter(|_| modifiers.contains(KeyModifiers::SHIFT)) {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.cursor_position);
             }
-            (KeyCode::PageDown, _) => {
-                self.scroll_page_down();
+            self.perform(action)?;
+            if self.selection_anchor == Some(self.cursor_position) {
+                self.selection_anchor = None;
             }
-            (KeyCode::Char(c), _) => {
+            return Ok(());
+        }
+        match code {
+            KeyCode::Char(c) => {
+                self.delete_selection();
                 self.insert_char(c);
             }
-            (KeyCode::Backspace, _) => {
-                self.delete_char();
+            KeyCode::Backspace => {
+                if self.selection_anchor.is_some() {
+                    self.delete_selection();
+                } else {
+                    self.delete_char();
+                }
             }
-            (KeyCode::Enter, _) => {
+            KeyCode::Enter => {
+                self.delete_selection();
                 self.insert_newline();
             }
-            (KeyCode::Left, _) => {
-                self.move_cursor_left();
-            }
-            (KeyCode::Right, _) => {
-                self.move_cursor_right();
-            }
-            (KeyCode::Up, _) => {
-                self.move_cursor_up();
-            }
-            (KeyCode::Down, _) => {
-                self.move_cursor_down();
-            }
             _ => {}
         }
+        // Любое действие, кроме движения с Shift, снимает выделение
+        self.selection_anchor = None;
         Ok(())
     }
 
-    fn process_search_keypress(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<(), io::Error> {
-        match (code, modifiers) {
-            (KeyCode::Esc, _) | (KeyCode::Enter, _) => {
-                self.exit_search_mode();
+    fn perform(&mut self, action: Action) -> Result<(), io::Error> {
+        match action {
+            Action::Help => self.help_scroll = Some(0),
+            Action::Quit => self.run_or_ask(PendingAction::Quit),
+            Action::Save => self.save_file()?,
+            Action::SaveAs => self.open_prompt(PromptKind::SaveAs),
+            Action::Open => self.open_prompt(PromptKind::Open),
+            Action::NewBuffer => {
+                self.add_buffer(Buffer::empty());
+                self.status_message = tr!("New buffer").to_string();
             }
-            (KeyCode::Char('f'), KeyModifiers::CONTROL) => {
-                self.find_next_match();
+            Action::BufferList => {
+                self.buffer_list = Some(self.active_buffer);
+                self.status_message = tr!("Buffers: Enter = switch, Delete = close, Esc = back").to_string();
             }
-            (KeyCode::Backspace, _) => {
-                if !self.search_query.is_empty() {
-                    self.search_query.pop();
-                    self.perform_search();
+            Action::NextBuffer => self.cycle_buffer(true),
+            Action::PreviousBuffer => self.cycle_buffer(false),
+            Action::GoToLine => self.open_prompt(PromptKind::GoToLine),
+            Action::Find => self.enter_search_mode(),
+            Action::Replace => self.enter_replace_mode(),
+            Action::FindNext => self.jump_from_cursor(true),
+            Action::FindPrevious => self.jump_from_cursor(false),
+            // Esc сначала снимает выделение, а без него гасит подсветку последнего поиска
+            Action::ClearHighlights => {
+                if self.selection_anchor.is_none() && !self.search_matches.is_empty() {
+                    self.search_query.clear();
+                    self.search_matches.clear();
+                    self.status_message = tr!(HELP_MESSAGE).to_string();
                 }
             }
-            (KeyCode::Char(c), _) => {
-                self.search_query.push(c);
-                self.perform_search();
+            Action::Undo => self.undo(),
+            Action::Copy => {
+                self.copy_selection();
             }
-            _ => {}
+            Action::Cut => self.cut_selection(),
+            Action::Paste => {
+                let text = self.read_clipboard();
+                self.paste_text(&text);
+            }
+            Action::SoftWrap => self.toggle_soft_wrap(),
+            Action::LineNumbers => self.cycle_line_numbers(),
+            Action::Left => self.move_cursor_left(),
+            Action::Right => self.move_cursor_right(),
+            Action::Up => self.move_cursor_up(),
+            Action::Down => self.move_cursor_down(),
+            Action::WordLeft => self.move_word_left(),
+            Action::WordRight => self.move_word_right(),
+            Action::LineStart => self.cursor_position.x = 0,
+            Action::LineEnd => self.cursor_position.x = self.current_line().len(),
+            Action::DocumentStart => self.cursor_position = CursorPosition::default(),
+            Action::DocumentEnd => {
+                let y = self.content.len() - 1;
+                self.cursor_position = CursorPosition { x: self.content[y].len(), y };
+            }
+            Action::PageUp => self.scroll_page_up(),
+            Action::PageDown => self.scroll_page_down(),
         }
         Ok(())
     }
 
-    fn enter_search_mode(&mut self) {
-        self.search_mode = true;
-        self.search_query.clear();
-        self.search_matches.clear();
-        self.current_match = 0;
-        self.status_message = "Search mode: Type to search, Enter to exit".to_string();
+    // Справка: таблица клавиш, затем то, что в таблицу не помещается
+    fn help_lines(&self) -> Vec<String> {
+        let mut lines = vec![tr!("Keys (Esc = close, arrows and PageUp/PageDown = scroll)").to_string(), String::new()];
+        let mut actions: Vec<Action> = Vec::new();
+        for &(_, _, action) in KEYMAP {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+        let keys: Vec<String> = actions
+            .iter()
+            .map(|&action| {
+                let names: Vec<String> = KEYMAP
+                    .iter()
+                    .filter(|&&(_, _, bound)| bound == action)
+                    .map(|&(code, modifiers, _)| key_name(code, modifiers))
+                    .collect();
+                names.join(", ")
+            })
+            .collect();
+        let column = keys.iter().map(|keys| keys.chars().count()).max().unwrap_or(0) + 2;
+        for (keys, action) in keys.iter().zip(actions) {
+            lines.push(format!("  {:<column$}{}", keys, action.title()));
+        }
+        lines.push(String::new());
+        lines.push(tr!("Shift + any movement key extends the selection").to_string());
+        if self.mouse {
+            lines.push(tr!("Mouse: click places the cursor, drag selects, wheel scrolls").to_string());
+        }
+        lines.push(tr!("Search mode: Type to search, Enter to exit").to_string());
+        lines.push(
+            tr!("Replace mode: Tab = switch field, Enter = replace, Ctrl-A = replace all, Esc = exit").to_string(),
+        );
+        lines.push(tr!("File name prompts: Tab = complete the path, Enter = confirm, Esc = cancel").to_string());
+        lines
     }
 
-    fn exit_search_mode(&mut self) {
-        self.search_mode = false;
-        self.search_matches.clear();
-        self.current_match = 0;
-        self.status_message = "Help: Ctrl-Q = quit, Ctrl-S = save, Ctrl-F = search".to_string();
+    fn print_help(&self, frame: &mut Vec<Vec<u8>>) {
+        let Some(scroll) = self.help_scroll else {
+            return;
+        };
+        let width = self.terminal_size.0 as usize;
+        for line in self.help_lines().into_iter().skip(scroll).take(self.visible_lines()) {
+            frame.push(Self::truncate_to_width(line, width).into_bytes());
+        }
     }
 
-    fn perform_search(&mut self) {
-        self.search_matches.clear();
-        self.current_match = 0;
-
-        if self.search_query.is_empty() {
+    fn process_help_keypress(&mut self, code: KeyCode) {
+        let Some(scroll) = self.help_scroll else {
             return;
-        }
+        };
+        let max_scroll = self.help_lines().len().saturating_sub(self.visible_lines());
+        let page = self.visible_lines();
+        self.help_scroll = match code {
+            KeyCode::Esc | KeyCode::F(1) | KeyCode::Char('q') => None,
+            KeyCode::Up => Some(scroll.saturating_sub(1)),
+            KeyCode::Down => Some((scroll + 1).min(max_scroll)),
+            KeyCode::PageUp => Some(scroll.saturating_sub(page)),
+            KeyCode::PageDown => Some((scroll + page).min(max_scroll)),
+            KeyCode::Home => Some(0),
+            KeyCode::End => Some(max_scroll),
+            _ => Some(scroll),
+        };
+    }
 
-        for (line_num, line) in self.content.iter().enumerate() {
-            let mut start = 0;
-            while let Some(pos) = line[start..].find(&self.search_query) {
-                let absolute_pos = start + pos;
-                let end_pos = absolute_pos + self.search_query.len();
-                self.search_matches.push(Match {
-                    line: line_num,
-                    start: absolute_pos,
-                    end: end_pos,
-                });
-                start = end_pos;
+    // Щелчок ставит курсор, перетаскивание выделяет, Shift + щелчок продлевает выделение, колесо прокручивает
+    fn process_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(position) = self.screen_to_position(mouse.column as usize, mouse.row as usize) else {
+                    return;
+                };
+                if mouse.modifiers.contains(KeyModifiers::SHIFT) {
+                    self.selection_anchor.get_or_insert(self.cursor_position);
+                } else {
+                    self.selection_anchor = None;
+                }
+                self.cursor_position = position;
+                self.drag_start = Some(position);
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let Some(position) = self.screen_to_position(mouse.column as usize, mouse.row as usize) else {
+                    return;
+                };
+                if self.selection_anchor.is_none() {
+                    self.selection_anchor = self.drag_start.map(|start| self.clamp_position(start));
+                }
+                self.cursor_position = position;
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.drag_start = None;
+                if self.selection_anchor == Some(self.cursor_position) {
+                    self.selection_anchor = None;
+                }
             }
+            MouseEventKind::ScrollUp => self.scroll_lines(-MOUSE_SCROLL_LINES),
+            MouseEventKind::ScrollDown => self.scroll_lines(MOUSE_SCROLL_LINES),
+            _ => {}
         }
+    }
 
-        if !self.search_matches.is_empty() {
-            self.jump_to_match(0);
+    // Позиция, которая наверняка есть в тексте: строка существует, столбец на границе символа
+    fn clamp_position(&self, position: CursorPosition) -> CursorPosition {
+        let y = position.y.min(self.content.len() - 1);
+        let line = &self.content[y];
+        let mut x = position.x.min(line.len());
+        while !line.is_char_boundary(x) {
+            x -= 1;
         }
+        CursorPosition { x, y }
     }
 
-    fn find_next_match(&mut self) {
-        if !self.search_matches.is_empty() {
-            self.current_match = (self.current_match + 1) % self.search_matches.len();
-            self.jump_to_match(self.current_match);
+    // Позиция в тексте под клеткой экрана; None — щелчок по строке поиска или состояния
+    fn screen_to_position(&self, column: usize, row: usize) -> Option<CursorPosition> {
+        let visible_lines = self.visible_lines();
+        if row >= visible_lines {
+            return None;
+        }
+        let last = self.content.len() - 1;
+        let column = column.saturating_sub(self.gutter_width());
+        if !self.soft_wrap {
+            let y = (self.scroll_offset + row).min(last);
+            let x = byte_at_column(&self.content[y], self.horizontal_offset + column);
+            return Some(CursorPosition { x, y });
         }
+        let mut remaining = row;
+        for y in self.scroll_offset..=last {
+            let rows = wrap_rows(&self.content[y], self.wrap_width());
+            if remaining < rows.len() {
+                let x = x_in_row(&self.content[y], &rows, remaining, column);
+                return Some(CursorPosition { x, y });
+            }
+            remaining -= rows.len();
+        }
+        Some(CursorPosition { x: self.content[last].len(), y: last })
     }
 
-    fn jump_to_match(&mut self, match_index: usize) {
-        if match_index < self.search_matches.len() {
-            let mat = &self.search_matches[match_index];
-            self.cursor_position.y = mat.line;
-            self.cursor_position.x = mat.start;
-            self.current_match = match_index;
+    // Колесо двигает текст, а курсор остается на экране
+    fn scroll_lines(&mut self, delta: isize) {
+        let visible_lines = self.visible_lines();
+        let max_offset = self.content.len().saturating_sub(visible_lines.min(self.content.len()));
+        self.scroll_offset = self.scroll_offset.saturating_add_signed(delta).min(max_offset);
+        let first = self.scroll_offset;
+        let last = (self.scroll_offset + visible_lines).min(self.content.len()) - 1;
+        let y = self.cursor_position.y.clamp(first, last);
+        if y != self.cursor_position.y {
+            self.move_to_line(y);
         }
     }
 
-    // Остальные методы остаются без изменений...
-    fn scroll_page_up(&mut self) {
-        let visible_lines = (self.terminal_size.1 - 2) as usize;
-        if self.scroll_offset >= visible_lines {
-            self.scroll_offset -= visible_lines;
-            self.cursor_position.y = self.cursor_position.y.saturating_sub(visible_lines);
-            let current_line_len = self.content[self.cursor_position.y].len();
-            self.cursor_position.x = self.cursor_position.x.min(current_line_len);
+    // Начало и конец выделения по порядку
+    fn selection_range(&self) -> Option<(CursorPosition, CursorPosition)> {
+        let anchor = self.selection_anchor?;
+        let cursor = self.cursor_position;
+        if (anchor.y, anchor.x) <= (cursor.y, cursor.x) {
+            Some((anchor, cursor))
+        } else {
+            Some((cursor, anchor))
+        }
+    }
+
+    fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection_range()?;
+        if start.y == end.y {
+            return Some(self.content[start.y][start.x..end.x].to_string());
+        }
+        let mut text = self.content[start.y][start.x..].to_string();
+        for line in &self.content[start.y + 1..end.y] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&self.content[end.y][..end.x]);
+        Some(text)
+    }
+
+    // Удаление выделенного можно отменить, как и замену
+    fn delete_selection(&mut self) {
+        let Some((start, end)) = self.selection_range() else {
+            return;
+        };
+        self.push_undo();
+        self.mark_modified();
+        let tail = self.content[end.y][end.x..].to_string();
+        self.content[start.y].truncate(start.x);
+        self.content[start.y].push_str(&tail);
+        self.content.drain(start.y + 1..=end.y);
+        self.cursor_position = start;
+        self.selection_anchor = None;
+    }
+
+    fn copy_selection(&mut self) -> bool {
+        let Some(text) = self.selected_text() else {
+            return false;
+        };
+        self.status_message = match write_clipboard(&text) {
+            Ok(()) => tr!("Copied {} characters", text.chars().count()),
+            Err(e) => e,
+        };
+        self.clipboard = text;
+        true
+    }
+
+    fn cut_selection(&mut self) {
+        if self.copy_selection() {
+            self.delete_selection();
+        }
+    }
+
+    // Системный буфер, а если он недоступен (ssh, нет графики) — то, что копировали здесь же
+    fn read_clipboard(&self) -> String {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .unwrap_or_else(|_| self.clipboard.clone())
+    }
+
+    // Вставка может быть многострочной; курсор встает после вставленного
+    fn paste_text(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if text.is_empty() {
+            return;
+        }
+        if self.selection_anchor.is_some() {
+            self.delete_selection();
+        } else {
+            self.push_undo();
+        }
+        self.mark_modified();
+        let line = &mut self.content[self.cursor_position.y];
+        let tail = line.split_off(self.cursor_position.x);
+        let mut lines = text.split('\n');
+        line.push_str(lines.next().unwrap_or_default());
+        for pasted in lines {
+            self.cursor_position.y += 1;
+            self.content.insert(self.cursor_position.y, pasted.to_string());
+        }
+        let line = &mut self.content[self.cursor_position.y];
+        self.cursor_position.x = line.len();
+        line.push_str(&tail);
+    }
+
+    fn process_search_keypress(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<(), io::Error> {
+        match (code, modifiers) {
+            (KeyCode::Enter, _) if self.replace_mode => {
+                self.replace_current();
+            }
+            (KeyCode::Char('a'), KeyModifiers::CONTROL) if self.replace_mode => {
+                self.replace_all();
+            }
+            (KeyCode::Tab, _) if self.replace_mode => {
+                self.editing_replacement = !self.editing_replacement;
+            }
+            (KeyCode::Esc, _) | (KeyCode::Enter, _) => {
+                self.exit_search_mode();
+            }
+            (KeyCode::Char('f'), KeyModifiers::CONTROL) | (KeyCode::F(3), KeyModifiers::NONE) => {
+                self.find_next_match();
+            }
+            (KeyCode::F(3), KeyModifiers::SHIFT) => {
+                self.find_previous_match();
+            }
+            (KeyCode::Backspace, _) => {
+                if self.editing_replacement {
+                    self.replace_query.pop();
+                } else if !self.search_query.is_empty() {
+                    self.search_query.pop();
+                    self.perform_search();
+                }
+            }
+            (KeyCode::Char(c), _) => {
+                if self.editing_replacement {
+                    self.replace_query.push(c);
+                } else {
+                    self.search_query.push(c);
+                    self.perform_search();
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn enter_search_mode(&mut self) {
+        self.selection_anchor = None;
+        self.search_mode = true;
+        self.search_query.clear();
+        self.search_matches.clear();
+        self.current_match = 0;
+        self.status_message = tr!("Search mode: Type to search, Enter to exit").to_string();
+    }
+
+    fn enter_replace_mode(&mut self) {
+        self.enter_search_mode();
+        self.replace_mode = true;
+        self.editing_replacement = false;
+        self.status_message = tr!("Replace mode: Tab = switch field, Enter = replace, Ctrl-A = replace all, Esc = exit").to_string();
+    }
+
+    // Найденное остается подсвеченным: по нему можно ходить F3 и Shift + F3
+    fn exit_search_mode(&mut self) {
+        self.search_mode = false;
+        self.replace_mode = false;
+        self.editing_replacement = false;
+        self.status_message = if self.search_matches.is_empty() {
+            tr!(HELP_MESSAGE).to_string()
+        } else {
+            tr!("{} matches: F3 = next, Shift-F3 = previous, Esc = clear", self.search_matches.len())
+        };
+    }
+
+    // Байтовое смещение начала строки в тексте, склеенном через "\n"
+    fn line_offset(&self, line: usize) -> usize {
+        self.content[..line].iter().map(|l| l.len() + 1).sum()
+    }
+
+    fn mark_modified(&mut self) {
+        self.modified = true;
+        self.matches_stale = !self.search_query.is_empty();
+    }
+
+    fn push_undo(&mut self) {
+        self.undo_stack.push(UndoEntry {
+            content: self.content.clone(),
+            cursor_x: self.cursor_position.x,
+            cursor_y: self.cursor_position.y,
+        });
+    }
+
+    fn undo(&mut self) {
+        if let Some(entry) = self.undo_stack.pop() {
+            self.content = entry.content;
+            self.mark_modified();
+            self.cursor_position.x = entry.cursor_x;
+            self.cursor_position.y = entry.cursor_y;
+            self.status_message = tr!("Undo").to_string();
+        } else {
+            self.status_message = tr!("Nothing to undo").to_string();
+        }
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.content = text.split('\n').map(String::from).collect();
+        self.mark_modified();
+    }
+
+    fn replace_current(&mut self) {
+        if self.current_match >= self.search_matches.len() {
+            return;
+        }
+
+        let mat = self.search_matches[self.current_match].clone();
+        let start = self.line_offset(mat.line) + mat.start;
+        let end = self.line_offset(mat.end_line) + mat.end;
+        let replacement = unescape_pattern(&self.replace_query);
+
+        self.push_undo();
+        let mut text = self.content.join("\n");
+        text.replace_range(start..end, &replacement);
+        self.set_text(&text);
+
+        // Пересчитываем совпадения и переходим к первому после замененного текста
+        self.perform_search();
+        let resume_at = start + replacement.len();
+        let next = self
+            .search_matches
+            .iter()
+            .position(|m| self.line_offset(m.line) + m.start >= resume_at)
+            .unwrap_or(0);
+        if self.search_matches.is_empty() {
+            self.cursor_position.y = mat.line;
+            self.cursor_position.x = mat.start;
+        } else {
+            self.jump_to_match(next);
+        }
+        self.status_message = tr!("Replaced 1 occurrence").to_string();
+    }
+
+    fn replace_all(&mut self) {
+        let count = self.search_matches.len();
+        if count == 0 {
+            return;
+        }
+
+        let pattern = unescape_pattern(&self.search_query);
+        let replacement = unescape_pattern(&self.replace_query);
+
+        self.push_undo();
+        let text = self.content.join("\n").replace(&pattern, &replacement);
+        self.set_text(&text);
+
+        self.cursor_position.y = self.cursor_position.y.min(self.content.len() - 1);
+        let column = self.cursor_position.x.min(self.current_line().len());
+        self.cursor_position.x = floor_boundary(self.current_line(), column);
+        self.perform_search();
+        self.status_message = tr!("Replaced {} occurrences", count);
+    }
+
+    fn perform_search(&mut self) {
+        self.find_matches();
+        if !self.search_matches.is_empty() {
+            self.jump_to_match(0);
+        }
+    }
+
+    // После правки совпадения ищем заново, но курсор не двигаем:
+    // текущим становится первое совпадение от курсора
+    fn refresh_matches(&mut self) {
+        self.find_matches();
+        let cursor = (self.cursor_position.y, self.cursor_position.x);
+        self.current_match = self
+            .search_matches
+            .iter()
+            .position(|m| (m.line, m.start) >= cursor)
+            .unwrap_or(0);
+    }
+
+    fn find_matches(&mut self) {
+        self.search_matches.clear();
+        self.current_match = 0;
+        self.matches_stale = false;
+
+        if self.search_query.is_empty() {
+            return;
+        }
+
+        // Ищем по всему тексту сразу, чтобы запрос с \n находил совпадения через переносы строк
+        let pattern = unescape_pattern(&self.search_query);
+        if pattern.is_empty() {
+            return;
+        }
+        let text = self.content.join("\n");
+
+        // Байтовое смещение начала каждой строки в объединенном тексте
+        let mut line_starts = Vec::with_capacity(self.content.len());
+        let mut offset = 0;
+        for line in &self.content {
+            line_starts.push(offset);
+            offset += line.len() + 1;
+        }
+        let position_of = |byte_pos: usize| {
+            let line = line_starts.partition_point(|&s| s <= byte_pos) - 1;
+            (line, byte_pos - line_starts[line])
+        };
+
+        let mut start = 0;
+        while let Some(pos) = text[start..].find(&pattern) {
+            let absolute_pos = start + pos;
+            let end_pos = absolute_pos + pattern.len();
+            let (line, col) = position_of(absolute_pos);
+            let (end_line, end_col) = position_of(end_pos);
+            self.search_matches.push(Match {
+                line,
+                start: col,
+                end_line,
+                end: end_col,
+            });
+            start = end_pos;
+        }
+    }
+
+    fn find_next_match(&mut self) {
+        if !self.search_matches.is_empty() {
+            self.current_match = (self.current_match + 1) % self.search_matches.len();
+            self.jump_to_match(self.current_match);
+        }
+    }
+
+    fn find_previous_match(&mut self) {
+        if !self.search_matches.is_empty() {
+            let count = self.search_matches.len();
+            self.jump_to_match((self.current_match + count - 1) % count);
+        }
+    }
+
+    // Вне режима поиска курсор мог уйти от текущего совпадения — ищем ближайшее от него, по кругу
+    fn jump_from_cursor(&mut self, forward: bool) {
+        if self.search_matches.is_empty() {
+            self.status_message = tr!("No search results: press Ctrl-F to search").to_string();
+            return;
+        }
+        let cursor = (self.cursor_position.y, self.cursor_position.x);
+        let position = |m: &Match| (m.line, m.start);
+        let target = if forward {
+            self.search_matches.iter().position(|m| position(m) > cursor).unwrap_or(0)
+        } else {
+            self.search_matches
+                .iter()
+                .rposition(|m| position(m) < cursor)
+                .unwrap_or(self.search_matches.len() - 1)
+        };
+        self.jump_to_match(target);
+        self.status_message = tr!("Match {} of {}", target + 1, self.search_matches.len());
+    }
+
+    fn jump_to_match(&mut self, match_index: usize) {
+        if match_index < self.search_matches.len() {
+            let mat = &self.search_matches[match_index];
+            self.cursor_position.y = mat.line;
+            self.cursor_position.x = mat.start;
+            self.current_match = match_index;
+        }
+    }
+
+    fn current_line(&self) -> &str {
+        &self.content[self.cursor_position.y]
+    }
+
+    // Переход на другую строку сохраняет столбец на экране, а не номер байта
+    fn move_to_line(&mut self, y: usize) {
+        let column = self.current_line()[..self.cursor_position.x].width();
+        self.cursor_position.y = y;
+        self.cursor_position.x = byte_at_column(self.current_line(), column);
+    }
+
+    fn scroll_page_up(&mut self) {
+        let visible_lines = self.visible_lines();
+        if self.scroll_offset >= visible_lines {
+            self.scroll_offset -= visible_lines;
+            self.move_to_line(self.cursor_position.y.saturating_sub(visible_lines));
         }
     }
 
     fn scroll_page_down(&mut self) {
-        let visible_lines = (self.terminal_size.1 - 2) as usize;
+        let visible_lines = self.visible_lines();
         self.scroll_offset += visible_lines;
         if self.scroll_offset > self.content.len().saturating_sub(visible_lines) {
             self.scroll_offset = self.content.len().saturating_sub(visible_lines);
         }
-        self.cursor_position.y = (self.cursor_position.y + visible_lines).min(self.content.len() - 1);
-        let current_line_len = self.content[self.cursor_position.y].len();
-        self.cursor_position.x = self.cursor_position.x.min(current_line_len);
+        self.move_to_line((self.cursor_position.y + visible_lines).min(self.content.len() - 1));
     }
 
     fn insert_char(&mut self, c: char) {
@@ -396,21 +1558,26 @@ impl Editor {
         
         if self.cursor_position.x <= current_line.len() {
             current_line.insert(self.cursor_position.x, c);
-            self.cursor_position.x += 1;
+            self.cursor_position.x += c.len_utf8();
+            self.mark_modified();
         }
     }
 
+    // Backspace стирает графему целиком: букву с ударением или флаг из нескольких символов
     fn delete_char(&mut self) {
         if self.cursor_position.x > 0 {
             let current_line = &mut self.content[self.cursor_position.y];
-            current_line.remove(self.cursor_position.x - 1);
-            self.cursor_position.x -= 1;
+            let start = prev_boundary(current_line, self.cursor_position.x);
+            current_line.replace_range(start..self.cursor_position.x, "");
+            self.cursor_position.x = start;
+            self.mark_modified();
         } else if self.cursor_position.y > 0 {
             let current_line = self.content.remove(self.cursor_position.y);
             self.cursor_position.y -= 1;
             let prev_line = &mut self.content[self.cursor_position.y];
             self.cursor_position.x = prev_line.len();
             prev_line.push_str(&current_line);
+            self.mark_modified();
         }
     }
 
@@ -420,11 +1587,41 @@ impl Editor {
         self.content.insert(self.cursor_position.y + 1, new_line);
         self.cursor_position.y += 1;
         self.cursor_position.x = 0;
+        self.mark_modified();
+    }
+
+    // К началу слова слева; из начала строки — в конец предыдущей
+    fn move_word_left(&mut self) {
+        if self.cursor_position.x == 0 {
+            self.move_cursor_left();
+        } else {
+            self.cursor_position.x = prev_word_start(self.current_line(), self.cursor_position.x);
+        }
+    }
+
+    // К концу слова справа; из конца строки — в начало следующей
+    fn move_word_right(&mut self) {
+        if self.cursor_position.x == self.current_line().len() {
+            self.move_cursor_right();
+        } else {
+            self.cursor_position.x = next_word_end(self.current_line(), self.cursor_position.x);
+        }
+    }
+
+    // Строки считаем с единицы; номер больше последней строки ведет на последнюю
+    fn go_to_line(&mut self, input: &str) {
+        match input.trim().parse::<usize>() {
+            Ok(line) if line > 0 => {
+                self.cursor_position = CursorPosition { x: 0, y: (line - 1).min(self.content.len() - 1) };
+                self.status_message = tr!("Line {}", self.cursor_position.y + 1);
+            }
+            _ => self.status_message = tr!("Not a line number: {}", input),
+        }
     }
 
     fn move_cursor_left(&mut self) {
         if self.cursor_position.x > 0 {
-            self.cursor_position.x -= 1;
+            self.cursor_position.x = prev_boundary(self.current_line(), self.cursor_position.x);
         } else if self.cursor_position.y > 0 {
             self.cursor_position.y -= 1;
             self.cursor_position.x = self.content[self.cursor_position.y].len();
@@ -432,53 +1629,775 @@ impl Editor {
     }
 
     fn move_cursor_right(&mut self) {
-        let current_line_len = self.content[self.cursor_position.y].len();
+        let current_line_len = self.current_line().len();
         if self.cursor_position.x < current_line_len {
-            self.cursor_position.x += 1;
+            self.cursor_position.x = next_boundary(self.current_line(), self.cursor_position.x);
         } else if self.cursor_position.y < self.content.len() - 1 {
             self.cursor_position.y += 1;
             self.cursor_position.x = 0;
         }
     }
 
+    // С переносом вверх и вниз ходим по строкам экрана, а не по строкам текста
     fn move_cursor_up(&mut self) {
-        if self.cursor_position.y > 0 {
-            self.cursor_position.y -= 1;
-            let current_line_len = self.content[self.cursor_position.y].len();
-            self.cursor_position.x = self.cursor_position.x.min(current_line_len);
+        if self.soft_wrap {
+            let rows = wrap_rows(self.current_line(), self.wrap_width());
+            let row = row_of(&rows, self.cursor_position.x);
+            let column = self.current_line()[rows[row].0..self.cursor_position.x].width();
+            if row > 0 {
+                self.cursor_position.x = x_in_row(self.current_line(), &rows, row - 1, column);
+            } else if self.cursor_position.y > 0 {
+                self.cursor_position.y -= 1;
+                let rows = wrap_rows(self.current_line(), self.wrap_width());
+                self.cursor_position.x = x_in_row(self.current_line(), &rows, rows.len() - 1, column);
+            }
+        } else if self.cursor_position.y > 0 {
+            self.move_to_line(self.cursor_position.y - 1);
         }
     }
 
     fn move_cursor_down(&mut self) {
-        if self.cursor_position.y < self.content.len() - 1 {
-            self.cursor_position.y += 1;
-            let current_line_len = self.content[self.cursor_position.y].len();
-            self.cursor_position.x = self.cursor_position.x.min(current_line_len);
+        if self.soft_wrap {
+            let rows = wrap_rows(self.current_line(), self.wrap_width());
+            let row = row_of(&rows, self.cursor_position.x);
+            let column = self.current_line()[rows[row].0..self.cursor_position.x].width();
+            if row + 1 < rows.len() {
+                self.cursor_position.x = x_in_row(self.current_line(), &rows, row + 1, column);
+            } else if self.cursor_position.y < self.content.len() - 1 {
+                self.cursor_position.y += 1;
+                let rows = wrap_rows(self.current_line(), self.wrap_width());
+                self.cursor_position.x = x_in_row(self.current_line(), &rows, 0, column);
+            }
+        } else if self.cursor_position.y < self.content.len() - 1 {
+            self.move_to_line(self.cursor_position.y + 1);
         }
     }
 
+    // У безымянного файла сначала спрашиваем имя
     fn save_file(&mut self) -> Result<(), io::Error> {
-        let content = self.content.join("\n");
-        
-        if let Some(filename) = &self.filename {
-            fs::write(filename, content)?;
-            self.status_message = format!("Saved to {}", filename);
+        let Some(filename) = &self.filename else {
+            self.open_prompt(PromptKind::SaveAs);
+            return Ok(());
+        };
+        fs::write(filename, self.content.join("\n"))?;
+        self.status_message = tr!("Saved to {}", filename);
+        self.modified = false;
+        Ok(())
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind) {
+        // Для «Сохранить как» подставляем текущее имя, чтобы его было удобно поправить
+        let input = match kind {
+            PromptKind::SaveAs => self.filename.clone().unwrap_or_default(),
+            PromptKind::Open | PromptKind::SaveChanges | PromptKind::GoToLine => String::new(),
+        };
+        self.prompt = Some(Prompt { kind, input });
+    }
+
+    fn process_prompt_keypress(&mut self, code: KeyCode) {
+        let Some(prompt) = &mut self.prompt else {
+            return;
+        };
+        if prompt.kind == PromptKind::SaveChanges {
+            self.process_save_changes_keypress(code);
+            return;
+        }
+        match code {
+            KeyCode::Esc => {
+                self.prompt = None;
+                self.pending = None;
+                self.status_message = tr!("Cancelled").to_string();
+            }
+            KeyCode::Enter => {
+                let Some(prompt) = self.prompt.take() else {
+                    return;
+                };
+                if prompt.input.is_empty() {
+                    return;
+                }
+                let path = expand_home(&prompt.input).to_string_lossy().to_string();
+                match prompt.kind {
+                    PromptKind::SaveAs => {
+                        // Если записать не удалось, файл остается под прежним именем
+                        let previous = self.filename.replace(path.clone());
+                        if self.save_or_report() {
+                            // С новым расширением может смениться и синтаксис
+                            self.syntax = SyntaxHighlight::for_file(&path, self.colors.dark);
+                        } else {
+                            self.filename = previous;
+                        }
+                    }
+                    PromptKind::Open => self.open_buffer(&path),
+                    PromptKind::GoToLine => self.go_to_line(&prompt.input),
+                    PromptKind::SaveChanges => {}
+                }
+            }
+            KeyCode::Tab if prompt.kind != PromptKind::GoToLine => {
+                let (completed, candidates) = complete_path(&prompt.input);
+                prompt.input = completed;
+                if candidates.len() > 1 {
+                    self.status_message = candidates.join("  ");
+                }
+            }
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Char(c) => prompt.input.push(c),
+            _ => {}
+        }
+    }
+
+    // y — сохранить и продолжить, n — продолжить без сохранения, Esc — остаться в файле
+    fn process_save_changes_keypress(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y' | 'Y') => {
+                self.prompt = None;
+                if self.filename.is_none() {
+                    // Действие выполнится после ввода имени
+                    self.open_prompt(PromptKind::SaveAs);
+                } else {
+                    self.save_or_report();
+                }
+            }
+            // Отказ от сохранения закрывает буфер: при выходе о нем больше не спросим
+            KeyCode::Char('n' | 'N') => {
+                self.prompt = None;
+                self.close_buffer();
+                if matches!(self.pending, Some(PendingAction::Close)) {
+                    self.pending = None;
+                }
+                self.run_pending();
+            }
+            KeyCode::Esc => {
+                self.prompt = None;
+                self.pending = None;
+                self.status_message = tr!("Cancelled").to_string();
+            }
+            _ => {}
+        }
+    }
+
+    // Ошибку сохранения показываем в строке состояния; отложенное действие выполняем только после успеха
+    fn save_or_report(&mut self) -> bool {
+        match self.save_file() {
+            Ok(()) => {
+                self.run_pending();
+                true
+            }
+            Err(e) => {
+                self.pending = None;
+                self.status_message = tr!("Error: {}", e);
+                false
+            }
+        }
+    }
+
+    // Несохраненные изменения не выбрасываем молча: сначала спрашиваем
+    fn run_or_ask(&mut self, action: PendingAction) {
+        self.pending = Some(action);
+        if self.modified {
+            self.open_prompt(PromptKind::SaveChanges);
         } else {
-            self.filename = Some("output.txt".to_string());
-            fs::write("output.txt", content)?;
-            self.status_message = String::from("Saved to output.txt");
+            self.run_pending();
         }
-        Ok(())
     }
 
-    pub fn open_file(mut self, filename: &str) -> Result<Self, io::Error> {
-        let content = fs::read_to_string(filename)?;
+    // Перед выходом по очереди показываем все буферы с несохраненными изменениями
+    fn run_pending(&mut self) {
+        match self.pending.take() {
+            Some(PendingAction::Quit) => match self.buffers.iter().position(|buffer| buffer.modified) {
+                Some(index) => {
+                    self.switch_buffer(index);
+                    self.run_or_ask(PendingAction::Quit);
+                }
+                None => self.should_quit = true,
+            },
+            Some(PendingAction::Close) => self.close_buffer(),
+            None => {}
+        }
+    }
+
+    // Enter — перейти к буферу, Delete — закрыть его, Esc — вернуться к тексту
+    fn process_buffer_list_keypress(&mut self, code: KeyCode) {
+        let Some(selected) = self.buffer_list else {
+            return;
+        };
+        match code {
+            KeyCode::Up => self.buffer_list = Some(selected.saturating_sub(1)),
+            KeyCode::Down => self.buffer_list = Some((selected + 1).min(self.buffers.len() - 1)),
+            KeyCode::Enter => {
+                self.buffer_list = None;
+                self.switch_buffer(selected);
+            }
+            KeyCode::Delete => {
+                self.switch_buffer(selected);
+                // На вопрос о сохранении отвечают, глядя на сам текст
+                if self.modified {
+                    self.buffer_list = None;
+                }
+                self.run_or_ask(PendingAction::Close);
+                if self.buffer_list.is_some() {
+                    self.buffer_list = Some(self.active_buffer);
+                }
+            }
+            KeyCode::Esc => self.buffer_list = None,
+            _ => {}
+        }
+    }
+
+    // Состояние активного буфера уходит из полей редактора в Buffer
+    fn take_buffer(&mut self) -> Buffer {
+        Buffer {
+            content: mem::take(&mut self.content),
+            cursor_position: self.cursor_position,
+            filename: self.filename.take(),
+            modified: self.modified,
+            scroll_offset: self.scroll_offset,
+            horizontal_offset: self.horizontal_offset,
+            search_query: mem::take(&mut self.search_query),
+            search_matches: mem::take(&mut self.search_matches),
+            current_match: self.current_match,
+            undo_stack: mem::take(&mut self.undo_stack),
+            selection_anchor: self.selection_anchor.take(),
+            syntax: self.syntax.take(),
+        }
+    }
+
+    fn restore_buffer(&mut self, buffer: Buffer) {
+        self.content = buffer.content;
+        self.cursor_position = buffer.cursor_position;
+        self.filename = buffer.filename;
+        self.modified = buffer.modified;
+        self.scroll_offset = buffer.scroll_offset;
+        self.horizontal_offset = buffer.horizontal_offset;
+        self.search_query = buffer.search_query;
+        self.search_matches = buffer.search_matches;
+        self.current_match = buffer.current_match;
+        self.matches_stale = false;
+        self.undo_stack = buffer.undo_stack;
+        self.selection_anchor = buffer.selection_anchor;
+        self.syntax = buffer.syntax;
+        self.drag_start = None;
+    }
+
+    fn switch_buffer(&mut self, index: usize) {
+        if index == self.active_buffer || index >= self.buffers.len() {
+            return;
+        }
+        self.buffers[self.active_buffer] = self.take_buffer();
+        let buffer = mem::take(&mut self.buffers[index]);
+        self.restore_buffer(buffer);
+        self.active_buffer = index;
+        self.status_message = display_name(self.filename.as_deref()).to_string();
+    }
+
+    fn cycle_buffer(&mut self, forward: bool) {
+        let count = self.buffers.len();
+        if count == 1 {
+            self.status_message = tr!("Only one buffer is open").to_string();
+            return;
+        }
+        let offset = if forward { 1 } else { count - 1 };
+        self.switch_buffer((self.active_buffer + offset) % count);
+    }
+
+    // Новый буфер встает в конец списка и становится активным
+    fn add_buffer(&mut self, buffer: Buffer) {
+        self.buffers[self.active_buffer] = self.take_buffer();
+        self.buffers.push(Buffer::default());
+        self.active_buffer = self.buffers.len() - 1;
+        self.restore_buffer(buffer);
+    }
+
+    // Последний буфер не закрывается, а становится пустым безымянным; иначе переходим к соседнему слева
+    fn close_buffer(&mut self) {
+        if self.buffers.len() == 1 {
+            self.restore_buffer(Buffer::empty());
+            return;
+        }
+        self.buffers.remove(self.active_buffer);
+        let index = self.active_buffer.saturating_sub(1);
+        let buffer = mem::take(&mut self.buffers[index]);
+        self.restore_buffer(buffer);
+        self.active_buffer = index;
+    }
+
+    // Пустой безымянный буфер, как при запуске без файла: его можно занять открываемым файлом
+    fn is_blank(&self) -> bool {
+        self.filename.is_none() && !self.modified && self.content.len() == 1 && self.content[0].is_empty()
+    }
+
+    // Уже открытый файл второй раз не открываем, а переключаемся на него
+    fn open_buffer(&mut self, path: &str) {
+        if self.filename.as_deref() == Some(path) {
+            return;
+        }
+        if let Some(index) = self.buffers.iter().position(|buffer| buffer.filename.as_deref() == Some(path)) {
+            self.switch_buffer(index);
+            return;
+        }
+        let origin = (!self.is_blank()).then_some(self.active_buffer);
+        if origin.is_some() {
+            self.add_buffer(Buffer::empty());
+        }
+        if let Err(e) = self.load_file(path) {
+            if let Some(origin) = origin {
+                self.close_buffer();
+                self.switch_buffer(origin);
+            }
+            self.status_message = tr!("Error: {}", e);
+        }
+    }
+
+    // Несуществующий файл — новый документ: он появится при первом сохранении
+    fn load_file(&mut self, filename: &str) -> Result<(), io::Error> {
+        let (content, status) = match fs::read_to_string(filename) {
+            Ok(content) => (content, tr!("Opened {}", filename)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (String::new(), tr!("New file {}", filename)),
+            Err(e) => return Err(e),
+        };
         self.content = content.lines().map(String::from).collect();
         if self.content.is_empty() {
             self.content.push(String::new());
         }
+        self.status_message = status;
         self.filename = Some(filename.to_string());
-        self.status_message = format!("Opened {}", filename);
+        self.modified = false;
+        self.cursor_position = CursorPosition::default();
+        self.scroll_offset = 0;
+        self.horizontal_offset = 0;
+        self.selection_anchor = None;
+        self.search_matches.clear();
+        self.undo_stack.clear();
+        self.syntax = SyntaxHighlight::for_file(filename, self.colors.dark);
+        Ok(())
+    }
+
+    // Файлы из командной строки открываются каждый в своем буфере; активным остается первый
+    pub fn open_file(mut self, filename: &str) -> Result<Self, io::Error> {
+        if self.is_blank() {
+            self.load_file(filename)?;
+            return Ok(self);
+        }
+        let origin = self.active_buffer;
+        self.add_buffer(Buffer::empty());
+        self.load_file(filename)?;
+        self.switch_buffer(origin);
         Ok(self)
     }
-}
\ No newline at end of file
+}
+
+// Подсветка синтаксиса по расширению файла. Цвета считаются по строкам сверху вниз и запоминаются:
+// после правки пересчитываются только строки от первой измененной до конца экрана
+struct SyntaxHighlight {
+    syntax_set: SyntaxSet,
+    syntax: SyntaxReference,
+    theme: Theme,
+    depth: ColorDepth,
+    lines: Vec<String>,                        // Строки, для которых посчитаны цвета
+    states: Vec<(ParseState, HighlightState)>, // Состояние разбора после каждой из них
+    spans: Vec<Vec<(usize, usize, Color)>>,    // Участки строк в байтах и их цвет
+}
+
+impl SyntaxHighlight {
+    fn for_file(filename: &str, dark: bool) -> Option<Self> {
+        let extension = Path::new(filename).extension()?.to_str()?;
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let syntax = syntax_set.find_syntax_by_extension(extension)?.clone();
+        // Обычный текст красить нечем
+        if syntax.name == "Plain Text" {
+            return None;
+        }
+        let theme_name = if dark { "base16-ocean.dark" } else { "InspiredGitHub" };
+        let theme = ThemeSet::load_defaults().themes.remove(theme_name)?;
+        Some(Self {
+            syntax_set,
+            syntax,
+            theme,
+            depth: ColorDepth::detect(),
+            lines: Vec::new(),
+            states: Vec::new(),
+            spans: Vec::new(),
+        })
+    }
+
+    // Досчитывает цвета до строки end (не включая); все после первой измененной строки считается заново
+    fn update(&mut self, content: &[String], end: usize) {
+        let changed = self
+            .lines
+            .iter()
+            .zip(content)
+            .position(|(old, new)| old != new)
+            .unwrap_or(self.lines.len().min(content.len()));
+        self.lines.truncate(changed);
+        self.states.truncate(changed);
+        self.spans.truncate(changed);
+
+        let highlighter = Highlighter::new(&self.theme);
+        for text in content.iter().take(end).skip(changed) {
+            let (mut parse, mut highlight) = match self.states.last() {
+                Some(state) => state.clone(),
+                None => (ParseState::new(&self.syntax), HighlightState::new(&highlighter, ScopeStack::new())),
+            };
+            // Синтаксисы рассчитаны на строки с переводом строки в конце
+            let line = format!("{}\n", text);
+            // Строку, на которой разбор споткнулся, оставляем без цвета.
+            // Соседние участки одного цвета склеиваем, чтобы не повторять один и тот же цвет в выводе
+            let mut spans: Vec<(usize, usize, Color)> = Vec::new();
+            if let Ok(ops) = parse.parse_line(&line, &self.syntax_set) {
+                for (style, _, range) in RangedHighlightIterator::new(&mut highlight, &ops, &line, &highlighter) {
+                    let (start, end) = (range.start, range.end.min(text.len()));
+                    let color = self.depth.color(style.foreground);
+                    match spans.last_mut() {
+                        _ if start >= end => {}
+                        Some(last) if last.1 == start && last.2 == color => last.1 = end,
+                        _ => spans.push((start, end, color)),
+                    }
+                }
+            }
+            self.lines.push(text.clone());
+            self.states.push((parse, highlight));
+            self.spans.push(spans);
+        }
+    }
+
+    // Цвета годятся, только если строка с тех пор не менялась
+    fn spans(&self, line_num: usize, line: &str) -> &[(usize, usize, Color)] {
+        match (self.lines.get(line_num), self.spans.get(line_num)) {
+            (Some(cached), Some(spans)) if cached == line => spans,
+            _ => &[],
+        }
+    }
+}
+
+// Сколько цветов умеет терминал: 24-битные цвета темы сводим к палитре на 256 или 16 цветов
+#[derive(Clone, Copy)]
+enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    // COLORTERM выставляют терминалы с 24-битным цветом, а 256 цветов обычно видно по TERM
+    fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    fn color(self, color: syntect::highlighting::Color) -> Color {
+        let rgb = [color.r, color.g, color.b];
+        match self {
+            ColorDepth::TrueColor => Color::Rgb { r: color.r, g: color.g, b: color.b },
+            ColorDepth::Ansi256 => Color::AnsiValue(ansi256(rgb)),
+            ColorDepth::Ansi16 => ansi16(rgb),
+        }
+    }
+}
+
+fn color_distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter().zip(b).map(|(&x, y)| (x.abs_diff(y) as u32).pow(2)).sum()
+}
+
+// Ближайший цвет из куба 6×6×6 или серой шкалы палитры на 256 цветов
+fn ansi256(rgb: [u8; 3]) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |value: u8| (0..6).min_by_key(|&index| LEVELS[index].abs_diff(value)).unwrap_or(0);
+    let [r, g, b] = rgb.map(level);
+    let cube = [LEVELS[r], LEVELS[g], LEVELS[b]];
+    let average = rgb.iter().map(|&value| value as usize).sum::<usize>() / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23);
+    let gray = (8 + 10 * gray_index) as u8;
+    if color_distance(rgb, cube) <= color_distance(rgb, [gray; 3]) {
+        (16 + 36 * r + 6 * g + b) as u8
+    } else {
+        (232 + gray_index) as u8
+    }
+}
+
+// Один из 16 стандартных цветов терминала. Ближайший по расстоянию почти всегда серый —
+// у тем приглушенные цвета, — поэтому сравниваем оттенок: какие каналы заметно ярче остальных
+fn ansi16(rgb: [u8; 3]) -> Color {
+    let max = rgb.iter().copied().max().unwrap_or(0);
+    let min = rgb.iter().copied().min().unwrap_or(0);
+    if max - min < 30 {
+        return match max {
+            0..=63 => Color::Black,
+            64..=149 => Color::DarkGrey,
+            150..=219 => Color::Grey,
+            _ => Color::White,
+        };
+    }
+    let threshold = min as u32 + (max - min) as u32 * 3 / 5;
+    let [r, g, b] = rgb.map(|value| value as u32 >= threshold);
+    let bright = max > 200;
+    match (r, g, b, bright) {
+        (true, false, false, true) => Color::Red,
+        (true, false, false, false) => Color::DarkRed,
+        (false, true, false, true) => Color::Green,
+        (false, true, false, false) => Color::DarkGreen,
+        (true, true, false, true) => Color::Yellow,
+        (true, true, false, false) => Color::DarkYellow,
+        (false, false, true, true) => Color::Blue,
+        (false, false, true, false) => Color::DarkBlue,
+        (true, false, true, true) => Color::Magenta,
+        (true, false, true, false) => Color::DarkMagenta,
+        (false, true, true, true) => Color::Cyan,
+        (false, true, true, false) => Color::DarkCyan,
+        _ => Color::Grey,
+    }
+}
+
+impl Prompt {
+    // При нескольких буферах в вопросе о сохранении важно, о каком файле речь
+    fn label(&self, filename: &str) -> String {
+        match self.kind {
+            PromptKind::SaveAs => tr!("Save as: ").to_string(),
+            PromptKind::Open => tr!("Open: ").to_string(),
+            PromptKind::SaveChanges => tr!("{}: save changes? (y/n/esc)", filename),
+            PromptKind::GoToLine => tr!("Go to line: ").to_string(),
+        }
+    }
+}
+
+fn display_name(filename: Option<&str>) -> &str {
+    filename.unwrap_or(tr!("[No Name]"))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+// Дополнение пути по Tab: единственный вариант подставляется целиком (у папки — с «/» в конце),
+// при нескольких — их общее начало. Вторым значением возвращаются все подходящие имена
+fn complete_path(input: &str) -> (String, Vec<String>) {
+    let (dir, prefix) = match input.rfind('/') {
+        Some(slash) => (&input[..=slash], &input[slash + 1..]),
+        None => ("", input),
+    };
+    let listed = if dir.is_empty() { PathBuf::from(".") } else { expand_home(dir) };
+    let Ok(entries) = fs::read_dir(&listed) else {
+        return (input.to_string(), Vec::new());
+    };
+    let mut candidates: Vec<String> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let mut name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            if Path::new(&listed).join(&name).is_dir() {
+                name.push('/');
+            }
+            Some(name)
+        })
+        .collect();
+    candidates.sort();
+
+    let Some(first) = candidates.first() else {
+        return (input.to_string(), candidates);
+    };
+    let mut common = first.clone();
+    for candidate in &candidates[1..] {
+        let length = common
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(c, _)| c.len_utf8())
+            .sum();
+        common.truncate(length);
+    }
+    (format!("{}{}", dir, common), candidates)
+}
+
+fn action_for(code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+    // Shift + Tab приходит отдельной клавишей BackTab, а буква с Ctrl + Shift — иногда заглавной
+    let (code, modifiers) = match code {
+        KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+        KeyCode::Char(c) => (KeyCode::Char(c.to_ascii_lowercase()), modifiers),
+        _ => (code, modifiers),
+    };
+    KEYMAP
+        .iter()
+        .find(|&&(key, bound, _)| key == code && bound == modifiers)
+        .map(|&(_, _, action)| action)
+}
+
+// Название сочетания для справки: «Ctrl-Shift-S», «Shift-F3»
+fn key_name(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let mut name = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl-");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt-");
+    }
+    if modifiers.contains(KeyModifiers::SHIFT) || code == KeyCode::BackTab {
+        name.push_str("Shift-");
+    }
+    let key = match code {
+        KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+        KeyCode::F(number) => format!("F{}", number),
+        KeyCode::Tab | KeyCode::BackTab => "Tab".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        other => format!("{:?}", other),
+    };
+    name.push_str(&key);
+    name
+}
+
+// Выключить все, что включает Editor::run; ошибки здесь уже некуда сообщить
+fn restore_terminal() {
+    let _ = execute!(io::stdout(), DisableMouseCapture, DisableBracketedPaste, LeaveAlternateScreen, Show);
+    let _ = disable_raw_mode();
+}
+
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+// Слово — отрезок между границами слов Unicode, в котором есть буква или цифра
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+// Пробелы и знаки препинания перед словом перескакиваем
+fn prev_word_start(line: &str, x: usize) -> usize {
+    line.split_word_bound_indices()
+        .rev()
+        .find(|&(start, segment)| start < x && is_word(segment))
+        .map_or(0, |(start, _)| start)
+}
+
+fn next_word_end(line: &str, x: usize) -> usize {
+    line.split_word_bound_indices()
+        .map(|(start, segment)| (start + segment.len(), segment))
+        .find(|&(end, segment)| end > x && is_word(segment))
+        .map_or(line.len(), |(end, _)| end)
+}
+
+fn prev_boundary(line: &str, x: usize) -> usize {
+    line[..x].grapheme_indices(true).next_back().map_or(0, |(index, _)| index)
+}
+
+fn next_boundary(line: &str, x: usize) -> usize {
+    line[x..].graphemes(true).next().map_or(x, |grapheme| x + grapheme.len())
+}
+
+// Ближайшая граница графемы не правее x
+fn floor_boundary(line: &str, x: usize) -> usize {
+    if x >= line.len() {
+        return line.len();
+    }
+    line.grapheme_indices(true)
+        .map(|(index, _)| index)
+        .take_while(|&index| index <= x)
+        .last()
+        .unwrap_or(0)
+}
+
+// Разбивка строки на строки экрана шириной width: по пробелам, а слово длиннее строки — посимвольно.
+// Пробел, на котором перенесли, остается в конце предыдущей строки экрана, поэтому
+// ширина ее может быть на столбец больше width — для этого wrap_width оставляет запас
+fn wrap_rows(line: &str, width: usize) -> Vec<(usize, usize)> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut column = 0;
+    let mut last_break = None; // Байт сразу после последнего пробела в текущей строке экрана
+    for (index, grapheme) in line.grapheme_indices(true) {
+        let grapheme_width = grapheme.width();
+        // Пробел может повиснуть в запасном последнем столбце, не перенося слово перед ним
+        let hanging = grapheme.chars().all(char::is_whitespace) && column + grapheme_width <= width + 1;
+        if column + grapheme_width > width && index > start && !hanging {
+            let end = last_break.unwrap_or(index);
+            rows.push((start, end));
+            start = end;
+            column = line[start..index].width();
+            last_break = None;
+        }
+        column += grapheme_width;
+        if grapheme.chars().all(char::is_whitespace) {
+            last_break = Some(index + grapheme.len());
+        }
+    }
+    rows.push((start, line.len()));
+    rows
+}
+
+// Строка экрана, на которой стоит x; на границе курсор относится к следующей
+fn row_of(rows: &[(usize, usize)], x: usize) -> usize {
+    rows.iter().rposition(|&(start, _)| start <= x).unwrap_or(0)
+}
+
+// Позиция в строке экрана row на столбце column; не дальше ее конца, иначе курсор уйдет на следующую
+fn x_in_row(line: &str, rows: &[(usize, usize)], row: usize, column: usize) -> usize {
+    let (start, end) = rows[row];
+    let x = start + byte_at_column(&line[start..end], column);
+    if x == end && row + 1 < rows.len() {
+        prev_boundary(line, end).max(start)
+    } else {
+        x
+    }
+}
+
+// Байты графем, целиком попадающих в столбцы от from до to
+fn column_range(line: &str, from: usize, to: usize) -> (usize, usize) {
+    let mut start = None;
+    let mut column = 0;
+    for (index, grapheme) in line.grapheme_indices(true) {
+        if start.is_none() && column >= from {
+            start = Some(index);
+        }
+        column += grapheme.width();
+        if column > to {
+            let start = start.unwrap_or(index);
+            return (start, index.max(start));
+        }
+    }
+    (start.unwrap_or(line.len()), line.len())
+}
+
+// Байт, с которого начинается графема в столбце column; широкий символ не режем пополам
+fn byte_at_column(line: &str, column: usize) -> usize {
+    let mut width = 0;
+    for (index, grapheme) in line.grapheme_indices(true) {
+        width += grapheme.width();
+        if width > column {
+            return index;
+        }
+    }
+    line.len()
+}
+
+// По ssh системный буфер — на машине пользователя: туда достает только OSC 52,
+// который понимают большинство современных терминалов
+fn write_clipboard(text: &str) -> Result<(), String> {
+    let over_ssh = std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some();
+    if !over_ssh {
+        if let Ok(()) = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+            return Ok(());
+        }
+    }
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)
+        .and_then(|_| stdout.flush())
+        .map_err(|e| tr!("Clipboard error: {}", e))
+}
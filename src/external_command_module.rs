@@ -0,0 +1,352 @@
+// external_command_module.rs
+use eframe::egui;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+const TOAST_DURATION: Duration = Duration::from_secs(8);
+const MAX_HISTORY: usize = 10;
+// Длинный stderr в сообщении обрезаем
+const MAX_TOAST_CHARS: usize = 600;
+// Сколько дочитывать вывод после выхода команды: запущенный ею фоновый процесс может держать трубу вечно
+const PIPE_GRACE: Duration = Duration::from_millis(500);
+
+struct CommandOutput {
+    stdout: String,
+    stderr: String,
+    status: Result<Option<i32>, String>, // Код выхода; Err — не запустилась или превышено время
+}
+
+// Запущенная команда: что отдали ей на вход и куда вернуть результат
+struct RunningCommand {
+    receiver: Receiver<CommandOutput>,
+    source: String,                // Текст документа на момент запуска
+    range: Option<(usize, usize)>, // Выделение в символах; None — весь документ
+    trailing_newline: bool,        // Был ли перевод строки в конце входа
+}
+
+#[derive(Default)]
+pub struct ExternalCommandModule {
+    pub show_window: bool,
+    command: String,
+    selection_only: bool,
+    history: Vec<String>,
+    running: Option<RunningCommand>,
+    toast: Option<(String, Instant)>,
+    focus_command: bool,
+}
+
+pub enum ExternalCommandResult {
+    None,
+    ConfigChanged,
+    // Заменить символы диапазона (None — весь документ) на вывод команды
+    Replace { range: Option<(usize, usize)>, text: String },
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+// Ввод пишется и вывод читается в отдельных потоках, иначе большой текст заблокирует трубу
+fn run_with_timeout(command: &str, input: String, timeout: Duration) -> CommandOutput {
+    let mut child = match shell_command(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            return CommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                status: Err(tr!("Не удалось запустить команду: {}", e)),
+            }
+        }
+    };
+
+    let mut stdin = child.stdin.take();
+    thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    // Вывод приходит кусками: после выхода команды забираем то, что успело прийти, не дожидаясь конца трубы
+    let read = |pipe: Option<Box<dyn Read + Send>>| {
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+        thread::spawn(move || {
+            let Some(mut pipe) = pipe else {
+                return;
+            };
+            let mut buffer = [0; 8192];
+            while let Ok(count) = pipe.read(&mut buffer) {
+                if count == 0 || sender.send(buffer[..count].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+        receiver
+    };
+    let stdout = read(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = read(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let started = Instant::now();
+    let status: Result<Option<i32>, String> = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status.code()),
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Вывод не ждем: процессы, запущенные оболочкой, могут еще держать трубы открытыми
+                return CommandOutput {
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    status: Err(tr!("Команда не завершилась за {} с и была остановлена", timeout.as_secs())),
+                };
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => break Err(e.to_string()),
+        }
+    };
+
+    let deadline = Instant::now() + PIPE_GRACE;
+    CommandOutput {
+        stdout: collect_output(&stdout, deadline),
+        stderr: collect_output(&stderr, deadline),
+        status,
+    }
+}
+
+fn collect_output(receiver: &Receiver<Vec<u8>>, deadline: Instant) -> String {
+    let mut bytes = Vec::new();
+    while let Ok(chunk) = receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        bytes.extend(chunk);
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+impl ExternalCommandModule {
+    pub fn open(&mut self, has_selection: bool) {
+        self.show_window = true;
+        self.selection_only = has_selection;
+        self.focus_command = true;
+    }
+
+    fn show_message(&mut self, message: String) {
+        let message = if message.chars().count() > MAX_TOAST_CHARS {
+            format!("{}…", message.chars().take(MAX_TOAST_CHARS).collect::<String>())
+        } else {
+            message
+        };
+        self.toast = Some((message, Instant::now()));
+    }
+
+    // source — весь текст, selection — выделение в символах, если есть
+    fn start(&mut self, source: &str, selection: Option<(usize, usize)>, timeout: Duration) {
+        let command = self.command.trim().to_string();
+        if command.is_empty() || self.running.is_some() {
+            return;
+        }
+        self.history.retain(|c| c != &command);
+        self.history.insert(0, command.clone());
+        self.history.truncate(MAX_HISTORY);
+
+        let range = selection.filter(|_| self.selection_only);
+        let input: String = match range {
+            Some((start, end)) => source.chars().skip(start).take(end - start).collect(),
+            None => source.to_string(),
+        };
+
+        let trailing_newline = input.ends_with('\n');
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(run_with_timeout(&command, input, timeout));
+        });
+        self.running = Some(RunningCommand {
+            receiver,
+            source: source.to_string(),
+            range,
+            trailing_newline,
+        });
+    }
+
+    // Проверяет, не закончилась ли команда; результат применяется, только если текст с тех пор не меняли
+    pub fn poll(&mut self, ctx: &egui::Context, text: &str) -> ExternalCommandResult {
+        self.show_toast(ctx);
+        let Some(running) = &self.running else {
+            return ExternalCommandResult::None;
+        };
+        let Ok(output) = running.receiver.try_recv() else {
+            ctx.request_repaint_after(Duration::from_millis(50));
+            return ExternalCommandResult::None;
+        };
+        let running = self.running.take().unwrap();
+
+        let stderr = output.stderr.trim();
+        match output.status {
+            Err(e) => {
+                self.show_message(e);
+                ExternalCommandResult::None
+            }
+            Ok(code) if code != Some(0) => {
+                let code = code.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string());
+                let mut message = tr!("Команда завершилась с кодом {}", code);
+                if !stderr.is_empty() {
+                    message = format!("{}\n{}", message, stderr);
+                }
+                self.show_message(message);
+                ExternalCommandResult::None
+            }
+            Ok(_) if running.source != text => {
+                self.show_message(tr!("Текст изменился, пока выполнялась команда; результат не вставлен").to_string());
+                ExternalCommandResult::None
+            }
+            Ok(_) => {
+                if !stderr.is_empty() {
+                    self.show_message(stderr.to_string());
+                }
+                self.show_window = false;
+                // sort и подобные дописывают перевод строки, которого во входе не было
+                let mut text = output.stdout;
+                if !running.trailing_newline && text.ends_with('\n') {
+                    text.pop();
+                    if text.ends_with('\r') {
+                        text.pop();
+                    }
+                }
+                ExternalCommandResult::Replace { range: running.range, text }
+            }
+        }
+    }
+
+    fn show_toast(&mut self, ctx: &egui::Context) {
+        let Some((message, shown)) = &self.toast else {
+            return;
+        };
+        if shown.elapsed() > TOAST_DURATION {
+            self.toast = None;
+            return;
+        }
+
+        egui::Area::new(egui::Id::new("external_command_toast"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -48.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(480.0);
+                    ui.monospace(message.as_str());
+                });
+            });
+        ctx.request_repaint_after(Duration::from_millis(500));
+    }
+
+    pub fn show_window(
+        &mut self,
+        ctx: &egui::Context,
+        text: &str,
+        selection: Option<(usize, usize)>,
+        config: &mut Config,
+    ) -> ExternalCommandResult {
+        let mut result = ExternalCommandResult::None;
+        if !self.show_window {
+            return result;
+        }
+        if selection.is_none() {
+            self.selection_only = false;
+        }
+
+        let mut open = self.show_window;
+        let mut run = false;
+        egui::Window::new(tr!("Выполнить команду"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(tr!("Текст передается команде на вход и заменяется ее выводом:"));
+                let command_id = ui.make_persistent_id("external_command");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.command)
+                        .id(command_id)
+                        .hint_text("sort | uniq")
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(f32::INFINITY),
+                );
+                if self.focus_command {
+                    ui.memory_mut(|m| m.request_focus(command_id));
+                    self.focus_command = false;
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    run = true;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.selection_only, false, tr!("Весь документ"));
+                    if ui
+                        .add_enabled(selection.is_some(), egui::RadioButton::new(self.selection_only, tr!("Выделение")))
+                        .clicked()
+                    {
+                        self.selection_only = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Ограничение времени:"));
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut config.command_timeout_secs)
+                                .clamp_range(1..=600)
+                                .suffix(tr!(" с")),
+                        )
+                        .changed()
+                    {
+                        result = ExternalCommandResult::ConfigChanged;
+                    }
+                });
+
+                if !self.history.is_empty() {
+                    egui::ComboBox::from_id_source("external_command_history")
+                        .selected_text(tr!("Недавние команды"))
+                        .width(ui.available_width())
+                        .show_ui(ui, |ui| {
+                            for command in &self.history {
+                                if ui.selectable_label(false, command).clicked() {
+                                    self.command = command.clone();
+                                }
+                            }
+                        });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let ready = !self.command.trim().is_empty() && self.running.is_none();
+                    if ui.add_enabled(ready, egui::Button::new(tr!("Выполнить"))).clicked() {
+                        run = true;
+                    }
+                    if self.running.is_some() {
+                        ui.spinner();
+                        ui.label(tr!("Выполняется..."));
+                    }
+                });
+            });
+
+        if run {
+            self.start(text, selection, Duration::from_secs(config.command_timeout_secs));
+        }
+        if !open {
+            self.show_window = false;
+        }
+
+        result
+    }
+}
@@ -0,0 +1,112 @@
+// fader.rs
+use rodio::Sink;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Длительность плавного включения, выключения и перехода между треками
+pub const FADE_DURATION: Duration = Duration::from_secs(1);
+// Как часто поток меняет громкость во время перехода
+const TICK: Duration = Duration::from_millis(20);
+
+// Что сделать с sink, когда громкость дошла до цели
+#[derive(Clone, Copy, PartialEq)]
+pub enum FadeEnd {
+    Keep,
+    Pause,
+    Stop,
+}
+
+struct Fade {
+    sink: Arc<Mutex<Sink>>,
+    from: f32,
+    to: f32,
+    started: Instant,
+    duration: Duration,
+    end: FadeEnd,
+}
+
+impl Fade {
+    // Возвращает true, когда переход закончен
+    fn step(&self) -> bool {
+        let progress = if self.duration.is_zero() {
+            1.0
+        } else {
+            (self.started.elapsed().as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        };
+        let Ok(sink) = self.sink.lock() else {
+            return true;
+        };
+        sink.set_volume(self.from + (self.to - self.from) * progress);
+        if progress < 1.0 {
+            return false;
+        }
+        match self.end {
+            FadeEnd::Keep => {}
+            FadeEnd::Pause => sink.pause(),
+            FadeEnd::Stop => sink.stop(),
+        }
+        true
+    }
+}
+
+// Поток, который плавно меняет громкость; затухающий трек он держит у себя, пока тот не стихнет
+pub struct Fader {
+    sender: Sender<Fade>,
+}
+
+impl Default for Fader {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let _ = thread::Builder::new()
+            .name("music-fader".to_string())
+            .spawn(move || run(receiver));
+        Self { sender }
+    }
+}
+
+impl Fader {
+    // Новый переход для того же sink заменяет предыдущий: пауза, прерванная продолжением, не сработает
+    pub fn fade(&self, sink: &Arc<Mutex<Sink>>, to: f32, duration: Duration, end: FadeEnd) {
+        let fade = Fade {
+            sink: Arc::clone(sink),
+            from: 0.0, // Текущую громкость поток прочитает сам
+            to,
+            started: Instant::now(),
+            duration,
+            end,
+        };
+        // Поток почему-то не запустился — меняем громкость сразу
+        if let Err(mpsc::SendError(fade)) = self.sender.send(fade) {
+            let fade = Fade { duration: Duration::ZERO, ..fade };
+            fade.step();
+        }
+    }
+}
+
+fn run(receiver: Receiver<Fade>) {
+    let mut fades: Vec<Fade> = Vec::new();
+    loop {
+        // Без активных переходов поток спит до следующей команды
+        let received = if fades.is_empty() {
+            match receiver.recv() {
+                Ok(fade) => Some(fade),
+                Err(_) => return,
+            }
+        } else {
+            match receiver.recv_timeout(TICK) {
+                Ok(fade) => Some(fade),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        };
+
+        if let Some(mut fade) = received {
+            fades.retain(|f| !Arc::ptr_eq(&f.sink, &fade.sink));
+            fade.from = fade.sink.lock().map(|s| s.volume()).unwrap_or(fade.to);
+            fades.push(fade);
+        }
+        fades.retain(|fade| !fade.step());
+    }
+}
@@ -0,0 +1,159 @@
+// file_properties_module.rs
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+// Сведения о файле на диске; счетчики текста берутся из редактора
+struct FileInfo {
+    size: u64,
+    modified: Option<String>,
+    encoding: String,
+    line_endings: &'static str,
+    read_only: bool,
+}
+
+#[derive(Default)]
+pub struct FilePropertiesModule {
+    pub show_window: bool,
+    path: Option<PathBuf>,
+    default_encoding: String,
+    info: Option<Result<FileInfo, String>>, // None — документ еще не сохранен
+}
+
+// Так же, как при открытии: UTF-8, если байты корректны, иначе кодировка из настроек
+pub fn detect_encoding(bytes: &[u8], default_encoding: &str) -> String {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return tr!("UTF-8 с BOM").to_string();
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return "UTF-8".to_string();
+    }
+    encoding_rs::Encoding::for_label(default_encoding.as_bytes())
+        .unwrap_or(encoding_rs::WINDOWS_1251)
+        .name()
+        .to_string()
+}
+
+fn line_endings(bytes: &[u8]) -> &'static str {
+    let crlf = bytes.windows(2).filter(|pair| pair == b"\r\n").count();
+    let lf = bytes.iter().filter(|&&b| b == b'\n').count() - crlf;
+    match (crlf, lf) {
+        (0, 0) => tr!("Нет переводов строк"),
+        (_, 0) => "CRLF (Windows)",
+        (0, _) => "LF (Unix)",
+        _ => tr!("Смешанные (CRLF и LF)"),
+    }
+}
+
+fn read_info(path: &Path, default_encoding: &str) -> Result<FileInfo, String> {
+    let metadata = fs::metadata(path).map_err(|e| tr!("Ошибка чтения файла: {}", e))?;
+    let bytes = fs::read(path).map_err(|e| tr!("Ошибка чтения файла: {}", e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Local>::from(time).format("%d.%m.%Y %H:%M:%S").to_string());
+    Ok(FileInfo {
+        size: metadata.len(),
+        modified,
+        encoding: detect_encoding(&bytes, default_encoding),
+        line_endings: line_endings(&bytes),
+        read_only: metadata.permissions().readonly(),
+    })
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["Б", "КБ", "МБ", "ГБ"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, tr!(UNITS[0]))
+    } else {
+        tr!("{} {} ({} байт)", format!("{:.1}", size), tr!(UNITS[unit]), bytes)
+    }
+}
+
+impl FilePropertiesModule {
+    pub fn open(&mut self, path: Option<&Path>, default_encoding: &str) {
+        self.show_window = true;
+        self.path = path.map(Path::to_path_buf);
+        self.default_encoding = default_encoding.to_string();
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.info = self.path.as_deref().map(|path| read_info(path, &self.default_encoding));
+    }
+
+    // lines, words, chars — счетчики текста в редакторе, с несохраненными правками
+    pub fn show_window(&mut self, ctx: &egui::Context, lines: usize, words: usize, chars: usize) {
+        if !self.show_window {
+            return;
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Свойства файла"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("file_properties_grid").num_columns(2).striped(true).show(ui, |ui| {
+                    ui.label(tr!("Путь:"));
+                    match &self.path {
+                        Some(path) => ui.add(egui::Label::new(path.display().to_string()).wrap(true)),
+                        None => ui.label(tr!("Файл еще не сохранен")),
+                    };
+                    ui.end_row();
+
+                    match &self.info {
+                        Some(Ok(info)) => {
+                            ui.label(tr!("Размер:"));
+                            ui.label(format_size(info.size));
+                            ui.end_row();
+                            ui.label(tr!("Изменен:"));
+                            ui.label(info.modified.as_deref().unwrap_or("—"));
+                            ui.end_row();
+                            ui.label(tr!("Кодировка:"));
+                            ui.label(&info.encoding);
+                            ui.end_row();
+                            ui.label(tr!("Переводы строк:"));
+                            ui.label(info.line_endings);
+                            ui.end_row();
+                            ui.label(tr!("Только чтение:"));
+                            ui.label(if info.read_only { tr!("Да") } else { tr!("Нет") });
+                            ui.end_row();
+                        }
+                        Some(Err(e)) => {
+                            ui.label(tr!("Ошибка:"));
+                            ui.colored_label(egui::Color32::RED, e);
+                            ui.end_row();
+                        }
+                        None => {}
+                    }
+
+                    ui.label(tr!("Строк:"));
+                    ui.label(lines.to_string());
+                    ui.end_row();
+                    ui.label(tr!("Слов:"));
+                    ui.label(words.to_string());
+                    ui.end_row();
+                    ui.label(tr!("Символов:"));
+                    ui.label(chars.to_string());
+                    ui.end_row();
+                });
+
+                ui.separator();
+                if ui.add_enabled(self.path.is_some(), egui::Button::new(tr!("Обновить"))).clicked() {
+                    self.refresh();
+                }
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+    }
+}
@@ -0,0 +1,291 @@
+// file_tree_module.rs
+use eframe::egui;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+pub struct FileTreeModule {
+    pub show_panel: bool,
+    entries: HashMap<PathBuf, Vec<(PathBuf, bool)>>, // Кэш содержимого папок: (путь, это папка)
+    new_file: Option<(PathBuf, String)>,             // Папка и имя создаваемого файла
+    rename: Option<(PathBuf, String)>,               // Файл и его новое имя
+    confirm_delete: Option<PathBuf>,
+    pub error_message: Option<String>,
+}
+
+pub enum FileTreeResult {
+    None,
+    OpenFile(PathBuf),
+    Renamed(PathBuf, PathBuf),
+    Deleted(PathBuf),
+}
+
+enum TreeAction {
+    Open(PathBuf),
+    NewFile(PathBuf),
+    Rename(PathBuf),
+    Delete(PathBuf),
+}
+
+impl Default for FileTreeModule {
+    fn default() -> Self {
+        Self {
+            show_panel: true,
+            entries: HashMap::new(),
+            new_file: None,
+            rename: None,
+            confirm_delete: None,
+            error_message: None,
+        }
+    }
+}
+
+impl FileTreeModule {
+    pub fn refresh(&mut self) {
+        self.entries.clear();
+    }
+
+    fn list_dir(&mut self, dir: &Path) -> Vec<(PathBuf, bool)> {
+        if let Some(entries) = self.entries.get(dir) {
+            return entries.clone();
+        }
+
+        let mut entries: Vec<(PathBuf, bool)> = fs::read_dir(dir)
+            .map(|read_dir| {
+                read_dir
+                    .filter_map(|e| e.ok())
+                    .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+                    .map(|e| {
+                        let path = e.path();
+                        let is_dir = path.is_dir();
+                        (path, is_dir)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Сначала папки, потом файлы, внутри — по алфавиту
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        self.entries.insert(dir.to_path_buf(), entries.clone());
+        entries
+    }
+
+    pub fn show_panel(&mut self, ctx: &egui::Context, root: &Path, current_file: Option<&Path>) -> FileTreeResult {
+        if !self.show_panel {
+            return FileTreeResult::None;
+        }
+
+        let mut action = None;
+
+        egui::SidePanel::left("file_tree_panel")
+            .resizable(true)
+            .default_width(220.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let root_name = root
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| root.display().to_string());
+                    ui.strong(root_name);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("⟳").on_hover_text(tr!("Обновить")).clicked() {
+                            self.refresh();
+                        }
+                        if ui.small_button("+").on_hover_text(tr!("Новый файл")).clicked() {
+                            action = Some(TreeAction::NewFile(root.to_path_buf()));
+                        }
+                    });
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        self.show_dir(ui, root, current_file, &mut action);
+                    });
+            });
+
+        let mut result = FileTreeResult::None;
+        match action {
+            Some(TreeAction::Open(path)) => result = FileTreeResult::OpenFile(path),
+            Some(TreeAction::NewFile(dir)) => self.new_file = Some((dir, String::new())),
+            Some(TreeAction::Rename(path)) => {
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                self.rename = Some((path, name));
+            }
+            Some(TreeAction::Delete(path)) => self.confirm_delete = Some(path),
+            None => {}
+        }
+
+        if let Some(dialog_result) = self.show_dialogs(ctx) {
+            result = dialog_result;
+        }
+
+        result
+    }
+
+    fn show_dir(&mut self, ui: &mut egui::Ui, dir: &Path, current_file: Option<&Path>, action: &mut Option<TreeAction>) {
+        for (path, is_dir) in self.list_dir(dir) {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            if is_dir {
+                let response = egui::CollapsingHeader::new(format!("📁 {}", name))
+                    .id_source(&path)
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.show_dir(ui, &path, current_file, action);
+                    });
+                response.header_response.context_menu(|ui| {
+                    if ui.button(tr!("Новый файл")).clicked() {
+                        *action = Some(TreeAction::NewFile(path.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Переименовать")).clicked() {
+                        *action = Some(TreeAction::Rename(path.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Удалить")).clicked() {
+                        *action = Some(TreeAction::Delete(path.clone()));
+                        ui.close_menu();
+                    }
+                });
+            } else {
+                let is_current = current_file == Some(path.as_path());
+                let response = ui.selectable_label(is_current, format!("📄 {}", name));
+                if response.clicked() {
+                    *action = Some(TreeAction::Open(path.clone()));
+                }
+                response.context_menu(|ui| {
+                    if ui.button(tr!("Открыть")).clicked() {
+                        *action = Some(TreeAction::Open(path.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Переименовать")).clicked() {
+                        *action = Some(TreeAction::Rename(path.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Удалить")).clicked() {
+                        *action = Some(TreeAction::Delete(path.clone()));
+                        ui.close_menu();
+                    }
+                });
+            }
+        }
+    }
+
+    fn show_dialogs(&mut self, ctx: &egui::Context) -> Option<FileTreeResult> {
+        let mut result = None;
+
+        if let Some((dir, mut name)) = self.new_file.take() {
+            let mut keep_open = true;
+            egui::Window::new(tr!("Новый файл"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(tr!("Папка: {}", dir.display()));
+                    ui.text_edit_singleline(&mut name);
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("Создать")).clicked() && !name.trim().is_empty() {
+                            let path = dir.join(name.trim());
+                            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                                Ok(_) => {
+                                    self.refresh();
+                                    self.error_message = None;
+                                    result = Some(FileTreeResult::OpenFile(path));
+                                }
+                                Err(e) => {
+                                    self.error_message = Some(tr!("Ошибка создания файла: {}", e));
+                                }
+                            }
+                            keep_open = false;
+                        }
+                        if ui.button(tr!("Отмена")).clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if keep_open {
+                self.new_file = Some((dir, name));
+            }
+        }
+
+        if let Some((path, mut name)) = self.rename.take() {
+            let mut keep_open = true;
+            egui::Window::new(tr!("Переименовать"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!("{}", path.display()));
+                    ui.text_edit_singleline(&mut name);
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("Переименовать")).clicked() && !name.trim().is_empty() {
+                            let new_path = path.with_file_name(name.trim());
+                            match fs::rename(&path, &new_path) {
+                                Ok(_) => {
+                                    self.refresh();
+                                    self.error_message = None;
+                                    result = Some(FileTreeResult::Renamed(path.clone(), new_path));
+                                }
+                                Err(e) => {
+                                    self.error_message = Some(tr!("Ошибка переименования: {}", e));
+                                }
+                            }
+                            keep_open = false;
+                        }
+                        if ui.button(tr!("Отмена")).clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if keep_open {
+                self.rename = Some((path, name));
+            }
+        }
+
+        if let Some(path) = self.confirm_delete.take() {
+            let mut keep_open = true;
+            egui::Window::new(tr!("Удаление"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(tr!("Удалить {}?", path.display()));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("Удалить")).clicked() {
+                            let removed = if path.is_dir() {
+                                fs::remove_dir_all(&path)
+                            } else {
+                                fs::remove_file(&path)
+                            };
+                            match removed {
+                                Ok(_) => {
+                                    self.refresh();
+                                    self.error_message = None;
+                                    result = Some(FileTreeResult::Deleted(path.clone()));
+                                }
+                                Err(e) => {
+                                    self.error_message = Some(tr!("Ошибка удаления: {}", e));
+                                }
+                            }
+                            keep_open = false;
+                        }
+                        if ui.button(tr!("Отмена")).clicked() {
+                            keep_open = false;
+                        }
+                    });
+                });
+            if keep_open {
+                self.confirm_delete = Some(path);
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,121 @@
+// file_watch_module.rs
+use eframe::egui;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::SystemTime;
+
+use crate::i18n::tr;
+
+// Время изменения и размер файла: по ним отличаем чужую запись от своей
+type DiskState = Option<(SystemTime, u64)>;
+
+fn disk_state(path: &Path) -> DiskState {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[derive(Default)]
+pub struct FileWatchModule {
+    file: Option<PathBuf>,
+    known: DiskState, // Каким файл был, когда мы его открыли или сохранили
+    watcher: Option<RecommendedWatcher>,
+    events: Option<Receiver<()>>,
+    show_prompt: bool,
+}
+
+pub enum FileWatchResult {
+    None,
+    Reload,
+    ShowDiff,
+}
+
+impl FileWatchModule {
+    // Вызывается каждый кадр; при смене файла следим за его папкой — так видны и замены через переименование
+    pub fn track_file(&mut self, ctx: &egui::Context, path: Option<&Path>) {
+        if self.file.as_deref() == path {
+            return;
+        }
+        self.file = path.map(Path::to_path_buf);
+        self.watcher = None;
+        self.events = None;
+        self.show_prompt = false;
+        self.sync();
+
+        let Some(dir) = path.and_then(Path::parent) else {
+            return;
+        };
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        let watched = path.map(Path::to_path_buf);
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if event.is_ok_and(|event| event.paths.iter().any(|p| Some(p) == watched.as_ref())) {
+                let _ = sender.send(());
+                ctx.request_repaint();
+            }
+        });
+        // Если следить не вышло (сетевой диск, лимит inotify), остается проверка при сохранении
+        if let Ok(mut watcher) = watcher {
+            if watcher.watch(dir, RecursiveMode::NonRecursive).is_ok() {
+                self.watcher = Some(watcher);
+                self.events = Some(receiver);
+            }
+        }
+    }
+
+    // Запомнить, что текст в редакторе совпадает с файлом на диске (после открытия или сохранения)
+    pub fn sync(&mut self) {
+        self.known = self.file.as_deref().and_then(disk_state);
+        self.show_prompt = false;
+    }
+
+    // Файл на диске новее того, что мы открывали или сохраняли. Удаленный файл не в счет: затирать нечего
+    pub fn changed_on_disk(&self) -> bool {
+        let current = self.file.as_deref().and_then(disk_state);
+        self.known.is_some() && current.is_some() && current != self.known
+    }
+
+    pub fn show_prompt(&mut self) {
+        self.show_prompt = true;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> FileWatchResult {
+        if let Some(events) = &self.events {
+            if events.try_iter().count() > 0 && self.changed_on_disk() {
+                self.show_prompt = true;
+            }
+        }
+        if !self.show_prompt {
+            return FileWatchResult::None;
+        }
+
+        let mut result = FileWatchResult::None;
+        let name = self
+            .file
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        egui::Window::new(tr!("Файл изменен на диске"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-16.0, 40.0))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tr!("Файл {} изменила другая программа.", name));
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Перезагрузить")).clicked() {
+                        result = FileWatchResult::Reload;
+                    }
+                    if ui.button(tr!("Оставить мою версию")).clicked() {
+                        // Следующее сохранение перезапишет файл на диске
+                        self.sync();
+                    }
+                    if ui.button(tr!("Показать различия")).clicked() {
+                        result = FileWatchResult::ShowDiff;
+                    }
+                });
+            });
+        result
+    }
+}
@@ -0,0 +1,468 @@
+// find_in_files_module.rs
+use eframe::egui;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use crate::i18n::tr;
+use crate::search_module::find_matches_cancellable;
+
+// Файлы больше этого размера не просматриваются
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+// Ограничение на общее число результатов, чтобы не перегружать окно
+const MAX_RESULTS: usize = 10_000;
+// Длина фрагмента строки, показываемого в результатах
+const PREVIEW_CHARS: usize = 200;
+
+#[derive(Clone)]
+pub struct FileMatch {
+    pub path: PathBuf,
+    pub line_number: usize, // Номер строки, начиная с 1
+    pub char_offset: usize, // Позиция совпадения в файле в символах
+    pub line_text: String,
+}
+
+enum FindMessage {
+    Matches(Vec<FileMatch>),
+    Progress(usize),
+    Error(String),
+    Done,
+}
+
+#[derive(Default)]
+pub struct FindInFilesModule {
+    pub show_window: bool,
+    pub folder: Option<PathBuf>,
+    pub pattern: String,
+    pub extensions: String, // Через запятую, например "txt, md"; пусто — все файлы
+    pub use_regex: bool,
+    pub case_sensitive: bool,
+    pub results: Vec<FileMatch>,
+    pub files_scanned: usize,
+    pub running: bool,
+    pub error_message: Option<String>,
+    cancel: Option<Arc<AtomicBool>>,
+    receiver: Option<Receiver<FindMessage>>,
+}
+
+pub enum FindInFilesResult {
+    None,
+    OpenFile(PathBuf, usize),
+}
+
+impl FindInFilesModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+        if !self.show_window {
+            self.stop();
+        }
+    }
+
+    pub fn start(&mut self, ctx: &egui::Context) {
+        self.stop();
+        self.results.clear();
+        self.files_scanned = 0;
+        self.error_message = None;
+
+        let Some(folder) = self.folder.clone() else {
+            self.error_message = Some(tr!("Выберите папку для поиска").to_string());
+            return;
+        };
+        if self.pattern.is_empty() {
+            return;
+        }
+
+        let matcher = if self.use_regex {
+            match regex::RegexBuilder::new(&self.pattern)
+                .case_insensitive(!self.case_sensitive)
+                .build()
+            {
+                Ok(re) => Matcher::Regex(re),
+                Err(e) => {
+                    self.error_message = Some(tr!("Ошибка в регулярном выражении: {}", e));
+                    return;
+                }
+            }
+        } else {
+            Matcher::Plain(self.pattern.clone(), self.case_sensitive)
+        };
+
+        let extensions: Vec<String> = self
+            .extensions
+            .split(',')
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect();
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+
+        let walker = Walker {
+            matcher,
+            extensions,
+            cancel: cancel.clone(),
+            sender,
+            ctx,
+            files_scanned: 0,
+            results_sent: 0,
+            visited: HashSet::new(),
+        };
+        thread::spawn(move || walker.run(&folder));
+
+        self.cancel = Some(cancel);
+        self.receiver = Some(receiver);
+        self.running = true;
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.receiver = None;
+        self.running = false;
+    }
+
+    fn poll_results(&mut self) {
+        let Some(receiver) = &self.receiver else {
+            return;
+        };
+
+        let mut finished = false;
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                FindMessage::Matches(matches) => self.results.extend(matches),
+                FindMessage::Progress(files) => self.files_scanned = files,
+                FindMessage::Error(e) => self.error_message = Some(e),
+                FindMessage::Done => finished = true,
+            }
+        }
+
+        if finished {
+            self.receiver = None;
+            self.cancel = None;
+            self.running = false;
+        }
+    }
+
+    pub fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::F)) {
+            self.toggle_window();
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> FindInFilesResult {
+        self.poll_results();
+
+        if !self.show_window {
+            return FindInFilesResult::None;
+        }
+
+        let mut result = FindInFilesResult::None;
+        let mut open = self.show_window;
+
+        egui::Window::new(tr!("Найти в файлах"))
+            .open(&mut open)
+            .default_width(500.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("📁 Папка...")).clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                            self.folder = Some(folder);
+                        }
+                    }
+                    let folder_label = self
+                        .folder
+                        .as_ref()
+                        .map(|f| f.display().to_string())
+                        .unwrap_or_else(|| tr!("Папка не выбрана").to_string());
+                    ui.label(folder_label);
+                });
+
+                let mut start_requested = false;
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.pattern)
+                            .hint_text(tr!("Что искать..."))
+                            .desired_width(300.0)
+                    );
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        start_requested = true;
+                    }
+                    if self.running {
+                        if ui.button(tr!("Стоп")).clicked() {
+                            self.stop();
+                        }
+                    } else if ui.button(tr!("Найти")).clicked() {
+                        start_requested = true;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Расширения:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.extensions)
+                            .hint_text("txt, md")
+                            .desired_width(120.0)
+                    );
+                    ui.checkbox(&mut self.use_regex, tr!("Регулярное выражение"));
+                    ui.checkbox(&mut self.case_sensitive, tr!("С учетом регистра"));
+                });
+
+                if start_requested {
+                    self.start(ui.ctx());
+                }
+
+                if let Some(error) = &self.error_message {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if self.running {
+                        ui.spinner();
+                    }
+                    ui.label(tr!(
+                        tr!("Просмотрено файлов: {} • Найдено: {}"),
+                        self.files_scanned,
+                        self.results.len()
+                    ));
+                });
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for file_match in &self.results {
+                            let path = self
+                                .folder
+                                .as_ref()
+                                .and_then(|f| file_match.path.strip_prefix(f).ok())
+                                .unwrap_or(&file_match.path);
+                            let label = format!(
+                                "{}:{}  {}",
+                                path.display(),
+                                file_match.line_number,
+                                file_match.line_text
+                            );
+                            if ui
+                                .add(egui::Label::new(egui::RichText::new(label).monospace()).sense(egui::Sense::click()))
+                                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                                .clicked()
+                            {
+                                result = FindInFilesResult::OpenFile(
+                                    file_match.path.clone(),
+                                    file_match.char_offset,
+                                );
+                            }
+                        }
+                    });
+            });
+
+        if !open {
+            self.show_window = false;
+            self.stop();
+        }
+
+        result
+    }
+}
+
+enum Matcher {
+    Plain(String, bool),
+    Regex(regex::Regex),
+}
+
+impl Matcher {
+    // Возвращает позиции начала совпадений в символах, по возрастанию
+    fn find_starts(&self, text: &str, cancel: &AtomicBool) -> Option<Vec<usize>> {
+        match self {
+            Matcher::Plain(pattern, case_sensitive) => {
+                find_matches_cancellable(text, pattern, *case_sensitive, cancel)
+                    .map(|matches| matches.into_iter().map(|(start, _)| start).collect())
+            }
+            Matcher::Regex(re) => {
+                let mut starts = Vec::new();
+                let mut last_byte = 0;
+                let mut char_pos = 0;
+                for m in re.find_iter(text) {
+                    if cancel.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    char_pos += text[last_byte..m.start()].chars().count();
+                    last_byte = m.start();
+                    starts.push(char_pos);
+                }
+                Some(starts)
+            }
+        }
+    }
+}
+
+struct Walker {
+    matcher: Matcher,
+    extensions: Vec<String>,
+    cancel: Arc<AtomicBool>,
+    sender: Sender<FindMessage>,
+    ctx: egui::Context,
+    files_scanned: usize,
+    results_sent: usize,
+    visited: HashSet<PathBuf>, // Настоящие пути пройденных папок: ссылка на папку выше не зациклит обход
+}
+
+impl Walker {
+    fn run(mut self, folder: &Path) {
+        self.walk(folder);
+        let _ = self.sender.send(FindMessage::Progress(self.files_scanned));
+        let _ = self.sender.send(FindMessage::Done);
+        self.ctx.request_repaint();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed) || self.results_sent >= MAX_RESULTS
+    }
+
+    fn walk(&mut self, dir: &Path) {
+        // Папку, уже пройденную по другому пути или по символической ссылке, второй раз не обходим
+        let canonical = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        if !self.visited.insert(canonical) {
+            return;
+        }
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                let _ = self.sender.send(FindMessage::Error(tr!(
+                    tr!("Не удалось прочитать папку {}: {}"),
+                    dir.display(),
+                    e
+                )));
+                return;
+            }
+        };
+
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        paths.sort();
+
+        for path in paths {
+            if self.is_cancelled() {
+                return;
+            }
+
+            // Скрытые файлы и папки (.git и т.п.) пропускаем
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with('.'))
+                .unwrap_or(false);
+            if hidden {
+                continue;
+            }
+
+            if path.is_dir() {
+                self.walk(&path);
+            } else if self.extension_allowed(&path) {
+                self.search_file(&path);
+            }
+        }
+    }
+
+    fn extension_allowed(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return true;
+        }
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| self.extensions.contains(&e.to_lowercase()))
+            .unwrap_or(false)
+    }
+
+    fn search_file(&mut self, path: &Path) {
+        let too_big = fs::metadata(path).map(|m| m.len() > MAX_FILE_SIZE).unwrap_or(true);
+        if too_big {
+            return;
+        }
+        let Ok(bytes) = fs::read(path) else {
+            return;
+        };
+        // Двоичные файлы не текстовые: в них встречаются нулевые байты
+        if bytes.iter().take(8000).any(|&b| b == 0) {
+            return;
+        }
+        let Ok(text) = String::from_utf8(bytes) else {
+            return;
+        };
+
+        self.files_scanned += 1;
+
+        let Some(starts) = self.matcher.find_starts(&text, &self.cancel) else {
+            return;
+        };
+
+        let limit = MAX_RESULTS - self.results_sent;
+        let matches: Vec<FileMatch> = collect_line_info(&text, &starts)
+            .into_iter()
+            .take(limit)
+            .map(|(line_number, char_offset, line_text)| FileMatch {
+                path: path.to_path_buf(),
+                line_number,
+                char_offset,
+                line_text,
+            })
+            .collect();
+
+        if self.files_scanned.is_multiple_of(50) {
+            let _ = self.sender.send(FindMessage::Progress(self.files_scanned));
+            self.ctx.request_repaint();
+        }
+
+        if !matches.is_empty() {
+            self.results_sent += matches.len();
+            let _ = self.sender.send(FindMessage::Matches(matches));
+            let _ = self.sender.send(FindMessage::Progress(self.files_scanned));
+            self.ctx.request_repaint();
+        }
+    }
+}
+
+// Для каждой позиции совпадения (в символах) находит номер строки и текст этой строки
+fn collect_line_info(text: &str, char_starts: &[usize]) -> Vec<(usize, usize, String)> {
+    let mut results = Vec::with_capacity(char_starts.len());
+    let mut wanted = char_starts.iter().peekable();
+    let mut line_number = 1;
+    let mut line_start_byte = 0;
+
+    for (char_index, (byte_index, c)) in text.char_indices().enumerate() {
+        while let Some(&&start) = wanted.peek() {
+            if start != char_index {
+                break;
+            }
+            let line_end = text[line_start_byte..]
+                .find('\n')
+                .map(|i| line_start_byte + i)
+                .unwrap_or(text.len());
+            let line_text: String = text[line_start_byte..line_end]
+                .trim()
+                .chars()
+                .take(PREVIEW_CHARS)
+                .collect();
+            results.push((line_number, start, line_text));
+            wanted.next();
+        }
+
+        if wanted.peek().is_none() {
+            break;
+        }
+
+        if c == '\n' {
+            line_number += 1;
+            line_start_byte = byte_index + 1;
+        }
+    }
+
+    results
+}
@@ -0,0 +1,425 @@
+// format_module.rs
+use eframe::egui;
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum FormatKind {
+    #[default]
+    Auto, // JSON или XML — по первому значащему символу
+    Json,
+    Xml,
+}
+
+// Ошибка разбора; offset — номер символа, с которого начинается проблема
+pub struct FormatError {
+    pub message: String,
+    pub offset: usize,
+}
+
+// Форматер пишет токены как есть: порядок ключей и запись чисел не меняются
+struct JsonFormatter<'a> {
+    chars: &'a [char],
+    pos: usize,
+    indent: &'a str,
+    out: String,
+}
+
+impl JsonFormatter<'_> {
+    fn error(&self, message: &str) -> FormatError {
+        FormatError {
+            message: message.to_string(),
+            offset: self.pos,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn new_line(&mut self, depth: usize) {
+        self.out.push('\n');
+        for _ in 0..depth {
+            self.out.push_str(self.indent);
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<(), FormatError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.container(depth, '}'),
+            Some('[') => self.container(depth, ']'),
+            Some('"') => self.string(),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.literal("true"),
+            Some('f') => self.literal("false"),
+            Some('n') => self.literal("null"),
+            None => Err(self.error(tr!("Неожиданный конец текста"))),
+            Some(_) => Err(self.error(tr!("Ожидалось значение"))),
+        }
+    }
+
+    // Объект или массив: элементы по одному на строке
+    fn container(&mut self, depth: usize, close: char) -> Result<(), FormatError> {
+        let is_object = close == '}';
+        self.out.push(self.chars[self.pos]);
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.out.push(close);
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            self.new_line(depth + 1);
+            if is_object {
+                self.skip_whitespace();
+                if self.peek() != Some('"') {
+                    return Err(self.error(tr!("Ожидался ключ в кавычках")));
+                }
+                self.string()?;
+                self.skip_whitespace();
+                if self.peek() != Some(':') {
+                    return Err(self.error(tr!("Ожидалось «:»")));
+                }
+                self.pos += 1;
+                self.out.push_str(": ");
+            }
+            self.value(depth + 1)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.out.push(',');
+                    self.pos += 1;
+                }
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    self.new_line(depth);
+                    self.out.push(close);
+                    return Ok(());
+                }
+                _ if is_object => return Err(self.error(tr!("Ожидалось «,» или «}»"))),
+                _ => return Err(self.error(tr!("Ожидалось «,» или «]»"))),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<(), FormatError> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                None => {
+                    self.pos = start;
+                    return Err(self.error(tr!("Незакрытая строка")));
+                }
+                Some('"') => break,
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't') => self.pos += 1,
+                        Some('u') => {
+                            let hex = self.chars.get(self.pos + 1..self.pos + 5);
+                            if !hex.is_some_and(|h| h.iter().all(|c| c.is_ascii_hexdigit())) {
+                                return Err(self.error(tr!("Неверная escape-последовательность")));
+                            }
+                            self.pos += 5;
+                        }
+                        _ => return Err(self.error(tr!("Неверная escape-последовательность"))),
+                    }
+                }
+                Some(c) if (c as u32) < 0x20 => return Err(self.error(tr!("Управляющий символ внутри строки"))),
+                Some(_) => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        self.out.extend(&self.chars[start..self.pos]);
+        Ok(())
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<(), FormatError> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        let leading_zero = self.peek() == Some('0');
+        let integer = self.digits();
+        let mut valid = integer > 0 && !(leading_zero && integer > 1);
+        if valid && self.peek() == Some('.') {
+            self.pos += 1;
+            valid = self.digits() > 0;
+        }
+        if valid && matches!(self.peek(), Some('e' | 'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            valid = self.digits() > 0;
+        }
+        if !valid {
+            self.pos = start;
+            return Err(self.error(tr!("Неверное число")));
+        }
+        self.out.extend(&self.chars[start..self.pos]);
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str) -> Result<(), FormatError> {
+        let length = word.chars().count();
+        let found: String = self.chars.iter().skip(self.pos).take(length).collect();
+        if found != word {
+            return Err(self.error(tr!("Ожидалось значение")));
+        }
+        self.pos += length;
+        self.out.push_str(word);
+        Ok(())
+    }
+}
+
+pub fn format_json(text: &str, indent: &str) -> Result<String, FormatError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut formatter = JsonFormatter {
+        chars: &chars,
+        pos: 0,
+        indent,
+        out: String::new(),
+    };
+    formatter.value(0)?;
+    formatter.skip_whitespace();
+    if formatter.pos < chars.len() {
+        return Err(formatter.error(tr!("Лишние символы после конца JSON")));
+    }
+    Ok(formatter.out)
+}
+
+pub fn format_xml(text: &str, indent_char: u8, indent_size: usize) -> Result<String, FormatError> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut writer = Writer::new_with_indent(Vec::new(), indent_char, indent_size);
+    let char_offset = |byte: u64| {
+        text.get(..byte as usize)
+            .map(|s| s.chars().count())
+            .unwrap_or(text.chars().count())
+    };
+
+    let mut open_tags = Vec::new();
+    loop {
+        // Событие начинается после пробелов, которые reader отбрасывает
+        let before = reader.buffer_position() as usize;
+        let tag_start = before + text[before..].len() - text[before..].trim_start().len();
+        let event = match reader.read_event() {
+            Ok(event) => event,
+            Err(e) => {
+                return Err(FormatError {
+                    message: tr!("Ошибка XML: {}", e),
+                    offset: char_offset(reader.error_position()),
+                })
+            }
+        };
+        // Атрибуты reader не разбирает сам — без этой проверки прошел бы <a x=1>
+        if let Event::Start(tag) | Event::Empty(tag) = &event {
+            if let Some(Err(e)) = tag.attributes().find(Result::is_err) {
+                return Err(FormatError {
+                    message: tr!("Ошибка XML: {}", e),
+                    offset: char_offset(tag_start as u64),
+                });
+            }
+        }
+        match &event {
+            Event::Start(_) => open_tags.push(tag_start as u64),
+            Event::End(_) => {
+                open_tags.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        writer.write_event(event).map_err(|e| FormatError {
+            message: tr!("Ошибка XML: {}", e),
+            offset: char_offset(reader.buffer_position()),
+        })?;
+    }
+    if let Some(&position) = open_tags.last() {
+        return Err(FormatError {
+            message: tr!("Тег не закрыт").to_string(),
+            offset: char_offset(position),
+        });
+    }
+    Ok(String::from_utf8_lossy(&writer.into_inner()).to_string())
+}
+
+fn detect_kind(text: &str) -> FormatKind {
+    if text.trim_start().starts_with('<') {
+        FormatKind::Xml
+    } else {
+        FormatKind::Json
+    }
+}
+
+#[derive(Default)]
+pub struct FormatModule {
+    pub show_window: bool,
+    error: Option<FormatError>,
+    checked: bool, // Последняя проверка прошла без ошибок
+}
+
+pub enum FormatResult {
+    None,
+    ConfigChanged,
+    Replace {
+        range: Option<(usize, usize)>,
+        text: String,
+    },
+    JumpTo(usize),
+}
+
+impl FormatModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+        self.error = None;
+        self.checked = false;
+    }
+
+    fn format(&self, text: &str, config: &Config) -> Result<String, FormatError> {
+        let kind = match config.format_kind {
+            FormatKind::Auto => detect_kind(text),
+            kind => kind,
+        };
+        let (indent_char, indent_size) = if config.format_use_tabs {
+            (b'\t', 1)
+        } else {
+            (b' ', config.format_indent)
+        };
+        match kind {
+            FormatKind::Xml => format_xml(text, indent_char, indent_size),
+            _ => format_json(text, &(indent_char as char).to_string().repeat(indent_size)),
+        }
+    }
+
+    // Работает с выделением, если оно есть, иначе со всем документом
+    pub fn show_window(
+        &mut self,
+        ctx: &egui::Context,
+        text: &str,
+        selection: Option<(usize, usize)>,
+        config: &mut Config,
+    ) -> FormatResult {
+        let mut result = FormatResult::None;
+        if !self.show_window {
+            return result;
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Форматировать JSON/XML"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Формат:"));
+                    changed |= ui
+                        .radio_value(&mut config.format_kind, FormatKind::Auto, tr!("Определить"))
+                        .changed();
+                    changed |= ui
+                        .radio_value(&mut config.format_kind, FormatKind::Json, "JSON")
+                        .changed();
+                    changed |= ui
+                        .radio_value(&mut config.format_kind, FormatKind::Xml, "XML")
+                        .changed();
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Отступ:"));
+                    changed |= ui
+                        .add_enabled(
+                            !config.format_use_tabs,
+                            egui::DragValue::new(&mut config.format_indent)
+                                .clamp_range(1..=8)
+                                .suffix(tr!(" пробелов")),
+                        )
+                        .changed();
+                    changed |= ui.checkbox(&mut config.format_use_tabs, tr!("Табуляция")).changed();
+                });
+                if changed {
+                    result = FormatResult::ConfigChanged;
+                }
+                ui.label(if selection.is_some() {
+                    tr!("Будет отформатировано выделение")
+                } else {
+                    tr!("Будет отформатирован весь документ")
+                });
+                ui.separator();
+
+                let (start, end) = selection.unwrap_or((0, text.chars().count()));
+                let source: String = text.chars().skip(start).take(end - start).collect();
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Форматировать")).clicked() {
+                        match self.format(&source, config) {
+                            Ok(formatted) => {
+                                self.error = None;
+                                self.checked = false;
+                                result = FormatResult::Replace {
+                                    range: selection,
+                                    text: formatted,
+                                };
+                            }
+                            Err(e) => {
+                                self.error = Some(FormatError {
+                                    offset: e.offset + start,
+                                    ..e
+                                })
+                            }
+                        }
+                    }
+                    if ui.button(tr!("Проверить")).clicked() {
+                        match self.format(&source, config) {
+                            Ok(_) => {
+                                self.error = None;
+                                self.checked = true;
+                            }
+                            Err(e) => {
+                                self.error = Some(FormatError {
+                                    offset: e.offset + start,
+                                    ..e
+                                })
+                            }
+                        }
+                    }
+                });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), &error.message);
+                    let before: String = text.chars().take(error.offset).collect();
+                    let line = before.matches('\n').count() + 1;
+                    let column = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) + 1;
+                    if ui.link(tr!("Строка {}, столбец {}", line, column)).clicked() {
+                        result = FormatResult::JumpTo(error.offset);
+                    }
+                } else if self.checked {
+                    ui.colored_label(egui::Color32::from_rgb(100, 200, 100), tr!("Ошибок не найдено"));
+                }
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+        result
+    }
+}
@@ -0,0 +1,193 @@
+// git_module.rs
+use eframe::egui;
+use git2::Repository;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::diff_module::{line_statuses, LineStatus};
+use crate::i18n::tr;
+
+// Открытый файл в репозитории: его версия в HEAD и отличия от нее построчно
+#[derive(Default)]
+pub struct GitModule {
+    pub show_commit: bool,
+    file: Option<PathBuf>,   // Файл, для которого прочитан HEAD
+    repo_root: Option<PathBuf>,
+    relative_path: PathBuf,  // Путь файла внутри рабочей копии
+    branch: String,
+    head_text: Option<String>, // None — файла нет в HEAD (новый)
+    changes: Vec<(usize, LineStatus)>,
+    text_hash: Option<u64>,  // Для какого текста посчитаны changes
+    message: String,
+    last_commit: Option<String>,
+}
+
+pub enum GitResult {
+    None,
+    Commit, // Нажали «Закоммитить»: сохранить файл и вызвать commit
+}
+
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl GitModule {
+    pub fn is_repo(&self) -> bool {
+        self.repo_root.is_some()
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    pub fn changes(&self) -> &[(usize, LineStatus)] {
+        &self.changes
+    }
+
+    // Текст файла в HEAD; для нового файла — пустой
+    pub fn head_text(&self) -> &str {
+        self.head_text.as_deref().unwrap_or("")
+    }
+
+    // Вызывается каждый кадр: при смене файла заново ищет репозиторий и читает HEAD
+    pub fn track_file(&mut self, path: Option<&Path>) {
+        if self.file.as_deref() == path {
+            return;
+        }
+        self.file = path.map(Path::to_path_buf);
+        self.refresh();
+    }
+
+    pub fn refresh(&mut self) {
+        self.repo_root = None;
+        self.head_text = None;
+        self.changes.clear();
+        self.text_hash = None;
+
+        let Some(file) = &self.file else {
+            return;
+        };
+        let Some(repo) = file.parent().and_then(|dir| Repository::discover(dir).ok()) else {
+            return;
+        };
+        let Some(workdir) = repo.workdir().map(Path::to_path_buf) else {
+            return;
+        };
+        // Пути сравниваем канонические: иначе символические ссылки и «..» не совпадут с workdir
+        let file = file.canonicalize().unwrap_or_else(|_| file.clone());
+        let workdir = workdir.canonicalize().unwrap_or(workdir);
+        let Ok(relative) = file.strip_prefix(&workdir) else {
+            return;
+        };
+
+        self.relative_path = relative.to_path_buf();
+        self.repo_root = Some(workdir);
+        self.branch = repo
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(str::to_string))
+            .unwrap_or_else(|| "HEAD".to_string());
+        self.head_text = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_tree().ok())
+            .and_then(|tree| tree.get_path(&self.relative_path).ok())
+            .and_then(|entry| entry.to_object(&repo).ok())
+            .and_then(|object| object.into_blob().ok())
+            .map(|blob| String::from_utf8_lossy(blob.content()).to_string());
+    }
+
+    // Пересчитывает отметки на полях, если текст изменился
+    pub fn update(&mut self, text: &str) {
+        if !self.is_repo() {
+            return;
+        }
+        let hash = text_hash(text);
+        if self.text_hash == Some(hash) {
+            return;
+        }
+        self.text_hash = Some(hash);
+        self.changes = line_statuses(self.head_text(), text);
+    }
+
+    // Добавляет открытый файл в индекс и создает коммит поверх HEAD
+    pub fn commit(&mut self) -> Result<(), String> {
+        let root = self.repo_root.clone().ok_or_else(|| tr!("Файл не в репозитории git").to_string())?;
+        let git_error = |e: git2::Error| tr!("Ошибка git: {}", e.message());
+
+        let repo = Repository::open(&root).map_err(git_error)?;
+        let mut index = repo.index().map_err(git_error)?;
+        index.add_path(&self.relative_path).map_err(git_error)?;
+        index.write().map_err(git_error)?;
+        let tree_id = index.write_tree().map_err(git_error)?;
+        let tree = repo.find_tree(tree_id).map_err(git_error)?;
+        let signature = repo.signature().map_err(git_error)?;
+        // В пустом репозитории HEAD еще не указывает на коммит — это будет первый
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+        let id = repo
+            .commit(Some("HEAD"), &signature, &signature, self.message.trim(), &tree, &parents)
+            .map_err(git_error)?;
+
+        let short_id = id.to_string().chars().take(7).collect::<String>();
+        self.last_commit = Some(tr!("Создан коммит {}", short_id));
+        self.message.clear();
+        self.refresh();
+        Ok(())
+    }
+
+    pub fn show_commit_window(&mut self, ctx: &egui::Context, unsaved: bool) -> GitResult {
+        if !self.show_commit {
+            return GitResult::None;
+        }
+
+        let mut result = GitResult::None;
+        let mut open = self.show_commit;
+        egui::Window::new(tr!("Коммит"))
+            .open(&mut open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                if !self.is_repo() {
+                    ui.label(tr!("Файл не в репозитории git"));
+                    return;
+                }
+                egui::Grid::new("git_commit_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr!("Ветка:"));
+                    ui.monospace(&self.branch);
+                    ui.end_row();
+                    ui.label(tr!("Файл:"));
+                    ui.monospace(self.relative_path.display().to_string());
+                    ui.end_row();
+                });
+                if unsaved {
+                    ui.label(tr!("Перед коммитом файл будет сохранен"));
+                }
+
+                ui.label(tr!("Сообщение:"));
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.message)
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY),
+                );
+
+                ui.horizontal(|ui| {
+                    let ready = !self.message.trim().is_empty();
+                    if ui.add_enabled(ready, egui::Button::new(tr!("Закоммитить"))).clicked() {
+                        result = GitResult::Commit;
+                    }
+                    if let Some(last) = &self.last_commit {
+                        ui.label(last);
+                    }
+                });
+            });
+
+        if !open {
+            self.show_commit = false;
+        }
+        result
+    }
+}
@@ -0,0 +1,123 @@
+// hash_module.rs
+use eframe::egui;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+#[derive(Clone, Copy, PartialEq, Default)]
+enum HashSource {
+    Selection,
+    #[default]
+    Document, // Текст в редакторе в UTF-8, с несохраненными правками
+    File, // Байты файла на диске, в его кодировке
+}
+
+#[derive(Default)]
+pub struct HashModule {
+    pub show_window: bool,
+    selection: Option<String>,
+    document: String,
+    path: Option<PathBuf>,
+    source: HashSource,
+    hashes: Vec<(&'static str, String)>,
+    error: Option<String>,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn compute(bytes: &[u8]) -> Vec<(&'static str, String)> {
+    vec![
+        ("MD5", hex(&Md5::digest(bytes))),
+        ("SHA-1", hex(&Sha1::digest(bytes))),
+        ("SHA-256", hex(&Sha256::digest(bytes))),
+    ]
+}
+
+impl HashModule {
+    // Текст запоминаем при открытии: хеш относится к тому, что было в момент вызова
+    pub fn open(&mut self, selection: Option<String>, document: &str, path: Option<&Path>) {
+        self.show_window = true;
+        self.source = if selection.is_some() {
+            HashSource::Selection
+        } else {
+            HashSource::Document
+        };
+        self.selection = selection;
+        self.document = document.to_string();
+        self.path = path.map(Path::to_path_buf);
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let bytes = match self.source {
+            HashSource::Selection => Ok(self.selection.clone().unwrap_or_default().into_bytes()),
+            HashSource::Document => Ok(self.document.clone().into_bytes()),
+            HashSource::File => match &self.path {
+                Some(path) => fs::read(path).map_err(|e| tr!("Ошибка чтения файла: {}", e)),
+                None => Err(tr!("Файл еще не сохранен").to_string()),
+            },
+        };
+        match bytes {
+            Ok(bytes) => {
+                self.hashes = compute(&bytes);
+                self.error = None;
+            }
+            Err(e) => {
+                self.hashes.clear();
+                self.error = Some(e);
+            }
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) {
+        if !self.show_window {
+            return;
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Вычислить хеш"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let mut source = self.source;
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(self.selection.is_some(), |ui| {
+                        ui.radio_value(&mut source, HashSource::Selection, tr!("Выделение"));
+                    });
+                    ui.radio_value(&mut source, HashSource::Document, tr!("Текст документа"));
+                    ui.add_enabled_ui(self.path.is_some(), |ui| {
+                        ui.radio_value(&mut source, HashSource::File, tr!("Файл на диске"));
+                    });
+                });
+                if source != self.source {
+                    self.source = source;
+                    self.refresh();
+                }
+                ui.separator();
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+                }
+                egui::Grid::new("hash_grid").num_columns(3).show(ui, |ui| {
+                    for (name, value) in &self.hashes {
+                        ui.label(*name);
+                        ui.add(egui::Label::new(egui::RichText::new(value).monospace()).selectable(true));
+                        if ui.small_button(tr!("Копировать")).clicked() {
+                            ui.output_mut(|o| o.copied_text = value.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// history_module.rs
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{config_dir, Config};
+use crate::i18n::tr;
+
+const HISTORY_DIR: &str = "history";
+// Старые версии сверх этого числа удаляются
+const MAX_SNAPSHOTS: usize = 100;
+const SNAPSHOT_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+// Копия файла на момент сохранения; label — время снимка для списка
+struct Snapshot {
+    path: PathBuf,
+    label: String,
+}
+
+#[derive(Default)]
+pub struct HistoryModule {
+    pub show_window: bool,
+    file: Option<PathBuf>,
+    snapshots: Vec<Snapshot>, // Новые — первыми
+    selected: usize,
+}
+
+pub enum HistoryResult {
+    None,
+    ConfigChanged,
+    Compare { title: String, old_label: String, new_label: String, old: String, new: String },
+    Restore(String),
+}
+
+// FNV-1a: имя папки должно быть одинаковым между запусками, а DefaultHasher этого не обещает
+fn path_hash(path: &Path) -> u64 {
+    path.to_string_lossy().bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Папка со снимками одного файла: имя файла плюс хеш полного пути, чтобы одноименные файлы не смешивались
+fn snapshots_dir(file: &Path) -> Option<PathBuf> {
+    let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let name = file.file_name()?.to_string_lossy().to_string();
+    config_dir().map(|dir| dir.join(HISTORY_DIR).join(format!("{}-{:016x}", name, path_hash(&file))))
+}
+
+fn list_snapshots(dir: &Path) -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    paths.sort();
+    paths.reverse();
+    paths
+        .into_iter()
+        .map(|path| {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            let label = chrono::NaiveDateTime::parse_from_str(&stem, SNAPSHOT_FORMAT)
+                .map(|time| time.format("%d.%m.%Y %H:%M:%S").to_string())
+                .unwrap_or(stem);
+            Snapshot { path, label }
+        })
+        .collect()
+}
+
+impl HistoryModule {
+    // Сохраняет текст как новую версию, если он отличается от последней
+    pub fn snapshot(&mut self, file: &Path, text: &str) -> Result<(), String> {
+        let Some(dir) = snapshots_dir(file) else {
+            return Ok(());
+        };
+        let snapshots = list_snapshots(&dir);
+        if let Some(latest) = snapshots.first() {
+            if fs::read_to_string(&latest.path).is_ok_and(|latest| latest == text) {
+                return Ok(());
+            }
+        }
+
+        fs::create_dir_all(&dir).map_err(|e| tr!("Ошибка сохранения истории версий: {}", e))?;
+        let name = format!("{}.txt", chrono::Local::now().format(SNAPSHOT_FORMAT));
+        fs::write(dir.join(name), text).map_err(|e| tr!("Ошибка сохранения истории версий: {}", e))?;
+        for old in snapshots.iter().skip(MAX_SNAPSHOTS - 1) {
+            let _ = fs::remove_file(&old.path);
+        }
+
+        if self.file.as_deref() == Some(file) {
+            self.refresh();
+        }
+        Ok(())
+    }
+
+    pub fn open(&mut self, file: Option<&Path>) {
+        self.show_window = true;
+        self.file = file.map(Path::to_path_buf);
+        self.selected = 0;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.snapshots = self
+            .file
+            .as_deref()
+            .and_then(snapshots_dir)
+            .map(|dir| list_snapshots(&dir))
+            .unwrap_or_default();
+        self.selected = self.selected.min(self.snapshots.len().saturating_sub(1));
+    }
+
+    fn read(&self, index: usize) -> Result<String, String> {
+        fs::read_to_string(&self.snapshots[index].path).map_err(|e| tr!("Ошибка чтения версии: {}", e))
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, text: &str, config: &mut Config) -> Result<HistoryResult, String> {
+        let mut result = Ok(HistoryResult::None);
+        if !self.show_window {
+            return result;
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("История версий"))
+            .open(&mut open)
+            .default_size([420.0, 360.0])
+            .show(ctx, |ui| {
+                if ui.checkbox(&mut config.keep_history, tr!("Сохранять версии при сохранении файла")).changed() {
+                    result = Ok(HistoryResult::ConfigChanged);
+                }
+                ui.separator();
+
+                let Some(file) = self.file.clone() else {
+                    ui.label(tr!("Файл еще не сохранен"));
+                    return;
+                };
+                if self.snapshots.is_empty() {
+                    ui.label(tr!("Сохраненных версий нет"));
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 40.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (index, snapshot) in self.snapshots.iter().enumerate() {
+                            if ui.selectable_label(index == self.selected, &snapshot.label).clicked() {
+                                self.selected = index;
+                            }
+                        }
+                    });
+                ui.separator();
+
+                let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let label = self.snapshots[self.selected].label.clone();
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Сравнить с текущим текстом")).clicked() {
+                        result = self.read(self.selected).map(|old| HistoryResult::Compare {
+                            title: tr!("История версий: {}", name),
+                            old_label: label.clone(),
+                            new_label: tr!("В редакторе").to_string(),
+                            old,
+                            new: text.to_string(),
+                        });
+                    }
+                    let previous = self.selected + 1;
+                    if ui
+                        .add_enabled(previous < self.snapshots.len(), egui::Button::new(tr!("Сравнить с предыдущей")))
+                        .clicked()
+                    {
+                        result = self.read(previous).and_then(|old| {
+                            Ok(HistoryResult::Compare {
+                                title: tr!("История версий: {}", name),
+                                old_label: self.snapshots[previous].label.clone(),
+                                new_label: label.clone(),
+                                old,
+                                new: self.read(self.selected)?,
+                            })
+                        });
+                    }
+                    if ui.button(tr!("Восстановить")).clicked() {
+                        result = self.read(self.selected).map(HistoryResult::Restore);
+                    }
+                });
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+        result
+    }
+}
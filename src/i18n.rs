@@ -0,0 +1,914 @@
+// i18n.rs
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::config::Language;
+
+// Перевод строки интерфейса на текущий язык.
+// tr!("Текст") — строка; tr!("Строк: {}", n) — подстановка аргументов вместо {} по порядку.
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::translate($key)
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::translate($key), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+pub(crate) use tr;
+
+static ENGLISH: AtomicBool = AtomicBool::new(false);
+
+pub fn set_language(language: Language) {
+    ENGLISH.store(language == Language::English, Ordering::Relaxed);
+}
+
+// Пары (русский, английский). Ключом служит сама строка на любом из языков:
+// интерфейс GUI написан по-русски, а сообщения TUI — по-английски.
+// Строки без перевода показываются как есть.
+const STRINGS: &[(&str, &str)] = &[
+    // Меню
+    ("Файл", "File"),
+    ("Новый", "New"),
+    ("Открыть", "Open"),
+    ("Открыть папку...", "Open folder..."),
+    ("Быстрое открытие (Ctrl + P)", "Quick open (Ctrl + P)"),
+    ("Сохранить", "Save"),
+    ("Сохранить как", "Save as"),
+    ("Настройки", "Settings"),
+    ("Выйти", "Quit"),
+    ("Редактировать", "Edit"),
+    ("Найти (Ctrl + F)", "Find (Ctrl + F)"),
+    ("Найти в файлах (Ctrl + Shift + F)", "Find in files (Ctrl + Shift + F)"),
+    ("Закладка (Ctrl + B)", "Bookmark (Ctrl + B)"),
+    ("Вырезать (Ctrl + X)", "Cut (Ctrl + X)"),
+    ("Копировать (Ctrl + C)", "Copy (Ctrl + C)"),
+    ("Вставить (Ctrl + V)", "Paste (Ctrl + V)"),
+    ("Вид", "View"),
+    ("Панель файлов", "File panel"),
+    ("Закладки", "Bookmarks"),
+    ("Миникарта", "Minimap"),
+    ("Разделить", "Split"),
+    ("Рядом", "Side by side"),
+    ("Одна над другой", "Top and bottom"),
+    ("Открыть файл во второй панели...", "Open file in second pane..."),
+    ("Закрыть разделение", "Close split"),
+    ("Тот же документ", "Same document"),
+    ("только чтение", "read-only"),
+    (
+        "Документ Word или файл не в UTF-8 — откройте его в основном окне, чтобы править",
+        "A Word document or a non-UTF-8 file: open it in the main window to edit it",
+    ),
+    ("Сохранить изменения во второй панели?", "Save changes in the second pane?"),
+    ("Увеличить (Ctrl + Плюс)", "Zoom in (Ctrl + Plus)"),
+    ("Уменьшить (Ctrl + Минус)", "Zoom out (Ctrl + Minus)"),
+    ("Исходный масштаб (Ctrl + 0)", "Reset zoom (Ctrl + 0)"),
+    ("Тема", "Theme"),
+    ("Редактор тем...", "Theme editor..."),
+    ("🔊 Музыка", "🔊 Music"),
+    ("🔇 Музыка", "🔇 Music"),
+    // Строка состояния
+    ("Без названия", "Untitled"),
+    ("{} • (изменен)", "{} • (modified)"),
+    ("Символов: {}", "Characters: {}"),
+    ("Слов: {}", "Words: {}"),
+    ("Строк: {}", "Lines: {}"),
+    ("Найдено: {}", "Found: {}"),
+    ("Масштаб: {}%", "Zoom: {}%"),
+    ("Ctrl + колесо мыши, Ctrl + Плюс/Минус; щелчок — 100%", "Ctrl + mouse wheel, Ctrl + Plus/Minus; click for 100%"),
+    // Музыка
+    ("Плейлист", "Playlist"),
+    ("Встроенная музыка", "Built-in music"),
+    ("Предыдущий трек", "Previous track"),
+    ("Следующий трек", "Next track"),
+    ("Случайный порядок", "Shuffle"),
+    ("Повторять плейлист", "Repeat playlist"),
+    ("В папке нет музыки", "No music in this folder"),
+    ("Двойной щелчок — играть трек", "Double-click to play a track"),
+    ("Панель воспроизведения", "Playback bar"),
+    ("Музыка", "Music"),
+    ("🎵 Файл...", "🎵 File..."),
+    ("Играть (F8)", "Play (F8)"),
+    ("Пауза (F8)", "Pause (F8)"),
+    ("Выбрать файл...", "Choose file..."),
+    ("Выбрать папку...", "Choose folder..."),
+    ("Фоновые звуки", "Ambient sounds"),
+    ("Выключены", "Off"),
+    ("Дождь", "Rain"),
+    ("Белый шум", "White noise"),
+    ("Коричневый шум", "Brown noise"),
+    ("Кафе", "Café"),
+    ("Ошибка звукового устройства: {}", "Audio device error: {}"),
+    ("Предыдущий трек (F7)", "Previous track (F7)"),
+    ("Играть/пауза (F8)", "Play/pause (F8)"),
+    ("Следующий трек (F9)", "Next track (F9)"),
+    ("Перемотка: Shift + F7 / Shift + F9", "Seek: Shift + F7 / Shift + F9"),
+    ("Ошибка открытия музыкального файла: {}", "Failed to open music file: {}"),
+    ("Не удалось воспроизвести {}: {}", "Cannot play {}: {}"),
+    (
+        "Формат {} не поддерживается или кодек внутри файла неизвестен: {}",
+        "Format {} is not supported or the codec inside is unknown: {}",
+    ),
+    ("Тестовый тон", "Test tone"),
+    ("Музыка выключена", "Music off"),
+    ("Громкость: {}%", "Volume: {}%"),
+    ("Остановить", "Stop"),
+    (" (пауза)", " (paused)"),
+    ("Включить звук", "Unmute"),
+    ("Выключить звук", "Mute"),
+    ("Музыкальный файл не найден. Воспроизводится тестовый тон.", "Music file not found. Playing a test tone."),
+    // Стартовая страница
+    ("Открыть...", "Open..."),
+    ("Недавние файлы", "Recent files"),
+    ("Пока ничего не открывалось", "Nothing opened yet"),
+    ("Справка по терминальному режиму", "Terminal mode help"),
+    ("Ctrl-Q — выход, Ctrl-S — сохранить", "Ctrl-Q = quit, Ctrl-S = save"),
+    ("Ctrl-F — поиск, Ctrl-R — замена, Ctrl-Z — отмена", "Ctrl-F = search, Ctrl-R = replace, Ctrl-Z = undo"),
+    ("Не показывать при запуске", "Don't show on startup"),
+    ("Недавние", "Recent"),
+    ("Начальная страница", "Start page"),
+    // Файлы
+    ("Текстовый редактор Глеба", "Gleb's Text Editor"),
+    ("Текстовые файлы", "Text files"),
+    ("Документы Word", "Word documents"),
+    ("Все файлы", "All files"),
+    ("Ошибка открытия TXT файла: {}", "Failed to open TXT file: {}"),
+    ("Ошибка чтения файла: {}", "Failed to read file: {}"),
+    ("Ошибка чтения DOCX файла: {}", "Failed to read DOCX file: {}"),
+    ("Ошибка открытия DOCX файла: {}", "Failed to open DOCX file: {}"),
+    ("Ошибка открытия DOC файла: {}", "Failed to open DOC file: {}"),
+    ("Ошибка сохранения файла: {}", "Failed to save file: {}"),
+    ("Ошибка открытия файла: {}", "Failed to open file: {}"),
+    ("Сохранение файла", "Save file"),
+    ("Сохранить изменения перед созданием нового файла?", "Save changes before creating a new file?"),
+    ("Сохранить изменения перед открытием другого файла?", "Save changes before opening another file?"),
+    ("Не сохранять", "Don't save"),
+    ("Отмена", "Cancel"),
+    // Поиск
+    ("Поиск", "Search"),
+    ("Введите текст для поиска...", "Text to find..."),
+    ("С учетом регистра", "Match case"),
+    ("Многострочный", "Multiline"),
+    ("Спецсимволы (\\n, \\t)", "Escapes (\\n, \\t)"),
+    ("Поиск...", "Searching..."),
+    ("Совпадений не найдено", "No matches"),
+    ("{} из {}", "{} of {}"),
+    ("Быстрые клавиши:", "Shortcuts:"),
+    ("• Ctrl+F - открыть/закрыть поиск", "• Ctrl+F - toggle search"),
+    ("• F3 - следующее совпадение", "• F3 - next match"),
+    ("• Shift+F3 - предыдущее совпадение", "• Shift+F3 - previous match"),
+    // Поиск в файлах
+    ("Найти в файлах", "Find in files"),
+    ("📁 Папка...", "📁 Folder..."),
+    ("Папка не выбрана", "No folder selected"),
+    ("Что искать...", "Find what..."),
+    ("Стоп", "Stop"),
+    ("Найти", "Find"),
+    ("Расширения:", "Extensions:"),
+    ("Регулярное выражение", "Regular expression"),
+    ("Просмотрено файлов: {} • Найдено: {}", "Files scanned: {} • Found: {}"),
+    ("Выберите папку для поиска", "Choose a folder to search"),
+    ("Ошибка в регулярном выражении: {}", "Invalid regular expression: {}"),
+    ("Не удалось прочитать папку {}: {}", "Failed to read folder {}: {}"),
+    // Быстрое открытие
+    ("Быстрое открытие", "Quick open"),
+    ("Имя файла...", "File name..."),
+    ("Ничего не найдено", "Nothing found"),
+    ("... и еще {}", "... and {} more"),
+    // Панель файлов
+    ("Обновить", "Refresh"),
+    ("Новый файл", "New file"),
+    ("Переименовать", "Rename"),
+    ("Удалить", "Delete"),
+    ("Папка: {}", "Folder: {}"),
+    ("Создать", "Create"),
+    ("Удаление", "Delete"),
+    ("Удалить {}?", "Delete {}?"),
+    ("Ошибка создания файла: {}", "Failed to create file: {}"),
+    ("Ошибка переименования: {}", "Failed to rename: {}"),
+    ("Ошибка удаления: {}", "Failed to delete: {}"),
+    // Закладки
+    ("Закладок нет. Ctrl+B — поставить закладку на текущей строке", "No bookmarks. Ctrl+B bookmarks the current line"),
+    ("Строка {}: {}", "Line {}: {}"),
+    ("F2 / Shift+F2 — следующая / предыдущая закладка", "F2 / Shift+F2 — next / previous bookmark"),
+    ("Ошибка сохранения закладок: {}", "Failed to save bookmarks: {}"),
+    // Настройки
+    ("Шрифт:", "Font:"),
+    ("Моноширинный", "Monospace"),
+    ("Обычный", "Proportional"),
+    ("Из файла", "From file"),
+    ("Файл шрифта:", "Font file:"),
+    ("Выбрать...", "Choose..."),
+    ("Шрифты", "Fonts"),
+    ("Убрать", "Remove"),
+    ("Размер шрифта:", "Font size:"),
+    ("Тема:", "Theme:"),
+    ("Ширина табуляции:", "Tab width:"),
+    ("Текущая строка:", "Current line:"),
+    ("Подсвечивать", "Highlight"),
+    ("Линейка:", "Ruler:"),
+    ("столбец ", "column "),
+    ("Автосохранение:", "Autosave:"),
+    (" с", " s"),
+    ("Громкость музыки:", "Music volume:"),
+    ("Громкость фоновых звуков:", "Ambient sounds volume:"),
+    ("Звук клавиш:", "Key sounds:"),
+    ("Механическая", "Mechanical"),
+    ("Пишущая машинка", "Typewriter"),
+    ("Тихая", "Soft"),
+    ("Громкость клавиш:", "Key sounds volume:"),
+    // Статистика
+    ("Инструменты", "Tools"),
+    ("Статистика...", "Statistics..."),
+    ("Статистика", "Statistics"),
+    ("Слов", "Words"),
+    ("Символов", "Characters"),
+    ("Символов без пробелов", "Characters without spaces"),
+    ("Строк", "Lines"),
+    ("Абзацев", "Paragraphs"),
+    ("Предложений", "Sentences"),
+    ("Средняя длина слова", "Average word length"),
+    ("Самая длинная строка", "Longest line"),
+    ("{} (строка {})", "{} (line {})"),
+    ("Время чтения", "Reading time"),
+    ("Скорость чтения:", "Reading speed:"),
+    (" слов/мин", " words/min"),
+    ("меньше минуты", "less than a minute"),
+    ("{} мин", "{} min"),
+    ("{} ч {} мин", "{} h {} min"),
+    // Частота слов
+    ("Частота слов...", "Word frequency..."),
+    ("Частота слов", "Word frequency"),
+    ("Показать:", "Show:"),
+    ("Без служебных слов", "Skip stop words"),
+    ("Весь документ", "Whole document"),
+    ("Выделение", "Selection"),
+    ("🔄 Обновить", "🔄 Refresh"),
+    ("Всего слов: {}", "Total words: {}"),
+    ("Слово", "Word"),
+    ("Количество", "Count"),
+    ("Экспорт в CSV...", "Export to CSV..."),
+    // Журнал письма
+    ("Журнал письма...", "Writing log..."),
+    ("Журнал письма", "Writing log"),
+    ("За этот сеанс:", "This session:"),
+    ("Сегодня:", "Today:"),
+    ("Цель на день:", "Daily goal:"),
+    ("0 — без цели", "0 — no goal"),
+    ("Последние {} дней", "Last {} days"),
+    ("{}: {} слов", "{}: {} words"),
+    ("Слов за сегодня; щелчок — журнал письма", "Words written today; click for the writing log"),
+    ("Ошибка сохранения журнала письма: {}", "Failed to save the writing log: {}"),
+    // Орфография
+    ("Проверка орфографии", "Spell checking"),
+    ("Загрузка словарей...", "Loading dictionaries..."),
+    ("Словари Hunspell (.aff и .dic) не найдены в {}", "No Hunspell dictionaries (.aff and .dic) found in {}"),
+    ("Словари: {}", "Dictionaries: {}"),
+    ("Нет вариантов", "No suggestions"),
+    ("Добавить «{}» в словарь", "Add \"{}\" to dictionary"),
+    ("Ошибка чтения словаря {}: {}", "Failed to read dictionary {}: {}"),
+    ("Ошибка сохранения словаря: {}", "Failed to save dictionary: {}"),
+    // Автодополнение
+    ("Автодополнение:", "Autocomplete:"),
+    ("Предлагать слова при наборе", "Suggest words while typing"),
+    ("Ctrl + Пробел — показать подсказки вручную", "Ctrl + Space shows suggestions manually"),
+    // Сниппеты
+    ("Сниппеты...", "Snippets..."),
+    ("Сниппеты", "Snippets"),
+    ("Вставить сниппет", "Insert snippet"),
+    ("Нет сниппетов", "No snippets"),
+    ("Добавить", "Add"),
+    ("Сокращение:", "Abbreviation:"),
+    ("Описание:", "Description:"),
+    ("Наберите сокращение и нажмите Tab. Tab / Shift + Tab — к следующему / предыдущему месту ввода.", "Type an abbreviation and press Tab. Tab / Shift + Tab move to the next / previous field."),
+    ("${1:текст} — место для ввода, $0 — курсор в конце, {{date}}, {{time}}, {{filename}} — дата, время, имя файла", "${1:text} is a field, $0 is the final cursor, {{date}}, {{time}}, {{filename}} are the date, time and file name"),
+    // Автозамена
+    ("Автозамена...", "Auto-replace..."),
+    ("Автозамена", "Auto-replace"),
+    ("Заменять при наборе", "Replace while typing"),
+    ("Кавычки-елочки «» вместо \"\"", "Typographic quotes «» instead of \"\""),
+    ("Backspace сразу после замены возвращает набранное.", "Backspace right after a replacement restores what you typed."),
+    ("Набрано", "Typed"),
+    ("Заменить на", "Replace with"),
+    ("По умолчанию", "Defaults"),
+    // Палитра команд
+    ("Палитра команд (Ctrl + Shift + P)", "Command palette (Ctrl + Shift + P)"),
+    ("Палитра команд", "Command palette"),
+    ("Команда...", "Command..."),
+    ("Закладка", "Bookmark"),
+    ("Разделить рядом", "Split side by side"),
+    ("Разделить одна над другой", "Split one above the other"),
+    ("Увеличить", "Zoom in"),
+    ("Уменьшить", "Zoom out"),
+    ("Исходный масштаб", "Reset zoom"),
+    ("Играть / пауза", "Play / pause"),
+    ("Выключить / включить звук", "Mute / unmute"),
+    // Плагины
+    ("Плагины", "Plugins"),
+    ("Нет команд плагинов", "No plugin commands"),
+    ("Перезагрузить плагины", "Reload plugins"),
+    ("Скрипты *.rhai из {}", "*.rhai scripts from {}"),
+    ("Ошибка в плагине {}: {}", "Plugin {} failed: {}"),
+    ("Команда плагина не найдена", "Plugin command not found"),
+    // Внешняя команда
+    ("Выполнить команду...", "Run command..."),
+    ("Выполнить команду", "Run command"),
+    ("Текст передается команде на вход и заменяется ее выводом:", "The text is piped to the command and replaced with its output:"),
+    ("Ограничение времени:", "Time limit:"),
+    ("Недавние команды", "Recent commands"),
+    ("Выполнить", "Run"),
+    ("Выполняется...", "Running..."),
+    ("Не удалось запустить команду: {}", "Failed to start the command: {}"),
+    ("Команда не завершилась за {} с и была остановлена", "The command did not finish in {} s and was stopped"),
+    ("Команда завершилась с кодом {}", "The command exited with code {}"),
+    ("Текст изменился, пока выполнялась команда; результат не вставлен", "The text changed while the command was running; the output was not inserted"),
+    // Сравнение текстов
+    ("Только изменения", "Changes only"),
+    ("⏶ Предыдущее", "⏶ Previous"),
+    ("⏷ Следующее", "⏷ Next"),
+    ("Различий нет", "No differences"),
+    ("⋯ без изменений: {} строк", "⋯ {} unchanged lines"),
+    ("В редакторе", "In the editor"),
+    ("Сравнить с сохраненным", "Compare with saved"),
+    ("Сравнение с сохраненным: {}", "Compare with saved: {}"),
+    ("На диске", "On disk"),
+    ("Сравнить файлы...", "Compare files..."),
+    ("Сравнить файлы", "Compare files"),
+    ("Сравнение файлов", "File comparison"),
+    ("Исходный:", "Original:"),
+    ("Измененный:", "Modified:"),
+    ("Текущий документ", "Current document"),
+    ("Вторая панель: {}", "Second pane: {}"),
+    ("Файл...", "File..."),
+    ("Обзор...", "Browse..."),
+    ("Сравнить", "Compare"),
+    ("⇄ Поменять местами", "⇄ Swap"),
+    ("Во второй панели нет файла", "The second pane has no file"),
+    ("Файл для сравнения не выбран", "No file selected for comparison"),
+    // Изменения файла на диске
+    ("Файл изменен на диске", "File changed on disk"),
+    ("Файл {} изменила другая программа.", "{} was changed by another program."),
+    ("Перезагрузить", "Reload"),
+    ("Оставить мою версию", "Keep my version"),
+    ("Показать различия", "Show differences"),
+    ("Файл изменен на диске, сохранение отменено", "The file changed on disk; saving was cancelled"),
+    // Git
+    ("Сравнить с HEAD", "Diff against HEAD"),
+    ("Сравнение с HEAD ({})", "Diff against HEAD ({})"),
+    ("Коммит...", "Commit..."),
+    ("Коммит", "Commit"),
+    ("Ветка:", "Branch:"),
+    ("Файл:", "File:"),
+    ("Сообщение:", "Message:"),
+    ("Перед коммитом файл будет сохранен", "The file will be saved before committing"),
+    ("Закоммитить", "Commit"),
+    ("Создан коммит {}", "Created commit {}"),
+    ("Файл не в репозитории git", "The file is not in a git repository"),
+    ("Ошибка git: {}", "Git error: {}"),
+    // История версий
+    ("История версий...", "Version history..."),
+    ("История версий", "Version history"),
+    ("История версий: {}", "Version history: {}"),
+    ("Сохранять версии при сохранении файла", "Keep a version on every save"),
+    ("Файл еще не сохранен", "The file has not been saved yet"),
+    ("Сохраненных версий нет", "No saved versions"),
+    ("Сравнить с текущим текстом", "Compare with current text"),
+    ("Сравнить с предыдущей", "Compare with previous"),
+    ("Восстановить", "Restore"),
+    ("Ошибка сохранения истории версий: {}", "Failed to save version history: {}"),
+    ("Ошибка чтения версии: {}", "Failed to read the version: {}"),
+    // Свойства файла
+    ("Свойства файла...", "File properties..."),
+    ("Свойства файла", "File properties"),
+    ("Путь:", "Path:"),
+    ("Размер:", "Size:"),
+    ("Изменен:", "Modified:"),
+    ("Кодировка:", "Encoding:"),
+    ("Переводы строк:", "Line endings:"),
+    ("Только чтение:", "Read-only:"),
+    ("Да", "Yes"),
+    ("Нет", "No"),
+    ("Ошибка:", "Error:"),
+    ("Строк:", "Lines:"),
+    ("Слов:", "Words:"),
+    ("Символов:", "Characters:"),
+    ("UTF-8 с BOM", "UTF-8 with BOM"),
+    ("Нет переводов строк", "No line breaks"),
+    ("Смешанные (CRLF и LF)", "Mixed (CRLF and LF)"),
+    ("Б", "B"),
+    ("КБ", "KB"),
+    ("МБ", "MB"),
+    ("ГБ", "GB"),
+    ("{} {} ({} байт)", "{} {} ({} bytes)"),
+    // Архивы
+    ("Архивы", "Archives"),
+    ("Архивы:", "Archives:"),
+    ("сохранять текст из .gz и .zip обратно в архив", "save text from .gz and .zip back into the archive"),
+    ("Документы Word:", "Word documents:"),
+    ("открывать с разметкой Markdown", "open with Markdown markup"),
+    ("Заголовки, полужирный и курсив, списки сохраняют структуру", "Headings, bold and italic, and lists keep their structure"),
+    ("Таблицы:", "Tables:"),
+    ("через табуляцию", "tab-separated"),
+    ("Ошибка записи DOCX файла: {}", "Error writing DOCX file: {}"),
+    (
+        "Не удалось сопоставить абзацы документа с файлом — сохраните его под другим именем",
+        "Could not match the document's paragraphs to the file: save it under another name",
+    ),
+    ("В таблицах документа Word можно менять только текст ячеек, но не число строк и столбцов", "Only cell text can be changed in Word document tables, not the number of rows and columns"),
+    ("Документ открыт с разметкой или добавками и сохраняется только под другим именем", "The document was opened with markup or additions and can only be saved under another name"),
+    ("Документ .doc сохраняется только под другим именем", "A .doc document can only be saved under another name"),
+    (
+        "В кодировке {} нет некоторых символов документа — сохраните его под другим именем в UTF-8",
+        "Some characters of the document are missing from {}: save it under another name in UTF-8",
+    ),
+    ("сохранять картинки в папку рядом с документом", "save images to a folder next to the document"),
+    ("В тексте на их месте — [изображение: имя файла]", "In the text they are replaced with [image: file name]"),
+    ("[изображение: {}]", "[image: {}]"),
+    ("Ошибка сохранения картинок: {}", "Error saving images: {}"),
+    ("заголовки отмечать знаками #", "mark headings with #"),
+    ("По ним строится структура документа (Вид → Структура документа)", "They build the document outline (View → Document outline)"),
+    ("колонтитулы в конце текста", "headers and footers at the end of the text"),
+    ("сноски: [^1] в тексте, пояснения в конце", "footnotes: [^1] in the text, notes at the end"),
+    ("примечания рецензентов цитатами после абзаца", "reviewer comments as quotes after the paragraph"),
+    ("спрашивать при каждом открытии .docx", "ask every time a .docx is opened"),
+    ("Открыть документ Word", "Open Word document"),
+    ("Открыть документ Word...", "Open Word document..."),
+    ("> Примечание ({}): {}", "> Comment ({}): {}"),
+    ("Верхний колонтитул:", "Header:"),
+    ("Нижний колонтитул:", "Footer:"),
+    ("Открыть из архива {}", "Open from archive {}"),
+    ("В архиве нет файлов", "The archive has no files"),
+    ("Ошибка чтения архива: {}", "Failed to read the archive: {}"),
+    ("Ошибка сжатия: {}", "Compression failed: {}"),
+    // Шифрование
+    ("Сохранить зашифрованным...", "Save encrypted..."),
+    ("Сохранить зашифрованным", "Save encrypted"),
+    ("Открыть зашифрованный файл", "Open encrypted file"),
+    ("Зашифрованные документы", "Encrypted documents"),
+    ("Пароль:", "Password:"),
+    ("Повторите пароль:", "Repeat password:"),
+    ("Забытый пароль восстановить нельзя.", "A forgotten password cannot be recovered."),
+    ("Пароли не совпадают", "Passwords do not match"),
+    ("Ошибка шифрования: {}", "Encryption failed: {}"),
+    ("Файл не является зашифрованным документом", "The file is not an encrypted document"),
+    ("Неверный пароль или файл поврежден", "Wrong password or corrupted file"),
+    // Большие файлы
+    ("Загрузка {}...", "Loading {}..."),
+    ("{}: {} строк, только чтение", "{}: {} lines, read-only"),
+    ("Найдено: {} (просмотрено {}%)", "Found: {} ({}% searched)"),
+    ("(показаны первые {})", "(first {} shown)"),
+    ("Закрыть", "Close"),
+    // Преобразования текста
+    ("Преобразовать", "Transform"),
+    ("ВЕРХНИЙ РЕГИСТР", "UPPER CASE"),
+    ("нижний регистр", "lower case"),
+    ("Каждое Слово С Заглавной", "Title Case"),
+    ("Сортировать строки по алфавиту", "Sort lines alphabetically"),
+    ("Сортировать строки естественно (2 < 10)", "Sort lines naturally (2 < 10)"),
+    ("Сортировать строки по числам", "Sort lines numerically"),
+    ("Удалить повторяющиеся строки", "Remove duplicate lines"),
+    ("Обратный порядок строк", "Reverse lines"),
+    ("Объединить строки через", "Join lines with"),
+    ("Переформатировать абзац", "Reflow paragraph"),
+    ("Переформатировать абзац (Alt + Q)", "Reflow paragraph (Alt + Q)"),
+    ("Ширина абзаца:", "Paragraph width:"),
+    ("Закодировать в Base64", "Encode to Base64"),
+    ("Раскодировать из Base64", "Decode from Base64"),
+    ("Закодировать для URL", "URL-encode"),
+    ("Раскодировать из URL", "URL-decode"),
+    ("Неверный Base64: {}", "Invalid Base64: {}"),
+    ("Результат не является текстом UTF-8", "The result is not UTF-8 text"),
+    // Парные скобки и кавычки
+    ("Парные символы:", "Auto-pairing:"),
+    ("Закрывать скобки и кавычки автоматически", "Close brackets and quotes automatically"),
+    ("Выделенный текст при наборе скобки или кавычки оборачивается ими", "Typing a bracket or quote wraps the selected text"),
+    // Строки
+    ("Строка", "Line"),
+    ("Дублировать (Ctrl + D)", "Duplicate (Ctrl + D)"),
+    ("Переместить вверх (Alt + Вверх)", "Move up (Alt + Up)"),
+    ("Переместить вниз (Alt + Вниз)", "Move down (Alt + Down)"),
+    ("Удалить строку (Ctrl + Shift + K)", "Delete line (Ctrl + Shift + K)"),
+    ("Дублировать строку", "Duplicate line"),
+    ("Переместить строку вверх", "Move line up"),
+    ("Переместить строку вниз", "Move line down"),
+    ("Удалить строку", "Delete line"),
+    ("Закомментировать (Ctrl + /)", "Toggle comment (Ctrl + /)"),
+    ("Закомментировать строки", "Toggle line comments"),
+    ("Для файлов этого типа комментарии не известны", "Comments are not known for this file type"),
+    // Отступы
+    ("Пробелами вместо табуляции", "Insert spaces instead of tabs"),
+    ("Преобразовать отступы", "Convert indentation"),
+    ("В пробелы", "To spaces"),
+    ("В табуляцию", "To tabs"),
+    ("Преобразовать отступы в пробелы", "Convert indentation to spaces"),
+    ("Преобразовать отступы в табуляцию", "Convert indentation to tabs"),
+    // Следующее вхождение
+    ("Выделить следующее вхождение (Ctrl + Shift + D)", "Select next occurrence (Ctrl + Shift + D)"),
+    ("Пропустить вхождение (Alt + Shift + D)", "Skip occurrence (Alt + Shift + D)"),
+    ("Выделить следующее вхождение", "Select next occurrence"),
+    ("Пропустить вхождение", "Skip occurrence"),
+    // Markdown
+    ("Ж", "B"),
+    ("К", "I"),
+    ("Ссылка", "Link"),
+    ("Таблица", "Table"),
+    ("Полужирный", "Bold"),
+    ("Курсив", "Italic"),
+    ("Код", "Code"),
+    ("Заголовок", "Heading"),
+    ("Отметить пункт", "Toggle checkbox"),
+    ("Выровнять таблицу", "Align table"),
+    // Структура документа
+    ("Структура документа", "Document outline"),
+    ("Заголовков нет. Строки, начинающиеся с # , ## , ### …, попадают в структуру", "No headings. Lines starting with # , ## , ### … appear in the outline"),
+    // Шаблоны документов
+    ("Новый из шаблона", "New from template"),
+    ("Шаблоны документов...", "Document templates..."),
+    ("Шаблоны документов", "Document templates"),
+    ("(новый)", "(new)"),
+    ("Создать документ", "Create document"),
+    ("{{date}}, {{time}}, {{author}} — дата, время, автор из настроек; {{cursor}} — где встанет курсор", "{{date}}, {{time}}, {{author}} are the date, time and author from settings; {{cursor}} marks the cursor position"),
+    ("Папка шаблонов: {}", "Templates folder: {}"),
+    ("Недопустимое имя шаблона: {}", "Invalid template name: {}"),
+    ("Не найдена папка настроек", "Settings folder not found"),
+    ("Шаблон «{}» уже есть", "Template \"{}\" already exists"),
+    ("Ошибка шаблона: {}", "Template error: {}"),
+    ("Автор:", "Author:"),
+    ("Подставляется вместо {{author}} в шаблонах", "Used for {{author}} in templates"),
+
+    // Журнал
+    ("Новая запись в журнале", "New journal entry"),
+    ("Не задан файл журнала", "Journal file is not set"),
+    ("Ошибка создания журнала: {}", "Error creating journal: {}"),
+    ("Прежние записи журнала только для чтения", "Earlier journal entries are read-only"),
+    ("Журнал:", "Journal:"),
+    ("файл", "file"),
+    ("заголовок записи", "entry heading"),
+    ("Формат даты strftime; относительный путь — от домашней папки", "strftime date format; relative paths start at the home folder"),
+    ("прежние записи только для чтения", "earlier entries are read-only"),
+
+    // Выделенное в файл и файл в текст
+    ("Сохранить выделенное как...", "Save selection as..."),
+    ("Сохранить выделенное как", "Save selection as"),
+    ("Вставить файл...", "Insert file..."),
+    ("Вставить файл", "Insert file"),
+    ("Сначала выделите текст", "Select some text first"),
+    ("Вставить", "Insert"),
+    ("определить", "detect"),
+    ("UTF-8, если файл в ней читается, иначе — {}", "UTF-8 if the file is valid UTF-8, otherwise {}"),
+    ("Неизвестная кодировка: {}", "Unknown encoding: {}"),
+    ("В тексте есть символы, которых нет в кодировке {}", "The text has characters that {} cannot represent"),
+
+    // Повторяющиеся строки
+    ("Повторяющиеся строки", "Duplicate lines"),
+    ("без учета пробелов", "ignore whitespace"),
+    ("Повторов нет", "No duplicates"),
+    ("Строк с повторами: {}, лишних копий: {}", "Duplicated lines: {}, extra copies: {}"),
+    ("Щелчок — к следующему вхождению", "Click to go to the next occurrence"),
+    ("Удалить повторы", "Remove duplicates"),
+    ("Остается первое вхождение каждой строки", "The first occurrence of each line is kept"),
+
+    // Замена по регулярному выражению
+    ("Замена по регулярному выражению...", "Regex replace..."),
+    ("Замена по регулярному выражению", "Regex replace"),
+    ("Шаблон:", "Pattern:"),
+    ("Замена:", "Replacement:"),
+    ("без учета регистра", "ignore case"),
+    ("весь документ", "whole document"),
+    ("выделение", "selection"),
+    ("только проверить: подсветить найденное, не заменяя", "test only: highlight matches without replacing"),
+    ("$1, ${имя} — группы, $$ — знак доллара, \\n и \\t — перевод строки и табуляция", "$1, ${name} are groups, $$ is a dollar sign, \\n and \\t are newline and tab"),
+    ("Введите шаблон", "Enter a pattern"),
+    ("Совпадений нет", "No matches"),
+    ("…и еще {}", "…and {} more"),
+    ("Заменить все", "Replace all"),
+
+    // Строка состояния
+    ("Имя файла", "File name"),
+    ("Ветка Git", "Git branch"),
+    ("Символы", "Characters"),
+    ("Слова", "Words"),
+    ("Строки", "Lines"),
+    ("Позиция курсора", "Cursor position"),
+    ("Кодировка", "Encoding"),
+    ("Цель на день", "Daily goal"),
+    ("Найдено при поиске", "Search matches"),
+    ("Масштаб", "Zoom"),
+    ("Стр {}, стлб {}", "Ln {}, Col {}"),
+    ("Кодировка файла; «Сохранить как» записывает в UTF-8", "The file's encoding; Save As writes UTF-8"),
+
+    // Цель по словам для документа
+    ("Цель по словам...", "Word goal..."),
+    ("Цель по словам", "Word goal"),
+    ("Цель запоминается для файла — сначала сохраните документ", "The goal is stored per file — save the document first"),
+    ("Слов в документе:", "Words in document:"),
+    ("Сейчас: {}", "Now: {}"),
+    ("Задать", "Set"),
+    ("Убрать цель", "Remove goal"),
+    ("Слов в документе из цели; щелчок — изменить цель", "Words in the document out of the goal; click to change the goal"),
+
+    // Режимы подсчета
+    ("с пробелами", "with spaces"),
+    ("без пробелов", "without spaces"),
+    ("все через пробел", "all tokens"),
+    ("без отдельных знаков", "without standalone punctuation"),
+    ("без чисел и знаков", "without numbers and punctuation"),
+    ("Слов без чисел", "Words without numbers"),
+    ("Всего через пробел", "Whitespace-separated tokens"),
+    ("Счет в строке состояния:", "Status bar counts:"),
+    ("символы", "characters"),
+    ("слова", "words"),
+    // Статистика абзацев
+    ("Статистика абзацев", "Paragraph statistics"),
+    ("{} сл. · {} предл.", "{} w. · {} sent."),
+    // Текст-заполнитель
+    ("Текст-заполнитель", "Placeholder text"),
+    ("Текст-заполнитель...", "Placeholder text..."),
+    ("Рыба-текст", "Russian filler"),
+    ("Абзацев:", "Paragraphs:"),
+    ("Начать с привычной фразы", "Start with the classic phrase"),
+    // Консольный режим
+    ("Консольный режим:", "Terminal mode:"),
+    ("мышь (--tui)", "mouse (--tui)"),
+    (
+        "Выключите, если мышь мешает копировать текст средствами терминала",
+        "Turn off if the mouse gets in the way of the terminal's own copy and paste",
+    ),
+    // Парные скобки
+    ("К парной скобке", "Go to matching bracket"),
+    ("К парной скобке (Ctrl + M)", "Go to matching bracket (Ctrl + M)"),
+    // Вставка с очисткой
+    ("Вставить как обычный текст", "Paste as plain text"),
+    ("Вставить как обычный текст (Ctrl + Shift + V)", "Paste as plain text (Ctrl + Shift + V)"),
+    ("Не удалось прочитать буфер обмена: {}", "Could not read the clipboard: {}"),
+    ("Обычная вставка:", "Plain paste:"),
+    ("Схлопывать пустые строки", "Collapse blank lines"),
+    ("Переносить по ширине абзаца", "Wrap to paragraph width"),
+    // Транслитерация
+    ("Транслитерация:", "Transliteration:"),
+    ("Кириллица → латиница", "Cyrillic → Latin"),
+    ("Латиница → кириллица", "Latin → Cyrillic"),
+    ("ГОСТ 7.79-2000 (Б)", "GOST 7.79-2000 (B)"),
+    ("Как в загранпаспорте", "Passport (ICAO)"),
+    // Хеши
+    ("Вычислить хеш...", "Compute hash..."),
+    ("Вычислить хеш", "Compute hash"),
+    ("Текст документа", "Document text"),
+    ("Файл на диске", "File on disk"),
+    ("Копировать", "Copy"),
+    (" символов", " characters"),
+    // Дата и время
+    ("Вставить дату/время", "Insert date/time"),
+    ("Форматы даты...", "Date formats..."),
+    ("Форматы даты", "Date formats"),
+    ("Отмеченный формат вставляется по F5 и подставляется вместо {{date}} в сниппетах", "The checked format is inserted with F5 and used for {{date}} in snippets"),
+    ("%d — день, %m — месяц, %Y — год, %H:%M — время, %B — «октября», %L — «октябрь», %b — «окт», %A — день недели, %a — «пт»", "%d — day, %m — month, %Y — year, %H:%M — time, %B — “October”, %b — “Oct”, %A — weekday, %a — “Fri”"),
+    // Таблица символов
+    ("Таблица символов...", "Character map..."),
+    ("Таблица символов", "Character map"),
+    ("Поиск:", "Search:"),
+    ("тире, arrow, pi...", "dash, arrow, pi..."),
+    ("Недавние:", "Recent:"),
+    ("Типографика", "Typography"),
+    ("Латиница-1", "Latin-1"),
+    ("Расширенная латиница", "Latin Extended"),
+    ("Греческий", "Greek"),
+    ("Знаки препинания", "Punctuation"),
+    ("Валюты", "Currency"),
+    ("Стрелки", "Arrows"),
+    ("Математика", "Math"),
+    ("Псевдографика", "Box drawing"),
+    ("Геометрические фигуры", "Geometric shapes"),
+    ("Разные символы", "Miscellaneous symbols"),
+    // Калькулятор
+    ("Вычислить выражение (Ctrl + Shift + E)", "Evaluate expression (Ctrl + Shift + E)"),
+    ("Заменить выражение результатом (Ctrl + Shift + R)", "Replace expression with result (Ctrl + Shift + R)"),
+    ("Вычислить выражение", "Evaluate expression"),
+    ("Заменить выражение результатом", "Replace expression with result"),
+    ("Ошибка в выражении: {}", "Expression error: {}"),
+    ("деление на ноль", "division by zero"),
+    ("не хватает «)»", "missing “)”"),
+    ("неожиданный символ «{}»", "unexpected character “{}”"),
+    ("выражение оборвалось", "unexpected end of expression"),
+    ("неверное число «{}»", "invalid number “{}”"),
+    ("неизвестное имя «{}»", "unknown name “{}”"),
+    ("{} принимает один аргумент", "{} takes one argument"),
+    ("неизвестная функция «{}»", "unknown function “{}”"),
+    ("результат не является числом", "the result is not a number"),
+    // Нумерация строк
+    ("Нумерация строк...", "Number lines..."),
+    ("Нумерация строк", "Number lines"),
+    ("Начать с:", "Start at:"),
+    ("Шаг:", "Step:"),
+    ("Выравнивание:", "Padding:"),
+    ("Без выравнивания", "None"),
+    ("Нулями", "Zeros"),
+    ("Пробелами", "Spaces"),
+    ("После номера:", "After number:"),
+    ("Пропускать пустые строки", "Skip empty lines"),
+    ("Изменятся выделенные строки", "Selected lines will change"),
+    ("Изменятся все строки документа", "All lines of the document will change"),
+    ("Пронумеровать", "Number"),
+    ("Убрать номера строк", "Strip line numbers"),
+    // Форматирование JSON/XML
+    ("Форматировать JSON/XML...", "Format JSON/XML..."),
+    ("Форматировать JSON/XML", "Format JSON/XML"),
+    ("Формат:", "Format:"),
+    ("Определить", "Detect"),
+    ("Отступ:", "Indent:"),
+    (" пробелов", " spaces"),
+    ("Табуляция", "Tabs"),
+    ("Будет отформатировано выделение", "The selection will be formatted"),
+    ("Будет отформатирован весь документ", "The whole document will be formatted"),
+    ("Форматировать", "Format"),
+    ("Проверить", "Validate"),
+    ("Строка {}, столбец {}", "Line {}, column {}"),
+    ("Ошибок не найдено", "No errors found"),
+    ("Неожиданный конец текста", "Unexpected end of text"),
+    ("Ожидалось значение", "Expected a value"),
+    ("Ожидался ключ в кавычках", "Expected a quoted key"),
+    ("Ожидалось «:»", "Expected “:”"),
+    ("Ожидалось «,» или «}»", "Expected “,” or “}”"),
+    ("Ожидалось «,» или «]»", "Expected “,” or “]”"),
+    ("Незакрытая строка", "Unterminated string"),
+    ("Неверная escape-последовательность", "Invalid escape sequence"),
+    ("Управляющий символ внутри строки", "Control character in string"),
+    ("Неверное число", "Invalid number"),
+    ("Лишние символы после конца JSON", "Unexpected characters after the end of JSON"),
+    ("Ошибка XML: {}", "XML error: {}"),
+    ("Тег не закрыт", "Unclosed tag"),
+    // Сохранение
+    ("Резервная копия:", "Backup copy:"),
+    ("хранить прежнюю версию в файле с ~ в конце имени", "keep the previous version in a file ending with ~"),
+    // Таймер помидоро
+    ("Таймер помидоро", "Pomodoro timer"),
+    ("Таймер остановлен", "Timer stopped"),
+    ("Работа", "Focus"),
+    ("Перерыв", "Break"),
+    ("Работа:", "Focus:"),
+    ("Перерыв:", "Break:"),
+    (" мин", " min"),
+    ("Завершено помидоров: {}", "Pomodoros completed: {}"),
+    ("▶ Начать", "▶ Start"),
+    ("⏸ Пауза", "⏸ Pause"),
+    ("▶ Продолжить", "▶ Resume"),
+    ("⏹ Сбросить", "⏹ Reset"),
+    ("⏭ Пропустить", "⏭ Skip"),
+    ("Музыка во время работы, пауза в перерыв", "Music while focusing, paused during breaks"),
+    ("Время отдохнуть: перерыв {} мин", "Time for a break: {} min"),
+    ("Перерыв окончен, за работу!", "Break is over, back to work!"),
+    ("Язык:", "Language:"),
+    ("Кодировка не-UTF-8 файлов:", "Encoding of non-UTF-8 files:"),
+    ("Сбросить настройки", "Reset settings"),
+    ("Ошибка сохранения настроек: {}", "Failed to save settings: {}"),
+    ("Ошибка загрузки шрифта: {}", "Failed to load font: {}"),
+    ("Файл не похож на шрифт TTF/OTF: {}", "Not a TTF/OTF font file: {}"),
+    // Темы
+    ("Темная", "Dark"),
+    ("Светлая", "Light"),
+    ("Моя тема", "My theme"),
+    ("Редактор тем", "Theme editor"),
+    ("Название:", "Name:"),
+    ("Основа:", "Base:"),
+    ("Фон текста:", "Text background:"),
+    ("Текст:", "Text:"),
+    ("Совпадения поиска:", "Search matches:"),
+    ("Текущее совпадение:", "Current match:"),
+    ("Строка состояния:", "Status bar:"),
+    ("Сохранить тему", "Save theme"),
+    ("Удалить тему", "Delete theme"),
+    ("Укажите другое название темы", "Choose a different theme name"),
+    // Терминальный редактор
+    (
+        "F1 — справка, Ctrl-Q — выход, Ctrl-S — сохранить, Ctrl-F — поиск, Ctrl-R — замена, Ctrl-Z — отмена",
+        "F1 = help, Ctrl-Q = quit, Ctrl-S = save, Ctrl-F = search, Ctrl-R = replace, Ctrl-Z = undo",
+    ),
+    ("Поиск: {}{} | Замена: {}{}", "Search: {}{} | Replace: {}{}"),
+    ("Поиск: {}", "Search: {}"),
+    (" [совпадений: {}, текущее: {}]", " [{} matches, current: {}]"),
+    (" [нет совпадений]", " [no matches]"),
+    ("[Без имени]", "[No Name]"),
+    (
+        "{}{}{} | Строка: {}/{}, Столбец: {} | Прокрутка: {} | {}",
+        "{}{}{} | Line: {}/{}, Col: {} | Scroll: {} | {}",
+    ),
+    ("Режим поиска: введите текст, Enter — выход", "Search mode: Type to search, Enter to exit"),
+    (
+        "Режим замены: Tab — другое поле, Enter — заменить, Ctrl-A — заменить все, Esc — выход",
+        "Replace mode: Tab = switch field, Enter = replace, Ctrl-A = replace all, Esc = exit",
+    ),
+    ("Отменено", "Undo"),
+    ("Нечего отменять", "Nothing to undo"),
+    ("Заменено одно совпадение", "Replaced 1 occurrence"),
+    ("Заменено совпадений: {}", "Replaced {} occurrences"),
+    ("Сохранено в {}", "Saved to {}"),
+    ("Открыт {}", "Opened {}"),
+    ("Новый файл {}", "New file {}"),
+    ("Скопировано символов: {}", "Copied {} characters"),
+    ("Ошибка буфера обмена: {}", "Clipboard error: {}"),
+    ("Перенос строк включен", "Soft wrap on"),
+    ("Перенос строк выключен", "Soft wrap off"),
+    ("Номера строк выключены", "Line numbers off"),
+    ("Номера строк включены", "Line numbers on"),
+    ("Относительные номера строк", "Relative line numbers"),
+    ("Сохранить как: ", "Save as: "),
+    ("Открыть: ", "Open: "),
+    ("Отменено пользователем", "Cancelled"),
+    ("Ошибка: {}", "Error: {}"),
+    ("{}: сохранить изменения? (y/n/esc)", "{}: save changes? (y/n/esc)"),
+    (
+        "Найдено: {}. F3 — следующее, Shift-F3 — предыдущее, Esc — убрать подсветку",
+        "{} matches: F3 = next, Shift-F3 = previous, Esc = clear",
+    ),
+    ("Ничего не найдено: для поиска нажмите Ctrl-F", "No search results: press Ctrl-F to search"),
+    ("Совпадение {} из {}", "Match {} of {}"),
+    ("Перейти к строке: ", "Go to line: "),
+    ("Строка {}", "Line {}"),
+    ("Это не номер строки: {}", "Not a line number: {}"),
+    ("Новый буфер", "New buffer"),
+    ("Открыт только один буфер", "Only one buffer is open"),
+    (
+        "Буферы: Enter — перейти, Delete — закрыть, Esc — назад",
+        "Buffers: Enter = switch, Delete = close, Esc = back",
+    ),
+    (
+        "Клавиши (Esc — закрыть, стрелки и PageUp/PageDown — прокрутка)",
+        "Keys (Esc = close, arrows and PageUp/PageDown = scroll)",
+    ),
+    ("Показать эту справку", "Show this help"),
+    ("Открыть файл", "Open file"),
+    ("Список буферов", "List of buffers"),
+    ("Следующий буфер", "Next buffer"),
+    ("Предыдущий буфер", "Previous buffer"),
+    ("Перейти к строке", "Go to line"),
+    ("Найти и заменить", "Find and replace"),
+    ("Следующее совпадение", "Next match"),
+    ("Предыдущее совпадение", "Previous match"),
+    ("Снять выделение, затем подсветку поиска", "Clear selection, then search highlights"),
+    ("Отменить последнее изменение", "Undo the last change"),
+    ("Вырезать", "Cut"),
+    ("Вставить из буфера обмена", "Paste"),
+    ("Переключить перенос строк", "Toggle soft wrap"),
+    ("Номера строк: нет, обычные, относительные", "Line numbers: off, absolute, relative"),
+    ("Символ влево", "Character left"),
+    ("Символ вправо", "Character right"),
+    ("Строка вверх", "Line up"),
+    ("Строка вниз", "Line down"),
+    ("Слово влево", "Word left"),
+    ("Слово вправо", "Word right"),
+    ("В начало строки", "Start of line"),
+    ("В конец строки", "End of line"),
+    ("В начало документа", "Start of document"),
+    ("В конец документа", "End of document"),
+    ("Страница вверх", "Page up"),
+    ("Страница вниз", "Page down"),
+    ("Shift + любая клавиша движения — выделение", "Shift + any movement key extends the selection"),
+    (
+        "Мышь: щелчок ставит курсор, перетаскивание выделяет, колесо прокручивает",
+        "Mouse: click places the cursor, drag selects, wheel scrolls",
+    ),
+    (
+        "Запрос имени файла: Tab — дополнить путь, Enter — подтвердить, Esc — отмена",
+        "File name prompts: Tab = complete the path, Enter = confirm, Esc = cancel",
+    ),
+];
+
+struct Tables {
+    to_english: HashMap<&'static str, &'static str>,
+    to_russian: HashMap<&'static str, &'static str>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| Tables {
+        to_english: STRINGS.iter().map(|&(ru, en)| (ru, en)).collect(),
+        to_russian: STRINGS.iter().map(|&(ru, en)| (en, ru)).collect(),
+    })
+}
+
+pub fn translate(key: &'static str) -> &'static str {
+    let table = if ENGLISH.load(Ordering::Relaxed) {
+        &tables().to_english
+    } else {
+        &tables().to_russian
+    };
+    table.get(key).copied().unwrap_or(key)
+}
+
+// Подстановка аргументов вместо {} по порядку; лишние {} остаются как есть
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(index) = rest.find("{}") {
+        result.push_str(&rest[..index]);
+        match args.next() {
+            Some(arg) => result.push_str(&arg.to_string()),
+            None => result.push_str("{}"),
+        }
+        rest = &rest[index + 2..];
+    }
+    result.push_str(rest);
+    result
+}
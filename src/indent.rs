@@ -0,0 +1,129 @@
+// indent.rs
+use crate::config::Config;
+use crate::line_ops::{line_start, lines_span, LineEdit};
+
+// Один уровень отступа: табуляция или tab_width пробелов
+pub fn indent_unit(config: &Config) -> String {
+    if config.insert_spaces {
+        " ".repeat(config.tab_width.max(1))
+    } else {
+        "\t".to_string()
+    }
+}
+
+// Tab без выделения строк: пробелы добиваются до следующей позиции табуляции
+pub fn tab_at(text: &[char], cursor: usize, config: &Config) -> String {
+    if !config.insert_spaces {
+        return "\t".to_string();
+    }
+    let width = config.tab_width.max(1);
+    let column = cursor - line_start(text, cursor);
+    " ".repeat(width - column % width)
+}
+
+// Новые границы выделения после правки строк: сдвиг считаем отдельно для строки каждого конца
+fn shift(text: &[char], position: usize, line_index: usize, deltas: &[isize]) -> usize {
+    let before: isize = deltas[..line_index].iter().sum();
+    let column = (position - line_start(text, position)) as isize;
+    let own = match deltas[line_index] {
+        // Граница в начале строки остается в начале: строки выделены целиком и после Tab
+        own if own > 0 && column == 0 => 0,
+        // При удалении отступа граница не должна заехать на предыдущую строку
+        own if own < 0 => own.max(-column),
+        own => own,
+    };
+    (position as isize + before + own) as usize
+}
+
+fn line_index(text: &[char], start: usize, position: usize) -> usize {
+    text[start..position].iter().filter(|&&c| c == '\n').count()
+}
+
+fn edit_lines(text: &[char], anchor: usize, cursor: usize, change: impl Fn(&str) -> String) -> Option<LineEdit> {
+    let (start, end) = lines_span(text, anchor, cursor);
+    let block: String = text[start..end].iter().collect();
+    let mut deltas = Vec::new();
+    let mut lines = Vec::new();
+    for line in block.split('\n') {
+        let changed = change(line);
+        deltas.push(changed.chars().count() as isize - line.chars().count() as isize);
+        lines.push(changed);
+    }
+    if deltas.iter().all(|&d| d == 0) {
+        return None;
+    }
+    // Концы выделения за последней строкой блока (в начале следующей) не сдвигаем внутрь нее
+    let clamp = |position: usize| position.min(end);
+    let anchor_line = line_index(text, start, clamp(anchor));
+    let cursor_line = line_index(text, start, clamp(cursor));
+    let total: isize = deltas.iter().sum();
+    let new_position = |position: usize, line: usize| {
+        if position > end {
+            (position as isize + total) as usize
+        } else {
+            shift(text, position, line, &deltas)
+        }
+    };
+    Some(LineEdit {
+        start,
+        end,
+        text: lines.join("\n"),
+        anchor: new_position(anchor, anchor_line),
+        cursor: new_position(cursor, cursor_line),
+    })
+}
+
+// Tab с выделением нескольких строк: отступ у каждой непустой
+pub fn indent_lines(text: &[char], anchor: usize, cursor: usize, config: &Config) -> Option<LineEdit> {
+    let unit = indent_unit(config);
+    edit_lines(text, anchor, cursor, |line| {
+        if line.trim().is_empty() {
+            line.to_string()
+        } else {
+            format!("{}{}", unit, line)
+        }
+    })
+}
+
+// Shift + Tab: одна табуляция или до tab_width пробелов в начале каждой строки
+pub fn dedent_lines(text: &[char], anchor: usize, cursor: usize, config: &Config) -> Option<LineEdit> {
+    let width = config.tab_width.max(1);
+    edit_lines(text, anchor, cursor, |line| {
+        if let Some(rest) = line.strip_prefix('\t') {
+            return rest.to_string();
+        }
+        let spaces = line.chars().take(width).take_while(|&c| c == ' ').count();
+        // Табуляция после неполного уровня пробелов тоже снимается: «  \tтекст»
+        let rest = &line[spaces..];
+        if spaces < width {
+            rest.strip_prefix('\t').unwrap_or(rest).to_string()
+        } else {
+            rest.to_string()
+        }
+    })
+}
+
+// Отступы всего текста — в пробелы или в табуляцию; пробелы, не дотягивающие до уровня, остаются
+pub fn convert_indentation(text: &str, to_spaces: bool, tab_width: usize) -> String {
+    let width = tab_width.max(1);
+    text.split('\n')
+        .map(|line| {
+            let body = line.trim_start_matches([' ', '\t']);
+            let indent = &line[..line.len() - body.len()];
+            let columns = indent.chars().fold(0, |column, c| {
+                if c == '\t' {
+                    column + width - column % width
+                } else {
+                    column + 1
+                }
+            });
+            let indent = if to_spaces {
+                " ".repeat(columns)
+            } else {
+                format!("{}{}", "\t".repeat(columns / width), " ".repeat(columns % width))
+            };
+            format!("{}{}", indent, body)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,57 @@
+// journal.rs
+use std::path::{Path, PathBuf};
+
+use crate::config::Language;
+use crate::date_module::format_date;
+
+// Файл журнала на сегодня. Шаблон — strftime; относительный путь и ~/ — от домашней папки
+pub fn journal_path(pattern: &str, language: Language) -> Option<PathBuf> {
+    let path = format_date(pattern.trim(), language);
+    let path = path.strip_prefix("~/").unwrap_or(&path);
+    if path.is_empty() {
+        return None;
+    }
+    let path = Path::new(path);
+    if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else {
+        dirs::home_dir().map(|home| home.join(path))
+    }
+}
+
+// Заголовок новой записи, отделенный от прежних пустой строкой
+pub fn entry_heading(text: &str, format: &str, language: Language) -> String {
+    let separator = if text.is_empty() || text.ends_with("\n\n") {
+        ""
+    } else if text.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{}{}\n", separator, format_date(format, language))
+}
+
+// Открытый журнал: все, что было до новой записи, править нельзя
+pub struct Journal {
+    pub path: PathBuf,
+    locked: String,
+    last_text: String, // Текст на прошлом кадре, к нему откатываем запрещенную правку
+}
+
+impl Journal {
+    pub fn new(path: PathBuf, locked: String, text: &str) -> Self {
+        Self { path, locked, last_text: text.to_string() }
+    }
+
+    // Возвращает прежний текст, если правка задела старые записи
+    pub fn check(&mut self, text: &str) -> Option<String> {
+        if text == self.last_text {
+            return None;
+        }
+        if !text.starts_with(&self.locked) {
+            return Some(self.last_text.clone());
+        }
+        self.last_text = text.to_string();
+        None
+    }
+}
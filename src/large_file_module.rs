@@ -0,0 +1,416 @@
+// large_file_module.rs
+use eframe::egui;
+use encoding_rs::Encoding;
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::i18n::tr;
+
+// Файлы больше этого открываются в просмотрщике: TextEdit раскладывает весь текст каждый кадр
+pub const LARGE_FILE_THRESHOLD: u64 = 20 * 1024 * 1024;
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+// Поиск идет кусками по столько строк, между кусками приходят результаты
+const SEARCH_BATCH_LINES: usize = 200_000;
+const MAX_SEARCH_RESULTS: usize = 10_000;
+// Очень длинные строки (минифицированный JSON, однострочные логи) показываем обрезанными
+const MAX_LINE_CHARS: usize = 5_000;
+const MATCH_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(120, 100, 20, 80);
+
+// Файл отображен в память, а не прочитан: в RAM попадают только страницы, которые показываются или ищутся
+struct LargeFile {
+    map: Mmap,
+    line_starts: Vec<usize>, // Начала строк в байтах
+}
+
+impl LargeFile {
+    fn bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    fn line(&self, index: usize) -> &[u8] {
+        let start = self.line_starts[index];
+        let end = self.line_starts.get(index + 1).copied().unwrap_or(self.bytes().len());
+        // У последней строки перевод строки тоже есть, если файл им заканчивается
+        let line = &self.bytes()[start..end];
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+
+    // Номер строки, в которой лежит байт
+    fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset) - 1
+    }
+}
+
+enum LoadMessage {
+    Progress(u64),
+    Done(LargeFile),
+    Failed(String),
+}
+
+enum SearchMessage {
+    Found(Vec<usize>, usize), // Строки с совпадениями и сколько строк уже просмотрено
+    Done,
+}
+
+struct SearchJob {
+    receiver: Receiver<SearchMessage>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Drop for SearchJob {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+pub struct LargeFileModule {
+    path: Option<PathBuf>,
+    encoding: &'static Encoding, // Для строк не в UTF-8
+    total: u64,
+    loaded: u64,
+    loader: Option<Receiver<LoadMessage>>,
+    file: Option<Arc<LargeFile>>,
+    error: Option<String>,
+    query: String,
+    case_sensitive: bool,
+    search: Option<SearchJob>,
+    matches: Vec<usize>,
+    searched_lines: usize,
+    current_match: Option<usize>,
+    scroll_to_line: Option<usize>,
+}
+
+impl Default for LargeFileModule {
+    fn default() -> Self {
+        Self {
+            path: None,
+            encoding: encoding_rs::WINDOWS_1251,
+            total: 0,
+            loaded: 0,
+            loader: None,
+            file: None,
+            error: None,
+            query: String::new(),
+            case_sensitive: false,
+            search: None,
+            matches: Vec::new(),
+            searched_lines: 0,
+            current_match: None,
+            scroll_to_line: None,
+        }
+    }
+}
+
+fn load(path: &Path, progress: &mpsc::Sender<LoadMessage>) -> Result<LargeFile, String> {
+    let file = File::open(path).map_err(|e| tr!("Ошибка открытия TXT файла: {}", e))?;
+    // Файл открыт только для чтения; если другая программа обрежет его, пока он открыт, просмотрщик упадет —
+    // это цена того, что гигабайтный лог не копируется в память целиком
+    let map = unsafe { Mmap::map(&file) }.map_err(|e| tr!("Ошибка открытия TXT файла: {}", e))?;
+    let mut line_starts = vec![0];
+    for (index, chunk) in map.chunks(CHUNK_SIZE).enumerate() {
+        let offset = index * CHUNK_SIZE;
+        line_starts.extend(chunk.iter().enumerate().filter(|(_, &b)| b == b'\n').map(|(i, _)| offset + i + 1));
+        // Окно закрыли — размечать дальше незачем
+        if progress.send(LoadMessage::Progress((offset + chunk.len()) as u64)).is_err() {
+            return Err(String::new());
+        }
+    }
+    // Перевод строки в самом конце не начинает новую строку
+    if line_starts.len() > 1 && line_starts.last() == Some(&map.len()) {
+        line_starts.pop();
+    }
+    Ok(LargeFile { map, line_starts })
+}
+
+// Байтовый regex не знает, что в cp1251 «Я» и «я» — пара, поэтому запрос ищем и в UTF-8,
+// и в кодировке файла, перечисляя для каждой буквы байты ее строчного и заглавного вариантов
+fn search_pattern(query: &str, case_sensitive: bool, encoding: &'static Encoding) -> Option<regex::bytes::Regex> {
+    let utf8 = if case_sensitive { regex::escape(query) } else { format!("(?i:{})", regex::escape(query)) };
+    let legacy: Option<String> = (encoding != encoding_rs::UTF_8 && !query.is_ascii())
+        .then(|| {
+            query
+                .chars()
+                .map(|c| {
+                    let mut variants = vec![c.to_string()];
+                    if !case_sensitive {
+                        variants.push(c.to_lowercase().collect());
+                        variants.push(c.to_uppercase().collect());
+                    }
+                    let mut alternatives: Vec<String> = variants
+                        .iter()
+                        .filter_map(|variant| {
+                            let (bytes, _, unmappable) = encoding.encode(variant);
+                            (!unmappable).then(|| bytes.iter().map(|b| format!("\\x{:02X}", b)).collect())
+                        })
+                        .collect();
+                    alternatives.dedup();
+                    // Буквы нет в кодировке файла — в ней запрос встретиться не может
+                    (!alternatives.is_empty()).then(|| format!("(?:{})", alternatives.join("|")))
+                })
+                .collect()
+        })
+        .flatten();
+    let pattern = match legacy {
+        Some(legacy) => format!("{}|(?-u:{})", utf8, legacy),
+        None => utf8,
+    };
+    regex::bytes::Regex::new(&pattern).ok()
+}
+
+fn search(file: &LargeFile, pattern: &regex::bytes::Regex, cancel: &AtomicBool, sender: &mpsc::Sender<SearchMessage>) {
+    let mut found = 0;
+    let mut line = 0;
+    while line < file.line_count() && found < MAX_SEARCH_RESULTS {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let end_line = (line + SEARCH_BATCH_LINES).min(file.line_count());
+        let start = file.line_starts[line];
+        let end = file.line_starts.get(end_line).copied().unwrap_or(file.bytes().len());
+
+        let mut lines: Vec<usize> = Vec::new();
+        for m in pattern.find_iter(&file.bytes()[start..end]) {
+            let match_line = file.line_of(start + m.start());
+            if lines.last() != Some(&match_line) {
+                lines.push(match_line);
+            }
+        }
+        lines.truncate(MAX_SEARCH_RESULTS - found);
+        found += lines.len();
+        if sender.send(SearchMessage::Found(lines, end_line)).is_err() {
+            return;
+        }
+        line = end_line;
+    }
+    let _ = sender.send(SearchMessage::Done);
+}
+
+impl LargeFileModule {
+    pub fn is_open(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    // Файл читается в фоновом потоке; пока он грузится, показываем прогресс
+    pub fn open(&mut self, path: &Path, size: u64, default_encoding: &str) {
+        self.close();
+        self.path = Some(path.to_path_buf());
+        self.encoding = Encoding::for_label(default_encoding.as_bytes()).unwrap_or(encoding_rs::WINDOWS_1251);
+        self.total = size;
+
+        let (sender, receiver) = mpsc::channel();
+        let path = path.to_path_buf();
+        thread::spawn(move || {
+            let message = match load(&path, &sender) {
+                Ok(file) => LoadMessage::Done(file),
+                Err(e) => LoadMessage::Failed(e),
+            };
+            let _ = sender.send(message);
+        });
+        self.loader = Some(receiver);
+    }
+
+    pub fn close(&mut self) {
+        *self = Self::default();
+    }
+
+    fn poll(&mut self, ctx: &egui::Context) {
+        if let Some(loader) = &self.loader {
+            for message in loader.try_iter() {
+                match message {
+                    LoadMessage::Progress(loaded) => self.loaded = loaded,
+                    LoadMessage::Done(file) => self.file = Some(Arc::new(file)),
+                    LoadMessage::Failed(e) => self.error = Some(e),
+                }
+            }
+            if self.file.is_some() || self.error.is_some() {
+                self.loader = None;
+            } else {
+                ctx.request_repaint_after(Duration::from_millis(50));
+            }
+        }
+
+        if let Some(job) = &self.search {
+            let mut done = false;
+            for message in job.receiver.try_iter() {
+                match message {
+                    SearchMessage::Found(lines, searched) => {
+                        self.matches.extend(lines);
+                        self.searched_lines = searched;
+                    }
+                    SearchMessage::Done => done = true,
+                }
+            }
+            if self.current_match.is_none() && !self.matches.is_empty() {
+                self.go_to_match(0);
+            }
+            if done {
+                self.search = None;
+            } else {
+                ctx.request_repaint_after(Duration::from_millis(50));
+            }
+        }
+    }
+
+    fn start_search(&mut self) {
+        self.search = None;
+        self.matches.clear();
+        self.searched_lines = 0;
+        self.current_match = None;
+        let Some(file) = self.file.clone() else {
+            return;
+        };
+        if self.query.is_empty() {
+            return;
+        }
+        let Some(pattern) = search_pattern(&self.query, self.case_sensitive, self.encoding) else {
+            return;
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let thread_cancel = cancel.clone();
+        thread::spawn(move || search(&file, &pattern, &thread_cancel, &sender));
+        self.search = Some(SearchJob { receiver, cancel });
+    }
+
+    fn go_to_match(&mut self, index: usize) {
+        self.current_match = Some(index);
+        self.scroll_to_line = self.matches.get(index).copied();
+    }
+
+    fn decode_line(&self, bytes: &[u8]) -> String {
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) => std::borrow::Cow::Borrowed(text),
+            Err(_) => self.encoding.decode_without_bom_handling(bytes).0,
+        };
+        match text.char_indices().nth(MAX_LINE_CHARS) {
+            Some((cut, _)) => format!("{}…", &text[..cut]),
+            None => text.into_owned(),
+        }
+    }
+
+    // Возвращает true, если просмотрщик закрыли
+    pub fn show(&mut self, ui: &mut egui::Ui, font: egui::FontId) -> bool {
+        self.poll(ui.ctx());
+        let name = self
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+            return ui.button(tr!("Закрыть")).clicked();
+        }
+        let Some(file) = self.file.clone() else {
+            ui.label(tr!("Загрузка {}...", name));
+            let progress = self.loaded as f32 / self.total.max(1) as f32;
+            ui.add(egui::ProgressBar::new(progress).show_percentage());
+            return ui.button(tr!("Отмена")).clicked();
+        };
+
+        let mut closed = false;
+        ui.horizontal(|ui| {
+            ui.label(tr!("{}: {} строк, только чтение", name, file.line_count()));
+            ui.separator();
+            let response = ui.add(egui::TextEdit::singleline(&mut self.query).hint_text(tr!("Найти")).desired_width(200.0));
+            let mut search = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            search |= ui.checkbox(&mut self.case_sensitive, tr!("С учетом регистра")).changed();
+            if search {
+                self.start_search();
+            }
+
+            let count = self.matches.len();
+            if ui.add_enabled(count > 0, egui::Button::new("⏶")).clicked() {
+                self.go_to_match(self.current_match.map(|i| (i + count - 1) % count).unwrap_or(0));
+            }
+            if ui.add_enabled(count > 0, egui::Button::new("⏷")).clicked() {
+                self.go_to_match(self.current_match.map(|i| (i + 1) % count).unwrap_or(0));
+            }
+            if self.search.is_some() {
+                ui.spinner();
+                let percent = self.searched_lines * 100 / file.line_count().max(1);
+                ui.label(tr!("Найдено: {} (просмотрено {}%)", count, percent));
+            } else if !self.query.is_empty() && count > 0 {
+                let current = self.current_match.map(|i| i + 1).unwrap_or(0);
+                ui.label(tr!("{} из {}", current, count));
+                if count >= MAX_SEARCH_RESULTS {
+                    ui.weak(tr!("(показаны первые {})", MAX_SEARCH_RESULTS));
+                }
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                closed = ui.button(tr!("Закрыть")).clicked();
+            });
+        });
+        ui.separator();
+
+        let row_height = ui.fonts(|f| f.row_height(&font));
+        let spacing = ui.spacing().item_spacing.y;
+        let mut scroll_area = egui::ScrollArea::both().auto_shrink([false; 2]);
+        if let Some(line) = self.scroll_to_line.take() {
+            let offset = (line as f32 * (row_height + spacing) - ui.available_height() / 3.0).max(0.0);
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let current_line = self.current_match.and_then(|i| self.matches.get(i)).copied();
+        let number_width = file.line_count().to_string().len();
+        scroll_area.show_rows(ui, row_height, file.line_count(), |ui, range| {
+            for index in range {
+                let text = format!("{:>width$}  {}", index + 1, self.decode_line(file.line(index)), width = number_width);
+                let fill = if Some(index) == current_line { MATCH_COLOR } else { egui::Color32::TRANSPARENT };
+                egui::Frame::none().fill(fill).show(ui, |ui| {
+                    ui.set_min_width(ui.available_width());
+                    ui.add(egui::Label::new(egui::RichText::new(text).font(font.clone())).wrap(false));
+                });
+            }
+        });
+        closed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Лог в cp1251: без перечисления вариантов букв «привет» не нашлось бы в «ПРИВЕТ»
+    #[test]
+    fn finds_cp1251_text_ignoring_case() {
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode("строка\nПРИВЕТ, мир");
+        let pattern = search_pattern("привет", false, encoding_rs::WINDOWS_1251).unwrap();
+        assert_eq!(pattern.find(&bytes).map(|m| m.start()), Some(7));
+        let pattern = search_pattern("привет", true, encoding_rs::WINDOWS_1251).unwrap();
+        assert!(!pattern.is_match(&bytes));
+        // Строки в UTF-8 в том же файле тоже находятся
+        assert!(search_pattern("привет", false, encoding_rs::WINDOWS_1251).unwrap().is_match("Привет".as_bytes()));
+    }
+
+    #[test]
+    fn maps_file_and_finds_line_starts() {
+        let path = std::env::temp_dir().join(format!("gte-large-{}.txt", std::process::id()));
+        std::fs::write(&path, "один\r\nдва\nтри\n").unwrap();
+        let (sender, _receiver) = mpsc::channel();
+        let file = load(&path, &sender).unwrap();
+        assert_eq!(file.line_count(), 3);
+        assert_eq!(file.line(0), "один".as_bytes());
+        assert_eq!(file.line(2), "три".as_bytes());
+        assert_eq!(file.line_of(file.line_starts[1] + 1), 1);
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
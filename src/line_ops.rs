@@ -0,0 +1,111 @@
+// line_ops.rs
+
+// Правка строк: символы start..end заменить на text, затем выделить anchor..cursor
+pub struct LineEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub anchor: usize,
+    pub cursor: usize,
+}
+
+pub fn line_start(text: &[char], index: usize) -> usize {
+    text[..index].iter().rposition(|&c| c == '\n').map_or(0, |i| i + 1)
+}
+
+pub fn line_end(text: &[char], index: usize) -> usize {
+    text[index..]
+        .iter()
+        .position(|&c| c == '\n')
+        .map_or(text.len(), |i| index + i)
+}
+
+// Строки, которых касается выделение, без последнего перевода строки. Выделение,
+// кончающееся в начале строки (тройной щелчок, Shift + Вниз), эту строку не захватывает
+pub fn lines_span(text: &[char], anchor: usize, cursor: usize) -> (usize, usize) {
+    let (start, mut end) = (anchor.min(cursor), anchor.max(cursor));
+    if end > start && text.get(end - 1) == Some(&'\n') {
+        end -= 1;
+    }
+    (line_start(text, start), line_end(text, end))
+}
+
+fn collect(text: &[char], start: usize, end: usize) -> String {
+    text[start..end].iter().collect()
+}
+
+// Ctrl + D: выделение — копия сразу после него; без выделения — копия строки ниже
+pub fn duplicate(text: &[char], anchor: usize, cursor: usize) -> LineEdit {
+    if anchor != cursor {
+        let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+        let copy = collect(text, start, end);
+        return LineEdit {
+            start: end,
+            end,
+            text: copy,
+            anchor: end,
+            cursor: end + (end - start),
+        };
+    }
+    let (start, end) = lines_span(text, anchor, cursor);
+    let shift = end - start + 1;
+    LineEdit {
+        start: end,
+        end,
+        text: format!("\n{}", collect(text, start, end)),
+        anchor: anchor + shift,
+        cursor: cursor + shift,
+    }
+}
+
+// Alt + Вверх / Alt + Вниз: строки под курсором или выделением меняются местами с соседней
+pub fn move_lines(text: &[char], anchor: usize, cursor: usize, up: bool) -> Option<LineEdit> {
+    let (start, end) = lines_span(text, anchor, cursor);
+    let block = collect(text, start, end);
+    if up {
+        let previous_start = line_start(text, start.checked_sub(1)?);
+        let previous = collect(text, previous_start, start - 1);
+        let shift = previous.chars().count() + 1;
+        Some(LineEdit {
+            start: previous_start,
+            end,
+            text: format!("{}\n{}", block, previous),
+            anchor: anchor - shift,
+            cursor: cursor - shift,
+        })
+    } else {
+        if end >= text.len() {
+            return None;
+        }
+        let next_end = line_end(text, end + 1);
+        let next = collect(text, end + 1, next_end);
+        let shift = next.chars().count() + 1;
+        Some(LineEdit {
+            start,
+            end: next_end,
+            text: format!("{}\n{}", next, block),
+            anchor: anchor + shift,
+            cursor: cursor + shift,
+        })
+    }
+}
+
+// Ctrl + Shift + K: удалить строки вместе с переводом строки, курсор — в начало следующей
+pub fn delete_lines(text: &[char], anchor: usize, cursor: usize) -> LineEdit {
+    let (start, end) = lines_span(text, anchor, cursor);
+    let (start, end, position) = if end < text.len() {
+        (start, end + 1, start)
+    } else if start > 0 {
+        // Последняя строка: убираем перевод строки перед ней и встаем на предыдущую
+        (start - 1, end, line_start(text, start - 1))
+    } else {
+        (start, end, start)
+    };
+    LineEdit {
+        start,
+        end,
+        text: String::new(),
+        anchor: position,
+        cursor: position,
+    }
+}
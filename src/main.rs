@@ -1,32 +1,214 @@
 // main.rs
 use eframe::egui;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use rodio::{OutputStream, Sink};
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
 use std::io::BufReader;
+use std::time::{Duration, Instant};
 
+mod ambient_module;
+mod archive_module;
+mod atomic_write;
+mod auto_pair;
+mod auto_replace_module;
+mod autocomplete_module;
+mod bookmarks_module;
+mod brackets;
+mod calculator;
+mod command_palette_module;
+mod comment;
+mod compare_module;
+mod config;
+mod crypto_module;
+mod date_module;
+mod diff_module;
+mod document_goal_module;
+mod docx_import;
+mod docx_module;
+mod docx_roundtrip;
+mod duplicates_module;
+mod editor;
+mod external_command_module;
+mod fader;
+mod format_module;
+mod file_properties_module;
+mod file_tree_module;
+mod file_watch_module;
+mod find_in_files_module;
+mod git_module;
+mod hash_module;
+mod history_module;
+mod i18n;
+mod indent;
+mod journal;
+mod large_file_module;
+mod line_ops;
+mod markdown_module;
+mod minimap_module;
+mod multi_cursor;
+mod outline_module;
+mod placeholder_module;
+mod playlist_module;
+mod plugin_module;
+mod pomodoro_module;
+mod quick_open_module;
+mod regex_replace_module;
 mod search_module;
+mod selection_file_module;
+mod sequence_module;
+mod settings_module;
+mod smart_paste;
+mod snippet_module;
+mod spellcheck_module;
+mod split_module;
+mod statistics_module;
+mod status_bar_module;
+mod symbol_module;
+mod template_module;
+mod theme_module;
+mod transform_module;
+mod translit;
+mod typing_sound_module;
+mod welcome_module;
+mod word_frequency_module;
+mod writing_session_module;
+use ambient_module::{AmbientModule, AmbientSound};
+use archive_module::{compress_archive, is_archive, read_archive, zip_entries, ArchiveKind, ArchiveModule, ArchiveResult};
+use atomic_write::write_atomically;
+use auto_replace_module::{AutoReplaceModule, AutoReplaceResult};
+use autocomplete_module::{AutocompleteModule, AutocompleteResult};
+use bookmarks_module::{BookmarksModule, BookmarksResult};
+use calculator::{evaluate, format_number};
+use command_palette_module::{Command, CommandPaletteModule, CommandPaletteResult};
+use compare_module::{CompareModule, CompareResult, CompareSource};
+use config::{Config, FontFamily, WindowGeometry};
+use crypto_module::{decrypt, encrypt, is_encrypted, CryptoModule, CryptoResult, ENCRYPTED_EXTENSION};
+use date_module::{format_date, DateModule, DateResult};
+use diff_module::{DiffModule, LineStatus};
+use document_goal_module::{goal_for, rename_goal, DocumentGoalModule, DocumentGoalResult};
+use docx_module::{DocxModule, DocxResult};
+use docx_roundtrip::DocxDocument;
+use duplicates_module::{DuplicatesModule, DuplicatesResult};
+use editor::Editor;
+use external_command_module::{ExternalCommandModule, ExternalCommandResult};
+use fader::{FadeEnd, Fader, FADE_DURATION};
+use file_properties_module::{detect_encoding, FilePropertiesModule};
+use file_tree_module::{FileTreeModule, FileTreeResult};
+use file_watch_module::{FileWatchModule, FileWatchResult};
+use find_in_files_module::{FindInFilesModule, FindInFilesResult};
+use format_module::{FormatModule, FormatResult};
+use git_module::{GitModule, GitResult};
+use hash_module::HashModule;
+use history_module::{HistoryModule, HistoryResult};
+use i18n::tr;
+use journal::Journal;
+use large_file_module::{LargeFileModule, LARGE_FILE_THRESHOLD};
+use markdown_module::{is_markdown, MarkdownAction};
+use minimap_module::{MinimapModule, MinimapResult, Viewport};
+use multi_cursor::{MultiCursor, MultiEdit};
+use outline_module::{OutlineModule, OutlineResult};
+use placeholder_module::{PlaceholderModule, PlaceholderResult};
+use playlist_module::{format_duration, read_track_info, PlaybackClock, PlaylistModule, PlaylistResult, TrackInfo, MUSIC_EXTENSIONS};
+use plugin_module::{plugins_dir, PluginModule};
+use pomodoro_module::{PomodoroModule, PomodoroResult};
+use quick_open_module::{QuickOpenModule, QuickOpenResult};
+use regex_replace_module::{RegexReplaceModule, RegexReplaceResult};
 use search_module::{SearchModule, SearchPanelResult};
+use selection_file_module::{SelectionFileModule, SelectionFileResult};
+use sequence_module::{number_lines, strip_line_numbers, SequenceModule, SequenceResult};
+use settings_module::{SettingsModule, SettingsResult};
+use snippet_module::{Snippet, SnippetModule, SnippetResult, SnippetVariables};
+use spellcheck_module::{dictionaries_dir, SpellCheckModule, SpellTarget};
+use split_module::{SplitModule, SplitOrientation, SplitSource};
+use statistics_module::{StatisticsModule, StatisticsResult};
+use status_bar_module::StatusItem;
+use symbol_module::{SymbolModule, SymbolResult};
+use template_module::{TemplateModule, TemplateResult, TemplateVariables};
+use theme_module::{available_themes, Palette, ThemeModule, ThemeResult};
+use transform_module::{Transform, TransformResult};
+use typing_sound_module::TypingSoundModule;
+use welcome_module::{WelcomeModule, WelcomeResult};
+use word_frequency_module::WordFrequencyModule;
+use writing_session_module::{WritingSessionModule, WritingSessionResult};
+
+const TEXT_EDIT_ID: &str = "main_text_edit";
+// Поле слева от текста для значков закладок
+const GUTTER_WIDTH: f32 = 16.0;
+const SPELLING_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 60, 60);
+const BOOKMARK_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 140, 230);
+const GIT_ADDED_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 180, 90);
+const GIT_MODIFIED_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 150, 220);
+const GIT_DELETED_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 80, 80);
+const GOAL_REACHED_COLOR: egui::Color32 = egui::Color32::from_rgb(70, 160, 80);
+// Ширина меток на полосе прокрутки
+const SCROLLBAR_MARKER_WIDTH: f32 = 6.0;
+// Имя семейства egui для шрифта, загруженного из файла
+const CUSTOM_FONT: &str = "custom";
+// Пределы и шаг масштаба текста (Ctrl + Плюс/Минус)
+// Шаг перемотки клавишами Shift + F7 / Shift + F9
+const SEEK_STEP: Duration = Duration::from_secs(10);
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 4.0;
+const ZOOM_STEP: f32 = 0.1;
+
+// Без X11 и Wayland окно не откроется — тогда сразу запускаем консольный редактор
+fn has_display() -> bool {
+    if cfg!(all(unix, not(target_os = "macos"))) {
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some()
+    } else {
+        true
+    }
+}
+
+fn run_tui(paths: &[&str]) -> std::io::Result<()> {
+    let mut editor = Editor::new();
+    for path in paths {
+        editor = editor.open_file(path)?;
+    }
+    editor.run()
+}
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let tui = args.iter().any(|arg| arg == "--tui");
+    if tui || !has_display() {
+        let paths: Vec<&str> = args.iter().filter(|arg| !arg.starts_with("--")).map(String::as_str).collect();
+        if let Err(e) = run_tui(&paths) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Язык и размер окна нужны еще до создания редактора
+    let config = Config::load();
+    i18n::set_language(config.language);
     let icon_data = include_bytes!("../assets/logo.png");
 
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size([800.0, 600.0])
+        .with_title(tr!("Текстовый редактор Глеба"))
+        .with_icon(
+            eframe::icon_data::from_png_bytes(icon_data)
+                .expect("Failed to load icon")
+        );
+    if let Some(window) = config.window {
+        viewport = viewport
+            .with_inner_size([window.width, window.height])
+            .with_position([window.x, window.y])
+            .with_maximized(window.maximized);
+    }
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([800.0, 600.0])
-            .with_title("Текстовый редактор Глеба")
-            .with_icon(
-                eframe::icon_data::from_png_bytes(icon_data)
-                    .expect("Failed to load icon")
-            ),
+        viewport,
         ..Default::default()
     };
 
     eframe::run_native(
         "Текстовый редактор Глеба",
         options,
-        Box::new(|_cc| Box::<TextEditor>::default()),
+        Box::new(|cc| Box::new(TextEditor::new(&cc.egui_ctx))),
     )
 }
 
@@ -39,12 +221,512 @@ struct TextEditor {
     error_message: Option<String>,
     music_playing: bool,
     current_song: String,
+    track_info: TrackInfo,         // Теги играющего трека
+    playback_clock: PlaybackClock, // Сколько сыграно от начала трека
     audio_sink: Option<Arc<Mutex<Sink>>>,
     _stream: Option<OutputStream>,
+    stream_handle: Option<OutputStreamHandle>, // Один выход на всю музыку, чтобы треки могли звучать вместе при переходе
+    fader: Fader,
     search_module: SearchModule,
+    find_in_files: FindInFilesModule,
+    quick_open: QuickOpenModule,
+    command_palette: CommandPaletteModule,
+    plugins: PluginModule,
+    external_command: ExternalCommandModule,
+    format: FormatModule,
+    sequence: SequenceModule,
+    hash: HashModule,
+    date: DateModule,
+    symbols: SymbolModule,
+    placeholder: PlaceholderModule,
+    multi_cursor: MultiCursor, // Выделения следующих вхождений (Ctrl + Shift + D)
+    git: GitModule,
+    diff: DiffModule,
+    compare: CompareModule,
+    file_watch: FileWatchModule,
+    file_properties: FilePropertiesModule,
+    archive: ArchiveModule,
+    crypto: CryptoModule,
+    selection_file: SelectionFileModule,
+    docx: DocxModule,
+    large_file: LargeFileModule,
+    history: HistoryModule,
+    file_tree: FileTreeModule,
+    workspace: Option<PathBuf>, // Открытая папка проекта
+    pending_cursor: Option<usize>, // Куда поставить курсор (в символах) при следующей отрисовке
+    pending_anchor: Option<usize>, // Другой конец выделения для pending_cursor
+    cursor_char: usize,            // Позиция курсора (в символах) на прошлом кадре
+    selection: Option<(usize, usize)>, // Выделение (в символах, начало < конец) на прошлом кадре
+    last_line_count: usize,
+    bookmarks: BookmarksModule,
+    outline: OutlineModule,
+    duplicates: DuplicatesModule,
+    regex_replace: RegexReplaceModule,
+    config: Config,
+    settings: SettingsModule,
+    themes: ThemeModule,
+    palette: Palette, // Цвета текущей темы, берутся из config при применении настроек
+    loaded_font: Option<PathBuf>, // Файл шрифта, который сейчас зарегистрирован в egui
+    custom_font_loaded: bool,
+    zoom: f32, // Масштаб текста на время сеанса, в config не пишется
+    minimap: MinimapModule,
+    viewport: Viewport,         // Прокрутка текста на прошлом кадре, для миникарты
+    pending_scroll: Option<f32>, // Куда прокрутить текст при следующей отрисовке
+    split: SplitModule,
+    welcome: WelcomeModule,
+    window_title: String, // Последний отправленный заголовок окна
+    playlist: PlaylistModule,
+    ambient: AmbientModule,
+    typing_sounds: TypingSoundModule,
+    pomodoro: PomodoroModule,
+    statistics: StatisticsModule,
+    word_frequency: WordFrequencyModule,
+    writing: WritingSessionModule,
+    document_goal: DocumentGoalModule,
+    spellcheck: SpellCheckModule,
+    spell_target: Option<SpellTarget>, // Слово, на котором открыто контекстное меню
+    autocomplete: AutocompleteModule,
+    autocomplete_forced: bool, // Нажали Ctrl + Пробел, подсказки покажем после отрисовки текста
+    snippets: SnippetModule,
+    templates: TemplateModule,
+    pending_template: Option<String>, // Шаблон для нового документа, если сначала спросили о сохранении
+    pending_open: Option<(PathBuf, Option<usize>)>, // Файл и позиция курсора, которые откроем после вопроса
+    journal: Option<Journal>,          // Открыт журнал с защитой прежних записей
+    encoding: Option<(PathBuf, String)>, // В какой кодировке прочитан файл, если не в UTF-8 с диска
+    auto_replace: AutoReplaceModule,
+    last_word_count: usize, // Слов в документе на прошлом кадре
+    user_edited: bool,      // Текст в этом кадре изменил пользователь, а не открытие файла
+    last_autosave: Option<Instant>,
+    text_generation: u64, // Растет при каждом изменении текста, чтобы не сравнивать его целиком
 }
 
 impl TextEditor {
+    fn new(ctx: &egui::Context) -> Self {
+        let mut editor = Self::default();
+        editor.config = Config::load();
+        editor.zoom = 1.0;
+        editor.welcome.show = editor.config.show_welcome;
+        editor.playlist.load(editor.config.music_source());
+        editor.playlist.shuffle = editor.config.music_shuffle;
+        editor.playlist.repeat = editor.config.music_repeat;
+        editor.playlist.show_playback_bar = true;
+        editor.writing.load();
+        editor.apply_config(ctx);
+        editor.reload_plugins();
+        // Ctrl + Плюс/Минус масштабируют только текст, а не весь интерфейс
+        ctx.options_mut(|o| o.zoom_with_keyboard = false);
+        editor
+    }
+
+    // === Настройки ===
+    fn apply_config(&mut self, ctx: &egui::Context) {
+        i18n::set_language(self.config.language);
+        self.palette = self.config.palette();
+        ctx.set_visuals(self.palette.visuals());
+        self.load_fonts(ctx);
+        self.apply_volume();
+        self.ambient.set_volume(self.config.ambient_volume);
+    }
+
+    // Шрифты пересобираются только при смене файла: set_fonts заново строит атлас глифов
+    fn load_fonts(&mut self, ctx: &egui::Context) {
+        if self.loaded_font == self.config.custom_font {
+            return;
+        }
+        self.loaded_font = self.config.custom_font.clone();
+        self.custom_font_loaded = false;
+
+        let mut fonts = egui::FontDefinitions::default();
+        if let Some(path) = &self.config.custom_font {
+            match fs::read(path) {
+                Ok(bytes) if Self::is_font_file(&bytes) => {
+                    fonts
+                        .font_data
+                        .insert(CUSTOM_FONT.to_owned(), egui::FontData::from_owned(bytes));
+                    // Символы, которых нет в выбранном шрифте, берем из стандартного моноширинного
+                    let mut family = vec![CUSTOM_FONT.to_owned()];
+                    family.extend(fonts.families[&egui::FontFamily::Monospace].iter().cloned());
+                    fonts.families.insert(egui::FontFamily::Name(CUSTOM_FONT.into()), family);
+                    self.custom_font_loaded = true;
+                }
+                Ok(_) => {
+                    self.error_message = Some(tr!("Файл не похож на шрифт TTF/OTF: {}", path.display()));
+                }
+                Err(e) => {
+                    self.error_message = Some(tr!("Ошибка загрузки шрифта: {}", e));
+                }
+            }
+        }
+        ctx.set_fonts(fonts);
+    }
+
+    // egui паникует на поврежденном шрифте, поэтому проверяем сигнатуру заранее
+    fn is_font_file(bytes: &[u8]) -> bool {
+        matches!(
+            bytes.get(..4),
+            Some([0x00, 0x01, 0x00, 0x00]) | Some(b"OTTO") | Some(b"true") | Some(b"ttcf")
+        )
+    }
+
+    fn editor_font(&self) -> egui::FontId {
+        let family = match self.config.font_family {
+            FontFamily::Monospace => egui::FontFamily::Monospace,
+            FontFamily::Proportional => egui::FontFamily::Proportional,
+            FontFamily::Custom if self.custom_font_loaded => egui::FontFamily::Name(CUSTOM_FONT.into()),
+            FontFamily::Custom => egui::FontFamily::Monospace,
+        };
+        egui::FontId::new(self.config.font_size * self.zoom, family)
+    }
+
+    fn handle_settings(&mut self, ctx: &egui::Context) {
+        match self.settings.show_window(ctx, &mut self.config) {
+            SettingsResult::None => {}
+            SettingsResult::Changed => self.apply_config(ctx),
+            SettingsResult::Save => {
+                self.apply_config(ctx);
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(e);
+                }
+            }
+        }
+    }
+
+    fn handle_statistics(&mut self, ctx: &egui::Context) {
+        if self.statistics.show_window(ctx, &self.text, &mut self.config) == StatisticsResult::ConfigChanged {
+            self.save_config();
+        }
+    }
+
+    fn handle_spellcheck(&mut self, ctx: &egui::Context) {
+        if !self.config.spell_check {
+            return;
+        }
+        if let Some(e) = self.spellcheck.update(&self.text, self.text_generation) {
+            self.error_message = Some(e);
+        }
+        if self.spellcheck.is_loading() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+    }
+
+    // До текстового поля, чтобы Backspace после автозамены не стер лишний символ
+    fn handle_auto_replace(&mut self, ctx: &egui::Context) {
+        let cursor = self.pending_cursor.unwrap_or(self.cursor_char);
+        if let Some(undo) = self.auto_replace.handle_keys(ctx, cursor) {
+            self.replace_char_range(undo.start, undo.end, &undo.text);
+        }
+        if self.auto_replace.show_window(ctx, &mut self.config) == AutoReplaceResult::ConfigChanged {
+            self.save_config();
+        }
+    }
+
+    // До текстового поля: открывающий символ вставляем парой сами, вместо текстового поля
+    fn handle_auto_pair(&mut self, ctx: &egui::Context) {
+        let editor_focused = ctx.memory(|m| m.has_focus(egui::Id::new(TEXT_EDIT_ID)));
+        if !self.config.auto_pair || !editor_focused {
+            return;
+        }
+        let cursor = self.pending_cursor.unwrap_or(self.cursor_char);
+        let chars: Vec<char> = self.text.chars().collect();
+        let selection = self.selection;
+        let config = &self.config;
+        let edit = ctx.input_mut(|i| {
+            if selection.is_none() && i.key_pressed(egui::Key::Backspace) {
+                let (start, end) = auto_pair::on_backspace(&chars, cursor)?;
+                return i
+                    .consume_key(egui::Modifiers::NONE, egui::Key::Backspace)
+                    .then(|| auto_pair::PairEdit { start, end, text: String::new(), anchor: start, cursor: start });
+            }
+            let (index, edit) = i.events.iter().enumerate().find_map(|(index, e)| match e {
+                egui::Event::Text(t) if t.chars().count() == 1 => {
+                    let typed = t.chars().next()?;
+                    auto_pair::on_typed(typed, &chars, cursor, selection, config).map(|edit| (index, edit))
+                }
+                _ => None,
+            })?;
+            i.events.remove(index);
+            Some(edit)
+        });
+        if let Some(edit) = edit {
+            if edit.start != edit.end || !edit.text.is_empty() {
+                self.replace_char_range(edit.start, edit.end, &edit.text);
+            }
+            self.select_char_range(edit.anchor, edit.cursor);
+        }
+    }
+
+    // После отрисовки текста, когда набранный символ уже в тексте
+    fn apply_auto_replace(&mut self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let typed = output.response.changed()
+            && ui.input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Text(_))));
+        if !self.config.auto_replace || !typed || self.selection.is_some() {
+            return;
+        }
+        if let Some(replacement) = self.auto_replace.on_typed(&self.text, self.cursor_char, &self.config) {
+            self.replace_char_range(replacement.start, replacement.end, &replacement.text);
+            ui.ctx().request_repaint();
+        }
+    }
+
+    // До текстового поля: стрелки, Enter и Tab при открытом списке достаются подсказкам
+    fn handle_autocomplete_keys(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Space)) {
+            self.autocomplete_forced = true;
+        }
+        let result = self.autocomplete.handle_keys(ctx);
+        self.apply_autocomplete_result(result);
+    }
+
+    fn apply_autocomplete_result(&mut self, result: AutocompleteResult) {
+        if let AutocompleteResult::Accept { start, word } = result {
+            self.replace_char_range(start, self.cursor_char, &word);
+        }
+    }
+
+    // После отрисовки текста: курсор и набранные символы этого кадра уже известны
+    fn update_autocomplete(&mut self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let forced = std::mem::take(&mut self.autocomplete_forced);
+        let typed = output.response.changed()
+            && ui.input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Text(_))));
+        if self.selection.is_some() {
+            self.autocomplete.close();
+            return;
+        }
+        self.autocomplete
+            .update(&self.text, self.cursor_char, typed, forced, self.config.autocomplete);
+
+        let cursor = output.galley.from_ccursor(egui::text::CCursor::new(self.cursor_char));
+        let cursor_rect = output
+            .galley
+            .pos_from_cursor(&cursor)
+            .translate(output.galley_pos.to_vec2());
+        let result = self.autocomplete.show_popup(ui.ctx(), cursor_rect.left_bottom());
+        if !matches!(result, AutocompleteResult::None) {
+            ui.ctx().request_repaint();
+        }
+        self.apply_autocomplete_result(result);
+    }
+
+    // Tab после сокращения разворачивает сниппет, дальше Tab / Shift + Tab ходят по местам для ввода
+    fn handle_snippets(&mut self, ctx: &egui::Context) {
+        if self.pending_cursor.is_none() {
+            self.snippets.track(self.count_characters(), self.cursor_char);
+        }
+
+        if self.snippets.in_session() {
+            let (escape, backward, forward) = ctx.input_mut(|i| {
+                let escape = i.consume_key(egui::Modifiers::NONE, egui::Key::Escape);
+                // Shift + Tab проверяем раньше: Tab без модификаторов совпал бы и с ним
+                let backward = i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab);
+                let forward = !backward && i.consume_key(egui::Modifiers::NONE, egui::Key::Tab);
+                (escape, backward, forward)
+            });
+            if escape {
+                self.snippets.end_session();
+            } else if backward || forward {
+                if let Some((start, end)) = self.snippets.jump(backward) {
+                    self.select_char_range(start, end);
+                }
+            }
+        } else if self.selection.is_none()
+            && ctx.input(|i| i.key_pressed(egui::Key::Tab) && i.modifiers.is_none())
+        {
+            let found = SnippetModule::find_abbreviation(&self.text, self.cursor_char, &self.config.snippets)
+                .map(|(start, snippet)| (start, snippet.clone()));
+            if let Some((start, snippet)) = found {
+                ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab));
+                self.insert_snippet(&snippet, start);
+            }
+        }
+
+        if self.snippets.show_window(ctx, &mut self.config) == SnippetResult::ConfigChanged {
+            self.save_config();
+        }
+    }
+
+    // Сниппет встает вместо символов от start до курсора (сокращения)
+    fn insert_snippet(&mut self, snippet: &Snippet, start: usize) {
+        let variables = SnippetVariables {
+            date: format_date(&self.config.date_format, self.config.language),
+            filename: self
+                .filename
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        let expansion = self
+            .snippets
+            .expand(snippet, start, self.cursor_char, self.count_characters(), &variables);
+        self.replace_char_range(expansion.start, expansion.end, &expansion.text);
+        self.select_char_range(expansion.selection.0, expansion.selection.1);
+    }
+
+    fn handle_word_frequency(&mut self, ctx: &egui::Context) {
+        if !self.word_frequency.show_window {
+            return;
+        }
+        let selection = self.selected_text();
+        self.word_frequency.show_window(ctx, &self.text, selection.as_deref());
+    }
+
+    // Считаем только слова, которые набрал пользователь: открытие файла в журнал не попадает
+    fn handle_writing_session(&mut self, ctx: &egui::Context) {
+        let words = self.count_words();
+        if std::mem::take(&mut self.user_edited) {
+            self.writing.record(words as i64 - self.last_word_count as i64);
+        }
+        self.last_word_count = words;
+
+        if let Err(e) = self.writing.autosave() {
+            self.error_message = Some(e);
+        }
+        if self.writing.show_window(ctx, &mut self.config) == WritingSessionResult::ConfigChanged {
+            self.save_config();
+        }
+    }
+
+    fn handle_document_goal(&mut self, ctx: &egui::Context) {
+        let words = self.status_words();
+        let result = self
+            .document_goal
+            .show_window(ctx, self.filename.as_deref(), words, &mut self.config);
+        if result == DocumentGoalResult::ConfigChanged {
+            self.save_config();
+        }
+    }
+
+    fn handle_themes(&mut self, ctx: &egui::Context) {
+        if self.themes.show_editor(ctx, &mut self.config) == ThemeResult::Changed {
+            self.apply_config(ctx);
+            if let Err(e) = self.config.save() {
+                self.error_message = Some(e);
+            }
+        }
+    }
+
+    fn set_theme(&mut self, ctx: &egui::Context, name: String) {
+        self.config.theme = name;
+        self.apply_config(ctx);
+        if let Err(e) = self.config.save() {
+            self.error_message = Some(e);
+        }
+    }
+
+    fn handle_zoom(&mut self, ctx: &egui::Context) {
+        let (zoom_in, zoom_out, reset, scroll_zoom) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Plus)
+                    || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Equals),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Minus),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Num0),
+                i.zoom_delta(), // Ctrl + колесо мыши или жест щипка
+            )
+        });
+
+        let mut zoom = self.zoom;
+        if reset {
+            zoom = 1.0;
+        }
+        if zoom_in {
+            zoom += ZOOM_STEP;
+        }
+        if zoom_out {
+            zoom -= ZOOM_STEP;
+        }
+        zoom *= scroll_zoom;
+        self.zoom = ((zoom * 100.0).round() / 100.0).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    // Заголовок вида "имя • — Текстовый редактор Глеба"; команду шлем только при изменении
+    fn handle_window_title(&mut self, ctx: &egui::Context) {
+        let filename = self
+            .filename
+            .as_deref()
+            .or(self.large_file.path())
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| tr!("Без названия").to_string());
+        let marker = if self.unsaved_changes { " •" } else { "" };
+        let title = format!("{}{} — {}", filename, marker, tr!("Текстовый редактор Глеба"));
+
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+    }
+
+    // Запоминаем размер и положение окна; в файл они попадают при выходе
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (inner, outer, maximized, minimized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.inner_rect,
+                viewport.outer_rect,
+                viewport.maximized.unwrap_or(false),
+                viewport.minimized.unwrap_or(false),
+            )
+        });
+        if minimized {
+            return;
+        }
+
+        let mut window = self.config.window.unwrap_or(WindowGeometry {
+            x: 0.0,
+            y: 0.0,
+            width: 800.0,
+            height: 600.0,
+            maximized: false,
+        });
+        window.maximized = maximized;
+        // У развернутого окна размер не запоминаем, чтобы вернуться к обычному
+        if !maximized {
+            if let (Some(inner), Some(outer)) = (inner, outer) {
+                window.x = outer.min.x;
+                window.y = outer.min.y;
+                window.width = inner.width();
+                window.height = inner.height();
+            }
+        }
+        self.config.window = Some(window);
+    }
+
+    fn handle_autosave(&mut self, ctx: &egui::Context) {
+        if !self.config.autosave || self.filename.is_none() {
+            return;
+        }
+
+        let interval = Duration::from_secs(self.config.autosave_interval_secs.max(5));
+        let now = Instant::now();
+        let last = *self.last_autosave.get_or_insert(now);
+
+        // Отсчет идет с момента, когда документ последний раз был сохранен
+        if !self.unsaved_changes {
+            self.last_autosave = Some(now);
+        } else if now.duration_since(last) >= interval {
+            // Автосохранение не задает вопросов: что нельзя записать на место как есть, оставляем до ручного сохранения
+            if self.filename.clone().is_some_and(|path| self.can_save_in_place(&path)) {
+                self.save_file();
+            }
+            self.last_autosave = Some(now);
+        }
+
+        ctx.request_repaint_after(interval);
+    }
+
+    // Файлы не в UTF-8 (например, старые документы в cp1251) читаем в кодировке из настроек
+    fn decode_text(&self, bytes: Vec<u8>) -> String {
+        match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => {
+                let encoding = encoding_rs::Encoding::for_label(self.config.default_encoding.as_bytes())
+                    .unwrap_or(encoding_rs::WINDOWS_1251);
+                let (text, _, _) = encoding.decode(e.as_bytes());
+                text.into_owned()
+            }
+        }
+    }
+
     // === Базовые методы подсчета ===
     fn count_words(&self) -> usize {
         self.text
@@ -53,10 +735,19 @@ impl TextEditor {
             .count()
     }
 
+    fn text_changed(&mut self) {
+        self.text_generation = self.text_generation.wrapping_add(1);
+    }
+
     fn count_characters(&self) -> usize {
         self.text.chars().count()
     }
 
+    // Слова так, как их считают в строке состояния и для цели документа
+    fn status_words(&self) -> usize {
+        statistics_module::count_words(&self.text, self.config.status_words)
+    }
+
     fn count_lines(&self) -> usize {
         if self.text.is_empty() {
             1
@@ -65,339 +756,2662 @@ impl TextEditor {
         }
     }
 
+    // === Строки и курсор ===
+    // Замена диапазона символов как правка пользователя: курсор встает после вставки
+    fn replace_char_range(&mut self, start: usize, end: usize, replacement: &str) {
+        let byte_index = |char_index: usize| {
+            self.text
+                .char_indices()
+                .nth(char_index)
+                .map(|(i, _)| i)
+                .unwrap_or(self.text.len())
+        };
+        let (start_byte, end_byte) = (byte_index(start), byte_index(end));
+        self.text.replace_range(start_byte..end_byte, replacement);
+        self.text_changed();
+        self.pending_cursor = Some(start + replacement.chars().count());
+        self.unsaved_changes = true;
+        self.user_edited = true;
+    }
+
+    fn select_char_range(&mut self, start: usize, end: usize) {
+        self.pending_anchor = Some(start);
+        self.pending_cursor = Some(end);
+    }
+
+    fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection?;
+        Some(self.text.chars().skip(start).take(end - start).collect())
+    }
+
+    fn cursor_line(&self) -> usize {
+        self.text
+            .chars()
+            .take(self.cursor_char)
+            .filter(|&c| c == '\n')
+            .count()
+    }
+
+    fn line_start_char(&self, line: usize) -> usize {
+        if line == 0 {
+            return 0;
+        }
+        self.text
+            .chars()
+            .enumerate()
+            .filter(|&(_, c)| c == '\n')
+            .nth(line - 1)
+            .map(|(i, _)| i + 1)
+            .unwrap_or_else(|| self.count_characters())
+    }
+
+    fn go_to_line(&mut self, line: usize) {
+        self.pending_cursor = Some(self.line_start_char(line));
+    }
+
     // === Музыка ===
+    // Пауза сохраняет позицию в треке; поток пересоздается только после остановки
     fn toggle_music(&mut self) {
         if self.music_playing {
-            self.stop_music();
+            self.pause_music();
         } else {
-            self.play_music();
+            self.resume_music();
         }
-        self.music_playing = !self.music_playing;
     }
 
-    fn play_music(&mut self) {
-        let music_paths = [
-            ("assets/theme.mp3", "Тема редактора"),
-            ("assets/music.mp3", "Фоновая музыка"),
-            ("assets/music.wav", "Фоновая музыка"),
-            ("music.mp3", "Фоновая музыка"),
-            ("music.wav", "Фоновая музыка"),
-        ];
-
-        for (path, song_name) in music_paths {
-            if let Ok(file) = std::fs::File::open(path) {
-                if let Ok((stream, stream_handle)) = OutputStream::try_default() {
-                    let sink = Sink::try_new(&stream_handle).unwrap();
-                    let reader = BufReader::new(file);
-
-                    if let Ok(source) = rodio::Decoder::new(reader) {
-                        sink.append(source);
-                        sink.set_volume(0.5);
-                        sink.play();
-
-                        self.audio_sink = Some(Arc::new(Mutex::new(sink)));
-                        self._stream = Some(stream);
-                        self.current_song = song_name.to_string();
-                        self.error_message = None;
-                        return;
-                    }
+    fn pause_music(&mut self) {
+        if let Some(sink) = &self.audio_sink {
+            self.fader.fade(sink, 0.0, FADE_DURATION, FadeEnd::Pause);
+        }
+        self.playback_clock.pause();
+        self.music_playing = false;
+    }
+
+    fn resume_music(&mut self) {
+        // Трек, который уже доиграл, начинаем заново
+        let resumed = self
+            .audio_sink
+            .as_ref()
+            .and_then(|sink| sink.lock().ok().map(|sink| {
+                if sink.empty() {
+                    false
+                } else {
+                    sink.play();
+                    true
                 }
+            }))
+            .unwrap_or(false);
+
+        if resumed {
+            if let Some(sink) = &self.audio_sink {
+                self.fader.fade(sink, self.effective_volume(), FADE_DURATION, FadeEnd::Keep);
             }
+            self.playback_clock.resume();
+        } else {
+            self.play_music();
         }
-
-        self.play_fallback_tone();
+        self.music_playing = true;
     }
 
-    fn play_fallback_tone(&mut self) {
-        if let Ok((stream, stream_handle)) = OutputStream::try_default() {
-            let sink = Sink::try_new(&stream_handle).unwrap();
+    fn effective_volume(&self) -> f32 {
+        if self.config.music_muted {
+            0.0
+        } else {
+            self.config.music_volume
+        }
+    }
 
-            let source = rodio::source::SineWave::new(440.0);
-            sink.append(source);
-            sink.set_volume(0.1);
-            sink.play();
+    // Через тот же поток, что и переходы, иначе затухание перебьет новую громкость.
+    // На паузе не трогаем: громкость восстановится при продолжении
+    fn apply_volume(&self) {
+        if !self.music_playing {
+            return;
+        }
+        if let Some(sink) = &self.audio_sink {
+            self.fader.fade(sink, self.effective_volume(), Duration::ZERO, FadeEnd::Keep);
+        }
+    }
 
-            self.audio_sink = Some(Arc::new(Mutex::new(sink)));
+    fn output_handle(&mut self) -> Option<OutputStreamHandle> {
+        if self.stream_handle.is_none() {
+            let (stream, handle) = OutputStream::try_default().ok()?;
             self._stream = Some(stream);
-            self.current_song = "Тестовый тон".to_string();
-            self.error_message = Some("Музыкальный файл не найден. Воспроизводится тестовый тон.".to_string());
+            self.stream_handle = Some(handle);
         }
+        self.stream_handle.clone()
     }
 
-    fn stop_music(&mut self) {
-        if let Some(sink) = &self.audio_sink {
-            if let Ok(sink) = sink.lock() {
-                sink.stop();
-            }
+    // Прежний трек затихает в потоке fader, пока новый набирает громкость
+    fn fade_out_current(&mut self) {
+        if let Some(sink) = self.audio_sink.take() {
+            self.fader.fade(&sink, 0.0, FADE_DURATION, FadeEnd::Stop);
         }
-        self.audio_sink = None;
-        self._stream = None;
-        self.current_song = "Музыка выключена".to_string();
     }
 
-    // === Файловые операции ===
-    fn open_file(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Текстовые файлы", &["txt", "doc", "docx"])
-            .add_filter("Документы Word", &["doc", "docx"])
-            .add_filter("Текстовые файлы", &["txt"])
-            .add_filter("Все файлы", &["*"])
-            .pick_file() 
-        {
-            match path.extension().and_then(|s| s.to_str()) {
-                Some("txt") => self.open_txt_file(&path),
-                Some("docx") => self.open_docx_file(&path),
-                Some("doc") => self.open_doc_file(&path),
-                _ => self.open_txt_file(&path),
-            }
+    fn save_config(&mut self) {
+        if let Err(e) = self.config.save() {
+            self.error_message = Some(e);
         }
     }
 
-    fn open_txt_file(&mut self, path: &PathBuf) {
-        match fs::read_to_string(path) {
-            Ok(content) => {
-                self.text = content;
-                self.filename = Some(path.clone());
-                self.unsaved_changes = false;
-                self.error_message = None;
-                self.search_module.matches.clear();
-            }
-            Err(e) => {
-                self.error_message = Some(format!("Ошибка открытия TXT файла: {}", e));
+    // Играем текущий трек плейлиста (или первый); если ни один файл не открылся — тестовый тон
+    fn play_music(&mut self) {
+        if let Some(index) = self.playlist.current.or_else(|| self.playlist.next_index()) {
+            if self.play_track(index) {
+                return;
             }
         }
+
+        self.play_fallback_tone();
     }
 
-    fn open_docx_file(&mut self, path: &PathBuf) {
-        match fs::read(path) {
-            Ok(bytes) => {
-                match Self::extract_text_from_docx(&bytes) {
-                    Ok(text) => {
-                        self.text = text;
-                        self.filename = Some(path.clone());
-                        self.unsaved_changes = false;
-                        self.error_message = None;
-                        self.search_module.matches.clear();
-                    }
-                    Err(e) => {
-                        self.error_message = Some(format!("Ошибка чтения DOCX файла: {}", e));
-                        let fallback_text = Self::extract_readable_text(&String::from_utf8_lossy(&bytes));
-                        self.text = fallback_text;
-                        self.filename = Some(path.clone());
-                        self.unsaved_changes = false;
-                        self.search_module.matches.clear();
-                    }
-                }
-            }
+    fn play_track(&mut self, index: usize) -> bool {
+        self.play_track_from(index, Duration::ZERO)
+    }
+
+    fn play_track_from(&mut self, index: usize, start: Duration) -> bool {
+        let Some(path) = self.playlist.tracks.get(index).cloned() else {
+            return false;
+        };
+        // Запоминаем трек и при ошибке, чтобы автопереход пошел дальше по списку
+        self.playlist.current = Some(index);
+
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
             Err(e) => {
-                self.error_message = Some(format!("Ошибка открытия DOCX файла: {}", e));
+                self.error_message = Some(tr!("Ошибка открытия музыкального файла: {}", e));
+                return false;
             }
-        }
-    }
+        };
+        let Some(stream_handle) = self.output_handle() else {
+            return false;
+        };
 
-    fn open_doc_file(&mut self, path: &PathBuf) {
-        match fs::read(path) {
-            Ok(bytes) => {
-                let text = Self::extract_readable_text(&String::from_utf8_lossy(&bytes));
-                self.text = text;
-                self.filename = Some(path.clone());
-                self.unsaved_changes = false;
+        match rodio::Decoder::new(BufReader::new(file)) {
+            Ok(source) => {
+                self.fade_out_current();
+
+                let sink = Sink::try_new(&stream_handle).unwrap();
+                sink.append(source.skip_duration(start));
+                sink.set_volume(0.0);
+                sink.play();
+
+                let sink = Arc::new(Mutex::new(sink));
+                self.fader.fade(&sink, self.effective_volume(), FADE_DURATION, FadeEnd::Keep);
+                self.audio_sink = Some(sink);
+                self.track_info = read_track_info(&path);
+                self.current_song = self.track_info.display_name(&path);
+                self.playback_clock.start_at(start);
+                self.music_playing = true;
                 self.error_message = None;
-                self.search_module.matches.clear();
+                true
+            }
+            Err(rodio::decoder::DecoderError::UnrecognizedFormat) => {
+                let extension = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+                self.error_message = Some(tr!(
+                    "Формат {} не поддерживается или кодек внутри файла неизвестен: {}",
+                    extension,
+                    PlaylistModule::track_name(&path)
+                ));
+                false
             }
             Err(e) => {
-                self.error_message = Some(format!("Ошибка открытия DOC файла: {}", e));
+                self.error_message = Some(tr!("Не удалось воспроизвести {}: {}", PlaylistModule::track_name(&path), e));
+                false
             }
         }
     }
 
-    fn save_file(&mut self) {
-        if let Some(path) = &self.filename {
-            match fs::write(path, &self.text) {
-                Ok(_) => {
-                    self.unsaved_changes = false;
-                    self.error_message = None;
-                }
-                Err(e) => {
-                    self.error_message = Some(format!("Ошибка сохранения файла: {}", e));
-                }
+    fn next_track(&mut self) {
+        match self.playlist.next_index() {
+            Some(index) => {
+                self.play_track(index);
             }
-        } else {
-            self.save_as();
+            None => self.stop_music(),
         }
     }
 
-    fn save_as(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Текстовые файлы", &["txt"])
-            .add_filter("Все файлы", &["*"])
-            .save_file() 
+    fn previous_track(&mut self) {
+        if let Some(index) = self.playlist.previous_index() {
+            self.play_track(index);
+        }
+    }
+
+    fn choose_music_folder(&mut self) {
+        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+            self.config.music_folder = Some(folder);
+            self.config.music_file = None;
+            self.reload_playlist();
+        }
+    }
+
+    fn choose_music_file(&mut self) {
+        if let Some(file) = rfd::FileDialog::new()
+            .add_filter(tr!("Музыка"), &MUSIC_EXTENSIONS)
+            .add_filter(tr!("Все файлы"), &["*"])
+            .pick_file()
         {
-            match fs::write(&path, &self.text) {
-                Ok(_) => {
-                    self.filename = Some(path);
-                    self.unsaved_changes = false;
-                    self.error_message = None;
+            self.config.music_file = Some(file);
+            self.reload_playlist();
+        }
+    }
+
+    fn use_builtin_music(&mut self) {
+        self.config.music_folder = None;
+        self.config.music_file = None;
+        self.reload_playlist();
+    }
+
+    // Источник музыки сменился: перечитываем список и, если музыка играла, играем новую
+    fn reload_playlist(&mut self) {
+        self.playlist.load(self.config.music_source());
+        self.save_config();
+        if self.music_playing {
+            self.stop_music();
+            self.play_music();
+        }
+    }
+
+    // rodio 0.17 не умеет перематывать: открываем трек заново и пропускаем начало
+    fn seek_music(&mut self, position: Duration) {
+        let Some(index) = self.playlist.current else {
+            return;
+        };
+        if self.audio_sink.is_none() {
+            return;
+        }
+        let position = match self.track_info.duration {
+            Some(total) => position.min(total),
+            None => position,
+        };
+        let paused = !self.music_playing;
+        if self.play_track_from(index, position) && paused {
+            self.pause_music();
+        }
+    }
+
+    fn apply_playlist_result(&mut self, result: PlaylistResult) {
+        match result {
+            PlaylistResult::Play(index) => {
+                self.play_track(index);
+            }
+            PlaylistResult::TogglePlayback => self.toggle_music(),
+            PlaylistResult::Next => self.next_track(),
+            PlaylistResult::Previous => self.previous_track(),
+            PlaylistResult::ChooseFile => self.choose_music_file(),
+            PlaylistResult::ChooseFolder => self.choose_music_folder(),
+            PlaylistResult::OptionsChanged => {
+                self.config.music_shuffle = self.playlist.shuffle;
+                self.config.music_repeat = self.playlist.repeat;
+                self.save_config();
+            }
+            PlaylistResult::Seek(position) => self.seek_music(position),
+            PlaylistResult::None => {}
+        }
+    }
+
+    // F7/F8/F9 — как медиаклавиши на клавиатурах ноутбуков; с Shift — перемотка
+    fn handle_media_keys(&mut self, ctx: &egui::Context) -> PlaylistResult {
+        let position = self.playback_clock.position();
+        ctx.input_mut(|i| {
+            if i.consume_key(egui::Modifiers::SHIFT, egui::Key::F7) {
+                PlaylistResult::Seek(position.saturating_sub(SEEK_STEP))
+            } else if i.consume_key(egui::Modifiers::SHIFT, egui::Key::F9) {
+                PlaylistResult::Seek(position + SEEK_STEP)
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::F7) {
+                PlaylistResult::Previous
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::F8) {
+                PlaylistResult::TogglePlayback
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::F9) {
+                PlaylistResult::Next
+            } else {
+                PlaylistResult::None
+            }
+        })
+    }
+
+    fn handle_music(&mut self, ctx: &egui::Context) {
+        let source = self.config.music_source().map(Path::to_path_buf);
+        let result = self.playlist.show_panel(ctx, source.as_deref(), self.music_playing);
+        self.apply_playlist_result(result);
+        let result = self.handle_media_keys(ctx);
+        self.apply_playlist_result(result);
+
+        // Трек доиграл или вот-вот доиграет — переходим к следующему с наложением
+        if self.music_playing && self.playlist.current.is_some() {
+            let finished = self
+                .audio_sink
+                .as_ref()
+                .and_then(|sink| sink.lock().ok().map(|sink| sink.empty()))
+                .unwrap_or(false);
+            let near_end = self
+                .track_info
+                .duration
+                .is_some_and(|total| self.playback_clock.position() + FADE_DURATION >= total);
+            if finished || near_end {
+                self.next_track();
+            }
+        }
+        // Время трека в строке состояния идет и без действий пользователя
+        if self.music_playing {
+            ctx.request_repaint_after(Duration::from_millis(500));
+        }
+    }
+
+    fn handle_playback_bar(&mut self, ctx: &egui::Context) {
+        if self.audio_sink.is_none() {
+            return;
+        }
+        let result = self.playlist.show_playback_bar(
+            ctx,
+            self.playback_clock.position(),
+            self.track_info.duration,
+            self.music_playing,
+        );
+        self.apply_playlist_result(result);
+    }
+
+    // Щелкаем, только когда ввод идет в текстовое поле, а не на горячие клавиши
+    fn handle_typing_sounds(&mut self, ctx: &egui::Context) {
+        if !self.config.typing_sounds || !ctx.wants_keyboard_input() {
+            return;
+        }
+        let (typed, enter) = ctx.input(|i| {
+            let mut typed = false;
+            let mut enter = false;
+            for event in &i.events {
+                match event {
+                    egui::Event::Text(_) => typed = true,
+                    egui::Event::Key { key, pressed: true, .. } => match key {
+                        egui::Key::Enter => enter = true,
+                        egui::Key::Backspace | egui::Key::Delete | egui::Key::Tab => typed = true,
+                        _ => {}
+                    },
+                    _ => {}
                 }
-                Err(e) => {
-                    self.error_message = Some(format!("Ошибка сохранения файла: {}", e));
+            }
+            (typed, enter)
+        });
+        if typed || enter {
+            self.typing_sounds.play(self.config.typing_sound_set, self.config.typing_volume, enter);
+        }
+    }
+
+    fn handle_pomodoro(&mut self, ctx: &egui::Context) {
+        let result = self.pomodoro.update(ctx, &self.config);
+        self.apply_pomodoro_result(result);
+        let result = self.pomodoro.show_panel(ctx, &mut self.config);
+        self.apply_pomodoro_result(result);
+    }
+
+    fn apply_pomodoro_result(&mut self, result: PomodoroResult) {
+        match result {
+            PomodoroResult::FocusStarted => {
+                if self.config.pomodoro_music && !self.music_playing {
+                    self.resume_music();
+                }
+            }
+            PomodoroResult::BreakStarted => {
+                if self.config.pomodoro_music && self.music_playing {
+                    self.pause_music();
                 }
             }
+            PomodoroResult::ConfigChanged => self.save_config(),
+            PomodoroResult::None => {}
         }
     }
 
-    fn new_file(&mut self) {
+    fn set_ambient_sound(&mut self, sound: Option<AmbientSound>) {
+        match sound {
+            Some(sound) => {
+                if let Err(e) = self.ambient.play(sound, self.config.ambient_volume) {
+                    self.error_message = Some(e);
+                }
+            }
+            None => self.ambient.stop(),
+        }
+    }
+
+    fn play_fallback_tone(&mut self) {
+        if let Some(stream_handle) = self.output_handle() {
+            self.fade_out_current();
+            let sink = Sink::try_new(&stream_handle).unwrap();
+
+            let source = rodio::source::SineWave::new(440.0);
+            sink.append(source);
+            sink.set_volume(0.1);
+            sink.play();
+
+            self.audio_sink = Some(Arc::new(Mutex::new(sink)));
+            self.current_song = tr!("Тестовый тон").to_string();
+            self.track_info = TrackInfo::default();
+            self.playback_clock.start();
+            self.error_message = Some(tr!("Музыкальный файл не найден. Воспроизводится тестовый тон.").to_string());
+        }
+    }
+
+    // Выход (_stream) оставляем открытым: через него еще доигрывает затухание
+    fn stop_music(&mut self) {
+        self.fade_out_current();
+        self.music_playing = false;
+        self.track_info = TrackInfo::default();
+        self.playback_clock.reset();
+        self.current_song = tr!("Музыка выключена").to_string();
+    }
+
+    // === Файловые операции ===
+    fn open_file(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr!("Текстовые файлы"), &["txt", "doc", "docx"])
+            .add_filter(tr!("Документы Word"), &["doc", "docx"])
+            .add_filter(tr!("Текстовые файлы"), &["txt"])
+            .add_filter(tr!("Архивы"), &["gz", "zip"])
+            .add_filter(tr!("Зашифрованные документы"), &[ENCRYPTED_EXTENSION])
+            .add_filter(tr!("Все файлы"), &["*"])
+            .pick_file() 
+        {
+            self.request_open(path, None);
+        }
+    }
+
+    // Открытие по выбору пользователя: несохраненные правки сначала предлагаем сохранить
+    fn request_open(&mut self, path: PathBuf, cursor: Option<usize>) {
         if self.unsaved_changes {
+            self.pending_open = Some((path, cursor));
             self.show_save_dialog = true;
             return;
         }
-        
+        self.open_path_at(&path, cursor);
+    }
+
+    fn open_path_at(&mut self, path: &PathBuf, cursor: Option<usize>) -> bool {
+        self.open_path(path);
+        let opened = self.filename.as_ref() == Some(path);
+        if let Some(cursor) = cursor.filter(|_| opened) {
+            self.pending_cursor = Some(cursor);
+        }
+        opened
+    }
+
+    // Продолжаем то, ради чего спрашивали о сохранении: открытие файла или создание нового
+    fn resume_after_save_dialog(&mut self, unsaved: bool) {
+        self.show_save_dialog = false;
+        self.unsaved_changes = false;
+        match self.pending_open.take() {
+            // Файл не открылся — в редакторе прежний текст, и правки в нем по-прежнему не сохранены
+            Some((path, cursor)) => {
+                if !self.open_path_at(&path, cursor) {
+                    self.unsaved_changes = unsaved;
+                }
+            }
+            None => self.new_file(),
+        }
+    }
+
+    fn cancel_save_dialog(&mut self) {
+        self.show_save_dialog = false;
+        self.pending_template = None;
+        self.pending_open = None;
+    }
+
+    // Документ Word — всегда через окно параметров импорта
+    fn open_word_document(&mut self) {
+        if let Some(path) = rfd::FileDialog::new().add_filter(tr!("Документы Word"), &["docx"]).pick_file() {
+            self.docx.ask_to_open(&path, &self.config.docx_import);
+        }
+    }
+
+    fn open_folder(&mut self) {
+        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+            if self.find_in_files.folder.is_none() {
+                self.find_in_files.folder = Some(folder.clone());
+            }
+            self.workspace = Some(folder);
+            self.file_tree.refresh();
+            self.file_tree.show_panel = true;
+        }
+    }
+
+    fn open_path(&mut self, path: &PathBuf) {
+        self.large_file.close();
+        match path.extension().and_then(|s| s.to_str()) {
+            Some("txt") => self.open_txt_file(path),
+            Some("docx") if self.config.docx_import.ask => self.docx.ask_to_open(path, &self.config.docx_import),
+            Some("docx") => self.open_docx_file(path),
+            Some("doc") => self.open_doc_file(path),
+            _ if is_encrypted(path) => self.crypto.ask_to_open(path),
+            _ if is_archive(path) => self.open_archive(path),
+            _ => self.open_txt_file(path),
+        }
+        self.finish_opening(path);
+    }
+
+    fn finish_opening(&mut self, path: &PathBuf) {
+        if self.filename.as_ref() == Some(path) {
+            self.welcome.show = false;
+            self.remember_recent_file(path);
+            self.file_watch.sync();
+        }
+    }
+
+    // Большой файл показываем в просмотрщике только для чтения; редактор при этом пуст
+    fn open_large_file(&mut self, path: &Path, size: u64) {
+        self.large_file.open(path, size, &self.config.default_encoding);
         self.text.clear();
+        self.text_changed();
         self.filename = None;
         self.unsaved_changes = false;
         self.error_message = None;
-        self.search_module.matches.clear();
+        self.search_module.clear_matches();
+        self.welcome.show = false;
+        self.remember_recent_file(path);
     }
 
-    // === Поиск ===
-    fn handle_search(&mut self, ctx: &egui::Context) {
-        let shortcuts_triggered_search = self.search_module.handle_shortcuts(ctx);
+    // .gz распаковываем сразу; из zip с несколькими файлами предлагаем выбрать один
+    fn open_archive(&mut self, path: &Path) {
+        if path.extension().is_some_and(|e| e == "gz") {
+            self.open_from_archive(path, ArchiveKind::Gzip);
+            return;
+        }
+        match zip_entries(path) {
+            Ok(entries) if entries.len() == 1 => {
+                let entry = entries.into_iter().next().unwrap_or_default();
+                self.open_from_archive(path, ArchiveKind::Zip(entry));
+            }
+            Ok(entries) => self.archive.show_picker(path, entries),
+            Err(e) => self.error_message = Some(e),
+        }
+    }
 
-        let search_result = self.search_module.show_search_panel(ctx);
-        
-        match search_result {
-            SearchPanelResult::SearchNeeded => {
-                self.search_module.search_in_text(&self.text);
+    fn open_from_archive(&mut self, path: &Path, kind: ArchiveKind) {
+        match read_archive(path, &kind) {
+            Ok(bytes) => {
+                self.encoding = Some((path.to_path_buf(), detect_encoding(&bytes, &self.config.default_encoding)));
+                self.text = self.decode_text(bytes);
+                self.text_changed();
+                self.filename = Some(path.to_path_buf());
+                self.unsaved_changes = false;
+                self.error_message = None;
+                self.search_module.clear_matches();
+                self.archive.set_current(path, kind);
             }
-            SearchPanelResult::NextMatch => {
-                self.search_module.next_match();
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    fn handle_archive(&mut self, ctx: &egui::Context) {
+        if let ArchiveResult::Open(path, entry) = self.archive.show_window(ctx) {
+            self.open_from_archive(&path, ArchiveKind::Zip(entry));
+            self.finish_opening(&path);
+        }
+    }
+
+    fn remember_recent_file(&mut self, path: &Path) {
+        self.config.add_recent_file(path);
+        if let Err(e) = self.config.save() {
+            self.error_message = Some(e);
+        }
+    }
+
+    fn handle_welcome(&mut self, ui: &mut egui::Ui) {
+        match self.welcome.show(ui, &mut self.config) {
+            WelcomeResult::NewFile => self.new_file(),
+            WelcomeResult::OpenFile => self.open_file(),
+            WelcomeResult::OpenFolder => self.open_folder(),
+            WelcomeResult::OpenRecent(path) => self.request_open(path, None),
+            WelcomeResult::ConfigChanged => {
+                if let Err(e) = self.config.save() {
+                    self.error_message = Some(e);
+                }
+            }
+            WelcomeResult::None => {}
+        }
+    }
+
+    fn open_txt_file(&mut self, path: &PathBuf) {
+        if let Some(size) = fs::metadata(path).ok().map(|m| m.len()).filter(|&size| size > LARGE_FILE_THRESHOLD) {
+            self.open_large_file(path, size);
+            return;
+        }
+        match fs::read(path) {
+            Ok(bytes) => {
+                self.encoding = Some((path.clone(), detect_encoding(&bytes, &self.config.default_encoding)));
+                self.text = self.decode_text(bytes);
+                self.text_changed();
+                self.filename = Some(path.clone());
+                self.unsaved_changes = false;
+                self.error_message = None;
+                self.search_module.clear_matches();
+            }
+            Err(e) => {
+                self.error_message = Some(tr!("Ошибка открытия TXT файла: {}", e));
+            }
+        }
+    }
+
+    fn open_docx_file(&mut self, path: &PathBuf) {
+        match fs::read(path) {
+            Ok(bytes) => {
+                match DocxDocument::open(&bytes, &self.config.docx_import) {
+                    Ok((text, document)) => {
+                        self.docx.set_current(path, document);
+                        self.text = text;
+                        self.text_changed();
+                        self.filename = Some(path.clone());
+                        self.unsaved_changes = false;
+                        self.error_message = None;
+                        self.search_module.clear_matches();
+                        if self.config.docx_import.extract_images {
+                            if let Err(e) = docx_import::save_images(&bytes, path) {
+                                self.error_message = Some(tr!("Ошибка сохранения картинок: {}", e));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        self.error_message = Some(tr!("Ошибка чтения DOCX файла: {}", e));
+                        let fallback_text = Self::extract_readable_text(&String::from_utf8_lossy(&bytes));
+                        self.text = fallback_text;
+                        self.text_changed();
+                        self.filename = Some(path.clone());
+                        self.unsaved_changes = false;
+                        self.search_module.clear_matches();
+                    }
+                }
+            }
+            Err(e) => {
+                self.error_message = Some(tr!("Ошибка открытия DOCX файла: {}", e));
+            }
+        }
+    }
+
+    fn open_doc_file(&mut self, path: &PathBuf) {
+        match fs::read(path) {
+            Ok(bytes) => {
+                let text = Self::extract_readable_text(&String::from_utf8_lossy(&bytes));
+                self.text = text;
+                self.text_changed();
+                self.filename = Some(path.clone());
+                self.unsaved_changes = false;
+                self.error_message = None;
+                self.search_module.clear_matches();
+            }
+            Err(e) => {
+                self.error_message = Some(tr!("Ошибка открытия DOC файла: {}", e));
+            }
+        }
+    }
+
+    fn save_file(&mut self) {
+        // В просмотрщике большого файла редактор пуст — сохранять нечего
+        if self.large_file.is_open() {
+            return;
+        }
+        if let Some(path) = self.filename.clone() {
+            // Не затираем молча то, что записала другая программа
+            if self.file_watch.changed_on_disk() {
+                self.error_message = Some(tr!("Файл изменен на диске, сохранение отменено").to_string());
+                self.file_watch.show_prompt();
+                return;
+            }
+            let Some(bytes) = self.encode_for_disk(&path) else {
+                return;
+            };
+            match write_atomically(&path, &bytes, self.config.backup_on_save) {
+                Ok(_) => {
+                    self.file_watch.sync();
+                    self.unsaved_changes = false;
+                    self.error_message = None;
+                    if let Err(e) = self.bookmarks.persist() {
+                        self.error_message = Some(e);
+                    }
+                    self.record_history();
+                }
+                Err(e) => {
+                    self.error_message = Some(tr!("Ошибка сохранения файла: {}", e));
+                }
+            }
+        } else {
+            self.save_as();
+        }
+    }
+
+    // Можно ли записать документ на место в прежнем формате, не спрашивая пароль, имя или разрешение
+    fn can_save_in_place(&mut self, path: &Path) -> bool {
+        if self.large_file.is_open() || self.file_watch.changed_on_disk() {
+            return false;
+        }
+        if is_encrypted(path) {
+            return self.crypto.password_for(path).is_some();
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("docx") => return self.docx.document_for(path).is_some(),
+            Some("doc") => return false,
+            _ => {}
+        }
+        if self.archive.kind_for(path).is_some() && !self.config.recompress_archives {
+            return false;
+        }
+        self.encode_text(path).is_some()
+    }
+
+    // Текст в той кодировке, в которой файл был прочитан. None — в ней нет каких-то символов документа
+    fn encode_text(&self, path: &Path) -> Option<Vec<u8>> {
+        let encoding = self
+            .encoding
+            .as_ref()
+            .filter(|(current, _)| current == path)
+            .and_then(|(_, name)| encoding_rs::Encoding::for_label(name.as_bytes()))
+            .unwrap_or(encoding_rs::UTF_8);
+        if encoding == encoding_rs::UTF_8 {
+            return Some(self.text.as_bytes().to_vec());
+        }
+        let (bytes, _, unmappable) = encoding.encode(&self.text);
+        (!unmappable).then(|| bytes.into_owned())
+    }
+
+    // Байты для записи на диск: зашифрованные, сжатые в архив или просто текст.
+    // None — сохранение отложено (ждем пароль, спрашиваем имя) или не удалось
+    fn encode_for_disk(&mut self, path: &Path) -> Option<Vec<u8>> {
+        if let Some(password) = self.crypto.password_for(path) {
+            return match encrypt(&self.text, password) {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    self.error_message = Some(e);
+                    None
+                }
+            };
+        }
+        if is_encrypted(path) {
+            // Пароль к этому файлу еще не вводили
+            self.crypto.ask_to_save(path);
+            return None;
+        }
+        // В .docx записываем разобранный при открытии документ с исправленными абзацами
+        if path.extension().is_some_and(|e| e == "docx") {
+            let Some(document) = self.docx.document_for(path) else {
+                self.error_message =
+                    Some(tr!("Документ открыт с разметкой или добавками и сохраняется только под другим именем").to_string());
+                self.save_as();
+                return None;
+            };
+            return match document.save(&self.text) {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    self.error_message = Some(e);
+                    None
+                }
+            };
+        }
+        // Из .doc при открытии достали только читаемый текст — записав его на место, мы бы потеряли документ
+        if path.extension().is_some_and(|e| e == "doc") {
+            self.error_message = Some(tr!("Документ .doc сохраняется только под другим именем").to_string());
+            self.save_as();
+            return None;
+        }
+        if self.archive.kind_for(path).is_some() && !self.config.recompress_archives {
+            self.save_as();
+            return None;
+        }
+        let Some(text) = self.encode_text(path) else {
+            let encoding = self.encoding.as_ref().map_or("", |(_, name)| name.as_str());
+            self.error_message = Some(tr!(
+                "В кодировке {} нет некоторых символов документа — сохраните его под другим именем в UTF-8",
+                encoding
+            ));
+            return None;
+        };
+        // Текст из архива либо сжимаем обратно, либо сохраняем как обычный файл под новым именем
+        match self.archive.kind_for(path) {
+            Some(kind) => match compress_archive(path, kind, &text) {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    self.error_message = Some(e);
+                    None
+                }
+            },
+            None => Some(text),
+        }
+    }
+
+    fn save_as(&mut self) {
+        if self.large_file.is_open() {
+            return;
+        }
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr!("Текстовые файлы"), &["txt"])
+            .add_filter(tr!("Все файлы"), &["*"])
+            .save_file() 
+        {
+            if is_encrypted(&path) {
+                self.crypto.ask_to_save(&path);
+                return;
+            }
+            self.write_new_file(path, self.text.as_bytes().to_vec());
+        }
+    }
+
+    // Запись документа под новым именем; закладки и недавние файлы переезжают следом
+    fn write_new_file(&mut self, path: PathBuf, bytes: Vec<u8>) -> bool {
+        match write_atomically(&path, &bytes, self.config.backup_on_save) {
+            Ok(_) => {
+                let bookmarks_moved = self.bookmarks.rename_file(self.filename.as_deref(), &path);
+                if rename_goal(&mut self.config.document_goals, self.filename.as_deref(), &path) {
+                    self.save_config();
+                }
+                self.remember_recent_file(&path);
+                self.filename = Some(path);
+                self.file_watch.sync();
+                self.unsaved_changes = false;
+                self.encoding = None;
+                self.error_message = bookmarks_moved.err();
+                self.record_history();
+                true
+            }
+            Err(e) => {
+                self.error_message = Some(tr!("Ошибка сохранения файла: {}", e));
+                false
+            }
+        }
+    }
+
+    // Кодировку выбирают уже после имени файла — в окне selection_file
+    fn save_selection_as(&mut self) {
+        let Some(text) = self.selected_text().filter(|text| !text.is_empty()) else {
+            self.error_message = Some(tr!("Сначала выделите текст").to_string());
+            return;
+        };
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr!("Текстовые файлы"), &["txt"])
+            .add_filter(tr!("Все файлы"), &["*"])
+            .save_file()
+        {
+            self.selection_file.ask_to_save(&path, text);
+        }
+    }
+
+    fn insert_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr!("Текстовые файлы"), &["txt"])
+            .add_filter(tr!("Все файлы"), &["*"])
+            .pick_file()
+        else {
+            return;
+        };
+        match fs::read(&path) {
+            Ok(bytes) => self.selection_file.ask_to_insert(&path, bytes, &self.config.default_encoding),
+            Err(e) => self.error_message = Some(tr!("Ошибка чтения файла: {}", e)),
+        }
+    }
+
+    fn handle_selection_file(&mut self, ctx: &egui::Context) {
+        match self.selection_file.show_window(ctx, &self.config.default_encoding) {
+            SelectionFileResult::Save(path, bytes) => match write_atomically(&path, &bytes, self.config.backup_on_save) {
+                Ok(_) => self.selection_file.close(),
+                Err(e) => self.selection_file.set_error(tr!("Ошибка сохранения файла: {}", e)),
+            },
+            SelectionFileResult::Insert(text) => self.insert_text(&text),
+            SelectionFileResult::None => {}
+        }
+    }
+
+    fn save_encrypted(&mut self) {
+        if let Some(mut path) = rfd::FileDialog::new()
+            .add_filter(tr!("Зашифрованные документы"), &[ENCRYPTED_EXTENSION])
+            .save_file()
+        {
+            if !is_encrypted(&path) {
+                let mut name = path.file_name().unwrap_or_default().to_os_string();
+                name.push(".");
+                name.push(ENCRYPTED_EXTENSION);
+                path.set_file_name(name);
+            }
+            self.crypto.ask_to_save(&path);
+        }
+    }
+
+    fn handle_crypto(&mut self, ctx: &egui::Context) {
+        match self.crypto.show_window(ctx) {
+            CryptoResult::Open(path, password) => {
+                let text = fs::read(&path)
+                    .map_err(|e| tr!("Ошибка чтения файла: {}", e))
+                    .and_then(|bytes| decrypt(&bytes, &password));
+                match text {
+                    Ok(text) => {
+                        self.text = text;
+                        self.text_changed();
+                        self.filename = Some(path.clone());
+                        self.unsaved_changes = false;
+                        self.error_message = None;
+                        self.search_module.clear_matches();
+                        self.crypto.set_current(&path, password);
+                        self.finish_opening(&path);
+                    }
+                    Err(e) => self.crypto.set_error(e),
+                }
+            }
+            CryptoResult::Save(path, password) => match encrypt(&self.text, &password) {
+                Ok(bytes) => {
+                    if self.write_new_file(path.clone(), bytes) {
+                        self.crypto.set_current(&path, password);
+                    }
+                }
+                Err(e) => self.crypto.set_error(e),
+            },
+            CryptoResult::None => {}
+        }
+    }
+
+    // Выбранные параметры остаются в настройках: по ним же перечитывается файл при сравнении
+    fn handle_docx(&mut self, ctx: &egui::Context) {
+        if let DocxResult::Open(path, options) = self.docx.show_window(ctx) {
+            if self.config.docx_import != options {
+                self.config.docx_import = options;
+                self.save_config();
+            }
+            self.large_file.close();
+            self.open_docx_file(&path);
+            self.finish_opening(&path);
+        }
+    }
+
+    fn handle_file_properties(&mut self, ctx: &egui::Context) {
+        if !self.file_properties.show_window {
+            return;
+        }
+        let (lines, words, chars) = (self.count_lines(), self.count_words(), self.count_characters());
+        self.file_properties.show_window(ctx, lines, words, chars);
+    }
+
+    fn handle_file_watch(&mut self, ctx: &egui::Context) {
+        self.file_watch.track_file(ctx, self.filename.as_deref());
+        match self.file_watch.show_window(ctx) {
+            FileWatchResult::Reload => {
+                if let Some(path) = self.filename.clone() {
+                    self.open_path(&path);
+                }
+            }
+            FileWatchResult::ShowDiff => self.compare_with_saved(),
+            FileWatchResult::None => {}
+        }
+    }
+
+    // Текст файла на диске в том виде, в каком его показал бы редактор при открытии
+    fn read_saved_text(&self, path: &Path) -> Result<String, String> {
+        if let Some(password) = self.crypto.password_for(path) {
+            let bytes = fs::read(path).map_err(|e| tr!("Ошибка чтения файла: {}", e))?;
+            return decrypt(&bytes, password);
+        }
+        if let Some(kind) = self.archive.kind_for(path) {
+            return read_archive(path, kind).map(|bytes| self.decode_text(bytes));
+        }
+        let bytes = fs::read(path).map_err(|e| tr!("Ошибка чтения файла: {}", e))?;
+        if path.extension().is_some_and(|e| e == "docx") {
+            self.extract_text_from_docx(&bytes).map_err(|e| tr!("Ошибка чтения DOCX файла: {}", e))
+        } else {
+            Ok(self.decode_text(bytes))
+        }
+    }
+
+    // Что изменилось с последнего сохранения
+    fn compare_with_saved(&mut self) {
+        let Some(path) = self.filename.clone() else {
+            self.error_message = Some(tr!("Файл еще не сохранен").to_string());
+            return;
+        };
+        match self.read_saved_text(&path) {
+            Ok(saved) => {
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let title = tr!("Сравнение с сохраненным: {}", name);
+                self.diff.open(&title, tr!("На диске"), tr!("В редакторе"), &saved, &self.text);
+            }
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    fn handle_compare(&mut self, ctx: &egui::Context) {
+        let split_file = match &self.split.source {
+            SplitSource::File { path, .. } => path.file_name().map(|n| n.to_string_lossy().to_string()),
+            SplitSource::SameBuffer => None,
+        };
+        if let CompareResult::Compare(old, new) = self.compare.show_window(ctx, split_file.as_deref()) {
+            match (self.compare_source(&old), self.compare_source(&new)) {
+                (Ok((old_label, old)), Ok((new_label, new))) => {
+                    self.diff.open(tr!("Сравнение файлов"), &old_label, &new_label, &old, &new);
+                }
+                (Err(e), _) | (_, Err(e)) => self.error_message = Some(e),
+            }
+        }
+    }
+
+    // Подпись и текст одной стороны сравнения
+    fn compare_source(&self, source: &CompareSource) -> Result<(String, String), String> {
+        let file_name = |path: &Path| path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        match source {
+            CompareSource::Document => {
+                let label = self.filename.as_deref().map(file_name).unwrap_or_else(|| tr!("Без названия").to_string());
+                Ok((label, self.text.clone()))
+            }
+            CompareSource::SplitPane => match &self.split.source {
+                SplitSource::File { path, text, .. } => Ok((file_name(path), text.clone())),
+                SplitSource::SameBuffer => Err(tr!("Во второй панели нет файла").to_string()),
+            },
+            CompareSource::File(Some(path)) => Ok((file_name(path), self.read_saved_text(path)?)),
+            CompareSource::File(None) => Err(tr!("Файл для сравнения не выбран").to_string()),
+        }
+    }
+
+    // Копия только что сохраненного текста для «Истории версий»
+    fn record_history(&mut self) {
+        if !self.config.keep_history {
+            return;
+        }
+        // Расшифрованный текст в историю не кладем: копии лежали бы на диске открытыми
+        if let Some(path) = self.filename.as_ref().filter(|path| !is_encrypted(path)) {
+            if let Err(e) = self.history.snapshot(path, &self.text) {
+                self.error_message = Some(e);
+            }
+        }
+    }
+
+    fn handle_history(&mut self, ctx: &egui::Context) {
+        match self.history.show_window(ctx, &self.text, &mut self.config) {
+            Ok(HistoryResult::None) => {}
+            Ok(HistoryResult::ConfigChanged) => self.save_config(),
+            Ok(HistoryResult::Compare { title, old_label, new_label, old, new }) => {
+                self.diff.open(&title, &old_label, &new_label, &old, &new);
+            }
+            Ok(HistoryResult::Restore(text)) => {
+                self.text = text;
+                self.text_changed();
+                self.pending_cursor = Some(self.cursor_char.min(self.count_characters()));
+                self.unsaved_changes = true;
+                self.user_edited = true;
+            }
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    fn new_file(&mut self) {
+        if self.unsaved_changes {
+            self.show_save_dialog = true;
+            return;
+        }
+        
+        self.text.clear();
+        self.text_changed();
+        self.filename = None;
+        self.unsaved_changes = false;
+        self.error_message = None;
+        self.search_module.clear_matches();
+        self.bookmarks.clear_unnamed();
+        self.welcome.show = false;
+        self.large_file.close();
+
+        if let Some(body) = self.pending_template.take() {
+            let variables = TemplateVariables {
+                date: format_date(&self.config.date_format, self.config.language),
+                author: self.config.author.clone(),
+            };
+            let (text, cursor) = template_module::render(&body, &variables);
+            self.text = text;
+            self.text_changed();
+            self.unsaved_changes = true;
+            let cursor = cursor.unwrap_or(0);
+            self.select_char_range(cursor, cursor);
+        }
+    }
+
+    fn new_from_template(&mut self, body: String) {
+        self.pending_template = Some(body);
+        self.new_file();
+    }
+
+    // Файл на сегодня создается при первой записи; курсор — под новым заголовком в конце
+    fn open_journal(&mut self) {
+        let Some(path) = journal::journal_path(&self.config.journal_path, self.config.language) else {
+            self.error_message = Some(tr!("Не задан файл журнала").to_string());
+            return;
+        };
+        if self.filename.as_ref() != Some(&path) {
+            if !path.exists() {
+                let created = path
+                    .parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|_| fs::write(&path, ""));
+                if let Err(e) = created {
+                    self.error_message = Some(tr!("Ошибка создания журнала: {}", e));
+                    return;
+                }
+            }
+            self.open_path(&path);
+            if self.filename.as_ref() != Some(&path) {
+                return;
+            }
+        }
+
+        let locked = self.text.clone();
+        let heading = journal::entry_heading(&self.text, &self.config.journal_heading, self.config.language);
+        let end = self.count_characters();
+        self.replace_char_range(end, end, &heading);
+        self.journal = self
+            .config
+            .journal_append_only
+            .then(|| Journal::new(path, locked, &self.text));
+    }
+
+    fn handle_journal(&mut self) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        if self.filename.as_ref() != Some(&journal.path) {
+            self.journal = None;
+            return;
+        }
+        if let Some(previous) = journal.check(&self.text) {
+            self.text = previous;
+            self.text_changed();
+            self.pending_cursor = Some(self.count_characters());
+            self.error_message = Some(tr!("Прежние записи журнала только для чтения").to_string());
+        }
+    }
+
+    fn handle_templates(&mut self, ctx: &egui::Context) {
+        match self.templates.show_window(ctx) {
+            TemplateResult::Create(body) => self.new_from_template(body),
+            TemplateResult::Error(e) => self.error_message = Some(e),
+            TemplateResult::None => {}
+        }
+    }
+
+    // === Поиск ===
+    fn handle_search(&mut self, ctx: &egui::Context) {
+        self.search_module.poll_search_results();
+
+        let shortcuts_triggered_search = self.search_module.handle_shortcuts(ctx);
+
+        let search_result = self.search_module.show_search_panel(ctx);
+        
+        match search_result {
+            SearchPanelResult::SearchNeeded => {
+                self.search_module.request_search(&self.text, ctx);
+            }
+            SearchPanelResult::NextMatch => {
+                self.search_module.next_match();
+            }
+            SearchPanelResult::PreviousMatch => {
+                self.search_module.previous_match();
+            }
+            SearchPanelResult::Close => {
+                self.search_module.toggle_search();
+            }
+            SearchPanelResult::None => {}
+        }
+
+        if shortcuts_triggered_search && self.search_module.show_search {
+            self.search_module.request_search(&self.text, ctx);
+        }
+    }
+
+    fn handle_find_in_files(&mut self, ctx: &egui::Context) {
+        self.find_in_files.handle_shortcuts(ctx);
+
+        if let FindInFilesResult::OpenFile(path, char_offset) = self.find_in_files.show_window(ctx) {
+            self.request_open(path, Some(char_offset));
+        }
+    }
+
+    fn handle_command_palette(&mut self, ctx: &egui::Context) {
+        self.command_palette.handle_shortcuts(ctx);
+
+        match self.command_palette.show_window(ctx) {
+            CommandPaletteResult::Run(command) => self.run_command(ctx, command),
+            CommandPaletteResult::RunPlugin(index) => self.run_plugin(index),
+            CommandPaletteResult::None => {}
+        }
+    }
+
+    // Вывод команды заменяет выделение или весь документ
+    fn handle_external_command(&mut self, ctx: &egui::Context) {
+        if let ExternalCommandResult::Replace { range, text } = self.external_command.poll(ctx, &self.text) {
+            match range {
+                Some((start, end)) => self.replace_char_range(start, end, &text),
+                None => {
+                    self.text = text;
+                    self.text_changed();
+                    self.pending_cursor = Some(self.cursor_char.min(self.count_characters()));
+                    self.unsaved_changes = true;
+                    self.user_edited = true;
+                }
+            }
+        }
+
+        let result = self.external_command.show_window(ctx, &self.text, self.selection, &mut self.config);
+        if matches!(result, ExternalCommandResult::ConfigChanged) {
+            self.save_config();
+        }
+    }
+
+    // === Преобразования текста ===
+    // Выделение или весь документ; для строковых преобразований выделение расширяется до целых строк
+    fn apply_transform(&mut self, transform: Transform) {
+        let (start, end) = match self.selection {
+            Some(selection) if transform.works_on_lines() => self.whole_lines(selection),
+            Some(selection) => selection,
+            None if transform == Transform::Reflow => match self.paragraph_range() {
+                Some(range) => range,
+                None => return,
+            },
+            None => (0, self.count_characters()),
+        };
+        let source: String = self.text.chars().skip(start).take(end - start).collect();
+        match transform.apply(&source, &self.config) {
+            Ok(result) => self.replace_transformed(start, end, &source, &result),
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    // Диапазон, расширенный до целых строк
+    fn whole_lines(&self, (mut start, mut end): (usize, usize)) -> (usize, usize) {
+        let chars: Vec<char> = self.text.chars().collect();
+        while start > 0 && chars[start - 1] != '\n' {
+            start -= 1;
+        }
+        // Выделение до начала следующей строки ее не захватывает
+        if end == start || chars[end - 1] != '\n' {
+            while end < chars.len() && chars[end] != '\n' {
+                end += 1;
+            }
+        }
+        (start, end)
+    }
+
+    // Выделение остается на преобразованном тексте, без выделения курсор не прыгает в конец
+    fn replace_transformed(&mut self, start: usize, end: usize, source: &str, result: &str) {
+        if result == source {
+            return;
+        }
+        let cursor = self.cursor_char;
+        self.replace_char_range(start, end, result);
+        if self.selection.is_some() {
+            self.select_char_range(start, start + result.chars().count());
+        } else {
+            self.pending_cursor = Some(cursor.min(self.count_characters()));
+        }
+    }
+
+    // === Калькулятор ===
+    // Выражение — выделение или строка до курсора; «12*3=» дополняется ответом
+    fn evaluate_expression(&mut self, replace: bool) {
+        let (start, end) = self.selection.unwrap_or_else(|| {
+            let line_start = self.line_start_char(self.cursor_line());
+            (line_start, self.cursor_char)
+        });
+        let source: String = self.text.chars().skip(start).take(end - start).collect();
+        let expression = source.trim_end();
+        let expression = expression.strip_suffix('=').unwrap_or(expression);
+        if expression.trim().is_empty() {
+            return;
+        }
+        match evaluate(expression) {
+            Ok((value, decimal_comma)) => {
+                let result = format_number(value, decimal_comma);
+                if replace {
+                    self.replace_char_range(start, end, &result);
+                } else if source.trim_end().ends_with('=') {
+                    let spacing = if source.ends_with(char::is_whitespace) { "" } else { " " };
+                    self.replace_char_range(end, end, &format!("{}{}", spacing, result));
+                } else {
+                    self.replace_char_range(end, end, &format!(" = {}", result));
+                }
+            }
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    // Ctrl + Shift + E дописывает « = результат», Ctrl + Shift + R заменяет выражение результатом
+    fn handle_calculator_keys(&mut self, ctx: &egui::Context) {
+        let shortcut = egui::Modifiers::COMMAND | egui::Modifiers::SHIFT;
+        let (append, replace) = ctx.input_mut(|i| (i.consume_key(shortcut, egui::Key::E), i.consume_key(shortcut, egui::Key::R)));
+        if append || replace {
+            self.evaluate_expression(replace);
+        }
+    }
+
+    // === Вставка с очисткой ===
+    fn paste_normalized(&mut self, text: &str) {
+        let text = smart_paste::normalize(text, &self.config);
+        self.insert_text(&text);
+    }
+
+    fn paste_plain(&mut self) {
+        match smart_paste::read_clipboard() {
+            Ok(text) => self.paste_normalized(&text),
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    // Ctrl + Shift + V приходит тем же событием вставки, что и Ctrl + V — забираем его у текстового поля
+    fn handle_smart_paste(&mut self, ctx: &egui::Context) {
+        let pasted = ctx.input_mut(|i| {
+            if !(i.modifiers.command && i.modifiers.shift) {
+                return None;
+            }
+            let index = i.events.iter().position(|e| matches!(e, egui::Event::Paste(_)))?;
+            match i.events.remove(index) {
+                egui::Event::Paste(text) => Some(text),
+                _ => None,
+            }
+        });
+        if let Some(text) = pasted {
+            self.paste_normalized(&text);
+        }
+    }
+
+    // === Строки: дублировать, переместить, удалить ===
+    fn apply_line_edit(&mut self, edit: line_ops::LineEdit) {
+        self.replace_char_range(edit.start, edit.end, &edit.text);
+        self.select_char_range(edit.anchor, edit.cursor);
+    }
+
+    // Выделение хранится упорядоченным; второй конец — тот, где нет курсора
+    fn anchor_and_cursor(&self) -> (usize, usize) {
+        let cursor = self.pending_cursor.unwrap_or(self.cursor_char);
+        match self.selection {
+            Some((start, end)) if self.pending_cursor.is_none() => (if cursor == start { end } else { start }, cursor),
+            _ => (cursor, cursor),
+        }
+    }
+
+    fn duplicate_lines(&mut self) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let (anchor, cursor) = self.anchor_and_cursor();
+        self.apply_line_edit(line_ops::duplicate(&chars, anchor, cursor));
+    }
+
+    fn move_lines(&mut self, up: bool) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let (anchor, cursor) = self.anchor_and_cursor();
+        if let Some(edit) = line_ops::move_lines(&chars, anchor, cursor, up) {
+            self.apply_line_edit(edit);
+        }
+    }
+
+    fn delete_lines(&mut self) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let (anchor, cursor) = self.anchor_and_cursor();
+        self.apply_line_edit(line_ops::delete_lines(&chars, anchor, cursor));
+    }
+
+    // Префикс комментария берем по расширению файла
+    fn toggle_comments(&mut self) {
+        let Some(style) = self.filename.as_deref().and_then(comment::comment_style) else {
+            self.error_message = Some(tr!("Для файлов этого типа комментарии не известны").to_string());
+            return;
+        };
+        let chars: Vec<char> = self.text.chars().collect();
+        let (anchor, cursor) = self.anchor_and_cursor();
+        self.apply_line_edit(comment::toggle_comments(&chars, anchor, cursor, style));
+    }
+
+    // Ctrl + D, Alt + Вверх/Вниз, Ctrl + Shift + K, Ctrl + /
+    fn handle_line_keys(&mut self, ctx: &egui::Context) {
+        let (delete, duplicate, up, down) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::K),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::D),
+                i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowDown),
+            )
+        });
+        if delete {
+            self.delete_lines();
+        }
+        if duplicate {
+            self.duplicate_lines();
+        }
+        if up || down {
+            self.move_lines(up);
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Slash)) {
+            self.toggle_comments();
+        }
+    }
+
+    // === Следующее вхождение и несколько курсоров ===
+    fn select_next_occurrence(&mut self, skip: bool) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let cursor = self.pending_cursor.unwrap_or(self.cursor_char);
+        let selection = if self.pending_cursor.is_some() { None } else { self.selection };
+        let next = if skip {
+            self.multi_cursor.skip(&chars)
+        } else {
+            self.multi_cursor.select_next(&chars, selection, cursor)
+        };
+        if let Some((start, end)) = next {
+            self.select_char_range(start, end);
+        }
+    }
+
+    // До текстового поля и остальных обработчиков набора: при нескольких выделениях
+    // набранный текст, Enter, Backspace и Delete достаются всем выделениям сразу
+    fn handle_multi_cursor(&mut self, ctx: &egui::Context) {
+        let shortcut = egui::Modifiers::COMMAND | egui::Modifiers::SHIFT;
+        let (next, skip) = ctx.input_mut(|i| {
+            (
+                i.consume_key(shortcut, egui::Key::D),
+                i.consume_key(egui::Modifiers::ALT | egui::Modifiers::SHIFT, egui::Key::D),
+            )
+        });
+        if next || skip {
+            self.select_next_occurrence(skip);
+            return;
+        }
+        if !self.multi_cursor.is_active() || self.pending_cursor.is_some() {
+            return;
+        }
+        // Выделение поменяли мышью или стрелками — остальные курсоры больше не нужны
+        let current = self.selection.unwrap_or((self.cursor_char, self.cursor_char));
+        if self.multi_cursor.primary() != Some(current) {
+            self.multi_cursor.clear();
+            return;
+        }
+
+        let (escape, edits) = ctx.input_mut(|i| {
+            let escape = i.consume_key(egui::Modifiers::NONE, egui::Key::Escape);
+            let mut edits = Vec::new();
+            i.events.retain(|event| {
+                let edit = match event {
+                    egui::Event::Text(text) | egui::Event::Paste(text) => MultiEdit::Insert(text.clone()),
+                    egui::Event::Key { key, pressed: true, modifiers, .. } if modifiers.is_none() => match key {
+                        egui::Key::Enter => MultiEdit::Insert("\n".to_string()),
+                        egui::Key::Backspace => MultiEdit::Backspace,
+                        egui::Key::Delete => MultiEdit::Delete,
+                        _ => return true,
+                    },
+                    _ => return true,
+                };
+                edits.push(edit);
+                false
+            });
+            (escape, edits)
+        });
+        if escape {
+            self.multi_cursor.clear();
+            return;
+        }
+        for edit in edits {
+            let chars: Vec<char> = self.text.chars().collect();
+            for (start, end, text) in self.multi_cursor.apply(&chars, &edit) {
+                self.replace_char_range(start, end, &text);
+            }
+        }
+        if let Some((start, end)) = self.multi_cursor.primary() {
+            self.select_char_range(start, end);
+        }
+    }
+
+    // Остальные выделения рисуем сами: текстовое поле знает только основное
+    fn paint_multi_cursor(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        if !self.multi_cursor.is_active() {
+            return;
+        }
+        let offset = output.galley_pos.to_vec2();
+        let visuals = ui.visuals();
+        for (start, end) in self.multi_cursor.secondary() {
+            if start == end {
+                let cursor = output.galley.from_ccursor(egui::text::CCursor::new(start));
+                let rect = output.galley.pos_from_cursor(&cursor).translate(offset);
+                ui.painter().line_segment([rect.center_top(), rect.center_bottom()], visuals.text_cursor);
+            } else {
+                for rect in Self::range_rects(&output.galley, start, end) {
+                    ui.painter().rect_filled(rect.translate(offset), egui::Rounding::ZERO, visuals.selection.bg_fill);
+                }
+            }
+        }
+    }
+
+    // === Markdown ===
+    fn is_markdown_file(&self) -> bool {
+        self.filename.as_deref().is_some_and(is_markdown)
+    }
+
+    fn apply_markdown(&mut self, action: MarkdownAction) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let (anchor, cursor) = self.anchor_and_cursor();
+        if let Some(edit) = action.apply(&chars, anchor, cursor) {
+            self.apply_line_edit(edit);
+        }
+    }
+
+    // После подсказок: Enter при открытом списке подсказок достается им, а не продолжению списка
+    fn handle_markdown_keys(&mut self, ctx: &egui::Context) {
+        if !self.is_markdown_file() || !ctx.memory(|m| m.has_focus(egui::Id::new(TEXT_EDIT_ID))) {
+            return;
+        }
+        if let Some(action) = markdown_module::handle_keys(ctx) {
+            self.apply_markdown(action);
+            return;
+        }
+        let enter = ctx.input(|i| i.key_pressed(egui::Key::Enter) && i.modifiers.is_none());
+        if !enter || self.selection.is_some() {
+            return;
+        }
+        let chars: Vec<char> = self.text.chars().collect();
+        let cursor = self.pending_cursor.unwrap_or(self.cursor_char);
+        if let Some(edit) = markdown_module::continue_list(&chars, cursor) {
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+            self.apply_line_edit(edit);
+        }
+    }
+
+    // === Отступы ===
+    // Tab с выделением нескольких строк сдвигает их вправо, Shift + Tab — влево.
+    // Вызывается после сниппетов и подсказок: Tab сначала достается им
+    fn handle_indent_keys(&mut self, ctx: &egui::Context) {
+        if !ctx.memory(|m| m.has_focus(egui::Id::new(TEXT_EDIT_ID))) {
+            return;
+        }
+        let (dedent, tab) = ctx.input_mut(|i| {
+            let dedent = i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab);
+            (dedent, !dedent && i.consume_key(egui::Modifiers::NONE, egui::Key::Tab))
+        });
+        if !dedent && !tab {
+            return;
+        }
+        let chars: Vec<char> = self.text.chars().collect();
+        let (anchor, cursor) = self.anchor_and_cursor();
+        let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+        let multiline = chars[start..end].contains(&'\n');
+        let edit = if dedent {
+            indent::dedent_lines(&chars, anchor, cursor, &self.config)
+        } else if multiline {
+            indent::indent_lines(&chars, anchor, cursor, &self.config)
+        } else {
+            let unit = indent::tab_at(&chars, start, &self.config);
+            let position = start + unit.chars().count();
+            Some(line_ops::LineEdit { start, end, text: unit, anchor: position, cursor: position })
+        };
+        if let Some(edit) = edit {
+            self.apply_line_edit(edit);
+        }
+    }
+
+    fn convert_indentation(&mut self, to_spaces: bool) {
+        let converted = indent::convert_indentation(&self.text, to_spaces, self.config.tab_width);
+        if converted == self.text {
+            return;
+        }
+        let line = self.cursor_line();
+        self.replace_char_range(0, self.count_characters(), &converted);
+        self.pending_cursor = Some(self.line_start_char(line));
+    }
+
+    // Ctrl + M — к парной скобке, в том числе на другой строке
+    fn jump_to_bracket(&mut self) {
+        let cursor = self.pending_cursor.unwrap_or(self.cursor_char);
+        if let Some(target) = brackets::jump_target(&self.text, cursor) {
+            self.pending_cursor = Some(target);
+        }
+    }
+
+    fn handle_bracket_keys(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::M)) {
+            self.jump_to_bracket();
+        }
+    }
+
+    // === Дата, время и символы ===
+    // Вместо выделения или в позицию курсора
+    fn insert_text(&mut self, text: &str) {
+        let (start, end) = self.selection.unwrap_or((self.cursor_char, self.cursor_char));
+        self.replace_char_range(start, end, text);
+    }
+
+    // F5 вставляет дату в формате по умолчанию, как в Блокноте
+    fn handle_date(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F5)) {
+            self.insert_text(&format_date(&self.config.date_format, self.config.language));
+        }
+        if let DateResult::ConfigChanged = self.date.show_window(ctx, &mut self.config) {
+            self.save_config();
+        }
+    }
+
+    fn handle_symbols(&mut self, ctx: &egui::Context) {
+        if let SymbolResult::Insert(c) = self.symbols.show_window(ctx, &mut self.config) {
+            self.insert_text(&c.to_string());
+            self.save_config();
+        }
+    }
+
+    fn handle_placeholder(&mut self, ctx: &egui::Context) {
+        if let PlaceholderResult::Insert(text) = self.placeholder.show_window(ctx) {
+            self.insert_text(&text);
+        }
+    }
+
+    // === Нумерация строк ===
+    fn handle_sequence(&mut self, ctx: &egui::Context) {
+        let result = self.sequence.show_window(ctx, self.selection.is_some(), &mut self.config);
+        if matches!(result, SequenceResult::None) {
+            return;
+        }
+        self.save_config();
+        let (start, end) = match self.selection {
+            Some(selection) => self.whole_lines(selection),
+            None => (0, self.count_characters()),
+        };
+        let source: String = self.text.chars().skip(start).take(end - start).collect();
+        let transformed = match result {
+            SequenceResult::Number => number_lines(&source, &self.config),
+            SequenceResult::Strip => strip_line_numbers(&source),
+            _ => return,
+        };
+        self.replace_transformed(start, end, &source, &transformed);
+    }
+
+    // Строки вокруг курсора до ближайших пустых; None, если курсор на пустой строке
+    fn paragraph_range(&self) -> Option<(usize, usize)> {
+        let lines: Vec<&str> = self.text.split('\n').collect();
+        let current = self.cursor_line().min(lines.len() - 1);
+        let is_blank = |line: usize| lines[line].trim().is_empty();
+        if is_blank(current) {
+            return None;
+        }
+        let mut first = current;
+        while first > 0 && !is_blank(first - 1) {
+            first -= 1;
+        }
+        let mut last = current;
+        while last + 1 < lines.len() && !is_blank(last + 1) {
+            last += 1;
+        }
+        let start = self.line_start_char(first);
+        let end = self.line_start_char(last) + lines[last].chars().count();
+        Some((start, end))
+    }
+
+    // Alt + Q — переформатировать абзац под курсором или выделенные строки
+    fn handle_reflow_keys(&mut self, ctx: &egui::Context) {
+        let reflow = ctx.input_mut(|i| {
+            let pressed = i.consume_key(egui::Modifiers::ALT, egui::Key::Q);
+            // Иначе на некоторых раскладках вместе с сочетанием в текст попадет буква
+            if pressed {
+                i.events.retain(|e| !matches!(e, egui::Event::Text(_)));
+            }
+            pressed
+        });
+        if reflow {
+            self.apply_transform(Transform::Reflow);
+        }
+    }
+
+    // === Форматирование JSON/XML ===
+    fn handle_format(&mut self, ctx: &egui::Context) {
+        match self.format.show_window(ctx, &self.text, self.selection, &mut self.config) {
+            FormatResult::None => {}
+            FormatResult::ConfigChanged => self.save_config(),
+            FormatResult::Replace { range, text } => match range {
+                Some((start, end)) => self.replace_char_range(start, end, &text),
+                None => {
+                    self.text = text;
+                    self.text_changed();
+                    self.pending_cursor = Some(self.cursor_char.min(self.count_characters()));
+                    self.unsaved_changes = true;
+                    self.user_edited = true;
+                }
+            },
+            FormatResult::JumpTo(offset) => self.pending_cursor = Some(offset.min(self.count_characters())),
+        }
+    }
+
+    fn handle_regex_replace(&mut self, ctx: &egui::Context) {
+        let result = self.regex_replace.show_window(ctx, &self.text, self.selection);
+        if let RegexReplaceResult::Replace { range, text } = result {
+            match range {
+                Some((start, end)) => self.replace_char_range(start, end, &text),
+                None => {
+                    self.text = text;
+                    self.text_changed();
+                    self.pending_cursor = Some(self.cursor_char.min(self.count_characters()));
+                    self.unsaved_changes = true;
+                    self.user_edited = true;
+                }
+            }
+        }
+    }
+
+    // === Git ===
+    fn handle_git(&mut self, ctx: &egui::Context) {
+        self.git.track_file(self.filename.as_deref());
+        self.git.update(&self.text);
+
+        if let GitResult::Commit = self.git.show_commit_window(ctx, self.unsaved_changes) {
+            if self.unsaved_changes {
+                self.save_file();
+            }
+            if !self.unsaved_changes {
+                if let Err(e) = self.git.commit() {
+                    self.error_message = Some(e);
+                }
+            }
+        }
+        self.diff.show_window(ctx);
+    }
+
+    fn diff_with_head(&mut self) {
+        let title = tr!("Сравнение с HEAD ({})", self.git.branch());
+        self.diff.open(&title, "HEAD", tr!("В редакторе"), self.git.head_text(), &self.text);
+    }
+
+    // === Плагины ===
+    fn reload_plugins(&mut self) {
+        let errors = self.plugins.load();
+        if !errors.is_empty() {
+            self.error_message = Some(errors.join("; "));
+        }
+        self.command_palette.set_plugin_commands(self.plugins.command_titles());
+    }
+
+    // Скрипт получает текст и выделение; изменения возвращаются в редактор как правка пользователя
+    fn run_plugin(&mut self, index: usize) {
+        let (anchor, cursor) = self.selection.unwrap_or((self.cursor_char, self.cursor_char));
+        match self.plugins.run(index, &self.text, anchor, cursor) {
+            Ok(outcome) => {
+                let text_changed = outcome.text.is_some();
+                if let Some(text) = outcome.text {
+                    self.text = text;
+                    self.text_changed();
+                    self.unsaved_changes = true;
+                    self.user_edited = true;
+                }
+                if let Some((anchor, cursor)) = outcome.selection {
+                    self.select_char_range(anchor, cursor);
+                } else if text_changed {
+                    self.pending_cursor = Some(self.cursor_char.min(self.count_characters()));
+                }
+            }
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    fn run_command(&mut self, ctx: &egui::Context, command: Command) {
+        match command {
+            Command::NewFile => self.new_file(),
+            Command::NewFromTemplate => self.templates.toggle_window(),
+            Command::OpenJournal => self.open_journal(),
+            Command::OpenFile => self.open_file(),
+            Command::OpenWordDocument => self.open_word_document(),
+            Command::OpenFolder => self.open_folder(),
+            Command::QuickOpen => {
+                if let Some(workspace) = &self.workspace {
+                    self.quick_open.open(workspace);
+                }
+            }
+            Command::Save => self.save_file(),
+            Command::SaveAs => self.save_as(),
+            Command::SaveSelectionAs => self.save_selection_as(),
+            Command::CompareWithSaved => self.compare_with_saved(),
+            Command::CompareFiles => self.compare.toggle_window(),
+            Command::FormatDocument => self.format.toggle_window(),
+            Command::ReflowParagraph => self.apply_transform(Transform::Reflow),
+            Command::Evaluate => self.evaluate_expression(false),
+            Command::InsertDate => self.insert_text(&format_date(&self.config.date_format, self.config.language)),
+            Command::Symbols => self.symbols.toggle_window(),
+            Command::InsertPlaceholder => self.placeholder.toggle_window(),
+            Command::PastePlain => self.paste_plain(),
+            Command::InsertFile => self.insert_file(),
+            Command::JumpToBracket => self.jump_to_bracket(),
+            Command::SelectNextOccurrence => self.select_next_occurrence(false),
+            Command::SkipOccurrence => self.select_next_occurrence(true),
+            Command::DuplicateLines => self.duplicate_lines(),
+            Command::MoveLinesUp => self.move_lines(true),
+            Command::MoveLinesDown => self.move_lines(false),
+            Command::DeleteLines => self.delete_lines(),
+            Command::ToggleComments => self.toggle_comments(),
+            Command::IndentToSpaces => self.convert_indentation(true),
+            Command::IndentToTabs => self.convert_indentation(false),
+            Command::EvaluateReplace => self.evaluate_expression(true),
+            Command::Settings => self.settings.toggle_window(),
+            Command::Quit => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            Command::Find => self.search_module.toggle_search(),
+            Command::FindInFiles => self.find_in_files.toggle_window(),
+            Command::RegexReplace => self.regex_replace.toggle_window(),
+            Command::ToggleBookmark => {
+                let line = self.cursor_line();
+                if let Err(e) = self.bookmarks.toggle(self.filename.as_deref(), line) {
+                    self.error_message = Some(e);
+                }
+            }
+            Command::ToggleFileTree => {
+                if self.workspace.is_some() {
+                    self.file_tree.show_panel = !self.file_tree.show_panel;
+                }
+            }
+            Command::ToggleBookmarksPanel => self.bookmarks.show_panel = !self.bookmarks.show_panel,
+            Command::ToggleOutline => self.outline.show_panel = !self.outline.show_panel,
+            Command::ToggleDuplicates => self.duplicates.show_panel = !self.duplicates.show_panel,
+            Command::ToggleMinimap => self.minimap.show_panel = !self.minimap.show_panel,
+            Command::ToggleWelcome => self.welcome.show = !self.welcome.show,
+            Command::TogglePlaylist => self.playlist.show_panel = !self.playlist.show_panel,
+            Command::TogglePlaybackBar => self.playlist.show_playback_bar = !self.playlist.show_playback_bar,
+            Command::TogglePomodoro => self.pomodoro.toggle_panel(),
+            Command::ToggleParagraphStats => {
+                self.config.paragraph_stats = !self.config.paragraph_stats;
+                self.save_config();
+            }
+            Command::SplitVertical => self.split.split(SplitOrientation::Vertical),
+            Command::SplitHorizontal => self.split.split(SplitOrientation::Horizontal),
+            Command::OpenInSplit => self.open_in_split(),
+            Command::CloseSplit => self.split.close(),
+            Command::ZoomIn => self.zoom = (self.zoom + ZOOM_STEP).min(MAX_ZOOM),
+            Command::ZoomOut => self.zoom = (self.zoom - ZOOM_STEP).max(MIN_ZOOM),
+            Command::ZoomReset => self.zoom = 1.0,
+            Command::ThemeEditor => self.themes.open_editor(&self.config),
+            Command::Statistics => self.statistics.toggle_window(),
+            Command::WordFrequency => self.word_frequency.open(self.selection.is_some()),
+            Command::WritingLog => self.writing.show_window = true,
+            Command::DocumentGoal => self.document_goal.open(self.filename.as_deref(), &self.config),
+            Command::AutoReplace => self.auto_replace.toggle_window(),
+            Command::Snippets => self.snippets.toggle_window(),
+            Command::ToggleSpellCheck => {
+                self.config.spell_check = !self.config.spell_check;
+                self.save_config();
+            }
+            Command::PlayPause => self.toggle_music(),
+            Command::PreviousTrack => self.previous_track(),
+            Command::NextTrack => self.next_track(),
+            Command::StopMusic => self.stop_music(),
+            Command::ToggleMute => {
+                self.config.music_muted = !self.config.music_muted;
+                self.apply_volume();
+                self.save_config();
+            }
+            Command::ChooseMusicFile => self.choose_music_file(),
+            Command::ChooseMusicFolder => self.choose_music_folder(),
+            Command::BuiltinMusic => self.use_builtin_music(),
+        }
+    }
+
+    fn handle_quick_open(&mut self, ctx: &egui::Context) {
+        self.quick_open.handle_shortcuts(ctx, self.workspace.as_deref());
+
+        if let QuickOpenResult::OpenFile(path) = self.quick_open.show_window(ctx) {
+            self.request_open(path, None);
+        }
+    }
+
+    fn handle_file_tree(&mut self, ctx: &egui::Context) {
+        let Some(workspace) = self.workspace.clone() else {
+            return;
+        };
+
+        match self.file_tree.show_panel(ctx, &workspace, self.filename.as_deref()) {
+            FileTreeResult::OpenFile(path) => self.request_open(path, None),
+            FileTreeResult::Renamed(from, to) => {
+                // Переименовали открытый файл или папку, в которой он лежит
+                if let Some(filename) = &self.filename {
+                    if let Ok(rest) = filename.strip_prefix(&from) {
+                        self.filename = Some(to.join(rest));
+                    }
+                }
+            }
+            FileTreeResult::Deleted(path) => {
+                // Текст удаленного файла остается в редакторе как несохраненный
+                if self.filename.as_ref().is_some_and(|f| f.starts_with(&path)) {
+                    self.filename = None;
+                    self.unsaved_changes = true;
+                }
+            }
+            FileTreeResult::None => {}
+        }
+
+        if let Some(error) = self.file_tree.error_message.take() {
+            self.error_message = Some(error);
+        }
+    }
+
+    fn handle_minimap(&mut self, ctx: &egui::Context) {
+        let result = self.minimap.show_panel(
+            ctx,
+            &self.text,
+            self.search_module.get_matches(),
+            self.viewport,
+            &self.palette,
+        );
+
+        if let MinimapResult::ScrollTo(fraction) = result {
+            let viewport = self.viewport;
+            let offset = fraction * viewport.content_height - viewport.visible_height / 2.0;
+            self.pending_scroll = Some(offset.max(0.0));
+        }
+    }
+
+    // === Панели текста ===
+    fn show_main_editor(&mut self, ui: &mut egui::Ui) {
+        let mut scroll_area = egui::ScrollArea::vertical()
+            .id_source("main_text_scroll")
+            .auto_shrink([false; 2])
+            .stick_to_bottom(false);
+        if let Some(offset) = self.pending_scroll.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+
+        let scroll_output = scroll_area.show(ui, |ui| {
+            let text_edit_id = egui::Id::new(TEXT_EDIT_ID);
+            let content_top = ui.max_rect().top();
+
+            // Переставляем курсор, если его попросили (например, переход к результату поиска)
+            if let Some(char_index) = self.pending_cursor {
+                let mut state = egui::TextEdit::load_state(ui.ctx(), text_edit_id).unwrap_or_default();
+                let ccursor = egui::text::CCursor::new(char_index);
+                let range = match self.pending_anchor.take() {
+                    Some(anchor) => egui::text::CCursorRange::two(egui::text::CCursor::new(anchor), ccursor),
+                    None => egui::text::CCursorRange::one(ccursor),
+                };
+                state.cursor.set_char_range(Some(range));
+                state.store(ui.ctx(), text_edit_id);
+            }
+
+            let editor_font = self.editor_font();
+            let text_edit = egui::TextEdit::multiline(&mut self.text)
+                .id(text_edit_id)
+                .code_editor()
+                .desired_rows(30)
+                .desired_width(f32::INFINITY)
+                .font(editor_font)
+                .text_color(self.palette.text_color())
+                .margin(egui::Margin { left: GUTTER_WIDTH, right: 4.0, top: 2.0, bottom: 2.0 })
+                .frame(true);
+
+            let output = text_edit.show(ui);
+            let response = &output.response;
+
+            if self.config.highlight_current_line {
+                self.paint_current_line(ui, &output);
+            }
+            if self.config.show_ruler {
+                self.paint_ruler(ui, &output);
+            }
+            self.paint_bracket_match(ui, &output);
+            self.paint_multi_cursor(ui, &output);
+
+            let previous_cursor_line = self.cursor_line();
+            if let Some(cursor_range) = output.cursor_range {
+                self.cursor_char = cursor_range.primary.ccursor.index;
+                let [start, end] = cursor_range.sorted_cursors();
+                self.selection = (start.ccursor.index != end.ccursor.index)
+                    .then_some((start.ccursor.index, end.ccursor.index));
+            }
+
+            // Вставили или удалили строки — закладки ниже правки съезжают вместе с текстом
+            let line_count = self.text.bytes().filter(|&b| b == b'\n').count() + 1;
+            if response.changed() && line_count != self.last_line_count {
+                let delta = line_count as isize - self.last_line_count as isize;
+                let edit_line = previous_cursor_line.min(self.cursor_line());
+                self.bookmarks.shift_lines(self.filename.as_deref(), edit_line, delta);
+            }
+            self.last_line_count = line_count;
+
+            self.paint_git_changes(ui, &output);
+            self.paint_duplicates(ui, &output);
+            if self.config.paragraph_stats {
+                self.paint_paragraph_stats(ui, &output);
+            }
+            self.paint_bookmarks(ui, &output);
+            if self.config.spell_check {
+                self.paint_spelling(ui, &output);
+                self.spelling_context_menu(&output);
+            }
+            self.apply_auto_replace(ui, &output);
+            self.update_autocomplete(ui, &output);
+
+            if let Some(char_index) = self.pending_cursor.take() {
+                let cursor = output.galley.from_ccursor(egui::text::CCursor::new(char_index));
+                let cursor_rect = output
+                    .galley
+                    .pos_from_cursor(&cursor)
+                    .translate(output.galley_pos.to_vec2());
+                ui.scroll_to_rect(cursor_rect, Some(egui::Align::Center));
+                response.request_focus();
+            }
+
+            // Добавляем визуальное выделение найденных совпадений
+            if !self.search_module.matches.is_empty() {
+                self.highlight_matches(ui, &output);
+            }
+            self.paint_regex_matches(ui, &output);
+
+            if response.changed() {
+                self.text_changed();
+                self.unsaved_changes = true;
+                self.user_edited = true;
+                if self.search_module.show_search && !self.search_module.search_text.is_empty() {
+                    self.search_module.request_search(&self.text, ui.ctx());
+                }
+            }
+
+            // Возвращаем фокус в текст, только если он не нужен полю ввода в другом окне
+            let other_widget_focused = ui.memory(|m| m.focused().is_some());
+            if !response.has_focus() && !other_widget_focused && !self.search_module.show_search {
+                response.request_focus();
+            }
+
+            self.scrollbar_markers(&output, content_top)
+        });
+
+        self.paint_scrollbar_markers(ui, &scroll_output);
+
+        self.viewport = Viewport {
+            offset: scroll_output.state.offset.y,
+            visible_height: scroll_output.inner_rect.height(),
+            content_height: scroll_output.content_size.y,
+        };
+    }
+
+    // Метки на полосе прокрутки: (высота в содержимом прокрутки, цвет)
+    fn scrollbar_markers(&self, output: &egui::text_edit::TextEditOutput, content_top: f32) -> Vec<(f32, egui::Color32)> {
+        let row_y = |char_index: usize| {
+            let cursor = output.galley.from_ccursor(egui::text::CCursor::new(char_index));
+            output.galley.pos_from_cursor(&cursor).center().y + output.galley_pos.y - content_top
+        };
+
+        let current_match = self.search_module.get_current_match_index();
+        let mut markers: Vec<(f32, egui::Color32)> = self
+            .search_module
+            .get_matches()
+            .iter()
+            .enumerate()
+            .map(|(index, &(start, _))| (row_y(start), self.palette.match_color(index == current_match)))
+            .collect();
+
+        for line in self.bookmarks.lines(self.filename.as_deref()) {
+            markers.push((row_y(self.line_start_char(line)), BOOKMARK_COLOR));
+        }
+        markers
+    }
+
+    fn paint_scrollbar_markers(&mut self, ui: &egui::Ui, scroll_output: &egui::scroll_area::ScrollAreaOutput<Vec<(f32, egui::Color32)>>) {
+        let content_height = scroll_output.content_size.y;
+        let track = scroll_output.inner_rect;
+        if scroll_output.inner.is_empty() || content_height <= track.height() {
+            return;
+        }
+
+        for (index, &(y, color)) in scroll_output.inner.iter().enumerate() {
+            let tick_y = track.top() + y / content_height * track.height();
+            let tick = egui::Rect::from_min_max(
+                egui::pos2(track.right() - SCROLLBAR_MARKER_WIDTH, tick_y - 1.5),
+                egui::pos2(track.right(), tick_y + 1.5),
+            );
+            ui.painter().rect_filled(tick, egui::Rounding::ZERO, color);
+
+            // Щелчок по метке прокручивает к ней текст
+            let response = ui.interact(tick.expand(1.0), ui.id().with(("scrollbar_marker", index)), egui::Sense::click());
+            if response.clicked() {
+                self.pending_scroll = Some((y - track.height() / 2.0).max(0.0));
+            }
+        }
+    }
+
+    fn show_split_pane(&mut self, ui: &mut egui::Ui) {
+        let editor_font = self.editor_font();
+        if self.split.show_pane(ui, &mut self.text, editor_font, self.palette.text_color()) {
+            self.text_changed();
+            self.unsaved_changes = true;
+            self.user_edited = true;
+            if self.search_module.show_search && !self.search_module.search_text.is_empty() {
+                self.search_module.request_search(&self.text, ui.ctx());
+            }
+        }
+        if let Some(error) = self.split.error_message.take() {
+            self.error_message = Some(error);
+        }
+    }
+
+    fn open_in_split(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr!("Текстовые файлы"), &["txt", "docx"])
+            .add_filter(tr!("Все файлы"), &["*"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        // Сохраняет вторая панель простым текстом в UTF-8, поэтому остальное открываем только для чтения
+        let text = match path.extension().and_then(|s| s.to_str()) {
+            Some("docx") => fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| self.extract_text_from_docx(&bytes).map_err(|e| e.to_string()))
+                .map(|text| (text, true)),
+            _ => fs::read(&path)
+                .map(|bytes| {
+                    let read_only = std::str::from_utf8(&bytes).is_err();
+                    (self.decode_text(bytes), read_only)
+                })
+                .map_err(|e| e.to_string()),
+        };
+
+        match text {
+            Ok((text, read_only)) => self.split.open_file(path, text, read_only),
+            Err(e) => self.error_message = Some(tr!("Ошибка открытия файла: {}", e)),
+        }
+    }
+
+    // === Закладки ===
+    fn handle_bookmarks(&mut self, ctx: &egui::Context) {
+        self.bookmarks.load();
+
+        let file = self.filename.clone();
+        let current_line = self.cursor_line();
+
+        // Shift+F2 проверяем раньше F2
+        let (toggle, previous, next) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::B),
+                i.consume_key(egui::Modifiers::SHIFT, egui::Key::F2),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::F2),
+            )
+        });
+
+        if toggle {
+            if let Err(e) = self.bookmarks.toggle(file.as_deref(), current_line) {
+                self.error_message = Some(e);
+            }
+        }
+        if previous {
+            if let Some(line) = self.bookmarks.previous(file.as_deref(), current_line) {
+                self.go_to_line(line);
+            }
+        }
+        if next {
+            if let Some(line) = self.bookmarks.next(file.as_deref(), current_line) {
+                self.go_to_line(line);
+            }
+        }
+
+        if let BookmarksResult::GoToLine(line) = self.bookmarks.show_panel(ctx, file.as_deref(), &self.text) {
+            self.go_to_line(line);
+        }
+    }
+
+    // === Структура документа ===
+    fn handle_outline(&mut self, ctx: &egui::Context) {
+        let current_line = self.cursor_line();
+        if let OutlineResult::GoToLine(line) = self.outline.show_panel(ctx, &self.text, current_line) {
+            self.go_to_line(line);
+        }
+    }
+
+    // === Строка состояния ===
+    // Элементы без содержимого (нет ветки, цели, найденного) не занимают места и не отделяются
+    fn status_item_shown(&self, item: StatusItem) -> bool {
+        match item {
+            StatusItem::GitBranch => self.git.is_repo(),
+            StatusItem::DailyGoal => self.config.daily_word_goal > 0,
+            StatusItem::DocumentGoal => goal_for(&self.config.document_goals, self.filename.as_deref()).is_some(),
+            StatusItem::SearchMatches => !self.search_module.matches.is_empty(),
+            StatusItem::Pomodoro => self.pomodoro.status_label().is_some(),
+            _ => true,
+        }
+    }
+
+    fn show_status_item(&mut self, ui: &mut egui::Ui, item: StatusItem) {
+        match item {
+            StatusItem::FileName => {
+                let filename = self
+                    .filename
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(tr!("Без названия"));
+
+                let file_status = if self.unsaved_changes {
+                    tr!("{} • (изменен)", filename)
+                } else {
+                    filename.to_string()
+                };
+                ui.label(file_status);
+            }
+            StatusItem::GitBranch => {
+                ui.label(format!("⎇ {}", self.git.branch()));
+            }
+            StatusItem::Characters => {
+                let characters = statistics_module::count_characters(&self.text, self.config.status_characters);
+                ui.label(tr!("Символов: {}", characters));
+            }
+            StatusItem::Words => {
+                ui.label(tr!("Слов: {}", self.status_words()));
+            }
+            StatusItem::Lines => {
+                ui.label(tr!("Строк: {}", self.count_lines()));
+            }
+            StatusItem::Position => {
+                let line = self.cursor_line();
+                let column = self.cursor_char - self.line_start_char(line);
+                ui.label(tr!("Стр {}, стлб {}", line + 1, column + 1));
+            }
+            StatusItem::Encoding => {
+                let encoding = self
+                    .encoding
+                    .as_ref()
+                    .filter(|(path, _)| self.filename.as_ref() == Some(path))
+                    .map_or("UTF-8", |(_, encoding)| encoding.as_str());
+                ui.label(encoding)
+                    .on_hover_text(tr!("Кодировка файла; «Сохранить как» записывает в UTF-8"));
+            }
+            StatusItem::DailyGoal => {
+                let written = self.writing.today_words();
+                let goal = self.config.daily_word_goal;
+                let progress = egui::ProgressBar::new((written as f32 / goal as f32).min(1.0))
+                    .desired_width(120.0)
+                    .text(format!("{} / {}", written, goal));
+                if ui
+                    .add(progress)
+                    .interact(egui::Sense::click())
+                    .on_hover_text(tr!("Слов за сегодня; щелчок — журнал письма"))
+                    .clicked()
+                {
+                    self.writing.show_window = !self.writing.show_window;
+                }
+            }
+            StatusItem::DocumentGoal => {
+                let goal = goal_for(&self.config.document_goals, self.filename.as_deref()).unwrap_or(1).max(1);
+                let words = self.status_words();
+                let percent = words * 100 / goal;
+                let mut progress = egui::ProgressBar::new((words as f32 / goal as f32).min(1.0))
+                    .desired_width(140.0)
+                    .text(format!("{} / {} ({}%)", words, goal, percent));
+                if words >= goal {
+                    progress = progress.fill(GOAL_REACHED_COLOR);
+                }
+                if ui
+                    .add(progress)
+                    .interact(egui::Sense::click())
+                    .on_hover_text(tr!("Слов в документе из цели; щелчок — изменить цель"))
+                    .clicked()
+                {
+                    self.document_goal.open(self.filename.as_deref(), &self.config);
+                }
+            }
+            StatusItem::SearchMatches => {
+                ui.label(tr!("Найдено: {}", self.search_module.matches.len()));
+            }
+            StatusItem::Zoom => {
+                if ui
+                    .selectable_label(false, tr!("Масштаб: {}%", (self.zoom * 100.0).round()))
+                    .on_hover_text(tr!("Ctrl + колесо мыши, Ctrl + Плюс/Минус; щелчок — 100%"))
+                    .clicked()
+                {
+                    self.zoom = 1.0;
+                }
+            }
+            StatusItem::Pomodoro => {
+                if let Some(label) = self.pomodoro.status_label() {
+                    if ui.selectable_label(self.pomodoro.show_panel, label).clicked() {
+                        self.pomodoro.toggle_panel();
+                    }
+                }
+            }
+            StatusItem::Music => {
+                let music_icon = if self.music_playing { "🎵" } else { "🔇" };
+                let paused = if !self.music_playing && self.audio_sink.is_some() { tr!(" (пауза)") } else { "" };
+                ui.label(format!("{} {}{}", music_icon, self.current_song, paused));
+                if self.audio_sink.is_some() {
+                    let elapsed = format_duration(self.playback_clock.position());
+                    let time = match self.track_info.duration {
+                        Some(total) => format!("{} / {}", elapsed, format_duration(total)),
+                        None => elapsed,
+                    };
+                    ui.monospace(time);
+                }
+            }
+        }
+    }
+
+    // === Повторяющиеся строки ===
+    fn handle_duplicates(&mut self, ctx: &egui::Context) {
+        match self.duplicates.show_panel(ctx, &self.text) {
+            DuplicatesResult::GoToLine(line) => self.go_to_line(line),
+            DuplicatesResult::RemoveDuplicates(ignore_whitespace) => {
+                let text = duplicates_module::remove_duplicates(&self.text, ignore_whitespace);
+                let cursor = self.cursor_char.min(text.chars().count());
+                let end = self.count_characters();
+                self.replace_char_range(0, end, &text);
+                self.pending_cursor = Some(cursor);
+            }
+            DuplicatesResult::None => {}
+        }
+    }
+
+    // Подложка под повторяющимися строками, пока открыта панель повторов
+    fn paint_duplicates(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let lines = self.duplicates.highlighted_lines();
+        if lines.is_empty() {
+            return;
+        }
+
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(self.text.chars().enumerate().filter(|&(_, c)| c == '\n').map(|(i, _)| i + 1))
+            .collect();
+        let bounds = output.response.rect;
+        let offset = output.galley_pos.to_vec2();
+        for (line, selected) in lines {
+            let Some(&start) = line_starts.get(line) else {
+                continue;
+            };
+            let end = line_starts.get(line + 1).map(|&next| next - 1).unwrap_or_else(|| self.count_characters());
+            let color = self.palette.match_color(selected).gamma_multiply(0.5);
+            for rect in Self::range_rects(&output.galley, start, end) {
+                let rect = rect.translate(offset);
+                let full_width = egui::Rect::from_x_y_ranges(bounds.x_range(), rect.y_range());
+                ui.painter().rect_filled(full_width, egui::Rounding::ZERO, color);
+            }
+        }
+    }
+
+    // Бледная подпись после последнего слова абзаца; рисуем только видимые абзацы
+    fn paint_paragraph_stats(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let clip = ui.clip_rect();
+        let offset = output.galley_pos.to_vec2();
+        let editor_font = self.editor_font();
+        let gap = ui.fonts(|fonts| fonts.glyph_width(&editor_font, ' ')) * 2.0;
+        let font = egui::FontId::proportional(editor_font.size * 0.8);
+        let color = ui.visuals().weak_text_color();
+        for paragraph in statistics_module::paragraph_statistics(&self.text, self.config.status_words) {
+            let cursor = output.galley.from_ccursor(egui::text::CCursor::new(paragraph.end));
+            let rect = output.galley.pos_from_cursor(&cursor).translate(offset);
+            if rect.bottom() < clip.top() {
+                continue;
+            }
+            if rect.top() > clip.bottom() {
+                break;
+            }
+            ui.painter().text(
+                egui::pos2(rect.right() + gap, rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                tr!("{} сл. · {} предл.", paragraph.words, paragraph.sentences),
+                font.clone(),
+                color,
+            );
+        }
+    }
+
+    // Волнистое подчеркивание под словами с ошибками; рисуем только видимую часть текста
+    fn paint_spelling(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let misspelled = self.spellcheck.misspelled();
+        if misspelled.is_empty() {
+            return;
+        }
+
+        let clip = ui.clip_rect();
+        let offset = output.galley_pos.to_vec2();
+        let first_visible = output.galley.cursor_from_pos(clip.left_top() - output.galley_pos).ccursor.index;
+        let last_visible = output.galley.cursor_from_pos(clip.right_bottom() - output.galley_pos).ccursor.index;
+        let painter = ui.painter();
+        let stroke = egui::Stroke::new(1.0, SPELLING_COLOR);
+
+        for &(start, end) in misspelled {
+            if end < first_visible || start > last_visible {
+                continue;
+            }
+            for rect in Self::range_rects(&output.galley, start, end) {
+                let rect = rect.translate(offset);
+                let y = rect.bottom() - 1.0;
+                let mut points = Vec::new();
+                let mut x = rect.left();
+                let mut up = true;
+                while x <= rect.right() {
+                    points.push(egui::pos2(x, if up { y - 1.5 } else { y + 0.5 }));
+                    x += 2.0;
+                    up = !up;
+                }
+                painter.add(egui::Shape::line(points, stroke));
+            }
+        }
+    }
+
+    // Правый щелчок по слову с ошибкой: варианты исправления и «Добавить в словарь»
+    fn spelling_context_menu(&mut self, output: &egui::text_edit::TextEditOutput) {
+        let response = output.response.clone();
+        if response.secondary_clicked() {
+            self.spell_target = response.interact_pointer_pos().and_then(|pos| {
+                let cursor = output.galley.cursor_from_pos(pos - output.galley_pos);
+                self.spellcheck.target_at(cursor.ccursor.index, &self.text)
+            });
+        }
+        let Some(target) = &self.spell_target else {
+            return;
+        };
+
+        let mut replacement = None;
+        let mut add_word = false;
+        response.context_menu(|ui| {
+            for suggestion in &target.suggestions {
+                if ui.button(suggestion).clicked() {
+                    replacement = Some(suggestion.clone());
+                    ui.close_menu();
+                }
+            }
+            if target.suggestions.is_empty() {
+                ui.label(tr!("Нет вариантов"));
+            }
+            ui.separator();
+            if ui.button(tr!("Добавить «{}» в словарь", target.word)).clicked() {
+                add_word = true;
+                ui.close_menu();
+            }
+        });
+
+        let Some(target) = self.spell_target.take_if(|_| replacement.is_some() || add_word) else {
+            return;
+        };
+        if let Some(replacement) = replacement {
+            self.replace_char_range(target.start, target.end, &replacement);
+        }
+        if add_word {
+            if let Err(e) = self.spellcheck.add_word(&target.word) {
+                self.error_message = Some(e);
+            }
+        }
+    }
+
+    fn paint_bookmarks(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let lines = self.bookmarks.lines(self.filename.as_deref());
+        if lines.is_empty() {
+            return;
+        }
+
+        let painter = ui.painter();
+        let gutter_x = output.response.rect.left() + GUTTER_WIDTH / 2.0;
+        for line in lines {
+            let cursor = output.galley.from_ccursor(egui::text::CCursor::new(self.line_start_char(line)));
+            let row_rect = output
+                .galley
+                .pos_from_cursor(&cursor)
+                .translate(output.galley_pos.to_vec2());
+            painter.circle_filled(
+                egui::pos2(gutter_x, row_rect.center().y),
+                4.0,
+                BOOKMARK_COLOR,
+            );
+        }
+    }
+
+    // Полоски у левого края: добавленные и измененные с HEAD строки, треугольник — место удаления
+    fn paint_git_changes(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let changes = self.git.changes();
+        if changes.is_empty() {
+            return;
+        }
+
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(self.text.chars().enumerate().filter(|&(_, c)| c == '\n').map(|(i, _)| i + 1))
+            .collect();
+        let painter = ui.painter();
+        let left = output.response.rect.left() + 1.0;
+        for &(line, status) in changes {
+            let start = line_starts.get(line).copied().unwrap_or_else(|| self.count_characters());
+            let end = line_starts.get(line + 1).map(|&next| next - 1).unwrap_or(start);
+            let offset = output.galley_pos.to_vec2();
+            let top = output.galley.pos_from_cursor(&output.galley.from_ccursor(egui::text::CCursor::new(start)));
+            let bottom = output.galley.pos_from_cursor(&output.galley.from_ccursor(egui::text::CCursor::new(end)));
+            let (top, bottom) = (top.translate(offset), bottom.translate(offset));
+            match status {
+                LineStatus::Deleted => {
+                    let y = top.top();
+                    painter.add(egui::Shape::convex_polygon(
+                        vec![egui::pos2(left, y - 4.0), egui::pos2(left + 5.0, y), egui::pos2(left, y + 4.0)],
+                        GIT_DELETED_COLOR,
+                        egui::Stroke::NONE,
+                    ));
+                }
+                LineStatus::Added | LineStatus::Modified => {
+                    let color = if status == LineStatus::Added { GIT_ADDED_COLOR } else { GIT_MODIFIED_COLOR };
+                    let rect = egui::Rect::from_x_y_ranges(left..=left + 3.0, top.top()..=bottom.bottom());
+                    painter.rect_filled(rect, 0.0, color);
+                }
+            }
+        }
+    }
+
+    // Рамка вокруг скобки у курсора и ее пары; без выделения, чтобы не мешать ему
+    fn paint_bracket_match(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let Some(cursor_range) = output.cursor_range else {
+            return;
+        };
+        if !cursor_range.is_empty() {
+            return;
+        }
+        let Some(pair) = brackets::matching_pair(&self.text, cursor_range.primary.ccursor.index) else {
+            return;
+        };
+        let stroke = egui::Stroke::new(1.0, self.palette.text_color().gamma_multiply(0.6));
+        let offset = output.galley_pos.to_vec2();
+        for index in [pair.at, pair.matching] {
+            for rect in Self::range_rects(&output.galley, index, index + 1) {
+                ui.painter().rect_stroke(rect.translate(offset), egui::Rounding::same(2.0), stroke);
             }
-            SearchPanelResult::PreviousMatch => {
-                self.search_module.previous_match();
+        }
+    }
+
+    // Полупрозрачная подложка под строкой с курсором, все ее экранные строки при переносе
+    fn paint_current_line(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let Some(cursor_range) = output.cursor_range else {
+            return;
+        };
+        let cursor = cursor_range.primary.ccursor.index;
+        let mut line_start = 0;
+        let mut line_end = None;
+        for (index, c) in self.text.chars().enumerate() {
+            if c != '\n' {
+                continue;
             }
-            SearchPanelResult::Close => {
-                self.search_module.toggle_search();
+            if index < cursor {
+                line_start = index + 1;
+            } else {
+                line_end = Some(index);
+                break;
             }
-            SearchPanelResult::None => {}
         }
+        let line_end = line_end.unwrap_or_else(|| self.count_characters());
 
-        if shortcuts_triggered_search && self.search_module.show_search {
-            self.search_module.search_in_text(&self.text);
+        let color = if ui.visuals().dark_mode {
+            egui::Color32::from_white_alpha(10)
+        } else {
+            egui::Color32::from_black_alpha(12)
+        };
+        let bounds = output.response.rect;
+        let offset = output.galley_pos.to_vec2();
+        for rect in Self::range_rects(&output.galley, line_start, line_end) {
+            let rect = rect.translate(offset);
+            let full_width = egui::Rect::from_x_y_ranges(bounds.x_range(), rect.y_range());
+            ui.painter().rect_filled(full_width, egui::Rounding::ZERO, color);
+        }
+    }
+
+    // Вертикальная линия на заданном столбце; ширину символа берем у пробела текущего шрифта
+    fn paint_ruler(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let font = self.editor_font();
+        let char_width = ui.fonts(|fonts| fonts.glyph_width(&font, ' '));
+        let x = output.galley_pos.x + char_width * self.config.ruler_column as f32;
+        let bounds = output.response.rect;
+        if x > bounds.right() {
+            return;
         }
+        ui.painter().vline(
+            x,
+            bounds.y_range(),
+            egui::Stroke::new(1.0, ui.visuals().widgets.noninteractive.bg_stroke.color),
+        );
     }
 
     // === Выделение найденных элементов ===
-fn highlight_matches(&self, ui: &egui::Ui, response: &egui::Response) {
+fn highlight_matches(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
     if self.search_module.matches.is_empty() {
         return;
     }
 
     let painter = ui.painter();
-    let rect = response.rect;
-    
-    // Получаем информацию о шрифте
-    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
-    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
-    
-    // Разбиваем текст на строки
-    let lines: Vec<&str> = self.text.lines().collect();
+    let offset = output.galley_pos.to_vec2();
     
     let current_match_index = self.search_module.get_current_match_index();
     let matches = self.search_module.get_matches();
-    
-    for (line_index, line) in lines.iter().enumerate() {
-        // Вычисляем начальную позицию этой строки в общем тексте
-        let line_start = lines.iter()
-            .take(line_index)
-            .map(|l| l.chars().count() + 1) // +1 для символа новой строки
-            .sum::<usize>();
-        
-        let line_end = line_start + line.chars().count();
-        
-        // Находим все совпадения в этой строке
-        for &(start, end) in matches {
-            if start >= line_start && end <= line_end {
-                let is_current = matches
-                    .iter()
-                    .position(|&m| m == (start, end))
-                    .map(|idx| idx == current_match_index)
-                    .unwrap_or(false);
-                
-                // Вычисляем позиции для выделения
-                let match_start_in_line = start - line_start;
-                let match_end_in_line = end - line_start;
-                
-                // Приблизительный расчет позиций (моноширинный шрифт)
-                let char_width = 8.0; // Ширина символа в моноширинном шрифте
-                let x_start = rect.left() + (match_start_in_line as f32 * char_width);
-                let x_end = rect.left() + (match_end_in_line as f32 * char_width);
-                let y_top = rect.top() + (line_index as f32 * row_height);
-                let y_bottom = y_top + row_height;
-                
-                let highlight_rect = egui::Rect::from_min_max(
-                    egui::pos2(x_start, y_top),
-                    egui::pos2(x_end, y_bottom)
-                );
-                
-                // Рисуем выделение
-                let color = if is_current {
-                    egui::Color32::from_rgba_unmultiplied(255, 100, 100, 180) // Полупрозрачный красный
-                } else {
-                    egui::Color32::from_rgba_unmultiplied(255, 255, 100, 120) // Полупрозрачный желтый
-                };
-                
-                painter.rect_filled(highlight_rect, egui::Rounding::ZERO, color);
-            }
+
+    for (match_index, &(start, end)) in matches.iter().enumerate() {
+        // Рисуем выделение цветами текущей темы
+        let color = self.palette.match_color(match_index == current_match_index);
+
+        for rect in Self::range_rects(&output.galley, start, end) {
+            painter.rect_filled(rect.translate(offset), egui::Rounding::ZERO, color);
         }
     }
 }
 
-    // === Утилиты для работы с документами ===
-    fn extract_text_from_docx(bytes: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
-        let docx = docx_rs::read_docx(bytes)?;
+    // Проверка в окне замены по регулярному выражению: найденное подсвечиваем, ничего не меняя
+    fn paint_regex_matches(&self, ui: &egui::Ui, output: &egui::text_edit::TextEditOutput) {
+        let offset = output.galley_pos.to_vec2();
+        let color = self.palette.match_color(false);
+        for &(start, end) in self.regex_replace.highlighted() {
+            for rect in Self::range_rects(&output.galley, start, end) {
+                ui.painter().rect_filled(rect.translate(offset), egui::Rounding::ZERO, color);
+            }
+        }
+    }
 
-        let mut text = String::new();
+    // Прямоугольники, покрывающие диапазон символов: по одному на каждую строку экрана,
+    // поэтому совпадения через перенос строки подсвечиваются целиком
+    fn range_rects(galley: &egui::Galley, start: usize, end: usize) -> Vec<egui::Rect> {
+        let start_cursor = galley.from_ccursor(egui::text::CCursor::new(start));
+        let end_cursor = galley.from_ccursor(egui::text::CCursor::new(end));
+        let start_pos = galley.pos_from_cursor(&start_cursor);
+        let end_pos = galley.pos_from_cursor(&end_cursor);
+        let first_row = start_cursor.rcursor.row;
+        let last_row = end_cursor.rcursor.row;
 
-        let document = docx.document;
-        for child in &document.children {
-            Self::extract_text_from_document(child, &mut text);
-        }
-
-        Ok(text.trim().to_string())
-    }
-
-    fn extract_text_from_document(document: &docx_rs::DocumentChild, text: &mut String) {
-        match document {
-            docx_rs::DocumentChild::Paragraph(para) => {
-                for child in &para.children {
-                    match child {
-                        docx_rs::ParagraphChild::Run(run) => {
-                            for text_child in &run.children {
-                                match text_child {
-                                    docx_rs::RunChild::Text(t) => {
-                                        text.push_str(&t.text);
-                                        text.push(' ');
-                                    }
-                                    docx_rs::RunChild::Break(_) => {
-                                        text.push('\n');
-                                    }
-                                    docx_rs::RunChild::Tab(_) => {
-                                        text.push('\t');
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                text.push('\n');
-            }
-            docx_rs::DocumentChild::Table(_) => {
-                text.push_str("\n[таблица]\n");
-            }
-            _ => {}
+        if first_row == last_row {
+            return vec![egui::Rect::from_min_max(start_pos.min, egui::pos2(end_pos.max.x, start_pos.max.y))];
+        }
+
+        let mut rects = Vec::with_capacity(last_row - first_row + 1);
+        let row_rect = |row: usize| galley.rows.get(row).map(|r| r.rect).unwrap_or(start_pos);
+        rects.push(egui::Rect::from_min_max(
+            start_pos.min,
+            egui::pos2(row_rect(first_row).right().max(start_pos.min.x + 4.0), start_pos.max.y),
+        ));
+        for row in first_row + 1..last_row {
+            rects.push(row_rect(row));
         }
+        rects.push(egui::Rect::from_min_max(
+            egui::pos2(row_rect(last_row).left(), end_pos.min.y),
+            end_pos.max,
+        ));
+        rects
+    }
+
+    // === Утилиты для работы с документами ===
+    fn extract_text_from_docx(&self, bytes: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+        docx_import::extract_text(bytes, &self.config.docx_import)
     }
 
     fn extract_readable_text(content: &str) -> String {
@@ -436,60 +3450,526 @@ fn highlight_matches(&self, ui: &egui::Ui, response: &egui::Response) {
 impl Drop for TextEditor {
     fn drop(&mut self) {
         self.stop_music();
+        self.ambient.stop();
+        let _ = self.writing.save();
+        let _ = self.config.save();
     }
 }
 
 impl eframe::App for TextEditor {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_multi_cursor(ctx);
+        self.handle_auto_replace(ctx);
+        self.handle_auto_pair(ctx);
+        self.handle_autocomplete_keys(ctx);
+        self.handle_markdown_keys(ctx);
+        self.handle_snippets(ctx);
+        self.handle_indent_keys(ctx);
+        self.handle_reflow_keys(ctx);
+        self.handle_calculator_keys(ctx);
+        self.handle_smart_paste(ctx);
+        self.handle_bracket_keys(ctx);
+        self.handle_line_keys(ctx);
+        self.handle_date(ctx);
+        self.handle_symbols(ctx);
+        self.handle_placeholder(ctx);
+        // Ctrl+Shift+F проверяем раньше, чем Ctrl+F
+        self.handle_find_in_files(ctx);
         self.handle_search(ctx);
+        // Ctrl+Shift+P — раньше, чем Ctrl+P
+        self.handle_command_palette(ctx);
+        self.handle_quick_open(ctx);
+        self.handle_bookmarks(ctx);
+        self.handle_outline(ctx);
+        self.handle_duplicates(ctx);
+        self.handle_settings(ctx);
+        self.handle_themes(ctx);
+        self.handle_zoom(ctx);
+        self.handle_music(ctx);
+        self.handle_typing_sounds(ctx);
+        self.handle_pomodoro(ctx);
+        self.handle_statistics(ctx);
+        self.handle_word_frequency(ctx);
+        self.handle_spellcheck(ctx);
+        self.handle_external_command(ctx);
+        self.handle_format(ctx);
+        self.handle_regex_replace(ctx);
+        self.handle_sequence(ctx);
+        self.hash.show_window(ctx);
+        self.handle_file_watch(ctx);
+        self.handle_archive(ctx);
+        self.handle_crypto(ctx);
+        self.handle_selection_file(ctx);
+        self.handle_document_goal(ctx);
+        self.handle_docx(ctx);
+        self.handle_templates(ctx);
+        self.handle_file_properties(ctx);
+        self.handle_history(ctx);
+        self.handle_compare(ctx);
+        self.handle_git(ctx);
+        self.track_window_geometry(ctx);
+        self.handle_autosave(ctx);
 
         // Верхняя панель меню
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
-                ui.menu_button("Файл", |ui| {
-                    if ui.button("Новый").clicked() {
+                ui.menu_button(tr!("Файл"), |ui| {
+                    if ui.button(tr!("Новый")).clicked() {
                         self.new_file();
                         ui.close_menu();
                     }
-                    if ui.button("Открыть").clicked() {
+                    ui.menu_button(tr!("Новый из шаблона"), |ui| {
+                        for template in self.templates.templates().to_vec() {
+                            if ui.button(&template.name).clicked() {
+                                self.new_from_template(template.body);
+                                ui.close_menu();
+                            }
+                        }
+                        if !self.templates.templates().is_empty() {
+                            ui.separator();
+                        }
+                        if ui.button(tr!("Шаблоны документов...")).clicked() {
+                            self.templates.toggle_window();
+                            ui.close_menu();
+                        }
+                    });
+                    if ui.button(tr!("Открыть")).clicked() {
                         self.open_file();
                         ui.close_menu();
                     }
-                    if ui.button("Сохранить").clicked() {
+                    if ui.button(tr!("Открыть папку...")).clicked() {
+                        self.open_folder();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Открыть документ Word...")).clicked() {
+                        self.open_word_document();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Новая запись в журнале")).clicked() {
+                        self.open_journal();
+                        ui.close_menu();
+                    }
+                    ui.add_enabled_ui(!self.config.recent_files.is_empty(), |ui| {
+                        ui.menu_button(tr!("Недавние"), |ui| {
+                            for path in self.config.recent_files.clone() {
+                                if ui.button(path.display().to_string()).clicked() {
+                                    self.request_open(path, None);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+                    if ui
+                        .add_enabled(self.workspace.is_some(), egui::Button::new(tr!("Быстрое открытие (Ctrl + P)")))
+                        .clicked()
+                    {
+                        if let Some(workspace) = &self.workspace {
+                            self.quick_open.open(workspace);
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Сохранить")).clicked() {
                         self.save_file();
                         ui.close_menu();
                     }
-                    if ui.button("Сохранить как").clicked() {
+                    if ui.button(tr!("Сохранить как")).clicked() {
                         self.save_as();
                         ui.close_menu();
                     }
+                    if ui
+                        .add_enabled(self.selection.is_some(), egui::Button::new(tr!("Сохранить выделенное как...")))
+                        .clicked()
+                    {
+                        self.save_selection_as();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Сохранить зашифрованным...")).clicked() {
+                        self.save_encrypted();
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(self.filename.is_some(), egui::Button::new(tr!("Сравнить с сохраненным")))
+                        .clicked()
+                    {
+                        self.compare_with_saved();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Свойства файла...")).clicked() {
+                        self.file_properties.open(self.filename.as_deref(), &self.config.default_encoding);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("История версий...")).clicked() {
+                        self.history.open(self.filename.as_deref());
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr!("Настройки")).clicked() {
+                        self.settings.toggle_window();
+                        ui.close_menu();
+                    }
                     ui.separator();
-                    if ui.button("Выйти").clicked() {
+                    if ui.button(tr!("Выйти")).clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
                 });
 
-                ui.menu_button("Редактировать", |ui| {
+                ui.menu_button(tr!("Редактировать"), |ui| {
                     // Убрал дублирующийся пункт "Поиск"
-                    if ui.button("Найти (Ctrl + F)").clicked() {
+                    if ui.button(tr!("Найти (Ctrl + F)")).clicked() {
                         self.search_module.toggle_search();
                         ui.close_menu();
                     }
+                    if ui.button(tr!("Найти в файлах (Ctrl + Shift + F)")).clicked() {
+                        self.find_in_files.toggle_window();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Замена по регулярному выражению...")).clicked() {
+                        self.regex_replace.toggle_window();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr!("Закладка (Ctrl + B)")).clicked() {
+                        let line = self.cursor_line();
+                        if let Err(e) = self.bookmarks.toggle(self.filename.as_deref(), line) {
+                            self.error_message = Some(e);
+                        }
+                        ui.close_menu();
+                    }
+                    ui.menu_button(tr!("Строка"), |ui| {
+                        if ui.button(tr!("Дублировать (Ctrl + D)")).clicked() {
+                            self.duplicate_lines();
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("Переместить вверх (Alt + Вверх)")).clicked() {
+                            self.move_lines(true);
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("Переместить вниз (Alt + Вниз)")).clicked() {
+                            self.move_lines(false);
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("Удалить строку (Ctrl + Shift + K)")).clicked() {
+                            self.delete_lines();
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("Закомментировать (Ctrl + /)")).clicked() {
+                            self.toggle_comments();
+                            ui.close_menu();
+                        }
+                    });
+                    if ui.button(tr!("Выделить следующее вхождение (Ctrl + Shift + D)")).clicked() {
+                        self.select_next_occurrence(false);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Пропустить вхождение (Alt + Shift + D)")).clicked() {
+                        self.select_next_occurrence(true);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("К парной скобке (Ctrl + M)")).clicked() {
+                        self.jump_to_bracket();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Переформатировать абзац (Alt + Q)")).clicked() {
+                        self.apply_transform(Transform::Reflow);
+                        ui.close_menu();
+                    }
+                    ui.menu_button(tr!("Вставить дату/время"), |ui| match self.date.show_menu(ui, &self.config) {
+                        DateResult::Insert(text) => self.insert_text(&text),
+                        DateResult::ConfigChanged => self.save_config(),
+                        DateResult::None => {}
+                    });
+                    if ui.button(tr!("Таблица символов...")).clicked() {
+                        self.symbols.toggle_window();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Текст-заполнитель...")).clicked() {
+                        self.placeholder.toggle_window();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Вычислить выражение (Ctrl + Shift + E)")).clicked() {
+                        self.evaluate_expression(false);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Заменить выражение результатом (Ctrl + Shift + R)")).clicked() {
+                        self.evaluate_expression(true);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr!("Вырезать (Ctrl + X)")).clicked() {
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Копировать (Ctrl + C)")).clicked() {
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Вставить (Ctrl + V)")).clicked() {
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Вставить как обычный текст (Ctrl + Shift + V)")).clicked() {
+                        self.paste_plain();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Вставить файл...")).clicked() {
+                        self.insert_file();
+                        ui.close_menu();
+                    }
+                });
+
+                ui.menu_button(tr!("Вид"), |ui| {
+                    if ui.button(tr!("Палитра команд (Ctrl + Shift + P)")).clicked() {
+                        self.command_palette.open();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    ui.add_enabled(
+                        self.workspace.is_some(),
+                        egui::Checkbox::new(&mut self.file_tree.show_panel, tr!("Панель файлов")),
+                    );
+                    ui.checkbox(&mut self.bookmarks.show_panel, tr!("Закладки"));
+                    ui.checkbox(&mut self.outline.show_panel, tr!("Структура документа"));
+                    ui.checkbox(&mut self.minimap.show_panel, tr!("Миникарта"));
+                    ui.checkbox(&mut self.welcome.show, tr!("Начальная страница"));
+                    ui.checkbox(&mut self.playlist.show_panel, tr!("Плейлист"));
+                    ui.checkbox(&mut self.playlist.show_playback_bar, tr!("Панель воспроизведения"));
+                    ui.checkbox(&mut self.pomodoro.show_panel, tr!("Таймер помидоро"));
+                    if ui.checkbox(&mut self.config.paragraph_stats, tr!("Статистика абзацев")).changed() {
+                        self.save_config();
+                    }
+                    ui.menu_button(tr!("Разделить"), |ui| {
+                        if ui.button(tr!("Рядом")).clicked() {
+                            self.split.split(SplitOrientation::Vertical);
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("Одна над другой")).clicked() {
+                            self.split.split(SplitOrientation::Horizontal);
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("Открыть файл во второй панели...")).clicked() {
+                            self.open_in_split();
+                            ui.close_menu();
+                        }
+                        if ui
+                            .add_enabled(self.split.orientation.is_some(), egui::Button::new(tr!("Закрыть разделение")))
+                            .clicked()
+                        {
+                            self.split.close();
+                            ui.close_menu();
+                        }
+                    });
+                    ui.separator();
+                    if ui.button(tr!("Увеличить (Ctrl + Плюс)")).clicked() {
+                        self.zoom = (self.zoom + ZOOM_STEP).min(MAX_ZOOM);
+                    }
+                    if ui.button(tr!("Уменьшить (Ctrl + Минус)")).clicked() {
+                        self.zoom = (self.zoom - ZOOM_STEP).max(MIN_ZOOM);
+                    }
+                    if ui.button(tr!("Исходный масштаб (Ctrl + 0)")).clicked() {
+                        self.zoom = 1.0;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    ui.menu_button(tr!("Тема"), |ui| {
+                        for (name, title) in available_themes(&self.config) {
+                            if ui.radio(self.config.theme == name, title).clicked() {
+                                self.set_theme(ctx, name);
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        if ui.button(tr!("Редактор тем...")).clicked() {
+                            self.themes.open_editor(&self.config);
+                            ui.close_menu();
+                        }
+                    });
+                });
+
+                ui.menu_button(tr!("Инструменты"), |ui| {
+                    if ui.button(tr!("Статистика...")).clicked() {
+                        self.statistics.toggle_window();
+                        ui.close_menu();
+                    }
+                    ui.checkbox(&mut self.duplicates.show_panel, tr!("Повторяющиеся строки"));
+                    if ui.button(tr!("Частота слов...")).clicked() {
+                        self.word_frequency.open(self.selection.is_some());
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Цель по словам...")).clicked() {
+                        self.document_goal.open(self.filename.as_deref(), &self.config);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Журнал письма...")).clicked() {
+                        self.writing.show_window = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr!("Автозамена...")).clicked() {
+                        self.auto_replace.toggle_window();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Сниппеты...")).clicked() {
+                        self.snippets.toggle_window();
+                        ui.close_menu();
+                    }
+                    ui.add_enabled_ui(!self.config.snippets.is_empty(), |ui| {
+                        ui.menu_button(tr!("Вставить сниппет"), |ui| {
+                            for snippet in self.config.snippets.clone() {
+                                let title = format!("{} — {}", snippet.abbreviation, snippet.description);
+                                if ui.button(title).clicked() {
+                                    self.insert_snippet(&snippet, self.cursor_char);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+                    if ui.button(tr!("Сравнить файлы...")).clicked() {
+                        self.compare.toggle_window();
+                        ui.close_menu();
+                    }
+                    ui.menu_button(tr!("Преобразовать"), |ui| {
+                        match transform_module::show_menu(ui, &mut self.config) {
+                            TransformResult::None => {}
+                            TransformResult::ConfigChanged => self.save_config(),
+                            TransformResult::Apply(transform) => self.apply_transform(transform),
+                        }
+                    });
+                    if ui.button(tr!("Вычислить хеш...")).clicked() {
+                        self.hash.open(self.selected_text(), &self.text, self.filename.as_deref());
+                        ui.close_menu();
+                    }
+                    ui.menu_button(tr!("Преобразовать отступы"), |ui| {
+                        if ui.button(tr!("В пробелы")).clicked() {
+                            self.convert_indentation(true);
+                            ui.close_menu();
+                        }
+                        if ui.button(tr!("В табуляцию")).clicked() {
+                            self.convert_indentation(false);
+                            ui.close_menu();
+                        }
+                    });
+                    if ui.button(tr!("Нумерация строк...")).clicked() {
+                        self.sequence.toggle_window();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Форматировать JSON/XML...")).clicked() {
+                        self.format.toggle_window();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Выполнить команду...")).clicked() {
+                        self.external_command.open(self.selection.is_some());
+                        ui.close_menu();
+                    }
+                    ui.add_enabled_ui(self.git.is_repo(), |ui| {
+                        ui.menu_button("Git", |ui| {
+                            if ui.button(tr!("Сравнить с HEAD")).clicked() {
+                                self.diff_with_head();
+                                ui.close_menu();
+                            }
+                            if ui.button(tr!("Коммит...")).clicked() {
+                                self.git.show_commit = true;
+                                ui.close_menu();
+                            }
+                            if ui.button(tr!("Обновить")).clicked() {
+                                self.git.refresh();
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                    ui.menu_button(tr!("Плагины"), |ui| {
+                        for index in 0..self.plugins.commands.len() {
+                            if ui.button(self.plugins.commands[index].title.clone()).clicked() {
+                                self.run_plugin(index);
+                                ui.close_menu();
+                            }
+                        }
+                        if self.plugins.commands.is_empty() {
+                            ui.label(tr!("Нет команд плагинов"));
+                        }
+                        ui.separator();
+                        if ui.button(tr!("Перезагрузить плагины")).clicked() {
+                            self.reload_plugins();
+                            ui.close_menu();
+                        }
+                        if let Some(dir) = plugins_dir() {
+                            ui.label(tr!("Скрипты *.rhai из {}", dir.display()));
+                        }
+                    });
+                    ui.separator();
+                    if ui.checkbox(&mut self.config.spell_check, tr!("Проверка орфографии")).changed() {
+                        self.save_config();
+                    }
+                    if self.config.spell_check {
+                        let names = self.spellcheck.dictionary_names();
+                        if self.spellcheck.is_loading() {
+                            ui.label(tr!("Загрузка словарей..."));
+                        } else if names.is_empty() {
+                            let folder = dictionaries_dir().map(|d| d.display().to_string()).unwrap_or_default();
+                            ui.label(tr!("Словари Hunspell (.aff и .dic) не найдены в {}", folder));
+                        } else {
+                            ui.label(tr!("Словари: {}", names.join(", ")));
+                        }
+                    }
+                });
+
+                ui.menu_button(tr!("Музыка"), |ui| {
+                    let play_label = if self.music_playing { tr!("Пауза (F8)") } else { tr!("Играть (F8)") };
+                    if ui.button(play_label).clicked() {
+                        self.toggle_music();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Предыдущий трек (F7)")).clicked() {
+                        self.previous_track();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Следующий трек (F9)")).clicked() {
+                        self.next_track();
+                        ui.close_menu();
+                    }
                     ui.separator();
-                    if ui.button("Вырезать (Ctrl + X)").clicked() {
+                    if ui.button(tr!("Выбрать файл...")).clicked() {
+                        self.choose_music_file();
                         ui.close_menu();
                     }
-                    if ui.button("Копировать (Ctrl + C)").clicked() {
+                    if ui.button(tr!("Выбрать папку...")).clicked() {
+                        self.choose_music_folder();
                         ui.close_menu();
                     }
-                    if ui.button("Вставить (Ctrl + V)").clicked() {
+                    if ui
+                        .add_enabled(self.config.music_source().is_some(), egui::Button::new(tr!("Встроенная музыка")))
+                        .clicked()
+                    {
+                        self.use_builtin_music();
                         ui.close_menu();
                     }
+                    if let Some(source) = self.config.music_source() {
+                        ui.label(source.display().to_string());
+                    }
+                    ui.separator();
+                    ui.menu_button(tr!("Фоновые звуки"), |ui| {
+                        if ui.radio(self.ambient.current.is_none(), tr!("Выключены")).clicked() {
+                            self.set_ambient_sound(None);
+                            ui.close_menu();
+                        }
+                        for sound in AmbientSound::ALL {
+                            if ui.radio(self.ambient.current == Some(sound), sound.title()).clicked() {
+                                self.set_ambient_sound(Some(sound));
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        let volume = ui
+                            .add(egui::Slider::new(&mut self.config.ambient_volume, 0.0..=1.0).show_value(false))
+                            .on_hover_text(tr!("Громкость: {}%", (self.config.ambient_volume * 100.0).round()));
+                        if volume.changed() {
+                            self.ambient.set_volume(self.config.ambient_volume);
+                        }
+                        if volume.drag_stopped() || (volume.changed() && !volume.dragged()) {
+                            self.save_config();
+                        }
+                    });
                 });
 
                 // Кнопка музыки
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    let button_text = if self.music_playing { "🔊 Музыка" } else { "🔇 Музыка" };
+                    let button_text = if self.music_playing { tr!("🔊 Музыка") } else { tr!("🔇 Музыка") };
                     let button_color = if self.music_playing { 
                         egui::Color32::from_rgb(100, 200, 100) 
                     } else { 
@@ -503,46 +3983,62 @@ impl eframe::App for TextEditor {
                     ).clicked() {
                         self.toggle_music();
                     }
+                    if ui.button("⏭").on_hover_text(tr!("Следующий трек")).clicked() {
+                        self.next_track();
+                    }
+                    if ui
+                        .add_enabled(self.audio_sink.is_some(), egui::Button::new("⏹"))
+                        .on_hover_text(tr!("Остановить"))
+                        .clicked()
+                    {
+                        self.stop_music();
+                    }
+
+                    // Громкость меняется сразу, а в настройки пишется, когда ползунок отпустили
+                    let volume = ui.add(
+                        egui::Slider::new(&mut self.config.music_volume, 0.0..=1.0)
+                            .show_value(false)
+                    ).on_hover_text(tr!("Громкость: {}%", (self.config.music_volume * 100.0).round()));
+                    if volume.changed() {
+                        self.config.music_muted = false;
+                        self.apply_volume();
+                    }
+                    if volume.drag_stopped() || (volume.changed() && !volume.dragged()) {
+                        self.save_config();
+                    }
+
+                    let mute_icon = if self.config.music_muted { "🔇" } else { "🔈" };
+                    let mute_hint = if self.config.music_muted { tr!("Включить звук") } else { tr!("Выключить звук") };
+                    if ui.small_button(mute_icon).on_hover_text(mute_hint).clicked() {
+                        self.config.music_muted = !self.config.music_muted;
+                        self.apply_volume();
+                        self.save_config();
+                    }
                 });
             });
         });
 
         // Нижняя панель статуса
-        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        let status_frame = egui::Frame::side_top_panel(&ctx.style()).fill(self.palette.status_bar_color());
+        egui::TopBottomPanel::bottom("status_bar").frame(status_frame).show(ctx, |ui| {
             ui.horizontal(|ui| {
-                let filename = self
-                    .filename
-                    .as_ref()
-                    .and_then(|p| p.file_name())
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("Без названия");
-
-                let file_status = if self.unsaved_changes {
-                    format!("{} • (изменен)", filename)
-                } else {
-                    filename.to_string()
-                };
-                ui.label(file_status);
-
-                ui.separator();
-
-                let chars = self.count_characters();
-                let words = self.count_words();
-                let lines = self.count_lines();
-                ui.label(format!("Символов: {}", chars));
-                ui.label(format!("Слов: {}", words));
-                ui.label(format!("Строк: {}", lines));
+                let mut previous_group = None;
+                for entry in self.config.status_bar.clone() {
+                    if !entry.visible || !self.status_item_shown(entry.item) {
+                        continue;
+                    }
+                    if previous_group.is_some_and(|group| group != entry.item.group()) {
+                        ui.separator();
+                    }
+                    previous_group = Some(entry.item.group());
+                    self.show_status_item(ui, entry.item);
+                }
 
-                if !self.search_module.matches.is_empty() {
+                if let Some(message) = &self.plugins.status_message {
                     ui.separator();
-                    ui.label(format!("Найдено: {}", self.search_module.matches.len()));
+                    ui.label(message);
                 }
 
-                ui.separator();
-
-                let music_icon = if self.music_playing { "🎵" } else { "🔇" };
-                ui.label(format!("{} {}", music_icon, self.current_song));
-
                 if let Some(error) = &self.error_message {
                     ui.separator();
                     ui.colored_label(egui::Color32::RED, error);
@@ -550,65 +4046,87 @@ impl eframe::App for TextEditor {
             });
         });
 
-        // Основная область текста
-        egui::CentralPanel::default().show(ctx, |ui| {
-            egui::ScrollArea::vertical()
-                .auto_shrink([false; 2])
-                .stick_to_bottom(false)
-                .show(ui, |ui| {
-                    let text_edit = egui::TextEdit::multiline(&mut self.text)
-                        .code_editor()
-                        .desired_rows(30)
-                        .desired_width(f32::INFINITY)
-                        .font(egui::TextStyle::Monospace)
-                        .frame(true);
+        // Панель воспроизведения — над строкой состояния
+        self.handle_playback_bar(ctx);
 
-                    let response = ui.add(text_edit);
+        // Боковая панель с файлами папки проекта
+        self.handle_file_tree(ctx);
+        self.handle_minimap(ctx);
 
-                    // Добавляем визуальное выделение найденных совпадений
-                    if !self.search_module.matches.is_empty() {
-                        self.highlight_matches(ui, &response);
-                    }
+        // Основная область текста
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.welcome.show {
+                self.handle_welcome(ui);
+                return;
+            }
+            if self.large_file.is_open() {
+                if self.large_file.show(ui, self.editor_font()) {
+                    self.large_file.close();
+                }
+                return;
+            }
 
-                    if response.changed() {
-                        self.unsaved_changes = true;
-                        if self.search_module.show_search && !self.search_module.search_text.is_empty() {
-                            self.search_module.search_in_text(&self.text);
-                        }
-                    }
+            if self.is_markdown_file() {
+                if let Some(action) = markdown_module::show_toolbar(ui) {
+                    self.apply_markdown(action);
+                }
+            }
 
-                    if !response.has_focus() && !self.search_module.show_search {
-                        response.request_focus();
-                    }
-                });
+            match self.split.orientation {
+                None => self.show_main_editor(ui),
+                Some(SplitOrientation::Vertical) => {
+                    ui.columns(2, |columns| {
+                        self.show_main_editor(&mut columns[0]);
+                        self.show_split_pane(&mut columns[1]);
+                    });
+                }
+                Some(SplitOrientation::Horizontal) => {
+                    let size = egui::vec2(ui.available_width(), ui.available_height() / 2.0);
+                    ui.allocate_ui(size, |ui| self.show_main_editor(ui));
+                    ui.separator();
+                    self.show_split_pane(ui);
+                }
+            }
         });
 
         // Диалог сохранения
         if self.show_save_dialog {
             let mut open = true;
-            egui::Window::new("Сохранение файла")
+            egui::Window::new(tr!("Сохранение файла"))
                 .open(&mut open)
                 .show(ctx, |ui| {
-                    ui.label("Сохранить изменения перед созданием нового файла?");
+                    if self.pending_open.is_some() {
+                        ui.label(tr!("Сохранить изменения перед открытием другого файла?"));
+                    } else {
+                        ui.label(tr!("Сохранить изменения перед созданием нового файла?"));
+                    }
                     ui.horizontal(|ui| {
-                        if ui.button("Сохранить").clicked() {
+                        if ui.button(tr!("Сохранить")).clicked() {
                             self.save_file();
-                            self.new_file();
-                            self.show_save_dialog = false;
+                            // Сохранение не удалось или его отменили — остаемся в документе
+                            if self.unsaved_changes {
+                                self.cancel_save_dialog();
+                            } else {
+                                self.resume_after_save_dialog(false);
+                            }
                         }
-                        if ui.button("Не сохранять").clicked() {
-                            self.new_file();
-                            self.show_save_dialog = false;
+                        if ui.button(tr!("Не сохранять")).clicked() {
+                            self.resume_after_save_dialog(true);
                         }
-                        if ui.button("Отмена").clicked() {
-                            self.show_save_dialog = false;
+                        if ui.button(tr!("Отмена")).clicked() {
+                            self.cancel_save_dialog();
                         }
                     });
                 });
 
             if !open {
-                self.show_save_dialog = false;
+                self.cancel_save_dialog();
             }
         }
+
+        self.handle_journal();
+        // В конце кадра, чтобы заголовок и журнал письма сразу отражали правки этого кадра
+        self.handle_writing_session(ctx);
+        self.handle_window_title(ctx);
     }
 }
\ No newline at end of file
@@ -0,0 +1,454 @@
+// markdown_module.rs
+use eframe::egui;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::line_ops::{line_end, line_start, lines_span, LineEdit};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MarkdownAction {
+    Bold,
+    Italic,
+    Code,
+    Link,
+    Heading,
+    Checkbox,
+    FormatTable,
+}
+
+impl MarkdownAction {
+    pub const ALL: [MarkdownAction; 7] = [
+        MarkdownAction::Bold,
+        MarkdownAction::Italic,
+        MarkdownAction::Code,
+        MarkdownAction::Link,
+        MarkdownAction::Heading,
+        MarkdownAction::Checkbox,
+        MarkdownAction::FormatTable,
+    ];
+
+    // Надпись на кнопке панели: «Ж» и «К», как в русских текстовых редакторах
+    fn button(self) -> egui::RichText {
+        match self {
+            MarkdownAction::Bold => egui::RichText::new(tr!("Ж")).strong(),
+            MarkdownAction::Italic => egui::RichText::new(tr!("К")).italics(),
+            MarkdownAction::Code => egui::RichText::new("`code`").monospace(),
+            MarkdownAction::Link => egui::RichText::new(tr!("Ссылка")),
+            MarkdownAction::Heading => egui::RichText::new("H#"),
+            MarkdownAction::Checkbox => egui::RichText::new("☑"),
+            MarkdownAction::FormatTable => egui::RichText::new(tr!("Таблица")),
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            MarkdownAction::Bold => tr!("Полужирный"),
+            MarkdownAction::Italic => tr!("Курсив"),
+            MarkdownAction::Code => tr!("Код"),
+            MarkdownAction::Link => tr!("Ссылка"),
+            MarkdownAction::Heading => tr!("Заголовок"),
+            MarkdownAction::Checkbox => tr!("Отметить пункт"),
+            MarkdownAction::FormatTable => tr!("Выровнять таблицу"),
+        }
+    }
+
+    pub fn shortcut(self) -> &'static str {
+        match self {
+            MarkdownAction::Bold => "Ctrl + Shift + B",
+            MarkdownAction::Italic => "Ctrl + I",
+            MarkdownAction::Code => "Ctrl + Shift + C",
+            MarkdownAction::Link => "Ctrl + L",
+            MarkdownAction::Heading => "Ctrl + Shift + H",
+            MarkdownAction::Checkbox => "Ctrl + Enter",
+            MarkdownAction::FormatTable => "Ctrl + Shift + T",
+        }
+    }
+
+    fn key(self) -> (egui::Modifiers, egui::Key) {
+        let shifted = egui::Modifiers::COMMAND | egui::Modifiers::SHIFT;
+        match self {
+            MarkdownAction::Bold => (shifted, egui::Key::B),
+            MarkdownAction::Italic => (egui::Modifiers::COMMAND, egui::Key::I),
+            MarkdownAction::Code => (shifted, egui::Key::C),
+            MarkdownAction::Link => (egui::Modifiers::COMMAND, egui::Key::L),
+            MarkdownAction::Heading => (shifted, egui::Key::H),
+            MarkdownAction::Checkbox => (egui::Modifiers::COMMAND, egui::Key::Enter),
+            MarkdownAction::FormatTable => (shifted, egui::Key::T),
+        }
+    }
+
+    // Правка текста; None — делать нечего (например, курсор не в таблице)
+    pub fn apply(self, text: &[char], anchor: usize, cursor: usize) -> Option<LineEdit> {
+        match self {
+            MarkdownAction::Bold => Some(toggle_inline(text, anchor, cursor, '*', 2)),
+            MarkdownAction::Italic => Some(toggle_inline(text, anchor, cursor, '*', 1)),
+            MarkdownAction::Code => Some(toggle_inline(text, anchor, cursor, '`', 1)),
+            MarkdownAction::Link => Some(link(text, anchor, cursor)),
+            MarkdownAction::Heading => Some(cycle_heading(text, cursor)),
+            MarkdownAction::Checkbox => toggle_checkboxes(text, anchor, cursor),
+            MarkdownAction::FormatTable => format_table(text, cursor),
+        }
+    }
+}
+
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"))
+}
+
+// Панель над текстом; подсказка у кнопки — название и сочетание клавиш
+pub fn show_toolbar(ui: &mut egui::Ui) -> Option<MarkdownAction> {
+    let mut result = None;
+    ui.horizontal(|ui| {
+        for action in MarkdownAction::ALL {
+            let hint = format!("{} ({})", action.title(), action.shortcut());
+            if ui.small_button(action.button()).on_hover_text(hint).clicked() {
+                result = Some(action);
+            }
+        }
+    });
+    result
+}
+
+// Сочетания клавиш — только для файлов Markdown, проверяет вызывающий код
+pub fn handle_keys(ctx: &egui::Context) -> Option<MarkdownAction> {
+    ctx.input_mut(|i| {
+        MarkdownAction::ALL.into_iter().find(|action| {
+            let (modifiers, key) = action.key();
+            i.consume_key(modifiers, key)
+        })
+    })
+}
+
+fn run_length(chars: impl Iterator<Item = char>, marker: char) -> usize {
+    chars.take_while(|&c| c == marker).count()
+}
+
+// «***текст***» — одновременно полужирный и курсив: звездочек 3 с каждой стороны
+fn run_matches(run: usize, width: usize, marker: char) -> bool {
+    run == width || (marker == '*' && run == 3)
+}
+
+// Обернуть выделение в маркер или снять его, если выделение уже обернуто.
+// Без выделения вставляется пара маркеров с курсором посередине
+fn toggle_inline(text: &[char], anchor: usize, cursor: usize, marker: char, width: usize) -> LineEdit {
+    let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+    let before = run_length(text[..start].iter().rev().copied(), marker);
+    let after = run_length(text[end..].iter().copied(), marker);
+    let inner: String = text[start..end].iter().collect();
+    if start != end && run_matches(before, width, marker) && run_matches(after, width, marker) {
+        return LineEdit {
+            start: start - width,
+            end: end + width,
+            text: inner,
+            anchor: start - width,
+            cursor: end - width,
+        };
+    }
+    let markers = marker.to_string().repeat(width);
+    LineEdit {
+        start,
+        end,
+        text: format!("{}{}{}", markers, inner, markers),
+        anchor: start + width,
+        cursor: end + width,
+    }
+}
+
+// Выделенный адрес становится ссылкой с пустым текстом, выделенный текст — ссылкой с пустым адресом;
+// курсор встает туда, где осталось пусто
+fn link(text: &[char], anchor: usize, cursor: usize) -> LineEdit {
+    let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+    let selected: String = text[start..end].iter().collect();
+    let is_url = ["http://", "https://", "www.", "mailto:"]
+        .iter()
+        .any(|prefix| selected.starts_with(prefix));
+    let (replacement, position) = if is_url {
+        (format!("[]({})", selected), start + 1)
+    } else {
+        let length = selected.chars().count();
+        (format!("[{}]()", selected), start + length + 3)
+    };
+    LineEdit {
+        start,
+        end,
+        text: replacement,
+        anchor: position,
+        cursor: position,
+    }
+}
+
+// Без заголовка → «#» → «##» → «###» → снова без заголовка
+fn cycle_heading(text: &[char], cursor: usize) -> LineEdit {
+    let start = line_start(text, cursor);
+    let end = line_end(text, cursor);
+    let line: String = text[start..end].iter().collect();
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let body = line[level..].trim_start();
+    let prefix = match level {
+        0..=2 => format!("{} ", "#".repeat(level + 1)),
+        _ => String::new(),
+    };
+    let old_prefix = line.chars().count() - body.chars().count();
+    let column = (cursor - start).saturating_sub(old_prefix);
+    let position = start + prefix.chars().count() + column;
+    LineEdit {
+        start,
+        end,
+        text: format!("{}{}", prefix, body),
+        anchor: position,
+        cursor: position,
+    }
+}
+
+// Отступ, маркер списка с пробелом и содержимое строки: «  - [ ] купить» → («  », «- [ ] », «купить»)
+fn split_list_item(line: &str) -> Option<(&str, String, &str)> {
+    let body = line.trim_start();
+    let indent = &line[..line.len() - body.len()];
+    let digits = body.len() - body.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker_length = if body.starts_with(['-', '*', '+']) {
+        1
+    } else if digits > 0 && body[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else {
+        return None;
+    };
+    let rest = body[marker_length..].strip_prefix(' ')?;
+    let mut marker = format!("{} ", &body[..marker_length]);
+    let rest = match ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find(|checkbox| rest.starts_with(*checkbox))
+    {
+        Some(checkbox) => {
+            marker.push_str(checkbox);
+            &rest[checkbox.len()..]
+        }
+        None => rest,
+    };
+    Some((indent, marker, rest))
+}
+
+// Ctrl + Enter: «[ ]» ↔ «[x]»; пункт без отметки ее получает, обычная строка становится пунктом
+fn toggle_checkboxes(text: &[char], anchor: usize, cursor: usize) -> Option<LineEdit> {
+    let (start, end) = lines_span(text, anchor, cursor);
+    let block: String = text[start..end].iter().collect();
+    let lines: Vec<String> = block
+        .split('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                return line.to_string();
+            }
+            match split_list_item(line) {
+                Some((indent, marker, rest)) if marker.ends_with("[ ] ") => {
+                    format!("{}{}[x] {}", indent, marker.trim_end_matches("[ ] "), rest)
+                }
+                Some((indent, marker, rest)) if marker.contains('[') => {
+                    format!("{}{}[ ] {}", indent, &marker[..marker.len() - 4], rest)
+                }
+                Some((indent, marker, rest)) => format!("{}{}[ ] {}", indent, marker, rest),
+                None => {
+                    let body = line.trim_start();
+                    format!("{}- [ ] {}", &line[..line.len() - body.len()], body)
+                }
+            }
+        })
+        .collect();
+    let replacement = lines.join("\n");
+    if replacement == block {
+        return None;
+    }
+    let length = replacement.chars().count();
+    // Отметка не сдвигает курсор на одной строке; блок строк остается выделенным целиком
+    let (anchor, cursor) = if anchor == cursor {
+        let position = (cursor + length).saturating_sub(block.chars().count()).max(start);
+        (position, position)
+    } else {
+        (start, start + length)
+    };
+    Some(LineEdit {
+        start,
+        end,
+        text: replacement,
+        anchor,
+        cursor,
+    })
+}
+
+// Enter в пункте списка продолжает список; Enter в пустом пункте заканчивает его
+pub fn continue_list(text: &[char], cursor: usize) -> Option<LineEdit> {
+    let start = line_start(text, cursor);
+    let line: String = text[start..cursor].iter().collect();
+    let (indent, marker, rest) = split_list_item(&line).or_else(|| {
+        let body = line.trim_start();
+        body.starts_with("> ")
+            .then(|| (&line[..line.len() - body.len()], "> ".to_string(), &body[2..]))
+    })?;
+    if rest.trim().is_empty() && line_end(text, cursor) == cursor {
+        return Some(LineEdit {
+            start,
+            end: cursor,
+            text: String::new(),
+            anchor: start,
+            cursor: start,
+        });
+    }
+    // Нумерация продолжается, новая отметка — пустая
+    let digits: String = marker.chars().take_while(char::is_ascii_digit).collect();
+    let marker = match digits.parse::<u64>() {
+        Ok(number) => format!("{}{}", number + 1, &marker[digits.len()..]),
+        Err(_) => marker,
+    }
+    .replace("[x] ", "[ ] ")
+    .replace("[X] ", "[ ] ");
+    let insert = format!("\n{}{}", indent, marker);
+    let position = cursor + insert.chars().count();
+    Some(LineEdit {
+        start: cursor,
+        end: cursor,
+        text: insert,
+        anchor: position,
+        cursor: position,
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Default,
+    Left,
+    Center,
+    Right,
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix('|').unwrap_or(trimmed);
+    // «\|» внутри ячейки — это символ, а не граница
+    let mut cells = vec![String::new()];
+    let mut escaped = false;
+    for c in trimmed.chars() {
+        if c == '|' && !escaped {
+            cells.push(String::new());
+        } else if let Some(cell) = cells.last_mut() {
+            cell.push(c);
+        }
+        escaped = c == '\\';
+    }
+    cells.iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+fn separator_align(cell: &str) -> Option<Align> {
+    let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+    if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+        return None;
+    }
+    Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+        (true, true) => Align::Center,
+        (true, false) => Align::Left,
+        (false, true) => Align::Right,
+        (false, false) => Align::Default,
+    })
+}
+
+fn pad(cell: &str, width: usize, align: Align) -> String {
+    let space = width - cell.chars().count();
+    match align {
+        Align::Right => format!("{}{}", " ".repeat(space), cell),
+        Align::Center => format!("{}{}{}", " ".repeat(space / 2), cell, " ".repeat(space - space / 2)),
+        _ => format!("{}{}", cell, " ".repeat(space)),
+    }
+}
+
+// Таблица — подряд идущие строки с «|» вокруг курсора; столбцы выравниваются по самой широкой ячейке
+fn format_table(text: &[char], cursor: usize) -> Option<LineEdit> {
+    let lines: Vec<String> = text
+        .iter()
+        .collect::<String>()
+        .split('\n')
+        .map(str::to_string)
+        .collect();
+    let current = text[..cursor].iter().filter(|&&c| c == '\n').count();
+    let is_row = |index: usize| lines[index].contains('|');
+    if !is_row(current) {
+        return None;
+    }
+    let mut first = current;
+    while first > 0 && is_row(first - 1) {
+        first -= 1;
+    }
+    let mut last = current;
+    while last + 1 < lines.len() && is_row(last + 1) {
+        last += 1;
+    }
+
+    let rows: Vec<Vec<String>> = lines[first..=last].iter().map(|line| table_cells(line)).collect();
+    let columns = rows.iter().map(Vec::len).max()?;
+    let separator = rows
+        .iter()
+        .position(|row| row.iter().all(|cell| separator_align(cell).is_some()));
+    let aligns: Vec<Align> = (0..columns)
+        .map(|column| {
+            separator
+                .and_then(|index| rows[index].get(column))
+                .and_then(|cell| separator_align(cell))
+                .unwrap_or(Align::Default)
+        })
+        .collect();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .enumerate()
+                .filter(|(index, _)| Some(*index) != separator)
+                .filter_map(|(_, row)| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+
+    let formatted: Vec<String> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let cells: Vec<String> = (0..columns)
+                .map(|column| {
+                    let width = widths[column];
+                    if Some(index) == separator {
+                        let dashes = match aligns[column] {
+                            Align::Default => "-".repeat(width),
+                            Align::Left => format!(":{}", "-".repeat(width - 1)),
+                            Align::Right => format!("{}:", "-".repeat(width - 1)),
+                            Align::Center => format!(":{}:", "-".repeat(width - 2)),
+                        };
+                        return dashes;
+                    }
+                    pad(row.get(column).map_or("", String::as_str), width, aligns[column])
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        })
+        .collect();
+
+    let start = lines[..first].iter().map(|line| line.chars().count() + 1).sum();
+    let end = start
+        + lines[first..=last]
+            .iter()
+            .map(|line| line.chars().count())
+            .sum::<usize>()
+        + (last - first);
+    let replacement = formatted.join("\n");
+    // Курсор — в начало той же строки таблицы
+    let position = start
+        + formatted[..current - first]
+            .iter()
+            .map(|line| line.chars().count() + 1)
+            .sum::<usize>();
+    Some(LineEdit {
+        start,
+        end,
+        text: replacement,
+        anchor: position,
+        cursor: position,
+    })
+}
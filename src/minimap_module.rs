@@ -0,0 +1,137 @@
+// minimap_module.rs
+use eframe::egui;
+
+use crate::theme_module::Palette;
+
+const MINIMAP_WIDTH: f32 = 90.0;
+// Высота строки на миникарте; длинный документ сжимается, чтобы поместиться целиком
+const MAX_LINE_HEIGHT: f32 = 3.0;
+// Ширина одного символа на миникарте
+const CHAR_WIDTH: f32 = 1.0;
+
+pub struct MinimapModule {
+    pub show_panel: bool,
+}
+
+// Положение прокрутки основного текста: смещение, высота видимой части и всего содержимого
+#[derive(Default, Clone, Copy)]
+pub struct Viewport {
+    pub offset: f32,
+    pub visible_height: f32,
+    pub content_height: f32,
+}
+
+pub enum MinimapResult {
+    None,
+    ScrollTo(f32), // Доля документа (0..1), которую нужно показать в центре экрана
+}
+
+impl Default for MinimapModule {
+    fn default() -> Self {
+        Self { show_panel: true }
+    }
+}
+
+impl MinimapModule {
+    pub fn show_panel(
+        &mut self,
+        ctx: &egui::Context,
+        text: &str,
+        matches: &[(usize, usize)],
+        viewport: Viewport,
+        palette: &Palette,
+    ) -> MinimapResult {
+        if !self.show_panel {
+            return MinimapResult::None;
+        }
+
+        let mut result = MinimapResult::None;
+
+        egui::SidePanel::right("minimap_panel")
+            .resizable(false)
+            .exact_width(MINIMAP_WIDTH)
+            .show(ctx, |ui| {
+                let rect = ui.available_rect_before_wrap();
+                let response = ui.allocate_rect(rect, egui::Sense::click_and_drag());
+                let painter = ui.painter_at(rect);
+
+                let lines: Vec<&str> = text.split('\n').collect();
+                let line_height = (rect.height() / lines.len() as f32).min(MAX_LINE_HEIGHT);
+                let document_height = line_height * lines.len() as f32;
+
+                // Несколько строк могут попасть в один пиксель: объединяем их в одну полосу
+                let mut buckets: Vec<Option<(usize, usize)>> = vec![None; rect.height().ceil() as usize + 1];
+                let mut line_starts = Vec::with_capacity(lines.len());
+                let mut char_index = 0;
+                for (index, line) in lines.iter().enumerate() {
+                    line_starts.push(char_index);
+                    char_index += line.chars().count() + 1;
+
+                    let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+                    let end = line.trim_end().chars().count();
+                    if end <= indent {
+                        continue;
+                    }
+                    let bucket = (index as f32 * line_height) as usize;
+                    if let Some(slot) = buckets.get_mut(bucket) {
+                        *slot = Some(match *slot {
+                            Some((start, stop)) => (start.min(indent), stop.max(end)),
+                            None => (indent, end),
+                        });
+                    }
+                }
+
+                let text_color = palette.text_color().gamma_multiply(0.5);
+                let bar_height = line_height.max(1.0);
+                for (y, bucket) in buckets.iter().enumerate() {
+                    if let Some((start, end)) = bucket {
+                        let left = rect.left() + 2.0 + *start as f32 * CHAR_WIDTH;
+                        let right = (rect.left() + 2.0 + *end as f32 * CHAR_WIDTH).min(rect.right() - 2.0);
+                        painter.rect_filled(
+                            egui::Rect::from_min_max(
+                                egui::pos2(left, rect.top() + y as f32),
+                                egui::pos2(right, rect.top() + y as f32 + bar_height),
+                            ),
+                            egui::Rounding::ZERO,
+                            text_color,
+                        );
+                    }
+                }
+
+                // Совпадения поиска — метки у правого края
+                let match_color = palette.match_color(true);
+                for &(start, _) in matches {
+                    let line = line_starts.partition_point(|&s| s <= start).saturating_sub(1);
+                    let y = rect.top() + line as f32 * line_height;
+                    painter.rect_filled(
+                        egui::Rect::from_min_max(
+                            egui::pos2(rect.right() - 6.0, y),
+                            egui::pos2(rect.right(), y + bar_height.max(2.0)),
+                        ),
+                        egui::Rounding::ZERO,
+                        match_color,
+                    );
+                }
+
+                // Видимая часть документа
+                if viewport.content_height > 0.0 {
+                    let top = viewport.offset / viewport.content_height;
+                    let bottom = (viewport.offset + viewport.visible_height) / viewport.content_height;
+                    let visible = egui::Rect::from_min_max(
+                        egui::pos2(rect.left(), rect.top() + top * document_height),
+                        egui::pos2(rect.right(), rect.top() + bottom.min(1.0) * document_height),
+                    );
+                    painter.rect_filled(visible, egui::Rounding::same(2.0), ui.visuals().selection.bg_fill.gamma_multiply(0.3));
+                }
+
+                if response.clicked() || response.dragged() {
+                    if let Some(pointer) = response.interact_pointer_pos() {
+                        let fraction = ((pointer.y - rect.top()) / document_height.max(1.0)).clamp(0.0, 1.0);
+                        result = MinimapResult::ScrollTo(fraction);
+                    }
+                }
+            });
+
+        result
+    }
+}
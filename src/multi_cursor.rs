@@ -0,0 +1,157 @@
+// multi_cursor.rs
+use std::sync::atomic::AtomicBool;
+
+use crate::search_module::find_matches_cancellable;
+
+// Несколько выделений сразу: набор текста, Backspace и Delete применяются к каждому.
+// Основное выделение — последнее добавленное, оно же выделение текстового поля
+#[derive(Default)]
+pub struct MultiCursor {
+    ranges: Vec<(usize, usize)>, // По возрастанию, без пересечений
+    primary: usize,
+    whole_word: bool, // Начали со слова под курсором — следующие вхождения тоже целыми словами
+}
+
+// Правка одного выделения при наборе
+pub enum MultiEdit {
+    Insert(String),
+    Backspace,
+    Delete,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_whole_word(text: &[char], start: usize, end: usize) -> bool {
+    let before = start.checked_sub(1).map(|i| text[i]);
+    let after = text.get(end).copied();
+    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+}
+
+impl MultiCursor {
+    pub fn is_active(&self) -> bool {
+        self.ranges.len() > 1
+    }
+
+    pub fn primary(&self) -> Option<(usize, usize)> {
+        self.ranges.get(self.primary).copied()
+    }
+
+    // Все выделения, кроме основного: их рисуем сами, основное рисует текстовое поле
+    pub fn secondary(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let primary = self.primary;
+        self.ranges
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| *index != primary)
+            .map(|(_, range)| *range)
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+        self.primary = 0;
+    }
+
+    // Первое нажатие без выделения берет слово под курсором, дальше — следующее вхождение
+    pub fn select_next(
+        &mut self,
+        text: &[char],
+        selection: Option<(usize, usize)>,
+        cursor: usize,
+    ) -> Option<(usize, usize)> {
+        // Выделение поменяли мышью или клавишами — начинаем заново от него
+        let current = selection.unwrap_or((cursor, cursor));
+        if self.primary() != Some(current) {
+            self.clear();
+            let (start, end) = match selection {
+                Some(range) => {
+                    self.whole_word = false;
+                    range
+                }
+                None => {
+                    let start = text[..cursor]
+                        .iter()
+                        .rposition(|&c| !is_word_char(c))
+                        .map_or(0, |i| i + 1);
+                    let end = text[cursor..]
+                        .iter()
+                        .position(|&c| !is_word_char(c))
+                        .map_or(text.len(), |i| cursor + i);
+                    self.whole_word = true;
+                    (start, end)
+                }
+            };
+            if start == end {
+                return None;
+            }
+            self.ranges.push((start, end));
+            return Some((start, end));
+        }
+        self.add_next(text)
+    }
+
+    // Текущее основное вхождение пропускаем: убираем его и берем следующее
+    pub fn skip(&mut self, text: &[char]) -> Option<(usize, usize)> {
+        if self.ranges.len() < 2 {
+            return None;
+        }
+        let (start, _) = self.primary()?;
+        let next = self.add_next(text)?;
+        if let Some(index) = self.ranges.iter().position(|&(s, _)| s == start) {
+            self.ranges.remove(index);
+            self.primary = self.ranges.iter().position(|&range| range == next).unwrap_or(0);
+        }
+        Some(next)
+    }
+
+    // Поиск тем же сопоставлением, что и в панели поиска, с учетом регистра; после конца — с начала текста
+    fn add_next(&mut self, text: &[char]) -> Option<(usize, usize)> {
+        let (start, end) = self.primary()?;
+        let pattern: String = text[start..end].iter().collect();
+        let source: String = text.iter().collect();
+        let matches = find_matches_cancellable(&source, &pattern, true, &AtomicBool::new(false))?;
+        let free = |&&(s, e): &&(usize, usize)| {
+            (!self.whole_word || is_whole_word(text, s, e)) && !self.ranges.iter().any(|&(rs, re)| s < re && rs < e)
+        };
+        let next = matches
+            .iter()
+            .filter(free)
+            .find(|&&(s, _)| s >= end)
+            .or_else(|| matches.iter().find(free))
+            .copied()?;
+        let index = self.ranges.partition_point(|&(s, _)| s < next.0);
+        self.ranges.insert(index, next);
+        self.primary = index;
+        Some(next)
+    }
+
+    // Правки от начала к концу: каждая сдвигает следующие выделения на свою разницу в длине.
+    // Возвращает замены (start, end, текст) в координатах уже измененного текста
+    pub fn apply(&mut self, text: &[char], edit: &MultiEdit) -> Vec<(usize, usize, String)> {
+        let mut replacements = Vec::new();
+        let mut offset: isize = 0;
+        let mut previous_end = 0;
+        for range in self.ranges.iter_mut() {
+            let (start, end) = *range;
+            let (from, to, insert) = match edit {
+                MultiEdit::Insert(typed) => (start, end, typed.clone()),
+                _ if start != end => (start, end, String::new()),
+                MultiEdit::Backspace if start > previous_end => (start - 1, end, String::new()),
+                MultiEdit::Delete if end < text.len() => (start, end + 1, String::new()),
+                _ => (start, end, String::new()),
+            };
+            previous_end = to;
+            let shifted = (from as isize + offset) as usize;
+            replacements.push((shifted, (to as isize + offset) as usize, insert.clone()));
+            let position = shifted + insert.chars().count();
+            *range = (position, position);
+            offset += insert.chars().count() as isize - (to - from) as isize;
+        }
+        // Курсоры, сошедшиеся в одной точке, сливаются
+        let primary = self.ranges[self.primary];
+        self.ranges.dedup();
+        self.primary = self.ranges.iter().position(|&range| range == primary).unwrap_or(0);
+        replacements
+    }
+}
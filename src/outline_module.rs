@@ -0,0 +1,92 @@
+// outline_module.rs
+use eframe::egui;
+
+use crate::i18n::tr;
+
+// Заголовок «# …» … «###### …» и номер его строки (с нуля)
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    pub line: usize,
+}
+
+#[derive(Default)]
+pub struct OutlineModule {
+    pub show_panel: bool,
+}
+
+pub enum OutlineResult {
+    None,
+    GoToLine(usize),
+}
+
+// Заголовки Markdown; строки внутри блоков кода ``` не считаются
+pub fn headings(text: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut in_code = false;
+    for (line, content) in text.split('\n').enumerate() {
+        let trimmed = content.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let level = content.chars().take_while(|&c| c == '#').count();
+        let rest = &content[level..];
+        if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t'])) {
+            let title = rest.trim().trim_end_matches('#').trim_end().to_string();
+            headings.push(Heading { level, title, line });
+        }
+    }
+    headings
+}
+
+impl OutlineModule {
+    pub fn show_panel(&mut self, ctx: &egui::Context, text: &str, current_line: usize) -> OutlineResult {
+        if !self.show_panel {
+            return OutlineResult::None;
+        }
+
+        let mut result = OutlineResult::None;
+        let mut open = self.show_panel;
+        let headings = headings(text);
+        // Раздел, в котором стоит курсор, — последний заголовок не ниже текущей строки
+        let current = headings.iter().rposition(|heading| heading.line <= current_line);
+
+        egui::Window::new(tr!("Структура документа"))
+            .open(&mut open)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                if headings.is_empty() {
+                    ui.label(tr!(
+                        "Заголовков нет. Строки, начинающиеся с # , ## , ### …, попадают в структуру"
+                    ));
+                    return;
+                }
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (index, heading) in headings.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0 * (heading.level - 1) as f32);
+                            let title = if heading.title.is_empty() {
+                                "…"
+                            } else {
+                                heading.title.as_str()
+                            };
+                            if ui.selectable_label(current == Some(index), title).clicked() {
+                                result = OutlineResult::GoToLine(heading.line);
+                            }
+                        });
+                    }
+                });
+            });
+
+        if !open {
+            self.show_panel = false;
+        }
+
+        result
+    }
+}
@@ -0,0 +1,163 @@
+// placeholder_module.rs
+use eframe::egui;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::i18n::tr;
+
+const LOREM_START: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+const LOREM_WORDS: &str = "\
+    lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor incididunt ut labore et \
+    dolore magna aliqua enim ad minim veniam quis nostrud exercitation ullamco laboris nisi aliquip ex \
+    ea commodo consequat duis aute irure in reprehenderit voluptate velit esse cillum fugiat nulla \
+    pariatur excepteur sint occaecat cupidatat non proident sunt culpa qui officia deserunt mollit anim \
+    id est laborum vitae nunc";
+
+// «Рыба» — русский пересказ того же текста-заполнителя
+const FISH_START: &str = "Далеко-далеко за словесными горами в стране гласных и согласных живут рыбные тексты.";
+const FISH_WORDS: &str = "\
+    вдали от всех живут они в буквенных домах на берегу семантика большого языкового океана маленький \
+    ручеек даль журчит по всей стране и обеспечивает ее всеми необходимыми правилами эта \
+    парадигматическая страна которой жареные члены предложения залетают прямо рот даже всемогущая \
+    пунктуация не имеет власти над рыбными текстами ведущими безорфографичный образ жизни однажды одна \
+    маленькая строчка рыбного текста решила выйти мир большой путь сквозь горы города";
+
+#[derive(PartialEq, Clone, Copy)]
+enum Kind {
+    Lorem,
+    Fish,
+}
+
+// Простой xorshift: тексту-заполнителю хватает, тянуть ради него rand незачем
+struct Random(u64);
+
+impl Random {
+    fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Random(nanos | 1)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+
+    fn between(&mut self, low: usize, high: usize) -> usize {
+        low + self.below(high - low + 1)
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+// Предложение из 5–12 слов с редкими запятыми
+fn sentence(words: &[&str], random: &mut Random) -> String {
+    let count = random.between(5, 12);
+    let mut sentence = capitalize(words[random.below(words.len())]);
+    for index in 1..count {
+        if index > 2 && index + 1 < count && random.below(6) == 0 {
+            sentence.push(',');
+        }
+        sentence.push(' ');
+        sentence.push_str(words[random.below(words.len())]);
+    }
+    sentence.push('.');
+    sentence
+}
+
+// Абзацы по 3–6 предложений через пустую строку
+fn generate(kind: Kind, paragraphs: usize, classic_start: bool) -> String {
+    let (start, words) = match kind {
+        Kind::Lorem => (LOREM_START, LOREM_WORDS),
+        Kind::Fish => (FISH_START, FISH_WORDS),
+    };
+    let words: Vec<&str> = words.split_whitespace().collect();
+    let mut random = Random::new();
+    let mut text = Vec::with_capacity(paragraphs);
+    for index in 0..paragraphs {
+        let mut sentences = Vec::new();
+        if index == 0 && classic_start {
+            sentences.push(start.to_string());
+        }
+        for _ in 0..random.between(3, 6) {
+            sentences.push(sentence(&words, &mut random));
+        }
+        text.push(sentences.join(" "));
+    }
+    text.join("\n\n")
+}
+
+pub struct PlaceholderModule {
+    pub show_window: bool,
+    kind: Kind,
+    paragraphs: usize,
+    classic_start: bool, // Первый абзац начинается с привычной фразы
+}
+
+impl Default for PlaceholderModule {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            kind: Kind::Fish,
+            paragraphs: 3,
+            classic_start: true,
+        }
+    }
+}
+
+pub enum PlaceholderResult {
+    None,
+    Insert(String),
+}
+
+impl PlaceholderModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> PlaceholderResult {
+        let mut result = PlaceholderResult::None;
+        if !self.show_window {
+            return result;
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Текст-заполнитель"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.kind, Kind::Fish, tr!("Рыба-текст"));
+                    ui.radio_value(&mut self.kind, Kind::Lorem, "Lorem ipsum");
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Абзацев:"));
+                    ui.add(egui::DragValue::new(&mut self.paragraphs).clamp_range(1..=100));
+                });
+                let start = match self.kind {
+                    Kind::Lorem => LOREM_START,
+                    Kind::Fish => FISH_START,
+                };
+                ui.checkbox(&mut self.classic_start, tr!("Начать с привычной фразы"))
+                    .on_hover_text(start);
+                ui.separator();
+                if ui.button(tr!("Вставить")).clicked() {
+                    result = PlaceholderResult::Insert(generate(self.kind, self.paragraphs, self.classic_start));
+                }
+            });
+
+        if !open || matches!(result, PlaceholderResult::Insert(_)) {
+            self.show_window = false;
+        }
+        result
+    }
+}
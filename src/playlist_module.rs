@@ -0,0 +1,359 @@
+// playlist_module.rs
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey};
+use symphonia::core::probe::Hint;
+
+use crate::i18n::tr;
+
+// Музыка, которая играет, пока папка с музыкой не выбрана
+const DEFAULT_TRACKS: [&str; 5] = [
+    "assets/theme.mp3",
+    "assets/music.mp3",
+    "assets/music.wav",
+    "music.mp3",
+    "music.wav",
+];
+
+pub const MUSIC_EXTENSIONS: [&str; 7] = ["mp3", "wav", "ogg", "oga", "flac", "m4a", "aac"];
+
+#[derive(Default)]
+pub struct PlaylistModule {
+    pub show_panel: bool,
+    pub tracks: Vec<PathBuf>,
+    pub current: Option<usize>,
+    pub shuffle: bool,
+    pub repeat: bool, // После последнего трека начинать сначала
+    pub show_playback_bar: bool,
+    seek_drag: Option<f32>, // Позиция ползунка (в секундах), пока его тянут
+}
+
+// Теги трека; чего нет в файле — None
+#[derive(Default, Clone)]
+pub struct TrackInfo {
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl TrackInfo {
+    // «Исполнитель — Название», а без тегов — имя файла
+    pub fn display_name(&self, path: &Path) -> String {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => format!("{} — {}", artist, title),
+            (None, Some(title)) => title.clone(),
+            _ => PlaylistModule::track_name(path),
+        }
+    }
+}
+
+// Сколько трек уже играет; rodio позицию не сообщает, поэтому считаем сами
+#[derive(Default)]
+pub struct PlaybackClock {
+    started: Option<Instant>, // Когда продолжили играть в последний раз; None — на паузе
+    elapsed: Duration,        // Сыграно до последней паузы
+}
+
+impl PlaybackClock {
+    pub fn start(&mut self) {
+        self.start_at(Duration::ZERO);
+    }
+
+    pub fn start_at(&mut self, position: Duration) {
+        self.elapsed = position;
+        self.started = Some(Instant::now());
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.elapsed += started.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        self.started.get_or_insert_with(Instant::now);
+    }
+
+    pub fn reset(&mut self) {
+        self.started = None;
+        self.elapsed = Duration::ZERO;
+    }
+
+    pub fn position(&self) -> Duration {
+        self.elapsed + self.started.map(|s| s.elapsed()).unwrap_or_default()
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+pub enum PlaylistResult {
+    None,
+    Play(usize),
+    TogglePlayback,
+    Next,
+    Previous,
+    ChooseFile,
+    ChooseFolder,
+    OptionsChanged,
+    Seek(Duration),
+}
+
+impl PlaylistModule {
+    // source — папка с музыкой или отдельный файл
+    pub fn load(&mut self, source: Option<&Path>) {
+        self.tracks = match source {
+            Some(file) if file.is_file() => vec![file.to_path_buf()],
+            Some(folder) => scan_folder(folder),
+            None => DEFAULT_TRACKS
+                .iter()
+                .map(PathBuf::from)
+                .filter(|path| path.exists())
+                .collect(),
+        };
+        self.current = None;
+    }
+
+    pub fn track_name(path: &Path) -> String {
+        path.file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string())
+    }
+
+    pub fn next_index(&self) -> Option<usize> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        if self.shuffle && self.tracks.len() > 1 {
+            // Случайный трек, но не тот же самый
+            let offset = 1 + random_number() % (self.tracks.len() - 1);
+            return Some((self.current.unwrap_or(0) + offset) % self.tracks.len());
+        }
+        match self.current {
+            None => Some(0),
+            Some(index) if index + 1 < self.tracks.len() => Some(index + 1),
+            Some(_) if self.repeat => Some(0),
+            Some(_) => None,
+        }
+    }
+
+    pub fn previous_index(&self) -> Option<usize> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        match self.current {
+            Some(index) if index > 0 => Some(index - 1),
+            _ => Some(self.tracks.len() - 1),
+        }
+    }
+
+    pub fn show_panel(&mut self, ctx: &egui::Context, source: Option<&Path>, playing: bool) -> PlaylistResult {
+        if !self.show_panel {
+            return PlaylistResult::None;
+        }
+
+        let mut result = PlaylistResult::None;
+        let mut open = self.show_panel;
+
+        egui::Window::new(tr!("Плейлист"))
+            .open(&mut open)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("📁 Папка...")).clicked() {
+                        result = PlaylistResult::ChooseFolder;
+                    }
+                    if ui.button(tr!("🎵 Файл...")).clicked() {
+                        result = PlaylistResult::ChooseFile;
+                    }
+                    let source_label = source
+                        .map(|f| f.display().to_string())
+                        .unwrap_or_else(|| tr!("Встроенная музыка").to_string());
+                    ui.label(source_label);
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("⏮").on_hover_text(tr!("Предыдущий трек")).clicked() {
+                        result = PlaylistResult::Previous;
+                    }
+                    let play_icon = if playing { "⏸" } else { "▶" };
+                    if ui.button(play_icon).clicked() {
+                        result = PlaylistResult::TogglePlayback;
+                    }
+                    if ui.button("⏭").on_hover_text(tr!("Следующий трек")).clicked() {
+                        result = PlaylistResult::Next;
+                    }
+                    ui.separator();
+                    if ui.toggle_value(&mut self.shuffle, "🔀").on_hover_text(tr!("Случайный порядок")).changed() {
+                        result = PlaylistResult::OptionsChanged;
+                    }
+                    if ui.toggle_value(&mut self.repeat, "🔁").on_hover_text(tr!("Повторять плейлист")).changed() {
+                        result = PlaylistResult::OptionsChanged;
+                    }
+                });
+
+                ui.separator();
+
+                if self.tracks.is_empty() {
+                    ui.label(tr!("В папке нет музыки"));
+                }
+
+                egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                    for (index, track) in self.tracks.iter().enumerate() {
+                        let is_current = self.current == Some(index);
+                        if ui.selectable_label(is_current, Self::track_name(track)).double_clicked() {
+                            result = PlaylistResult::Play(index);
+                        }
+                    }
+                });
+                ui.label(tr!("Двойной щелчок — играть трек"));
+            });
+
+        if !open {
+            self.show_panel = false;
+        }
+
+        result
+    }
+
+    // Полоса над строкой состояния: кнопки, время и ползунок перемотки
+    pub fn show_playback_bar(
+        &mut self,
+        ctx: &egui::Context,
+        position: Duration,
+        duration: Option<Duration>,
+        playing: bool,
+    ) -> PlaylistResult {
+        if !self.show_playback_bar {
+            return PlaylistResult::None;
+        }
+
+        let mut result = PlaylistResult::None;
+
+        egui::TopBottomPanel::bottom("playback_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.small_button("⏮").on_hover_text(tr!("Предыдущий трек (F7)")).clicked() {
+                    result = PlaylistResult::Previous;
+                }
+                let play_icon = if playing { "⏸" } else { "▶" };
+                if ui.small_button(play_icon).on_hover_text(tr!("Играть/пауза (F8)")).clicked() {
+                    result = PlaylistResult::TogglePlayback;
+                }
+                if ui.small_button("⏭").on_hover_text(tr!("Следующий трек (F9)")).clicked() {
+                    result = PlaylistResult::Next;
+                }
+
+                let total = duration.map(|d| d.as_secs_f32()).unwrap_or(0.0);
+                let mut seconds = self.seek_drag.unwrap_or(position.as_secs_f32()).min(total);
+                ui.monospace(format_duration(Duration::from_secs_f32(seconds)));
+
+                // Ползунок занимает все место, кроме подписи с длительностью справа
+                ui.spacing_mut().slider_width = (ui.available_width() - 60.0).max(50.0);
+                let response = ui
+                    .add_enabled(total > 0.0, egui::Slider::new(&mut seconds, 0.0..=total.max(1.0)).show_value(false))
+                    .on_hover_text(tr!("Перемотка: Shift + F7 / Shift + F9"));
+                if response.changed() {
+                    self.seek_drag = Some(seconds);
+                }
+                // Перематываем, когда ползунок отпустили, а не на каждом кадре перетаскивания
+                if response.drag_stopped() || response.clicked() {
+                    if let Some(target) = self.seek_drag.take() {
+                        result = PlaylistResult::Seek(Duration::from_secs_f32(target));
+                    }
+                }
+
+                ui.monospace(duration.map(format_duration).unwrap_or_else(|| "-:--".to_string()));
+            });
+        });
+
+        result
+    }
+}
+
+fn scan_folder(folder: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+
+    let mut tracks: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| MUSIC_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .collect();
+    tracks.sort();
+    tracks
+}
+
+// ID3, Vorbis comments и теги MP4 читает symphonia; звук при этом не декодируется
+pub fn read_track_info(path: &Path) -> TrackInfo {
+    let mut info = TrackInfo::default();
+    let Ok(file) = fs::File::open(path) else {
+        return info;
+    };
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let Ok(mut probed) = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) else {
+        return info;
+    };
+
+    // ID3v2 лежит перед контейнером, остальные теги — внутри него
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        apply_tags(&mut info, revision);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_tags(&mut info, revision);
+    }
+
+    if let Some(track) = probed.format.default_track() {
+        let params = &track.codec_params;
+        if let (Some(time_base), Some(frames)) = (params.time_base, params.n_frames) {
+            let time = time_base.calc_time(frames);
+            info.duration = Some(Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac));
+        } else if let (Some(rate), Some(frames)) = (params.sample_rate, params.n_frames) {
+            info.duration = Some(Duration::from_secs_f64(frames as f64 / rate as f64));
+        }
+    }
+
+    info
+}
+
+fn apply_tags(info: &mut TrackInfo, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        let value = tag.value.to_string().trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match tag.std_key {
+            Some(StandardTagKey::Artist) => info.artist = Some(value),
+            Some(StandardTagKey::TrackTitle) => info.title = Some(value),
+            _ => {}
+        }
+    }
+}
+
+// Для перемешивания хватает наносекунд текущего времени
+fn random_number() -> usize {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as usize)
+        .unwrap_or(0)
+}
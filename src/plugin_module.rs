@@ -0,0 +1,224 @@
+// plugin_module.rs
+use rhai::{CallFnOptions, Engine, Scope, AST};
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::config::config_dir;
+use crate::i18n::tr;
+
+const PLUGINS_DIR: &str = "plugins";
+// Скрипт с бесконечным циклом не должен подвесить редактор
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+// Папка со скриптами *.rhai
+pub fn plugins_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(PLUGINS_DIR))
+}
+
+// Команда, которую скрипт зарегистрировал через register_command("Название", "функция")
+pub struct PluginCommand {
+    pub title: String,
+    script: usize,
+    function: String,
+}
+
+// Что видит скрипт: текст, выделение (в символах) и сообщение для строки состояния
+#[derive(Default)]
+struct ScriptState {
+    text: String,
+    anchor: usize,
+    cursor: usize,
+    text_changed: bool,
+    cursor_changed: bool,
+    status: Option<String>,
+    registered: Vec<(String, String)>,
+}
+
+impl ScriptState {
+    fn selection(&self) -> (usize, usize) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_index)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text.len())
+    }
+
+    fn replace_selection(&mut self, replacement: &str) {
+        let (start, end) = self.selection();
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.text.replace_range(range, replacement);
+        self.cursor = start + replacement.chars().count();
+        self.anchor = self.cursor;
+        self.text_changed = true;
+        self.cursor_changed = true;
+    }
+}
+
+// Результат команды: новый текст и выделение, если скрипт их менял
+pub struct PluginOutcome {
+    pub text: Option<String>,
+    pub selection: Option<(usize, usize)>, // (якорь, курсор)
+}
+
+#[derive(Default)]
+pub struct PluginModule {
+    engine: Option<Engine>,
+    scripts: Vec<(PathBuf, AST)>,
+    pub commands: Vec<PluginCommand>,
+    pub status_message: Option<String>,
+    state: Rc<RefCell<ScriptState>>,
+}
+
+fn create_engine(state: &Rc<RefCell<ScriptState>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let s = state.clone();
+    engine.register_fn("text", move || s.borrow().text.clone());
+    let s = state.clone();
+    engine.register_fn("set_text", move |text: &str| {
+        let mut state = s.borrow_mut();
+        state.text = text.to_string();
+        let length = state.text.chars().count();
+        state.cursor = state.cursor.min(length);
+        state.anchor = state.anchor.min(length);
+        state.text_changed = true;
+    });
+    let s = state.clone();
+    engine.register_fn("selection", move || {
+        let state = s.borrow();
+        let (start, end) = state.selection();
+        state.text.chars().skip(start).take(end - start).collect::<String>()
+    });
+    let s = state.clone();
+    engine.register_fn("replace_selection", move |text: &str| s.borrow_mut().replace_selection(text));
+    let s = state.clone();
+    engine.register_fn("insert", move |text: &str| {
+        let mut state = s.borrow_mut();
+        state.anchor = state.cursor;
+        state.replace_selection(text);
+    });
+    let s = state.clone();
+    engine.register_fn("cursor", move || s.borrow().cursor as i64);
+    let s = state.clone();
+    engine.register_fn("set_cursor", move |position: i64| {
+        let mut state = s.borrow_mut();
+        let position = (position.max(0) as usize).min(state.text.chars().count());
+        state.cursor = position;
+        state.anchor = position;
+        state.cursor_changed = true;
+    });
+    let s = state.clone();
+    engine.register_fn("select", move |start: i64, end: i64| {
+        let mut state = s.borrow_mut();
+        let length = state.text.chars().count();
+        state.anchor = (start.max(0) as usize).min(length);
+        state.cursor = (end.max(0) as usize).min(length);
+        state.cursor_changed = true;
+    });
+    let s = state.clone();
+    engine.register_fn("status", move |message: &str| s.borrow_mut().status = Some(message.to_string()));
+    let s = state.clone();
+    engine.register_fn("register_command", move |title: &str, function: &str| {
+        s.borrow_mut().registered.push((title.to_string(), function.to_string()));
+    });
+
+    engine
+}
+
+impl PluginModule {
+    // Компилирует все скрипты папки и выполняет их верхний уровень, где вызывается register_command.
+    // Ошибки отдельных скриптов не мешают остальным
+    pub fn load(&mut self) -> Vec<String> {
+        self.scripts.clear();
+        self.commands.clear();
+        let state = self.state.clone();
+        let engine = self.engine.get_or_insert_with(|| create_engine(&state));
+
+        let Some(dir) = plugins_dir() else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "rhai"))
+            .collect();
+        paths.sort();
+
+        let mut errors = Vec::new();
+        for path in paths {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let ast = match fs::read_to_string(&path) {
+                Ok(source) => engine.compile(source).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            let ast = match ast {
+                Ok(ast) => ast,
+                Err(e) => {
+                    errors.push(tr!("Ошибка в плагине {}: {}", name, e));
+                    continue;
+                }
+            };
+
+            self.state.borrow_mut().registered.clear();
+            if let Err(e) = engine.run_ast_with_scope(&mut Scope::new(), &ast) {
+                errors.push(tr!("Ошибка в плагине {}: {}", name, e));
+                continue;
+            }
+            let script = self.scripts.len();
+            for (title, function) in self.state.borrow_mut().registered.drain(..) {
+                self.commands.push(PluginCommand { title, script, function });
+            }
+            self.scripts.push((path, ast));
+        }
+        errors
+    }
+
+    pub fn command_titles(&self) -> Vec<String> {
+        self.commands.iter().map(|c| c.title.clone()).collect()
+    }
+
+    // anchor и cursor — концы выделения в символах (совпадают, если выделения нет)
+    pub fn run(&mut self, index: usize, text: &str, anchor: usize, cursor: usize) -> Result<PluginOutcome, String> {
+        let Some(command) = self.commands.get(index) else {
+            return Err(tr!("Команда плагина не найдена").to_string());
+        };
+        let Some(engine) = &self.engine else {
+            return Err(tr!("Команда плагина не найдена").to_string());
+        };
+        let (path, ast) = &self.scripts[command.script];
+
+        *self.state.borrow_mut() = ScriptState {
+            text: text.to_string(),
+            anchor,
+            cursor,
+            ..Default::default()
+        };
+        // Верхний уровень скрипта уже выполнен при загрузке — повторно не запускаем
+        let options = CallFnOptions::new().eval_ast(false);
+        let result = engine.call_fn_with_options::<rhai::Dynamic>(options, &mut Scope::new(), ast, &command.function, ());
+
+        let mut state = self.state.borrow_mut();
+        if let Some(status) = state.status.take() {
+            self.status_message = Some(status);
+        }
+        if let Err(e) = result {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            return Err(tr!("Ошибка в плагине {}: {}", name, e));
+        }
+
+        Ok(PluginOutcome {
+            text: state.text_changed.then(|| std::mem::take(&mut state.text)),
+            selection: state.cursor_changed.then_some((state.anchor, state.cursor)),
+        })
+    }
+}
@@ -0,0 +1,237 @@
+// pomodoro_module.rs
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::playlist_module::format_duration;
+
+// Сколько секунд висит всплывающее сообщение о смене периода
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PomodoroPhase {
+    Focus,
+    Break,
+}
+
+#[derive(Default)]
+pub struct PomodoroModule {
+    pub show_panel: bool,
+    phase: Option<PomodoroPhase>, // None — таймер не запущен
+    phase_ends: Option<Instant>,  // None — на паузе
+    remaining: Duration,          // Сколько оставалось в момент паузы
+    completed: u32,               // Завершенных рабочих периодов за сеанс
+    toast: Option<(String, Instant)>,
+}
+
+#[derive(PartialEq)]
+pub enum PomodoroResult {
+    None,
+    FocusStarted,
+    BreakStarted,
+    ConfigChanged,
+}
+
+impl PomodoroModule {
+    pub fn toggle_panel(&mut self) {
+        self.show_panel = !self.show_panel;
+    }
+
+    fn phase_length(phase: PomodoroPhase, config: &Config) -> Duration {
+        let minutes = match phase {
+            PomodoroPhase::Focus => config.pomodoro_focus_minutes,
+            PomodoroPhase::Break => config.pomodoro_break_minutes,
+        };
+        Duration::from_secs(minutes.max(1) as u64 * 60)
+    }
+
+    fn start_phase(&mut self, phase: PomodoroPhase, config: &Config) -> PomodoroResult {
+        self.phase = Some(phase);
+        self.phase_ends = Some(Instant::now() + Self::phase_length(phase, config));
+        match phase {
+            PomodoroPhase::Focus => PomodoroResult::FocusStarted,
+            PomodoroPhase::Break => PomodoroResult::BreakStarted,
+        }
+    }
+
+    fn remaining(&self) -> Duration {
+        match self.phase_ends {
+            Some(ends) => ends.saturating_duration_since(Instant::now()),
+            None => self.remaining,
+        }
+    }
+
+    fn pause(&mut self) {
+        self.remaining = self.remaining();
+        self.phase_ends = None;
+    }
+
+    fn resume(&mut self) {
+        self.phase_ends = Some(Instant::now() + self.remaining);
+    }
+
+    fn stop(&mut self) {
+        self.phase = None;
+        self.phase_ends = None;
+    }
+
+    // Для строки состояния: "🍅 24:13" во время работы, "☕ 4:59" в перерыв
+    pub fn status_label(&self) -> Option<String> {
+        let icon = match self.phase? {
+            PomodoroPhase::Focus => "🍅",
+            PomodoroPhase::Break => "☕",
+        };
+        let paused = if self.phase_ends.is_none() { tr!(" (пауза)") } else { "" };
+        Some(format!("{} {}{}", icon, format_duration(self.remaining()), paused))
+    }
+
+    // Переключает период, когда время вышло; вызывается каждый кадр
+    pub fn update(&mut self, ctx: &egui::Context, config: &Config) -> PomodoroResult {
+        self.show_toast(ctx);
+
+        let (Some(phase), Some(ends)) = (self.phase, self.phase_ends) else {
+            return PomodoroResult::None;
+        };
+        if Instant::now() < ends {
+            ctx.request_repaint_after(Duration::from_secs(1));
+            return PomodoroResult::None;
+        }
+
+        let message = match phase {
+            PomodoroPhase::Focus => {
+                self.completed += 1;
+                tr!("Время отдохнуть: перерыв {} мин", config.pomodoro_break_minutes)
+            }
+            PomodoroPhase::Break => tr!("Перерыв окончен, за работу!").to_string(),
+        };
+        self.toast = Some((message, Instant::now()));
+        // Окно могли свернуть — просим систему привлечь внимание к нему
+        ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(
+            egui::UserAttentionType::Informational,
+        ));
+
+        let next = match phase {
+            PomodoroPhase::Focus => PomodoroPhase::Break,
+            PomodoroPhase::Break => PomodoroPhase::Focus,
+        };
+        self.start_phase(next, config)
+    }
+
+    fn show_toast(&mut self, ctx: &egui::Context) {
+        let Some((message, shown)) = &self.toast else {
+            return;
+        };
+        if shown.elapsed() > TOAST_DURATION {
+            self.toast = None;
+            return;
+        }
+
+        egui::Area::new(egui::Id::new("pomodoro_toast"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -48.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(egui::RichText::new(message.as_str()).size(16.0));
+                });
+            });
+        ctx.request_repaint_after(Duration::from_millis(500));
+    }
+
+    pub fn show_panel(&mut self, ctx: &egui::Context, config: &mut Config) -> PomodoroResult {
+        if !self.show_panel {
+            return PomodoroResult::None;
+        }
+
+        let mut result = PomodoroResult::None;
+        let mut open = self.show_panel;
+
+        egui::Window::new(tr!("Таймер помидоро"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let phase_title = match self.phase {
+                    None => tr!("Таймер остановлен"),
+                    Some(PomodoroPhase::Focus) => tr!("Работа"),
+                    Some(PomodoroPhase::Break) => tr!("Перерыв"),
+                };
+                let remaining = match self.phase {
+                    Some(_) => self.remaining(),
+                    None => Self::phase_length(PomodoroPhase::Focus, config),
+                };
+                ui.vertical_centered(|ui| {
+                    ui.label(phase_title);
+                    ui.label(egui::RichText::new(format_duration(remaining)).monospace().size(32.0));
+                    ui.label(tr!("Завершено помидоров: {}", self.completed));
+                });
+
+                ui.horizontal(|ui| {
+                    match (self.phase, self.phase_ends) {
+                        (None, _) => {
+                            if ui.button(tr!("▶ Начать")).clicked() {
+                                result = self.start_phase(PomodoroPhase::Focus, config);
+                            }
+                        }
+                        (Some(_), Some(_)) => {
+                            if ui.button(tr!("⏸ Пауза")).clicked() {
+                                self.pause();
+                            }
+                        }
+                        (Some(_), None) => {
+                            if ui.button(tr!("▶ Продолжить")).clicked() {
+                                self.resume();
+                            }
+                        }
+                    }
+                    if ui.add_enabled(self.phase.is_some(), egui::Button::new(tr!("⏹ Сбросить"))).clicked() {
+                        self.stop();
+                    }
+                    if let Some(phase) = self.phase {
+                        let skip_to = match phase {
+                            PomodoroPhase::Focus => PomodoroPhase::Break,
+                            PomodoroPhase::Break => PomodoroPhase::Focus,
+                        };
+                        if ui.button(tr!("⏭ Пропустить")).clicked() {
+                            result = self.start_phase(skip_to, config);
+                        }
+                    }
+                });
+
+                ui.separator();
+                egui::Grid::new("pomodoro_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr!("Работа:"));
+                    let focus = ui.add(
+                        egui::DragValue::new(&mut config.pomodoro_focus_minutes)
+                            .clamp_range(1..=180)
+                            .suffix(tr!(" мин")),
+                    );
+                    ui.end_row();
+
+                    ui.label(tr!("Перерыв:"));
+                    let rest = ui.add(
+                        egui::DragValue::new(&mut config.pomodoro_break_minutes)
+                            .clamp_range(1..=60)
+                            .suffix(tr!(" мин")),
+                    );
+                    ui.end_row();
+
+                    if (focus.changed() || rest.changed()) && result == PomodoroResult::None {
+                        result = PomodoroResult::ConfigChanged;
+                    }
+                });
+                if ui
+                    .checkbox(&mut config.pomodoro_music, tr!("Музыка во время работы, пауза в перерыв"))
+                    .changed()
+                    && result == PomodoroResult::None
+                {
+                    result = PomodoroResult::ConfigChanged;
+                }
+            });
+
+        if !open {
+            self.show_panel = false;
+        }
+
+        result
+    }
+}
@@ -0,0 +1,233 @@
+// quick_open_module.rs
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+// Ограничения, чтобы огромная папка не подвесила окно
+const MAX_FILES: usize = 50_000;
+const MAX_VISIBLE_RESULTS: usize = 50;
+
+#[derive(Default)]
+pub struct QuickOpenModule {
+    pub show: bool,
+    pub query: String,
+    root: PathBuf,
+    files: Vec<PathBuf>,          // Пути относительно `root`
+    filtered: Vec<usize>,         // Индексы в `files`, отсортированные по релевантности
+    selected: usize,
+    focus_query: bool,
+}
+
+pub enum QuickOpenResult {
+    None,
+    OpenFile(PathBuf),
+}
+
+impl QuickOpenModule {
+    pub fn open(&mut self, root: &Path) {
+        self.root = root.to_path_buf();
+        self.files.clear();
+        collect_files(root, root, &mut self.files);
+        self.files.sort();
+
+        self.show = true;
+        self.query.clear();
+        self.focus_query = true;
+        self.update_filter();
+    }
+
+    pub fn close(&mut self) {
+        self.show = false;
+        self.files.clear();
+        self.filtered.clear();
+    }
+
+    pub fn handle_shortcuts(&mut self, ctx: &egui::Context, workspace: Option<&Path>) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            if self.show {
+                self.close();
+            } else if let Some(root) = workspace {
+                self.open(root);
+            }
+        }
+    }
+
+    fn update_filter(&mut self) {
+        let mut scored: Vec<(usize, i32)> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(index, path)| {
+                fuzzy_score(&path.to_string_lossy(), &self.query).map(|score| (index, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| self.files[a.0].as_os_str().len().cmp(&self.files[b.0].as_os_str().len()))
+        });
+
+        self.filtered = scored.into_iter().map(|(index, _)| index).collect();
+        self.selected = 0;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> QuickOpenResult {
+        if !self.show {
+            return QuickOpenResult::None;
+        }
+
+        let mut result = QuickOpenResult::None;
+
+        // Клавиши забираем до того, как их получит поле ввода
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+
+        let visible = self.filtered.len().min(MAX_VISIBLE_RESULTS);
+        if up && self.selected > 0 {
+            self.selected -= 1;
+        }
+        if down && self.selected + 1 < visible {
+            self.selected += 1;
+        }
+        if enter {
+            if let Some(&index) = self.filtered.get(self.selected) {
+                result = QuickOpenResult::OpenFile(self.root.join(&self.files[index]));
+            }
+        }
+
+        egui::Window::new(tr!("Быстрое открытие"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .fixed_size([450.0, 0.0])
+            .show(ctx, |ui| {
+                let query_id = ui.make_persistent_id("quick_open_query");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text(tr!("Имя файла..."))
+                        .desired_width(f32::INFINITY)
+                        .id(query_id)
+                );
+                if self.focus_query {
+                    ui.memory_mut(|mem| mem.request_focus(query_id));
+                    self.focus_query = false;
+                }
+                if response.changed() {
+                    self.update_filter();
+                }
+
+                ui.separator();
+
+                if self.filtered.is_empty() {
+                    ui.label(tr!("Ничего не найдено"));
+                }
+
+                egui::ScrollArea::vertical().max_height(350.0).show(ui, |ui| {
+                    for (row, &index) in self.filtered.iter().take(MAX_VISIBLE_RESULTS).enumerate() {
+                        let path = &self.files[index];
+                        let label = ui.selectable_label(row == self.selected, path.display().to_string());
+                        if row == self.selected && (up || down) {
+                            label.scroll_to_me(None);
+                        }
+                        if label.clicked() {
+                            result = QuickOpenResult::OpenFile(self.root.join(path));
+                        }
+                    }
+                });
+
+                if self.filtered.len() > MAX_VISIBLE_RESULTS {
+                    ui.label(tr!("... и еще {}", self.filtered.len() - MAX_VISIBLE_RESULTS));
+                }
+            });
+
+        if escape || matches!(result, QuickOpenResult::OpenFile(_)) {
+            self.close();
+        }
+
+        result
+    }
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if files.len() >= MAX_FILES {
+            return;
+        }
+
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden {
+            continue;
+        }
+
+        // Ссылки на папки не раскрываем: ссылка на папку выше зациклила бы обход
+        let symlink = entry.file_type().is_ok_and(|t| t.is_symlink());
+        if path.is_dir() {
+            if !symlink {
+                collect_files(root, &path, files);
+            }
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_path_buf());
+        }
+    }
+}
+
+// Нечеткое сравнение: все символы запроса должны встретиться в пути по порядку.
+// Бонусы за подряд идущие совпадения, за начало слова и за совпадения в имени файла.
+pub fn fuzzy_score(candidate: &str, query: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let file_name_start = candidate
+        .rfind(['/', '\\'])
+        .map(|i| candidate[..=i].chars().count())
+        .unwrap_or(0);
+
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous_match: Option<usize> = None;
+    let mut previous_char = '/';
+
+    for (index, c) in candidate.chars().enumerate() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+
+        if c.to_lowercase().eq(std::iter::once(wanted)) {
+            score += 1;
+            if previous_match.is_some_and(|p| p + 1 == index) {
+                score += 5;
+            }
+            if matches!(previous_char, '/' | '\\' | '_' | '-' | '.' | ' ') {
+                score += 8;
+            }
+            if index >= file_name_start {
+                score += 2;
+            }
+            previous_match = Some(index);
+            query_chars.next();
+        }
+
+        previous_char = c;
+    }
+
+    if query_chars.peek().is_some() {
+        None
+    } else {
+        Some(score)
+    }
+}
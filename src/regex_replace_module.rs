@@ -0,0 +1,239 @@
+// regex_replace_module.rs
+use eframe::egui;
+use regex::{Regex, RegexBuilder};
+
+use crate::i18n::tr;
+use crate::search_module::unescape_pattern;
+
+// Сколько замен показывать в предпросмотре
+const PREVIEW_LIMIT: usize = 20;
+
+#[derive(PartialEq, Clone, Copy, Default)]
+enum Scope {
+    #[default]
+    Document,
+    Selection,
+}
+
+// Одна замена для предпросмотра; line — номер строки в документе (с нуля)
+struct Substitution {
+    line: usize,
+    found: String,
+    replaced: String,
+}
+
+#[derive(Default)]
+pub struct RegexReplaceModule {
+    pub show_window: bool,
+    pattern: String,
+    replacement: String,
+    case_insensitive: bool,
+    scope: Scope,
+    test_only: bool, // Только подсветить найденное, ничего не меняя
+    compiled: Option<(String, bool, Result<Regex, String>)>, // Шаблон и регистр, для которых собрано выражение
+    matches: Vec<(usize, usize)>, // В символах по всему тексту
+}
+
+pub enum RegexReplaceResult {
+    None,
+    Replace {
+        range: Option<(usize, usize)>, // None — весь документ
+        text: String,
+    },
+}
+
+// Показываем переводы строк и табуляцию, чтобы предпросмотр оставался в одну строку
+fn visible(text: &str) -> String {
+    text.replace('\n', "⏎").replace('\t', "→")
+}
+
+impl RegexReplaceModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+        self.matches.clear();
+    }
+
+    // Найденное для подсветки в тексте — только в режиме проверки
+    pub fn highlighted(&self) -> &[(usize, usize)] {
+        if self.show_window && self.test_only {
+            &self.matches
+        } else {
+            &[]
+        }
+    }
+
+    fn regex(&mut self) -> Option<Result<Regex, String>> {
+        if self.pattern.is_empty() {
+            return None;
+        }
+        let stale = self.compiled.as_ref().is_none_or(|(pattern, case_insensitive, _)| {
+            *pattern != self.pattern || *case_insensitive != self.case_insensitive
+        });
+        if stale {
+            let regex = RegexBuilder::new(&self.pattern)
+                .case_insensitive(self.case_insensitive)
+                .multi_line(true)
+                .build()
+                .map_err(|e| tr!("Ошибка в регулярном выражении: {}", e));
+            self.compiled = Some((self.pattern.clone(), self.case_insensitive, regex));
+        }
+        self.compiled.as_ref().map(|(_, _, regex)| regex.clone())
+    }
+
+    // Находит все совпадения в source, который начинается с символа start документа
+    fn find(&mut self, regex: &Regex, source: &str, start: usize, first_line: usize) -> Vec<Substitution> {
+        let replacement = unescape_pattern(&self.replacement);
+        let mut preview = Vec::new();
+        let (mut byte, mut char_index, mut line) = (0, start, first_line);
+        for captures in regex.captures_iter(source) {
+            let found = captures.get(0).expect("group 0 always matches");
+            let skipped = &source[byte..found.start()];
+            char_index += skipped.chars().count();
+            line += skipped.matches('\n').count();
+            let length = found.as_str().chars().count();
+            self.matches.push((char_index, char_index + length));
+            if preview.len() < PREVIEW_LIMIT {
+                let mut replaced = String::new();
+                captures.expand(&replacement, &mut replaced);
+                preview.push(Substitution {
+                    line,
+                    found: found.as_str().to_string(),
+                    replaced,
+                });
+            }
+            char_index += length;
+            line += found.as_str().matches('\n').count();
+            byte = found.end();
+        }
+        preview
+    }
+
+    pub fn show_window(
+        &mut self,
+        ctx: &egui::Context,
+        text: &str,
+        selection: Option<(usize, usize)>,
+    ) -> RegexReplaceResult {
+        let mut result = RegexReplaceResult::None;
+        if !self.show_window {
+            return result;
+        }
+
+        if selection.is_none() {
+            self.scope = Scope::Document;
+        }
+        let range = match self.scope {
+            Scope::Selection => selection,
+            Scope::Document => None,
+        };
+        let (start, end) = range.unwrap_or((0, text.chars().count()));
+        let first_line = text.chars().take(start).filter(|&c| c == '\n').count();
+        let source: String = text.chars().skip(start).take(end - start).collect();
+
+        self.matches.clear();
+        let regex = self.regex();
+        let preview = match &regex {
+            Some(Ok(regex)) => self.find(regex, &source, start, first_line),
+            _ => Vec::new(),
+        };
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Замена по регулярному выражению"))
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("regex_replace_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr!("Шаблон:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.pattern)
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                    ui.end_row();
+
+                    ui.label(tr!("Замена:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.replacement)
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                    ui.end_row();
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.case_insensitive, tr!("без учета регистра"));
+                    ui.separator();
+                    ui.radio_value(&mut self.scope, Scope::Document, tr!("весь документ"));
+                    ui.add_enabled_ui(selection.is_some(), |ui| {
+                        ui.radio_value(&mut self.scope, Scope::Selection, tr!("выделение"));
+                    });
+                });
+                ui.checkbox(
+                    &mut self.test_only,
+                    tr!("только проверить: подсветить найденное, не заменяя"),
+                );
+                ui.small(tr!(
+                    "$1, ${имя} — группы, $$ — знак доллара, \\n и \\t — перевод строки и табуляция"
+                ));
+                ui.separator();
+
+                match &regex {
+                    None => {
+                        ui.label(tr!("Введите шаблон"));
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 80, 80), e);
+                    }
+                    Some(Ok(_)) if self.matches.is_empty() => {
+                        ui.label(tr!("Совпадений нет"));
+                    }
+                    Some(Ok(_)) => {
+                        ui.label(tr!("Найдено: {}", self.matches.len()));
+                    }
+                }
+
+                if !preview.is_empty() && !self.test_only {
+                    egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                        egui::Grid::new("regex_preview_grid")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for substitution in &preview {
+                                    ui.label(format!("{}", substitution.line + 1));
+                                    ui.monospace(format!(
+                                        "{}  →  {}",
+                                        visible(&substitution.found),
+                                        visible(&substitution.replaced)
+                                    ));
+                                    ui.end_row();
+                                }
+                            });
+                        if self.matches.len() > preview.len() {
+                            ui.label(tr!("…и еще {}", self.matches.len() - preview.len()));
+                        }
+                    });
+                }
+
+                ui.separator();
+                let can_replace = !self.test_only && !self.matches.is_empty();
+                if ui
+                    .add_enabled(can_replace, egui::Button::new(tr!("Заменить все")))
+                    .clicked()
+                {
+                    if let Some(Ok(regex)) = &regex {
+                        let replacement = unescape_pattern(&self.replacement);
+                        result = RegexReplaceResult::Replace {
+                            range,
+                            text: regex.replace_all(&source, replacement.as_str()).into_owned(),
+                        };
+                    }
+                }
+            });
+
+        if !open {
+            self.show_window = false;
+            self.matches.clear();
+        }
+
+        result
+    }
+}
@@ -1,14 +1,31 @@
 // search_module.rs
 use eframe::egui;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::i18n::tr;
+
+// Пауза перед запуском поиска: пока пользователь печатает, поиск не стартует
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+// Как часто (в символах) фоновый поиск проверяет флаг отмены
+const CANCEL_CHECK_INTERVAL: usize = 4096;
 
 #[derive(Default)]
 pub struct SearchModule {
     pub search_text: String,
     pub case_sensitive: bool,
+    pub multiline: bool,      // Многострочное поле ввода запроса
+    pub use_escapes: bool,    // Понимать \n и \t в запросе
     pub show_search: bool,
-    pub matches: Vec<(usize, usize)>, // (start, end) позиции совпадений
+    pub matches: Vec<(usize, usize)>, // (start, end) позиции совпадений в символах
     pub current_match: usize,
     pub focus_search_field: bool,
+    pub searching: bool,
+    search_cancel: Option<Arc<AtomicBool>>,
+    search_receiver: Option<Receiver<Vec<(usize, usize)>>>,
 }
 
 impl SearchModule {
@@ -16,10 +33,15 @@ impl SearchModule {
         Self {
             search_text: String::new(),
             case_sensitive: false,
+            multiline: false,
+            use_escapes: false,
             show_search: false,
             matches: Vec::new(),
             current_match: 0,
             focus_search_field: false,
+            searching: false,
+            search_cancel: None,
+            search_receiver: None,
         }
     }
 
@@ -29,11 +51,16 @@ impl SearchModule {
             self.focus_search_field = true;
         } else {
             self.search_text.clear();
-            self.matches.clear();
-            self.current_match = 0;
+            self.clear_matches();
         }
     }
 
+    pub fn clear_matches(&mut self) {
+        self.cancel_search();
+        self.matches.clear();
+        self.current_match = 0;
+    }
+
     pub fn get_matches(&self) -> &[(usize, usize)] {
         &self.matches
     }
@@ -42,33 +69,79 @@ impl SearchModule {
         self.current_match
     }
 
-    pub fn search_in_text(&mut self, text: &str) {
-        self.matches.clear();
-        self.current_match = 0;
+    // Запускает поиск в фоновом потоке. Предыдущий незавершенный поиск отменяется,
+    // результат приходит через канал и забирается в `poll_search_results`.
+    pub fn request_search(&mut self, text: &str, ctx: &egui::Context) {
+        self.cancel_search();
 
         if self.search_text.is_empty() {
+            self.matches.clear();
+            self.current_match = 0;
             return;
         }
 
-        let search_pattern = if self.case_sensitive {
-            self.search_text.clone()
-        } else {
-            self.search_text.to_lowercase()
-        };
+        let text = text.to_string();
+        let pattern = self.effective_pattern();
+        let case_sensitive = self.case_sensitive;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+
+        let thread_cancel = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(SEARCH_DEBOUNCE);
+            if thread_cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            if let Some(matches) = find_matches_cancellable(&text, &pattern, case_sensitive, &thread_cancel) {
+                if sender.send(matches).is_ok() {
+                    ctx.request_repaint();
+                }
+            }
+        });
+
+        self.search_cancel = Some(cancel);
+        self.search_receiver = Some(receiver);
+        self.searching = true;
+    }
 
-        let text_to_search = if self.case_sensitive {
-            text.to_string()
+    // Запрос в том виде, в котором он ищется в тексте
+    pub fn effective_pattern(&self) -> String {
+        if self.use_escapes {
+            unescape_pattern(&self.search_text)
         } else {
-            text.to_lowercase()
+            self.search_text.clone()
+        }
+    }
+
+    pub fn poll_search_results(&mut self) {
+        let Some(receiver) = &self.search_receiver else {
+            return;
         };
 
-        let mut start = 0;
-        while let Some(pos) = text_to_search[start..].find(&search_pattern) {
-            let absolute_pos = start + pos;
-            let end_pos = absolute_pos + search_pattern.len();
-            self.matches.push((absolute_pos, end_pos));
-            start = end_pos;
+        match receiver.try_recv() {
+            Ok(matches) => {
+                self.matches = matches;
+                self.current_match = 0;
+                self.search_receiver = None;
+                self.search_cancel = None;
+                self.searching = false;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.search_receiver = None;
+                self.search_cancel = None;
+                self.searching = false;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+    }
+
+    fn cancel_search(&mut self) {
+        if let Some(cancel) = self.search_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
         }
+        self.search_receiver = None;
+        self.searching = false;
     }
 
     pub fn next_match(&mut self) {
@@ -103,19 +176,25 @@ impl SearchModule {
         let mut result = SearchPanelResult::None;
         let mut show_search_temp = self.show_search;
 
-        egui::Window::new("Поиск")
+        egui::Window::new(tr!("Поиск"))
             .open(&mut show_search_temp)
             .default_width(300.0)
             .show(ctx, |ui| {
                 let old_search_text = self.search_text.clone();
                 let old_case_sensitive = self.case_sensitive;
+                let old_use_escapes = self.use_escapes;
 
                 ui.horizontal(|ui| {
                     // Создаем уникальный ID для поля поиска
                     let search_field_id = ui.make_persistent_id("search_field");
-                    let response = ui.add(
+                    let search_field = if self.multiline {
+                        egui::TextEdit::multiline(&mut self.search_text).desired_rows(3)
+                    } else {
                         egui::TextEdit::singleline(&mut self.search_text)
-                            .hint_text("Введите текст для поиска...")
+                    };
+                    let response = ui.add(
+                        search_field
+                            .hint_text(tr!("Введите текст для поиска..."))
                             .desired_width(200.0)
                             .id(search_field_id)
                     );
@@ -136,7 +215,14 @@ impl SearchModule {
                 });
 
                 ui.horizontal(|ui| {
-                    if ui.checkbox(&mut self.case_sensitive, "С учетом регистра").changed() {
+                    if ui.checkbox(&mut self.case_sensitive, tr!("С учетом регистра")).changed() {
+                        result = SearchPanelResult::SearchNeeded;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.multiline, tr!("Многострочный"));
+                    if ui.checkbox(&mut self.use_escapes, tr!("Спецсимволы (\\n, \\t)")).changed() {
                         result = SearchPanelResult::SearchNeeded;
                     }
                 });
@@ -145,11 +231,16 @@ impl SearchModule {
 
                 // Информация о результатах поиска
                 if !self.search_text.is_empty() {
-                    if self.matches.is_empty() {
-                        ui.colored_label(egui::Color32::YELLOW, "Совпадений не найдено");
+                    if self.searching {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(tr!("Поиск..."));
+                        });
+                    } else if self.matches.is_empty() {
+                        ui.colored_label(egui::Color32::YELLOW, tr!("Совпадений не найдено"));
                     } else {
                         ui.horizontal(|ui| {
-                            ui.label(format!("Найдено: {}", self.matches.len()));
+                            ui.label(tr!("Найдено: {}", self.matches.len()));
 
                             if self.matches.len() > 1 {
                                 let match_count = self.matches.len();
@@ -158,7 +249,7 @@ impl SearchModule {
                                 if ui.button("◀").clicked() {
                                     result = SearchPanelResult::PreviousMatch;
                                 }
-                                ui.label(format!("{} из {}", current_match + 1, match_count));
+                                ui.label(tr!("{} из {}", current_match + 1, match_count));
                                 if ui.button("▶").clicked() {
                                     result = SearchPanelResult::NextMatch;
                                 }
@@ -169,17 +260,20 @@ impl SearchModule {
 
                 // Проверяем изменения после рендеринга
                 if result == SearchPanelResult::None {
-                    if old_search_text != self.search_text || old_case_sensitive != self.case_sensitive {
+                    if old_search_text != self.search_text
+                        || old_case_sensitive != self.case_sensitive
+                        || old_use_escapes != self.use_escapes
+                    {
                         result = SearchPanelResult::SearchNeeded;
                     }
                 }
 
                 // Клавиши быстрого доступа
                 ui.separator();
-                ui.label("Быстрые клавиши:");
-                ui.label("• Ctrl+F - открыть/закрыть поиск");
-                ui.label("• F3 - следующее совпадение");
-                ui.label("• Shift+F3 - предыдущее совпадение");
+                ui.label(tr!("Быстрые клавиши:"));
+                ui.label(tr!("• Ctrl+F - открыть/закрыть поиск"));
+                ui.label(tr!("• F3 - следующее совпадение"));
+                ui.label(tr!("• Shift+F3 - предыдущее совпадение"));
             });
 
         // Обновляем состояние окна
@@ -212,6 +306,100 @@ impl SearchModule {
     }
 }
 
+// Превращает `\n`, `\t` и `\\` в запросе в настоящие символы,
+// чтобы можно было искать текст через переносы строк
+pub fn unescape_pattern(pattern: &str) -> String {
+    let mut result = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+// Ищет все непересекающиеся вхождения `pattern` в `text`.
+// Позиции считаются в символах, а не в байтах, чтобы кириллица подсвечивалась верно.
+// Текст не копируется и не переводится в нижний регистр целиком: символы
+// сравниваются по одному через `char::to_lowercase`.
+// Поиск прерывается (возвращает None), если выставлен флаг `cancel`.
+pub fn find_matches_cancellable(
+    text: &str,
+    pattern: &str,
+    case_sensitive: bool,
+    cancel: &AtomicBool,
+) -> Option<Vec<(usize, usize)>> {
+    let mut matches = Vec::new();
+
+    if pattern.is_empty() {
+        return Some(matches);
+    }
+
+    let pattern_len = pattern.chars().count();
+    let mut rest = text;
+    let mut char_pos = 0;
+
+    while !rest.is_empty() {
+        if char_pos % CANCEL_CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        if starts_with_pattern(rest, pattern, case_sensitive) {
+            matches.push((char_pos, char_pos + pattern_len));
+            let skip = rest
+                .char_indices()
+                .nth(pattern_len)
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len());
+            rest = &rest[skip..];
+            char_pos += pattern_len;
+        } else {
+            let first_len = rest.chars().next().map(char::len_utf8).unwrap_or(1);
+            rest = &rest[first_len..];
+            char_pos += 1;
+        }
+    }
+
+    Some(matches)
+}
+
+fn starts_with_pattern(text: &str, pattern: &str, case_sensitive: bool) -> bool {
+    let mut text_chars = text.chars();
+    for p in pattern.chars() {
+        match text_chars.next() {
+            Some(c) if chars_equal(c, p, case_sensitive) => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn chars_equal(a: char, b: char, case_sensitive: bool) -> bool {
+    if a == b {
+        return true;
+    }
+    if case_sensitive {
+        return false;
+    }
+    // Для кириллицы (А/а, Ё/ё) и латиницы нижний регистр — один символ,
+    // поэтому длина совпадения в символах равна длине шаблона
+    a.to_lowercase().eq(b.to_lowercase())
+}
+
 #[derive(Debug, PartialEq)]
 pub enum SearchPanelResult {
     None,
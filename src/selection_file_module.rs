@@ -0,0 +1,205 @@
+// selection_file_module.rs
+use eframe::egui;
+use encoding_rs::Encoding;
+use std::path::{Path, PathBuf};
+
+use crate::config::ENCODINGS;
+use crate::i18n::tr;
+
+const UTF8_BOM: &str = "UTF-8 BOM";
+const PREVIEW_CHARS: usize = 2000;
+
+// Текст в байты выбранной кодировки; символы, которых в ней нет, — ошибка, а не «?»
+pub fn encode_text(text: &str, label: &str) -> Result<Vec<u8>, String> {
+    if label == UTF8_BOM {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(text.as_bytes());
+        return Ok(bytes);
+    }
+    let encoding = Encoding::for_label(label.as_bytes()).ok_or_else(|| tr!("Неизвестная кодировка: {}", label))?;
+    let (bytes, _, unmappable) = encoding.encode(text);
+    if unmappable {
+        return Err(tr!("В тексте есть символы, которых нет в кодировке {}", label));
+    }
+    Ok(bytes.into_owned())
+}
+
+// Без явной кодировки: BOM, затем UTF-8, иначе — кодировка по умолчанию из настроек
+pub fn decode_bytes(bytes: &[u8], label: Option<&str>, default_encoding: &str) -> String {
+    let encoding = match label {
+        Some(label) => Encoding::for_label(label.as_bytes()),
+        // Оборванный в конце символ — не повод считать файл не UTF-8: для предпросмотра берем начало
+        None if std::str::from_utf8(bytes).map_or_else(|e| e.error_len().is_none(), |_| true) => {
+            Some(encoding_rs::UTF_8)
+        }
+        None => Encoding::for_label(default_encoding.as_bytes()),
+    };
+    encoding
+        .unwrap_or(encoding_rs::WINDOWS_1251)
+        .decode(bytes)
+        .0
+        .into_owned()
+}
+
+enum Purpose {
+    Save(String),    // Выделенный текст
+    Insert(Vec<u8>), // Содержимое вставляемого файла
+}
+
+struct Prompt {
+    path: PathBuf,
+    purpose: Purpose,
+    encoding: Option<String>, // None — определить при вставке
+    preview: String,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct SelectionFileModule {
+    prompt: Option<Prompt>,
+}
+
+pub enum SelectionFileResult {
+    None,
+    Save(PathBuf, Vec<u8>),
+    Insert(String),
+}
+
+impl SelectionFileModule {
+    pub fn ask_to_save(&mut self, path: &Path, text: String) {
+        self.prompt = Some(Prompt {
+            path: path.to_path_buf(),
+            purpose: Purpose::Save(text),
+            encoding: Some("UTF-8".to_string()),
+            preview: String::new(),
+            error: None,
+        });
+    }
+
+    pub fn ask_to_insert(&mut self, path: &Path, bytes: Vec<u8>, default_encoding: &str) {
+        let preview = decode_bytes(&bytes[..bytes.len().min(PREVIEW_CHARS * 4)], None, default_encoding);
+        self.prompt = Some(Prompt {
+            path: path.to_path_buf(),
+            purpose: Purpose::Insert(bytes),
+            encoding: None,
+            preview: preview.chars().take(PREVIEW_CHARS).collect(),
+            error: None,
+        });
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.error = Some(error);
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.prompt = None;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, default_encoding: &str) -> SelectionFileResult {
+        let mut result = SelectionFileResult::None;
+        let Some(prompt) = &mut self.prompt else {
+            return result;
+        };
+
+        let mut open = true;
+        let inserting = matches!(prompt.purpose, Purpose::Insert(_));
+        let title = if inserting {
+            tr!("Вставить файл")
+        } else {
+            tr!("Сохранить выделенное как")
+        };
+        let name = prompt
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        egui::Window::new(title)
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(name);
+                let before = prompt.encoding.clone();
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Кодировка:"));
+                    let selected = match prompt.encoding.as_deref() {
+                        None => tr!("определить"),
+                        Some(UTF8_BOM) => tr!("UTF-8 с BOM"),
+                        Some(encoding) => encoding,
+                    }
+                    .to_string();
+                    egui::ComboBox::from_id_source("selection_file_encoding")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            if inserting {
+                                ui.selectable_value(&mut prompt.encoding, None, tr!("определить"))
+                                    .on_hover_text(tr!(
+                                        "UTF-8, если файл в ней читается, иначе — {}",
+                                        default_encoding
+                                    ));
+                            } else {
+                                ui.selectable_value(
+                                    &mut prompt.encoding,
+                                    Some(UTF8_BOM.to_string()),
+                                    tr!("UTF-8 с BOM"),
+                                );
+                            }
+                            for encoding in ENCODINGS {
+                                ui.selectable_value(&mut prompt.encoding, Some(encoding.to_string()), encoding);
+                            }
+                        });
+                });
+
+                if let Purpose::Insert(bytes) = &prompt.purpose {
+                    if prompt.encoding != before {
+                        let head = &bytes[..bytes.len().min(PREVIEW_CHARS * 4)];
+                        let preview = decode_bytes(head, prompt.encoding.as_deref(), default_encoding);
+                        prompt.preview = preview.chars().take(PREVIEW_CHARS).collect();
+                    }
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut prompt.preview.as_str())
+                                .code_editor()
+                                .desired_width(400.0),
+                        );
+                    });
+                } else if prompt.encoding != before {
+                    prompt.error = None;
+                }
+
+                if let Some(error) = &prompt.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+                }
+
+                let button = if inserting {
+                    tr!("Вставить")
+                } else {
+                    tr!("Сохранить")
+                };
+                if ui.button(button).clicked() {
+                    result = match &prompt.purpose {
+                        Purpose::Save(text) => match encode_text(text, prompt.encoding.as_deref().unwrap_or("UTF-8")) {
+                            Ok(bytes) => SelectionFileResult::Save(prompt.path.clone(), bytes),
+                            Err(e) => {
+                                prompt.error = Some(e);
+                                SelectionFileResult::None
+                            }
+                        },
+                        Purpose::Insert(bytes) => SelectionFileResult::Insert(decode_bytes(
+                            bytes,
+                            prompt.encoding.as_deref(),
+                            default_encoding,
+                        )),
+                    };
+                }
+            });
+
+        if !open || matches!(result, SelectionFileResult::Insert(_)) {
+            self.prompt = None;
+        }
+        result
+    }
+}
@@ -0,0 +1,156 @@
+// sequence_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum SequencePadding {
+    #[default]
+    None,
+    Zeros,  // 01, 02 … 10
+    Spaces, // Номера выровнены по правому краю
+}
+
+#[derive(Default)]
+pub struct SequenceModule {
+    pub show_window: bool,
+}
+
+pub enum SequenceResult {
+    None,
+    ConfigChanged,
+    Number,
+    Strip,
+}
+
+// Номера в начале каждой строки; пустые строки можно пропускать, не сбивая счет
+pub fn number_lines(text: &str, config: &Config) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    // Перевод строки в конце фрагмента — не отдельная строка
+    let count = if text.ends_with('\n') {
+        lines.len() - 1
+    } else {
+        lines.len()
+    };
+    let numbered = |line: &str| !(config.sequence_skip_empty && line.trim().is_empty());
+    let total = lines[..count].iter().filter(|line| numbered(line)).count() as i64;
+    let last = config.sequence_start + config.sequence_step * (total - 1).max(0);
+    let width = config.sequence_start.to_string().len().max(last.to_string().len());
+
+    let mut value = config.sequence_start;
+    let mut result = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        if index >= count || !numbered(line) {
+            result.push(line.to_string());
+            continue;
+        }
+        let number = match config.sequence_padding {
+            SequencePadding::None => value.to_string(),
+            SequencePadding::Zeros if value < 0 => format!("-{:0>1$}", value.unsigned_abs(), width - 1),
+            SequencePadding::Zeros => format!("{:0>1$}", value, width),
+            SequencePadding::Spaces => format!("{:>1$}", value, width),
+        };
+        result.push(format!("{}{}{}", number, config.sequence_separator, line));
+        value += config.sequence_step;
+    }
+    result.join("\n")
+}
+
+// Убирает номер в начале строки: «12. », «3) », «007 », «4:» и т.п. Строки без номера не меняются
+fn strip_number(line: &str) -> &str {
+    let trimmed = line.trim_start_matches([' ', '\t']);
+    let unsigned = trimmed.strip_prefix('-').unwrap_or(trimmed);
+    let digits = unsigned.len() - unsigned.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return line;
+    }
+    let rest = &unsigned[digits..];
+    let after_mark = rest.strip_prefix(['.', ')', ':', ']']).unwrap_or(rest);
+    let body = after_mark.trim_start_matches([' ', '\t']);
+    // После номера — пробел или конец строки, иначе это не номер: «3.14», «10кг»
+    if body.len() == after_mark.len() && !after_mark.is_empty() {
+        return line;
+    }
+    body
+}
+
+pub fn strip_line_numbers(text: &str) -> String {
+    text.split('\n').map(strip_number).collect::<Vec<_>>().join("\n")
+}
+
+impl SequenceModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, has_selection: bool, config: &mut Config) -> SequenceResult {
+        let mut result = SequenceResult::None;
+        if !self.show_window {
+            return result;
+        }
+
+        let before = config.clone();
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Нумерация строк"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("sequence_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr!("Начать с:"));
+                    ui.add(egui::DragValue::new(&mut config.sequence_start));
+                    ui.end_row();
+
+                    ui.label(tr!("Шаг:"));
+                    ui.add(egui::DragValue::new(&mut config.sequence_step));
+                    ui.end_row();
+
+                    ui.label(tr!("Выравнивание:"));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(
+                            &mut config.sequence_padding,
+                            SequencePadding::None,
+                            tr!("Без выравнивания"),
+                        );
+                        ui.radio_value(&mut config.sequence_padding, SequencePadding::Zeros, tr!("Нулями"));
+                        ui.radio_value(&mut config.sequence_padding, SequencePadding::Spaces, tr!("Пробелами"));
+                    });
+                    ui.end_row();
+
+                    ui.label(tr!("После номера:"));
+                    ui.add(egui::TextEdit::singleline(&mut config.sequence_separator).desired_width(60.0));
+                    ui.end_row();
+
+                    ui.label("");
+                    ui.checkbox(&mut config.sequence_skip_empty, tr!("Пропускать пустые строки"));
+                    ui.end_row();
+                });
+
+                let preview = number_lines("…\n…\n…", config);
+                ui.label(egui::RichText::new(preview).monospace().weak());
+                ui.label(if has_selection {
+                    tr!("Изменятся выделенные строки")
+                } else {
+                    tr!("Изменятся все строки документа")
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Пронумеровать")).clicked() {
+                        result = SequenceResult::Number;
+                    }
+                    if ui.button(tr!("Убрать номера строк")).clicked() {
+                        result = SequenceResult::Strip;
+                    }
+                });
+            });
+
+        if matches!(result, SequenceResult::None) && *config != before {
+            result = SequenceResult::ConfigChanged;
+        }
+        if !open {
+            self.show_window = false;
+        }
+        result
+    }
+}
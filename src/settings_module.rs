@@ -0,0 +1,306 @@
+// settings_module.rs
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, FontFamily, Language, TypingSoundSet, ENCODINGS};
+use crate::docx_module::options_ui;
+use crate::i18n::tr;
+use crate::statistics_module::{CharacterCount, WordCount};
+use crate::status_bar_module;
+use crate::theme_module::{available_themes, theme_title};
+
+// Пока тянут ползунок или число, настройки меняются каждый кадр; на диск пишем, когда правка затихнет
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+pub struct SettingsModule {
+    pub show_window: bool,
+    save_at: Option<Instant>, // Когда записать изменившиеся настройки
+}
+
+#[derive(PartialEq)]
+pub enum SettingsResult {
+    None,
+    Changed, // Применить, но пока не сохранять
+    Save,
+}
+
+impl SettingsModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+    }
+
+    // Настройки меняются сразу в `config`; вызывающий код применяет их, а на Save — и сохраняет
+    pub fn show_window(&mut self, ctx: &egui::Context, config: &mut Config) -> SettingsResult {
+        if !self.show_window {
+            return self.take_save(true);
+        }
+
+        let before = config.clone();
+        let mut open = self.show_window;
+        let mut reset = false;
+
+        egui::Window::new(tr!("Настройки"))
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("settings_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label(tr!("Шрифт:"));
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut config.font_family, FontFamily::Monospace, tr!("Моноширинный"));
+                            ui.selectable_value(&mut config.font_family, FontFamily::Proportional, tr!("Обычный"));
+                            ui.selectable_value(&mut config.font_family, FontFamily::Custom, tr!("Из файла"));
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Файл шрифта:"));
+                        ui.horizontal(|ui| {
+                            if ui.button(tr!("Выбрать...")).clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter(tr!("Шрифты"), &["ttf", "otf"])
+                                    .pick_file()
+                                {
+                                    config.custom_font = Some(path);
+                                    config.font_family = FontFamily::Custom;
+                                }
+                            }
+                            if let Some(path) = &config.custom_font {
+                                let name = path
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                ui.label(name).on_hover_text(path.display().to_string());
+                                if ui.small_button("✖").on_hover_text(tr!("Убрать")).clicked() {
+                                    config.custom_font = None;
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Размер шрифта:"));
+                        ui.add(egui::Slider::new(&mut config.font_size, 8.0..=40.0).step_by(1.0));
+                        ui.end_row();
+
+                        ui.label(tr!("Тема:"));
+                        egui::ComboBox::from_id_source("theme")
+                            .selected_text(theme_title(&config.theme).to_string())
+                            .show_ui(ui, |ui| {
+                                for (name, title) in available_themes(config) {
+                                    ui.selectable_value(&mut config.theme, name, title);
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(tr!("Ширина табуляции:"));
+                        ui.add(egui::Slider::new(&mut config.tab_width, 1..=8));
+                        ui.end_row();
+
+                        ui.label(tr!("Отступ:"));
+                        ui.checkbox(&mut config.insert_spaces, tr!("Пробелами вместо табуляции"));
+                        ui.end_row();
+
+                        ui.label(tr!("Текущая строка:"));
+                        ui.checkbox(&mut config.highlight_current_line, tr!("Подсвечивать"));
+                        ui.end_row();
+
+                        ui.label(tr!("Парные символы:"));
+                        ui.checkbox(&mut config.auto_pair, tr!("Закрывать скобки и кавычки автоматически"))
+                            .on_hover_text(tr!("Выделенный текст при наборе скобки или кавычки оборачивается ими"));
+                        ui.end_row();
+
+                        ui.label(tr!("Автодополнение:"));
+                        ui.checkbox(&mut config.autocomplete, tr!("Предлагать слова при наборе"))
+                            .on_hover_text(tr!("Ctrl + Пробел — показать подсказки вручную"));
+                        ui.end_row();
+
+                        ui.label(tr!("Линейка:"));
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut config.show_ruler, "");
+                            ui.add_enabled(
+                                config.show_ruler,
+                                egui::DragValue::new(&mut config.ruler_column)
+                                    .clamp_range(20..=300)
+                                    .prefix(tr!("столбец ")),
+                            );
+                            for column in [80, 100, 120] {
+                                if ui.add_enabled(config.show_ruler, egui::Button::new(column.to_string()).small()).clicked() {
+                                    config.ruler_column = column;
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Ширина абзаца:"));
+                        ui.add(
+                            egui::DragValue::new(&mut config.reflow_column)
+                                .clamp_range(20..=300)
+                                .suffix(tr!(" символов")),
+                        )
+                        .on_hover_text(tr!("Переформатировать абзац (Alt + Q)"));
+                        ui.end_row();
+
+                        ui.label(tr!("Обычная вставка:"));
+                        ui.vertical(|ui| {
+                            ui.checkbox(&mut config.paste_collapse_blank_lines, tr!("Схлопывать пустые строки"));
+                            ui.checkbox(&mut config.paste_reflow, tr!("Переносить по ширине абзаца"));
+                        })
+                        .response
+                        .on_hover_text(tr!("Вставить как обычный текст (Ctrl + Shift + V)"));
+                        ui.end_row();
+
+                        ui.label(tr!("Автосохранение:"));
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut config.autosave, "");
+                            ui.add_enabled(
+                                config.autosave,
+                                egui::DragValue::new(&mut config.autosave_interval_secs)
+                                    .clamp_range(5..=3600)
+                                    .suffix(tr!(" с")),
+                            );
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Резервная копия:"));
+                        ui.checkbox(&mut config.backup_on_save, tr!("хранить прежнюю версию в файле с ~ в конце имени"));
+                        ui.end_row();
+
+                        ui.label(tr!("Архивы:"));
+                        ui.checkbox(&mut config.recompress_archives, tr!("сохранять текст из .gz и .zip обратно в архив"));
+                        ui.end_row();
+
+                        ui.label(tr!("Документы Word:"));
+                        options_ui(ui, &mut config.docx_import);
+                        ui.end_row();
+
+                        ui.label(tr!("Громкость музыки:"));
+                        ui.add(egui::Slider::new(&mut config.music_volume, 0.0..=1.0));
+                        ui.end_row();
+
+                        ui.label(tr!("Громкость фоновых звуков:"));
+                        ui.add(egui::Slider::new(&mut config.ambient_volume, 0.0..=1.0));
+                        ui.end_row();
+
+                        ui.label(tr!("Звук клавиш:"));
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut config.typing_sounds, "");
+                            ui.add_enabled_ui(config.typing_sounds, |ui| {
+                                ui.selectable_value(&mut config.typing_sound_set, TypingSoundSet::Mechanical, tr!("Механическая"));
+                                ui.selectable_value(&mut config.typing_sound_set, TypingSoundSet::Typewriter, tr!("Пишущая машинка"));
+                                ui.selectable_value(&mut config.typing_sound_set, TypingSoundSet::Soft, tr!("Тихая"));
+                            });
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Громкость клавиш:"));
+                        ui.add_enabled(config.typing_sounds, egui::Slider::new(&mut config.typing_volume, 0.0..=1.0));
+                        ui.end_row();
+
+                        ui.label(tr!("Строка состояния:"));
+                        status_bar_module::settings_ui(ui, &mut config.status_bar);
+                        ui.end_row();
+
+                        ui.label(tr!("Счет в строке состояния:"));
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(tr!("символы"));
+                                for mode in CharacterCount::ALL {
+                                    ui.selectable_value(&mut config.status_characters, mode, mode.title());
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(tr!("слова"));
+                                for mode in WordCount::ALL {
+                                    ui.selectable_value(&mut config.status_words, mode, mode.title());
+                                }
+                            });
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Язык:"));
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut config.language, Language::Russian, tr!("Русский"));
+                            ui.selectable_value(&mut config.language, Language::English, "English");
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Автор:"));
+                        ui.text_edit_singleline(&mut config.author)
+                            .on_hover_text(tr!("Подставляется вместо {{author}} в шаблонах"));
+                        ui.end_row();
+
+                        ui.label(tr!("Журнал:"));
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(tr!("файл"));
+                                ui.text_edit_singleline(&mut config.journal_path)
+                                    .on_hover_text(tr!("Формат даты strftime; относительный путь — от домашней папки"));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(tr!("заголовок записи"));
+                                ui.text_edit_singleline(&mut config.journal_heading);
+                            });
+                            ui.checkbox(&mut config.journal_append_only, tr!("прежние записи только для чтения"));
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Кодировка не-UTF-8 файлов:"));
+                        egui::ComboBox::from_id_source("default_encoding")
+                            .selected_text(config.default_encoding.clone())
+                            .show_ui(ui, |ui| {
+                                for encoding in ENCODINGS {
+                                    ui.selectable_value(&mut config.default_encoding, encoding.to_string(), encoding);
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(tr!("Консольный режим:"));
+                        ui.checkbox(&mut config.tui_mouse, tr!("мышь (--tui)"))
+                            .on_hover_text(tr!("Выключите, если мышь мешает копировать текст средствами терминала"));
+                        ui.end_row();
+                    });
+
+                ui.separator();
+                if ui.button(tr!("Сбросить настройки")).clicked() {
+                    reset = true;
+                }
+            });
+
+        // Сохраненные пользователем темы, список недавних файлов и размер окна при сбросе не теряем
+        if reset {
+            let custom_themes = std::mem::take(&mut config.custom_themes);
+            let recent_files = std::mem::take(&mut config.recent_files);
+            let window = config.window;
+            *config = Config::default();
+            config.custom_themes = custom_themes;
+            config.recent_files = recent_files;
+            config.window = window;
+        }
+
+        if !open {
+            self.show_window = false;
+        }
+
+        if *config != before {
+            self.save_at = Some(Instant::now() + SAVE_DELAY);
+            ctx.request_repaint_after(SAVE_DELAY);
+            if self.show_window {
+                return SettingsResult::Changed;
+            }
+        }
+        self.take_save(!self.show_window)
+    }
+
+    // Save, если подошло время записать настройки или окно закрыли
+    fn take_save(&mut self, now: bool) -> SettingsResult {
+        match self.save_at {
+            Some(at) if now || Instant::now() >= at => {
+                self.save_at = None;
+                SettingsResult::Save
+            }
+            _ => SettingsResult::None,
+        }
+    }
+}
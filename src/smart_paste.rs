@@ -0,0 +1,101 @@
+// smart_paste.rs
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::transform_module::Transform;
+
+// UTF-8, прочитанный как windows-1252: так кавычки и тире приходят из старых страниц и писем
+const MOJIBAKE: [(&str, &str); 9] = [
+    ("â€”", "—"),
+    ("â€“", "–"),
+    ("â€œ", "“"),
+    ("â€\u{9d}", "”"),
+    ("â€ž", "„"),
+    ("â€™", "’"),
+    ("â€˜", "‘"),
+    ("â€¦", "…"),
+    ("Â\u{a0}", " "),
+];
+
+// Байты 0x80–0x9F из windows-1252, попавшие в текст управляющими символами
+fn windows_1252(c: char) -> Option<char> {
+    Some(match c {
+        '\u{85}' => '…',
+        '\u{91}' => '‘',
+        '\u{92}' => '’',
+        '\u{93}' => '“',
+        '\u{94}' => '”',
+        '\u{95}' => '•',
+        '\u{96}' => '–',
+        '\u{97}' => '—',
+        _ => return None,
+    })
+}
+
+fn clean_char(c: char) -> Option<char> {
+    if let Some(fixed) = windows_1252(c) {
+        return Some(fixed);
+    }
+    match c {
+        '\n' | '\t' => Some(c),
+        // Разрыв строки из Word и разделители строк Unicode
+        '\u{0B}' | '\u{0C}' | '\u{2028}' | '\u{2029}' => Some('\n'),
+        '\u{A0}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}' => Some(' '),
+        // Невидимые: мягкий перенос, нулевой ширины, метка порядка байтов
+        '\u{AD}' | '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' => None,
+        c if c.is_control() => None,
+        c => Some(c),
+    }
+}
+
+// Отступ в начале строки сохраняем, повторные пробелы внутри схлопываем, хвостовые убираем
+fn clean_line(line: &str) -> String {
+    let body = line.trim_start_matches([' ', '\t']);
+    let mut result = line[..line.len() - body.len()].to_string();
+    let mut space = false;
+    for c in body.chars() {
+        if c == ' ' {
+            space = true;
+            continue;
+        }
+        if space {
+            result.push(' ');
+            space = false;
+        }
+        result.push(c);
+    }
+    result
+}
+
+// Текст из браузера и Word: лишние пробелы, невидимые символы, тире windows-1252, пустые строки
+pub fn normalize(text: &str, config: &Config) -> String {
+    let mut text = text.replace("\r\n", "\n").replace('\r', "\n");
+    for (broken, fixed) in MOJIBAKE {
+        text = text.replace(broken, fixed);
+    }
+    let text: String = text.chars().filter_map(clean_char).collect();
+
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.split('\n').map(clean_line) {
+        let blank_before = lines.last().is_none_or(|last| last.trim().is_empty());
+        if line.trim().is_empty() && blank_before && (config.paste_collapse_blank_lines || lines.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let text = lines.join("\n");
+    if config.paste_reflow {
+        Transform::Reflow.apply(&text, config).unwrap_or(text)
+    } else {
+        text
+    }
+}
+
+pub fn read_clipboard() -> Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| tr!("Не удалось прочитать буфер обмена: {}", e))
+}
@@ -0,0 +1,316 @@
+// snippet_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+// Сниппет разворачивается из сокращения по Tab.
+// В тексте: ${1:подсказка}, ${1} или $1 — места для ввода, $0 — где окажется курсор в конце,
+// {{date}}, {{time}}, {{filename}} — подставляются при вставке, $$ — знак доллара
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct Snippet {
+    pub abbreviation: String,
+    pub description: String,
+    pub body: String,
+}
+
+pub fn default_snippets() -> Vec<Snippet> {
+    vec![
+        Snippet {
+            abbreviation: "письмо".to_string(),
+            description: "Деловое письмо".to_string(),
+            body: "Уважаемый ${1:Имя Отчество}!\n\n$0\n\nС уважением,\n${2:подпись}\n{{date}}".to_string(),
+        },
+        Snippet {
+            abbreviation: "дата".to_string(),
+            description: "Сегодняшняя дата".to_string(),
+            body: "{{date}}".to_string(),
+        },
+    ]
+}
+
+// Значения {{переменных}} на момент вставки
+pub struct SnippetVariables {
+    pub filename: String,
+    pub date: String, // В формате даты из настроек
+}
+
+// Что вставить вместо символов start..end и что выделить после вставки
+pub struct SnippetExpansion {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub selection: (usize, usize),
+}
+
+// Развернутый сниппет, по местам для ввода которого ходим Tab / Shift + Tab
+struct SnippetSession {
+    stops: Vec<(usize, usize)>, // Диапазоны в символах по всему тексту, $0 — последним
+    current: usize,
+    text_length: usize, // Символов в тексте, когда диапазоны были верны
+}
+
+#[derive(Default)]
+pub struct SnippetModule {
+    pub show_window: bool,
+    selected: usize,
+    session: Option<SnippetSession>,
+}
+
+#[derive(PartialEq)]
+pub enum SnippetResult {
+    None,
+    ConfigChanged,
+}
+
+// Раскрывает переменные и места для ввода; места возвращаются по номерам, $0 — в конце
+fn render(body: &str, variables: &SnippetVariables) -> (String, Vec<(usize, usize)>) {
+    let body = body
+        .replace("{{date}}", &variables.date)
+        .replace("{{time}}", &chrono::Local::now().format("%H:%M").to_string())
+        .replace("{{filename}}", &variables.filename);
+
+    let mut text = String::new();
+    let mut length = 0;
+    let mut stops: Vec<(u32, usize, usize)> = Vec::new();
+    let chars: Vec<char> = body.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c != '$' || i + 1 == chars.len() {
+            text.push(c);
+            length += 1;
+            i += 1;
+            continue;
+        }
+
+        let digits = |from: usize| chars[from..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let (number, placeholder, next) = if chars[i + 1] == '$' {
+            text.push('$');
+            length += 1;
+            i += 2;
+            continue;
+        } else if chars[i + 1] == '{' && digits(i + 2) > 0 {
+            let number_end = i + 2 + digits(i + 2);
+            let number: String = chars[i + 2..number_end].iter().collect();
+            let close = chars[number_end..].iter().position(|&c| c == '}').map(|p| number_end + p);
+            match (chars.get(number_end), close) {
+                (Some('}'), _) => (number, String::new(), number_end + 1),
+                (Some(':'), Some(close)) => (number, chars[number_end + 1..close].iter().collect(), close + 1),
+                _ => {
+                    text.push(c);
+                    length += 1;
+                    i += 1;
+                    continue;
+                }
+            }
+        } else if digits(i + 1) > 0 {
+            let number_end = i + 1 + digits(i + 1);
+            (chars[i + 1..number_end].iter().collect(), String::new(), number_end)
+        } else {
+            text.push(c);
+            length += 1;
+            i += 1;
+            continue;
+        };
+
+        let number: u32 = number.parse().unwrap_or(0);
+        let placeholder_length = placeholder.chars().count();
+        // Повтор номера — просто текст, место для ввода — только первое
+        if !stops.iter().any(|&(n, _, _)| n == number) {
+            stops.push((number, length, length + placeholder_length));
+        }
+        text.push_str(&placeholder);
+        length += placeholder_length;
+        i = next;
+    }
+
+    stops.sort_by_key(|&(number, _, _)| if number == 0 { u32::MAX } else { number });
+    (text, stops.into_iter().map(|(_, start, end)| (start, end)).collect())
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl SnippetModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+    }
+
+    pub fn in_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    pub fn end_session(&mut self) {
+        self.session = None;
+    }
+
+    // Сниппет, сокращение которого стоит прямо перед курсором: (начало сокращения, сниппет)
+    pub fn find_abbreviation<'a>(text: &str, cursor: usize, snippets: &'a [Snippet]) -> Option<(usize, &'a Snippet)> {
+        let before: Vec<char> = text.chars().take(cursor).collect();
+        let start = before.iter().rposition(|&c| !is_word_char(c)).map_or(0, |i| i + 1);
+        let word: String = before[start..].iter().collect();
+        if word.is_empty() {
+            return None;
+        }
+        snippets.iter().find(|s| s.abbreviation == word).map(|s| (start, s))
+    }
+
+    // Вставка сниппета вместо символов start..end; если есть места для ввода, начинается обход по Tab
+    pub fn expand(
+        &mut self,
+        snippet: &Snippet,
+        start: usize,
+        end: usize,
+        text_length: usize,
+        variables: &SnippetVariables,
+    ) -> SnippetExpansion {
+        let (text, stops) = render(&snippet.body, variables);
+        let inserted = text.chars().count();
+        let stops: Vec<(usize, usize)> = stops.into_iter().map(|(a, b)| (start + a, start + b)).collect();
+
+        let selection = stops.first().copied().unwrap_or((start + inserted, start + inserted));
+        // Одно место $0 — просто поставить курсор, обходить нечего
+        self.session = (stops.len() > 1 || stops.first().is_some_and(|&(a, b)| a != b)).then(|| SnippetSession {
+            stops,
+            current: 0,
+            text_length: text_length - (end - start) + inserted,
+        });
+
+        SnippetExpansion { start, end, text, selection }
+    }
+
+    // Правки внутри текущего места сдвигают его конец и все места после него;
+    // курсор ушел из места — обход закончен
+    pub fn track(&mut self, text_length: usize, cursor: usize) {
+        let Some(session) = &mut self.session else {
+            return;
+        };
+        let delta = text_length as isize - session.text_length as isize;
+        if delta != 0 {
+            session.text_length = text_length;
+            let shift = |value: usize| (value as isize + delta).max(0) as usize;
+            let current = session.current;
+            session.stops[current].1 = shift(session.stops[current].1);
+            for stop in &mut session.stops[current + 1..] {
+                *stop = (shift(stop.0), shift(stop.1));
+            }
+        }
+
+        let (start, end) = session.stops[session.current];
+        if cursor < start || cursor > end {
+            self.session = None;
+        }
+    }
+
+    // Следующее (или предыдущее) место для ввода, которое нужно выделить; None — обхода нет
+    pub fn jump(&mut self, backward: bool) -> Option<(usize, usize)> {
+        let session = self.session.as_mut()?;
+        let last = session.stops.len() - 1;
+        if !backward && session.current == last {
+            // Дальше мест нет — курсор в конец последнего
+            let end = session.stops[last].1;
+            self.session = None;
+            return Some((end, end));
+        }
+        session.current = if backward { session.current.saturating_sub(1) } else { session.current + 1 };
+        let stop = session.stops[session.current];
+        if session.current == last {
+            self.session = None;
+        }
+        Some(stop)
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, config: &mut Config) -> SnippetResult {
+        if !self.show_window {
+            return SnippetResult::None;
+        }
+
+        let mut result = SnippetResult::None;
+        let mut open = self.show_window;
+
+        egui::Window::new(tr!("Сниппеты"))
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(150.0);
+                        egui::ScrollArea::vertical().id_source("snippet_list").max_height(260.0).show(ui, |ui| {
+                            for (index, snippet) in config.snippets.iter().enumerate() {
+                                let title = if snippet.abbreviation.is_empty() { "…" } else { &snippet.abbreviation };
+                                if ui
+                                    .selectable_label(index == self.selected, title)
+                                    .on_hover_text(&snippet.description)
+                                    .clicked()
+                                {
+                                    self.selected = index;
+                                }
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            if ui.button(tr!("Добавить")).clicked() {
+                                config.snippets.push(Snippet::default());
+                                self.selected = config.snippets.len() - 1;
+                                result = SnippetResult::ConfigChanged;
+                            }
+                            if ui
+                                .add_enabled(self.selected < config.snippets.len(), egui::Button::new(tr!("Удалить")))
+                                .clicked()
+                            {
+                                config.snippets.remove(self.selected);
+                                self.selected = self.selected.saturating_sub(1);
+                                result = SnippetResult::ConfigChanged;
+                            }
+                        });
+                    });
+
+                    ui.separator();
+
+                    ui.vertical(|ui| {
+                        let Some(snippet) = config.snippets.get_mut(self.selected) else {
+                            ui.label(tr!("Нет сниппетов"));
+                            return;
+                        };
+                        egui::Grid::new("snippet_grid").num_columns(2).show(ui, |ui| {
+                            ui.label(tr!("Сокращение:"));
+                            if ui.text_edit_singleline(&mut snippet.abbreviation).changed() {
+                                result = SnippetResult::ConfigChanged;
+                            }
+                            ui.end_row();
+
+                            ui.label(tr!("Описание:"));
+                            if ui.text_edit_singleline(&mut snippet.description).changed() {
+                                result = SnippetResult::ConfigChanged;
+                            }
+                            ui.end_row();
+                        });
+                        if ui
+                            .add(
+                                egui::TextEdit::multiline(&mut snippet.body)
+                                    .code_editor()
+                                    .desired_rows(8)
+                                    .desired_width(f32::INFINITY),
+                            )
+                            .changed()
+                        {
+                            result = SnippetResult::ConfigChanged;
+                        }
+                    });
+                });
+
+                ui.separator();
+                ui.label(tr!("Наберите сокращение и нажмите Tab. Tab / Shift + Tab — к следующему / предыдущему месту ввода."));
+                ui.small(tr!("${1:текст} — место для ввода, $0 — курсор в конце, {{date}}, {{time}}, {{filename}} — дата, время, имя файла"));
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+
+        result
+    }
+}
@@ -0,0 +1,587 @@
+// spellcheck_module.rs
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use crate::config::config_dir;
+use crate::i18n::tr;
+
+const DICTIONARIES_DIR: &str = "dictionaries";
+const USER_DICTIONARY_FILE: &str = "user_dictionary.txt";
+const MAX_SUGGESTIONS: usize = 8;
+
+// Словари Hunspell (ru_RU.aff + ru_RU.dic, en_US.aff + en_US.dic) кладутся в папку настроек.
+// Поддерживаются приставки и окончания из .aff, двойные окончания через флаги продолжения,
+// NEEDAFFIX и FORBIDDENWORD. Составные слова (COMPOUNDFLAG и др.), CIRCUMFIX, ICONV/OCONV и морфология
+// не поддерживаются: слова, которые словарь собирает из частей, будут подчеркнуты как ошибки
+#[derive(Clone, Copy, PartialEq)]
+enum FlagMode {
+    Single, // Флаг — один символ
+    Long,   // Флаг — два символа
+    Numeric,
+}
+
+// Один символ условия: конкретная буква, набор [абв], исключение [^абв] или любой
+enum ConditionChar {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl ConditionChar {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionChar::Any => true,
+            ConditionChar::OneOf(chars) => chars.contains(&c),
+            ConditionChar::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<ConditionChar> {
+    let mut result = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => result.push(ConditionChar::Any),
+            '[' => {
+                let mut set: Vec<char> = chars.by_ref().take_while(|&c| c != ']').collect();
+                if set.first() == Some(&'^') {
+                    set.remove(0);
+                    result.push(ConditionChar::NoneOf(set));
+                } else {
+                    result.push(ConditionChar::OneOf(set));
+                }
+            }
+            c => result.push(ConditionChar::OneOf(vec![c])),
+        }
+    }
+    result
+}
+
+struct AffixRule {
+    flag: u32,
+    strip: String,
+    add: String,
+    condition: Vec<ConditionChar>,
+    cross_product: bool, // Можно сочетать с приставкой
+    continuation: Vec<u32>, // Флаги аффиксов, которые можно добавить поверх этого
+}
+
+impl AffixRule {
+    // Условие окончания проверяется по концу основы, условие приставки — по началу
+    fn suffix_condition_matches(&self, stem: &[char]) -> bool {
+        stem.len() >= self.condition.len()
+            && self
+                .condition
+                .iter()
+                .zip(&stem[stem.len() - self.condition.len()..])
+                .all(|(cond, &c)| cond.matches(c))
+    }
+
+    fn prefix_condition_matches(&self, stem: &[char]) -> bool {
+        stem.len() >= self.condition.len() && self.condition.iter().zip(stem).all(|(cond, &c)| cond.matches(c))
+    }
+}
+
+pub struct Dictionary {
+    pub name: String,
+    words: HashMap<String, Vec<u32>>,
+    // Правила сгруппированы по добавляемой части, чтобы не перебирать их все для каждого слова
+    suffixes: HashMap<String, Vec<AffixRule>>,
+    prefixes: HashMap<String, Vec<AffixRule>>,
+    try_chars: Vec<char>, // Буквы, которые пробуются при поиске вариантов
+    need_affix: Option<u32>, // Слово с этим флагом само по себе не встречается, только с аффиксом
+    forbidden: Option<u32>,  // Форма запрещена, даже если выводится по правилам
+}
+
+fn parse_flags(flags: &str, mode: FlagMode) -> Vec<u32> {
+    match mode {
+        FlagMode::Single => flags.chars().map(|c| c as u32).collect(),
+        FlagMode::Long => flags
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|pair| pair.iter().fold(0, |code, &c| (code << 16) | c as u32))
+            .collect(),
+        FlagMode::Numeric => flags.split(',').filter_map(|n| n.trim().parse().ok()).collect(),
+    }
+}
+
+// Кодировка файлов словаря указана в .aff строкой SET; старые русские словари бывают в KOI8-R
+fn read_encoded(path: &Path, encoding: &str) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| tr!("Ошибка чтения словаря {}: {}", path.display(), e))?;
+    let encoding = encoding_rs::Encoding::for_label(encoding.as_bytes()).unwrap_or(encoding_rs::UTF_8);
+    let (text, _, _) = encoding.decode(&bytes);
+    Ok(text.into_owned())
+}
+
+impl Dictionary {
+    pub fn load(aff_path: &Path, dic_path: &Path) -> Result<Self, String> {
+        // Строку SET ищем до декодирования: она всегда в ASCII
+        let raw = fs::read(aff_path).map_err(|e| tr!("Ошибка чтения словаря {}: {}", aff_path.display(), e))?;
+        let encoding = String::from_utf8_lossy(&raw)
+            .lines()
+            .find_map(|line| line.strip_prefix("SET ").map(|e| e.trim().to_string()))
+            .unwrap_or_else(|| "UTF-8".to_string());
+        let aff = read_encoded(aff_path, &encoding)?;
+        let dic = read_encoded(dic_path, &encoding)?;
+        let name = dic_path
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self::parse(name, &aff, &dic))
+    }
+
+    fn parse(name: String, aff: &str, dic: &str) -> Self {
+        let mut dictionary = Dictionary {
+            name,
+            words: HashMap::new(),
+            suffixes: HashMap::new(),
+            prefixes: HashMap::new(),
+            try_chars: Vec::new(),
+            need_affix: None,
+            forbidden: None,
+        };
+
+        let mut mode = FlagMode::Single;
+        let mut cross_products: HashMap<(bool, u32), bool> = HashMap::new();
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => mode = FlagMode::Long,
+                ["FLAG", "num", ..] => mode = FlagMode::Numeric,
+                ["TRY", chars, ..] => dictionary.try_chars = chars.chars().collect(),
+                ["NEEDAFFIX", flag, ..] => dictionary.need_affix = parse_flags(flag, mode).first().copied(),
+                ["FORBIDDENWORD", flag, ..] => dictionary.forbidden = parse_flags(flag, mode).first().copied(),
+                // Заголовок группы: "SFX флаг Y число"
+                [kind @ ("SFX" | "PFX"), flag, cross, count] if count.parse::<usize>().is_ok() => {
+                    let flag = parse_flags(flag, mode).first().copied().unwrap_or(0);
+                    cross_products.insert((*kind == "SFX", flag), *cross == "Y");
+                }
+                // Правило: "SFX флаг отрезать добавить условие"
+                [kind @ ("SFX" | "PFX"), flag, strip, add, condition, ..] => {
+                    let is_suffix = *kind == "SFX";
+                    let flag = parse_flags(flag, mode).first().copied().unwrap_or(0);
+                    let zero = |s: &str| if s == "0" { String::new() } else { s.to_string() };
+                    // После "/" в добавке идут флаги продолжения: «drink/X» + «SFX X 0 able/Y» + «SFX Y 0 s»
+                    let (add, continuation) = match add.split_once('/') {
+                        Some((add, flags)) => (add, parse_flags(flags, mode)),
+                        None => (*add, Vec::new()),
+                    };
+                    let rule = AffixRule {
+                        flag,
+                        strip: zero(strip),
+                        add: zero(add),
+                        condition: parse_condition(condition),
+                        cross_product: cross_products.get(&(is_suffix, flag)).copied().unwrap_or(false),
+                        continuation,
+                    };
+                    let rules = if is_suffix { &mut dictionary.suffixes } else { &mut dictionary.prefixes };
+                    rules.entry(rule.add.clone()).or_default().push(rule);
+                }
+                _ => {}
+            }
+        }
+
+        // Первая строка .dic — число слов; дальше "слово/ФЛАГИ [морфология]"
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, parse_flags(flags, mode)),
+                None => (entry, Vec::new()),
+            };
+            dictionary.words.entry(word.to_string()).or_default().extend(flags);
+        }
+
+        dictionary
+    }
+
+    fn has_flag(&self, flags: &[u32], flag: Option<u32>) -> bool {
+        flag.is_some_and(|flag| flags.contains(&flag))
+    }
+
+    // Флаги основы из словаря, если она принимает аффикс с флагом flag
+    fn stem_flags(&self, stem: &str, flag: u32) -> Option<&[u32]> {
+        self.words
+            .get(stem)
+            .map(Vec::as_slice)
+            .filter(|flags| flags.contains(&flag) && !self.has_flag(flags, self.forbidden))
+    }
+
+    // Пары «основа + окончание» для слова: окончание берется из правил, основа восстанавливается по strip
+    fn suffix_splits<'a>(&'a self, word: &'a str) -> impl Iterator<Item = (String, &'a AffixRule)> + 'a {
+        let boundaries = word.char_indices().map(|(i, _)| i).chain([word.len()]);
+        boundaries.skip(1).flat_map(move |split| {
+            let (base, ending) = word.split_at(split);
+            self.suffixes.get(ending).into_iter().flatten().filter_map(move |rule| {
+                let stem = format!("{}{}", base, rule.strip);
+                let stem_chars: Vec<char> = stem.chars().collect();
+                rule.suffix_condition_matches(&stem_chars).then_some((stem, rule))
+            })
+        })
+    }
+
+    fn check_suffixes(&self, word: &str, required_flag: Option<u32>) -> bool {
+        self.suffix_splits(word).any(|(stem, rule)| {
+            if required_flag.is_some() && !rule.cross_product {
+                return false;
+            }
+            // Флаг приставки может стоять у основы или прийти из продолжения окончания
+            let allows_prefix = |flags: &[u32], continuations: &[&[u32]]| {
+                required_flag.is_none_or(|required| {
+                    flags.contains(&required) || continuations.iter().any(|c| c.contains(&required))
+                })
+            };
+            if self.stem_flags(&stem, rule.flag).is_some_and(|flags| allows_prefix(flags, &[&rule.continuation])) {
+                return true;
+            }
+            // Второе окончание: внутреннее правило должно разрешать внешнее флагом продолжения
+            self.suffix_splits(&stem).any(|(root, inner)| {
+                inner.continuation.contains(&rule.flag)
+                    && (required_flag.is_none() || inner.cross_product)
+                    && self
+                        .stem_flags(&root, inner.flag)
+                        .is_some_and(|flags| allows_prefix(flags, &[&rule.continuation, &inner.continuation]))
+            })
+        })
+    }
+
+    pub fn check(&self, word: &str) -> bool {
+        if let Some(flags) = self.words.get(word) {
+            if self.has_flag(flags, self.forbidden) {
+                return false;
+            }
+            if !self.has_flag(flags, self.need_affix) {
+                return true;
+            }
+        }
+        if self.check_suffixes(word, None) {
+            return true;
+        }
+        let mut boundaries = word.char_indices().map(|(i, _)| i).skip(1);
+        boundaries.any(|split| {
+            let (start, rest) = word.split_at(split);
+            let Some(rules) = self.prefixes.get(start) else {
+                return false;
+            };
+            rules.iter().any(|rule| {
+                let stem = format!("{}{}", rule.strip, rest);
+                let stem_chars: Vec<char> = stem.chars().collect();
+                rule.prefix_condition_matches(&stem_chars)
+                    && (self.stem_flags(&stem, rule.flag).is_some()
+                        || (rule.cross_product && self.check_suffixes(&stem, Some(rule.flag))))
+            })
+        })
+    }
+}
+
+// Слово с ошибкой под курсором мыши для контекстного меню
+pub struct SpellTarget {
+    pub start: usize,
+    pub end: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+// Результат фоновой загрузки: словари и ошибки, которые надо показать
+type LoadResult = (Vec<Dictionary>, Vec<String>);
+
+#[derive(Default)]
+pub struct SpellCheckModule {
+    dictionaries: Vec<Dictionary>,
+    user_words: HashSet<String>,
+    loader: Option<Receiver<LoadResult>>,
+    loaded: bool,
+    cache: HashMap<String, bool>,
+    checked_generation: Option<u64>, // Поколение текста, для которого посчитаны ошибки
+    misspelled: Vec<(usize, usize)>, // Диапазоны символов с ошибками
+}
+
+pub fn dictionaries_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(DICTIONARIES_DIR))
+}
+
+fn user_dictionary_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(USER_DICTIONARY_FILE))
+}
+
+fn load_dictionaries() -> LoadResult {
+    let mut dictionaries = Vec::new();
+    let mut errors = Vec::new();
+    let Some(entries) = dictionaries_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return (dictionaries, errors);
+    };
+    let mut dic_files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "dic"))
+        .collect();
+    dic_files.sort();
+
+    for dic in dic_files {
+        let aff = dic.with_extension("aff");
+        match Dictionary::load(&aff, &dic) {
+            Ok(dictionary) => dictionaries.push(dictionary),
+            Err(e) => errors.push(e),
+        }
+    }
+    (dictionaries, errors)
+}
+
+// Слово — буквы, внутри допускаются дефис и апостроф («кто-то», «don't»)
+fn word_ranges(text: &str) -> Vec<(usize, usize, String)> {
+    let mut ranges = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+        if !chars[index].is_alphabetic() {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < chars.len()
+            && (chars[index].is_alphabetic()
+                || (matches!(chars[index], '-' | '\'' | '’')
+                    && chars.get(index + 1).is_some_and(|c| c.is_alphabetic())))
+        {
+            index += 1;
+        }
+        // Слово, склеенное с цифрами (v2, 5й), не проверяем
+        if chars.get(index).is_some_and(|c| c.is_numeric()) || (start > 0 && chars[start - 1].is_numeric()) {
+            continue;
+        }
+        ranges.push((start, index, chars[start..index].iter().collect()));
+    }
+    ranges
+}
+
+impl SpellCheckModule {
+    // Словари читаются в фоне: русский словарь разбирается заметное время
+    fn start_loading(&mut self) {
+        if self.loaded || self.loader.is_some() {
+            return;
+        }
+        if let Some(content) = user_dictionary_path().and_then(|path| fs::read_to_string(path).ok()) {
+            self.user_words = content.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+        }
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(load_dictionaries());
+        });
+        self.loader = Some(receiver);
+    }
+
+    // Вызывается каждый кадр, пока проверка включена; возвращает ошибки загрузки словарей.
+    // Текст перепроверяется, только когда сменилось его поколение (счетчик правок в редакторе)
+    pub fn update(&mut self, text: &str, generation: u64) -> Option<String> {
+        self.start_loading();
+        let mut error = None;
+        if let Some(loader) = &self.loader {
+            if let Ok((dictionaries, errors)) = loader.try_recv() {
+                self.dictionaries = dictionaries;
+                self.loader = None;
+                self.loaded = true;
+                self.checked_generation = None;
+                self.cache.clear();
+                error = errors.into_iter().next();
+            }
+        }
+        if !self.loaded || self.dictionaries.is_empty() {
+            self.misspelled.clear();
+            return error;
+        }
+
+        if self.checked_generation != Some(generation) {
+            self.checked_generation = Some(generation);
+            self.misspelled = word_ranges(text)
+                .into_iter()
+                .filter(|(_, _, word)| !self.is_correct_cached(word))
+                .map(|(start, end, _)| (start, end))
+                .collect();
+        }
+        error
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loader.is_some()
+    }
+
+    pub fn dictionary_names(&self) -> Vec<&str> {
+        self.dictionaries.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    pub fn misspelled(&self) -> &[(usize, usize)] {
+        &self.misspelled
+    }
+
+    pub fn misspelled_at(&self, char_index: usize) -> Option<(usize, usize)> {
+        self.misspelled
+            .iter()
+            .copied()
+            .find(|&(start, end)| start <= char_index && char_index <= end)
+    }
+
+    pub fn target_at(&self, char_index: usize, text: &str) -> Option<SpellTarget> {
+        let (start, end) = self.misspelled_at(char_index)?;
+        let word: String = text.chars().skip(start).take(end - start).collect();
+        let suggestions = self.suggestions(&word);
+        Some(SpellTarget { start, end, word, suggestions })
+    }
+
+    fn is_correct_cached(&mut self, word: &str) -> bool {
+        if let Some(&correct) = self.cache.get(word) {
+            return correct;
+        }
+        let correct = self.is_correct(word);
+        self.cache.insert(word.to_string(), correct);
+        correct
+    }
+
+    // «Слово» в начале предложения и «СЛОВО» в заголовке тоже правильные
+    fn is_correct(&self, word: &str) -> bool {
+        if word.chars().count() < 2 {
+            return true;
+        }
+        let lower = word.to_lowercase();
+        let mut variants = vec![word.to_string(), lower.clone()];
+        if word.chars().all(|c| !c.is_lowercase()) {
+            let mut chars = lower.chars();
+            if let Some(first) = chars.next() {
+                variants.push(first.to_uppercase().chain(chars).collect());
+            }
+        }
+        // Ё часто пишут как Е — принимаем оба варианта
+        if lower.contains('е') {
+            variants.push(lower.replace('е', "ё"));
+        }
+
+        variants.iter().any(|variant| {
+            self.user_words.contains(variant) || self.dictionaries.iter().any(|d| d.check(variant))
+        })
+    }
+
+    // Варианты на расстоянии одной правки: пропущенная, лишняя, замененная или переставленная буква
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.to_lowercase().chars().collect();
+        let mut alphabet: Vec<char> = self.dictionaries.iter().flat_map(|d| d.try_chars.iter().copied()).collect();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        let mut candidates: Vec<String> = Vec::new();
+        for i in 0..chars.len() {
+            let mut deleted = chars.clone();
+            deleted.remove(i);
+            candidates.push(deleted.into_iter().collect());
+            if i + 1 < chars.len() {
+                let mut swapped = chars.clone();
+                swapped.swap(i, i + 1);
+                candidates.push(swapped.into_iter().collect());
+            }
+        }
+        for i in 0..=chars.len() {
+            for &c in &alphabet {
+                if i < chars.len() && chars[i] != c {
+                    let mut replaced = chars.clone();
+                    replaced[i] = c;
+                    candidates.push(replaced.into_iter().collect());
+                }
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                candidates.push(inserted.into_iter().collect());
+            }
+        }
+        // Слово через дефис могли написать слитно или раздельно
+        for i in 1..chars.len() {
+            let (left, right): (String, String) = (chars[..i].iter().collect(), chars[i..].iter().collect());
+            if self.is_correct(&left) && self.is_correct(&right) {
+                candidates.push(format!("{} {}", left, right));
+            }
+        }
+
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let mut result: Vec<String> = Vec::new();
+        for candidate in candidates {
+            let correct = candidate.contains(' ') || self.is_correct(&candidate);
+            if !correct {
+                continue;
+            }
+            let candidate = if capitalized {
+                let mut chars = candidate.chars();
+                chars.next().map(|f| f.to_uppercase().chain(chars).collect()).unwrap_or_default()
+            } else {
+                candidate
+            };
+            if !result.contains(&candidate) {
+                result.push(candidate);
+            }
+            if result.len() >= MAX_SUGGESTIONS {
+                break;
+            }
+        }
+        result
+    }
+
+    pub fn add_word(&mut self, word: &str) -> Result<(), String> {
+        self.user_words.insert(word.to_string());
+        self.cache.insert(word.to_string(), true);
+        self.checked_generation = None;
+
+        let Some(path) = user_dictionary_path() else {
+            return Ok(());
+        };
+        let mut words: Vec<&String> = self.user_words.iter().collect();
+        words.sort();
+        let content: String = words.into_iter().map(|w| format!("{}\n", w)).collect();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| tr!("Ошибка сохранения словаря: {}", e))?;
+        }
+        fs::write(&path, content).map_err(|e| tr!("Ошибка сохранения словаря: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8
+NEEDAFFIX N
+FORBIDDENWORD F
+SFX X Y 1
+SFX X 0 able/Y .
+SFX Y Y 1
+SFX Y 0 s .
+PFX U Y 1
+PFX U 0 un .
+";
+    const DIC: &str = "4
+drink/XU
+think/NY
+thinks/F
+work/NY
+";
+
+    // Окончание поверх окончания разрешается флагом продолжения, а не флагом основы
+    #[test]
+    fn twofold_suffix_uses_continuation_flags() {
+        let dictionary = Dictionary::parse("en".to_string(), AFF, DIC);
+        assert!(dictionary.check("drink"));
+        assert!(dictionary.check("drinkable"));
+        assert!(dictionary.check("drinkables"));
+        assert!(dictionary.check("undrinkables"));
+        assert!(!dictionary.check("drinks"));
+    }
+
+    #[test]
+    fn needaffix_and_forbidden_words_are_rejected() {
+        let dictionary = Dictionary::parse("en".to_string(), AFF, DIC);
+        assert!(!dictionary.check("work"));
+        assert!(dictionary.check("works"));
+        assert!(!dictionary.check("think"));
+        assert!(!dictionary.check("thinks"));
+    }
+}
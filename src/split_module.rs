@@ -0,0 +1,206 @@
+// split_module.rs
+use eframe::egui;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::i18n::tr;
+
+const SPLIT_TEXT_EDIT_ID: &str = "split_text_edit";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SplitOrientation {
+    Vertical,   // Панели рядом
+    Horizontal, // Панели одна над другой
+}
+
+// Что показывает вторая панель
+#[derive(Default)]
+pub enum SplitSource {
+    #[default]
+    SameBuffer, // Тот же документ со своей прокруткой
+    File {
+        path: PathBuf,
+        text: String,
+        unsaved: bool,
+        read_only: bool, // .docx и файлы не в UTF-8: записав текст как есть, мы бы испортили файл
+    },
+}
+
+// Что сделать, когда пользователь ответит про несохраненные изменения во второй панели
+enum PendingSplit {
+    Close,
+    Open { path: PathBuf, text: String, read_only: bool },
+}
+
+#[derive(Default)]
+pub struct SplitModule {
+    pub orientation: Option<SplitOrientation>, // None — разделения нет
+    pub source: SplitSource,
+    pub error_message: Option<String>,
+    pending: Option<PendingSplit>,
+}
+
+impl SplitModule {
+    pub fn split(&mut self, orientation: SplitOrientation) {
+        self.orientation = Some(orientation);
+    }
+
+    fn has_unsaved(&self) -> bool {
+        matches!(self.source, SplitSource::File { unsaved: true, .. })
+    }
+
+    pub fn open_file(&mut self, path: PathBuf, text: String, read_only: bool) {
+        if self.has_unsaved() {
+            self.pending = Some(PendingSplit::Open { path, text, read_only });
+            return;
+        }
+        self.source = SplitSource::File { path, text, unsaved: false, read_only };
+        self.orientation.get_or_insert(SplitOrientation::Vertical);
+    }
+
+    pub fn close(&mut self) {
+        if self.has_unsaved() {
+            self.pending = Some(PendingSplit::Close);
+            return;
+        }
+        self.orientation = None;
+        self.source = SplitSource::SameBuffer;
+    }
+
+    fn save(&mut self) {
+        if let SplitSource::File { path, text, unsaved, read_only: false } = &mut self.source {
+            match fs::write(&*path, &*text) {
+                Ok(_) => *unsaved = false,
+                Err(e) => self.error_message = Some(tr!("Ошибка сохранения файла: {}", e)),
+            }
+        }
+    }
+
+    // Возвращает true, если во второй панели изменили основной документ
+    pub fn show_pane(&mut self, ui: &mut egui::Ui, main_text: &mut String, font: egui::FontId, text_color: egui::Color32) -> bool {
+        let mut close = false;
+        let mut save = false;
+
+        ui.horizontal(|ui| {
+            match &self.source {
+                SplitSource::SameBuffer => {
+                    ui.label(tr!("Тот же документ"));
+                }
+                SplitSource::File { path, unsaved, read_only, .. } => {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let title = if *unsaved { tr!("{} • (изменен)", name) } else { name };
+                    ui.label(title).on_hover_text(path.display().to_string());
+                    if *read_only {
+                        let hint =
+                            tr!("Документ Word или файл не в UTF-8 — откройте его в основном окне, чтобы править");
+                        ui.weak(tr!("только чтение")).on_hover_text(hint);
+                    } else if ui.small_button("💾").on_hover_text(tr!("Сохранить")).clicked() {
+                        save = true;
+                    }
+                }
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("✖").on_hover_text(tr!("Закрыть разделение")).clicked() {
+                    close = true;
+                }
+            });
+        });
+
+        let same_buffer = matches!(self.source, SplitSource::SameBuffer);
+        let mut changed = false;
+        egui::ScrollArea::vertical()
+            .id_source("split_text_scroll")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                // У &str текст в поле только выделяется и копируется
+                let mut read_only_text;
+                let text: &mut dyn egui::TextBuffer = match &mut self.source {
+                    SplitSource::SameBuffer => main_text,
+                    SplitSource::File { text, read_only: true, .. } => {
+                        read_only_text = text.as_str();
+                        &mut read_only_text
+                    }
+                    SplitSource::File { text, .. } => text,
+                };
+                let response = ui.add(
+                    egui::TextEdit::multiline(text)
+                        .id(egui::Id::new(SPLIT_TEXT_EDIT_ID))
+                        .code_editor()
+                        .desired_rows(30)
+                        .desired_width(f32::INFINITY)
+                        .font(font)
+                        .text_color(text_color),
+                );
+                changed = response.changed();
+            });
+
+        if changed && !same_buffer {
+            if let SplitSource::File { unsaved, .. } = &mut self.source {
+                *unsaved = true;
+            }
+        }
+        if save {
+            self.save();
+        }
+        if close {
+            self.close();
+        }
+        self.show_confirm(ui.ctx());
+
+        changed && same_buffer
+    }
+
+    fn show_confirm(&mut self, ctx: &egui::Context) {
+        if self.pending.is_none() {
+            return;
+        }
+        let mut answer = None;
+        let mut open = true;
+        let mut cancel = false;
+        egui::Window::new(tr!("Сохранение файла"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tr!("Сохранить изменения во второй панели?"));
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Сохранить")).clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button(tr!("Не сохранять")).clicked() {
+                        answer = Some(false);
+                    }
+                    if ui.button(tr!("Отмена")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if !open || cancel {
+            self.pending = None;
+            return;
+        }
+        let Some(save) = answer else {
+            return;
+        };
+        if save {
+            self.save();
+            // Не удалось сохранить — правки остаются в панели, ошибку покажет основное окно
+            if self.has_unsaved() {
+                self.pending = None;
+                return;
+            }
+        }
+        if let SplitSource::File { unsaved, .. } = &mut self.source {
+            *unsaved = false;
+        }
+        match self.pending.take() {
+            Some(PendingSplit::Close) => self.close(),
+            Some(PendingSplit::Open { path, text, read_only }) => self.open_file(path, text, read_only),
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,290 @@
+// statistics_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+// Что считать символами: издательства считают по-разному
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterCount {
+    #[default]
+    WithSpaces,
+    WithoutSpaces,
+}
+
+// Что считать словами
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WordCount {
+    #[default]
+    AllTokens, // Все, что разделено пробелами, включая «—» и «2024»
+    NoPunctuation, // Без знаков препинания, стоящих отдельно
+    LettersOnly,   // Только слова с буквами: без чисел и знаков
+}
+
+impl CharacterCount {
+    pub const ALL: [CharacterCount; 2] = [CharacterCount::WithSpaces, CharacterCount::WithoutSpaces];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            CharacterCount::WithSpaces => tr!("с пробелами"),
+            CharacterCount::WithoutSpaces => tr!("без пробелов"),
+        }
+    }
+}
+
+impl WordCount {
+    pub const ALL: [WordCount; 3] = [WordCount::AllTokens, WordCount::NoPunctuation, WordCount::LettersOnly];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            WordCount::AllTokens => tr!("все через пробел"),
+            WordCount::NoPunctuation => tr!("без отдельных знаков"),
+            WordCount::LettersOnly => tr!("без чисел и знаков"),
+        }
+    }
+}
+
+pub fn count_characters(text: &str, mode: CharacterCount) -> usize {
+    match mode {
+        CharacterCount::WithSpaces => text.chars().count(),
+        CharacterCount::WithoutSpaces => text.chars().filter(|c| !c.is_whitespace()).count(),
+    }
+}
+
+pub fn count_words(text: &str, mode: WordCount) -> usize {
+    let tokens = text.split_whitespace();
+    match mode {
+        WordCount::AllTokens => tokens.count(),
+        WordCount::NoPunctuation => tokens.filter(|word| word.chars().any(char::is_alphanumeric)).count(),
+        WordCount::LettersOnly => tokens.filter(|word| word.chars().any(char::is_alphabetic)).count(),
+    }
+}
+
+#[derive(Default)]
+pub struct StatisticsModule {
+    pub show_window: bool,
+}
+
+#[derive(PartialEq)]
+pub enum StatisticsResult {
+    None,
+    ConfigChanged,
+}
+
+// Слова и предложения одного абзаца; end — символ сразу после его последней буквы
+pub struct ParagraphStats {
+    pub end: usize,
+    pub words: usize,
+    pub sentences: usize,
+}
+
+// Абзацы разделяются пустыми строками, как и в общей статистике
+pub fn paragraph_statistics(text: &str, words: WordCount) -> Vec<ParagraphStats> {
+    let mut paragraphs = Vec::new();
+    let mut start = None; // Байт, с которого начался текущий абзац
+    let (mut end_byte, mut end_char) = (0, 0);
+    let (mut byte, mut char_index) = (0, 0);
+    let mut close = |start: usize, end_byte: usize, end_char: usize| {
+        let paragraph = &text[start..end_byte];
+        paragraphs.push(ParagraphStats {
+            end: end_char,
+            words: count_words(paragraph, words),
+            sentences: count_sentences(paragraph),
+        });
+    };
+    for line in text.split('\n') {
+        let content = line.trim_end();
+        if content.trim_start().is_empty() {
+            if let Some(start) = start.take() {
+                close(start, end_byte, end_char);
+            }
+        } else {
+            start.get_or_insert(byte);
+            end_byte = byte + content.len();
+            end_char = char_index + content.chars().count();
+        }
+        byte += line.len() + 1;
+        char_index += line.chars().count() + 1;
+    }
+    if let Some(start) = start {
+        close(start, end_byte, end_char);
+    }
+    paragraphs
+}
+
+#[derive(Default)]
+pub struct TextStatistics {
+    pub words: usize,
+    pub tokens: usize,       // Все, что разделено пробелами
+    pub letter_words: usize, // Без чисел и знаков препинания
+    pub characters: usize,
+    pub characters_no_spaces: usize,
+    pub lines: usize,
+    pub paragraphs: usize,
+    pub sentences: usize,
+    pub average_word_length: f32,
+    pub longest_line: (usize, usize), // (номер строки с единицы, длина в символах)
+}
+
+impl TextStatistics {
+    pub fn compute(text: &str) -> Self {
+        let mut stats = TextStatistics {
+            characters: text.chars().count(),
+            characters_no_spaces: count_characters(text, CharacterCount::WithoutSpaces),
+            tokens: count_words(text, WordCount::AllTokens),
+            letter_words: count_words(text, WordCount::LettersOnly),
+            ..Default::default()
+        };
+
+        // Длина слова — без знаков препинания по краям: «слово,» считается как 5 букв
+        let mut letters = 0;
+        for word in text.split_whitespace() {
+            let trimmed = word.trim_matches(|c: char| !c.is_alphanumeric());
+            if !trimmed.is_empty() {
+                stats.words += 1;
+                letters += trimmed.chars().count();
+            }
+        }
+        if stats.words > 0 {
+            stats.average_word_length = letters as f32 / stats.words as f32;
+        }
+
+        // Абзацы разделяются пустыми строками
+        let mut in_paragraph = false;
+        for (index, line) in text.lines().enumerate() {
+            stats.lines += 1;
+            let length = line.chars().count();
+            if length > stats.longest_line.1 {
+                stats.longest_line = (index + 1, length);
+            }
+            let blank = line.trim().is_empty();
+            if !blank && !in_paragraph {
+                stats.paragraphs += 1;
+            }
+            in_paragraph = !blank;
+        }
+        stats.lines = stats.lines.max(1);
+
+        stats.sentences = count_sentences(text);
+        stats
+    }
+
+    pub fn reading_minutes(&self, words_per_minute: u32) -> f32 {
+        self.words as f32 / words_per_minute.max(1) as f32
+    }
+}
+
+// Предложение заканчивается группой из . ! ? … перед пробелом или концом текста;
+// «т. е.» и сокращения посчитаются лишний раз, для оценки это неважно
+fn count_sentences(text: &str) -> usize {
+    let mut sentences = 0;
+    let mut has_words = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() {
+            has_words = true;
+        }
+        if matches!(c, '.' | '!' | '?' | '…') && has_words {
+            while chars.peek().is_some_and(|n| matches!(n, '.' | '!' | '?' | '…' | '"' | '»' | ')')) {
+                chars.next();
+            }
+            if chars.peek().is_none_or(|n| n.is_whitespace()) {
+                sentences += 1;
+                has_words = false;
+            }
+        }
+    }
+    // Последнее предложение без точки тоже считается
+    if has_words {
+        sentences += 1;
+    }
+    sentences
+}
+
+fn format_reading_time(minutes: f32) -> String {
+    if minutes < 1.0 {
+        return tr!("меньше минуты").to_string();
+    }
+    let minutes = minutes.round() as u32;
+    if minutes < 60 {
+        tr!("{} мин", minutes)
+    } else {
+        tr!("{} ч {} мин", minutes / 60, minutes % 60)
+    }
+}
+
+impl StatisticsModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, text: &str, config: &mut Config) -> StatisticsResult {
+        if !self.show_window {
+            return StatisticsResult::None;
+        }
+
+        let mut result = StatisticsResult::None;
+        let mut open = self.show_window;
+        let stats = TextStatistics::compute(text);
+
+        egui::Window::new(tr!("Статистика"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("statistics_grid")
+                    .num_columns(2)
+                    .spacing([24.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let row = |ui: &mut egui::Ui, name: &str, value: String| {
+                            ui.label(name);
+                            ui.monospace(value);
+                            ui.end_row();
+                        };
+                        row(ui, tr!("Слов"), stats.words.to_string());
+                        row(ui, tr!("Слов без чисел"), stats.letter_words.to_string());
+                        row(ui, tr!("Всего через пробел"), stats.tokens.to_string());
+                        row(ui, tr!("Символов"), stats.characters.to_string());
+                        row(ui, tr!("Символов без пробелов"), stats.characters_no_spaces.to_string());
+                        row(ui, tr!("Строк"), stats.lines.to_string());
+                        row(ui, tr!("Абзацев"), stats.paragraphs.to_string());
+                        row(ui, tr!("Предложений"), stats.sentences.to_string());
+                        row(ui, tr!("Средняя длина слова"), format!("{:.1}", stats.average_word_length));
+                        row(
+                            ui,
+                            tr!("Самая длинная строка"),
+                            tr!("{} (строка {})", stats.longest_line.1, stats.longest_line.0),
+                        );
+                        row(
+                            ui,
+                            tr!("Время чтения"),
+                            format_reading_time(stats.reading_minutes(config.reading_wpm)),
+                        );
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Скорость чтения:"));
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut config.reading_wpm)
+                                .clamp_range(50..=1000)
+                                .suffix(tr!(" слов/мин")),
+                        )
+                        .changed()
+                    {
+                        result = StatisticsResult::ConfigChanged;
+                    }
+                });
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+
+        result
+    }
+}
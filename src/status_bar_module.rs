@@ -0,0 +1,127 @@
+// status_bar_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+// Элементы строки состояния; порядок в ALL — порядок по умолчанию
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusItem {
+    FileName,
+    GitBranch,
+    Characters,
+    Words,
+    Lines,
+    Position,
+    Encoding,
+    DailyGoal,
+    DocumentGoal,
+    SearchMatches,
+    Zoom,
+    Pomodoro,
+    Music,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct StatusBarItem {
+    pub item: StatusItem,
+    pub visible: bool,
+}
+
+impl StatusItem {
+    pub const ALL: [StatusItem; 13] = [
+        StatusItem::FileName,
+        StatusItem::GitBranch,
+        StatusItem::Characters,
+        StatusItem::Words,
+        StatusItem::Lines,
+        StatusItem::Position,
+        StatusItem::Encoding,
+        StatusItem::DailyGoal,
+        StatusItem::DocumentGoal,
+        StatusItem::SearchMatches,
+        StatusItem::Zoom,
+        StatusItem::Pomodoro,
+        StatusItem::Music,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            StatusItem::FileName => tr!("Имя файла"),
+            StatusItem::GitBranch => tr!("Ветка Git"),
+            StatusItem::Characters => tr!("Символы"),
+            StatusItem::Words => tr!("Слова"),
+            StatusItem::Lines => tr!("Строки"),
+            StatusItem::Position => tr!("Позиция курсора"),
+            StatusItem::Encoding => tr!("Кодировка"),
+            StatusItem::DailyGoal => tr!("Цель на день"),
+            StatusItem::DocumentGoal => tr!("Цель по словам"),
+            StatusItem::SearchMatches => tr!("Найдено при поиске"),
+            StatusItem::Zoom => tr!("Масштаб"),
+            StatusItem::Pomodoro => tr!("Таймер помидоро"),
+            StatusItem::Music => tr!("Музыка"),
+        }
+    }
+
+    // Соседние элементы одной группы идут без разделителя
+    pub fn group(self) -> u8 {
+        match self {
+            StatusItem::FileName | StatusItem::GitBranch => 0,
+            StatusItem::Characters | StatusItem::Words | StatusItem::Lines => 1,
+            _ => self as u8 + 2,
+        }
+    }
+
+    // Позиция и кодировка появились позже — по умолчанию скрыты, чтобы строка не стала длиннее
+    fn visible_by_default(self) -> bool {
+        !matches!(self, StatusItem::Position | StatusItem::Encoding)
+    }
+}
+
+pub fn default_status_bar() -> Vec<StatusBarItem> {
+    StatusItem::ALL
+        .iter()
+        .map(|&item| StatusBarItem { item, visible: item.visible_by_default() })
+        .collect()
+}
+
+// Повторы из файла настроек убираем, новые элементы дописываем в конец
+pub fn normalize(items: &mut Vec<StatusBarItem>) {
+    let mut seen = Vec::new();
+    items.retain(|entry| {
+        let new = !seen.contains(&entry.item);
+        seen.push(entry.item);
+        new
+    });
+    for item in StatusItem::ALL {
+        if !seen.contains(&item) {
+            items.push(StatusBarItem { item, visible: item.visible_by_default() });
+        }
+    }
+}
+
+// Флажок и стрелки порядка для каждого элемента
+pub fn settings_ui(ui: &mut egui::Ui, items: &mut Vec<StatusBarItem>) {
+    let mut swap = None;
+    ui.vertical(|ui| {
+        let count = items.len();
+        for (index, entry) in items.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(index > 0, egui::Button::new("⏶").small()).clicked() {
+                    swap = Some(index - 1);
+                }
+                if ui.add_enabled(index + 1 < count, egui::Button::new("⏷").small()).clicked() {
+                    swap = Some(index);
+                }
+                ui.checkbox(&mut entry.visible, entry.item.title());
+            });
+        }
+        if ui.button(tr!("По умолчанию")).clicked() {
+            *items = default_status_bar();
+        }
+    });
+    if let Some(index) = swap {
+        items.swap(index, index + 1);
+    }
+}
@@ -0,0 +1,211 @@
+// symbol_module.rs
+use eframe::egui;
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+const MAX_RECENT: usize = 24;
+const MAX_SEARCH_RESULTS: usize = 300;
+
+// Русская типографика — с русскими названиями, чтобы искать «тире», а не «EM DASH»
+const TYPOGRAPHY: [(char, &str); 32] = [
+    ('—', "тире"),
+    ('–', "короткое тире"),
+    ('‑', "неразрывный дефис"),
+    ('«', "открывающая кавычка-елочка"),
+    ('»', "закрывающая кавычка-елочка"),
+    ('„', "открывающая кавычка-лапка"),
+    ('“', "закрывающая кавычка-лапка"),
+    ('”', "английская закрывающая кавычка"),
+    ('‘', "одинарная открывающая кавычка"),
+    ('’', "апостроф"),
+    ('…', "многоточие"),
+    ('№', "номер"),
+    ('§', "параграф"),
+    ('\u{00A0}', "неразрывный пробел"),
+    ('\u{202F}', "узкий неразрывный пробел"),
+    ('\u{2009}', "тонкая шпация"),
+    ('°', "градус"),
+    ('±', "плюс-минус"),
+    ('×', "умножение"),
+    ('÷', "деление"),
+    ('≈', "примерно равно"),
+    ('≠', "не равно"),
+    ('≤', "меньше или равно"),
+    ('≥', "больше или равно"),
+    ('′', "штрих, минута"),
+    ('″', "двойной штрих, секунда"),
+    ('₽', "рубль"),
+    ('€', "евро"),
+    ('©', "копирайт"),
+    ('®', "зарегистрированный знак"),
+    ('™', "товарный знак"),
+    ('•', "маркер списка"),
+];
+
+// Блоки Unicode, которые обычно нужны в тексте
+const BLOCKS: [(&str, u32, u32); 10] = [
+    ("Латиница-1", 0x00A1, 0x00FF),
+    ("Расширенная латиница", 0x0100, 0x017F),
+    ("Греческий", 0x0391, 0x03C9),
+    ("Знаки препинания", 0x2010, 0x205E),
+    ("Валюты", 0x20A0, 0x20C0),
+    ("Стрелки", 0x2190, 0x21FF),
+    ("Математика", 0x2200, 0x22FF),
+    ("Псевдографика", 0x2500, 0x257F),
+    ("Геометрические фигуры", 0x25A0, 0x25FF),
+    ("Разные символы", 0x2600, 0x26FF),
+];
+
+struct Symbol {
+    c: char,
+    name: String, // В нижнем регистре, для поиска
+}
+
+#[derive(Default)]
+pub struct SymbolModule {
+    pub show_window: bool,
+    query: String,
+    block: Option<usize>, // None — русская типографика
+    symbols: Vec<Symbol>, // Все символы с названиями; собираются при первом открытии
+}
+
+pub enum SymbolResult {
+    None,
+    Insert(char),
+}
+
+fn unicode_name(c: char) -> Option<String> {
+    unicode_names2::name(c).map(|name| name.to_string().to_lowercase())
+}
+
+fn symbol_name(c: char) -> String {
+    TYPOGRAPHY
+        .iter()
+        .find(|(symbol, _)| *symbol == c)
+        .map(|(_, name)| name.to_string())
+        .or_else(|| unicode_name(c))
+        .unwrap_or_default()
+}
+
+// Пробелы на кнопке не видны — показываем их скобками
+fn button_text(c: char) -> String {
+    if c.is_whitespace() {
+        "[ ]".to_string()
+    } else {
+        c.to_string()
+    }
+}
+
+impl SymbolModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+        if self.symbols.is_empty() {
+            self.symbols = TYPOGRAPHY
+                .iter()
+                .map(|(c, name)| Symbol {
+                    c: *c,
+                    name: format!("{} {}", name, unicode_name(*c).unwrap_or_default()),
+                })
+                .chain(BLOCKS.iter().flat_map(|(_, first, last)| {
+                    (*first..=*last)
+                        .filter_map(char::from_u32)
+                        .filter_map(|c| unicode_name(c).map(|name| Symbol { c, name }))
+                }))
+                .collect();
+        }
+    }
+
+    fn symbol_button(ui: &mut egui::Ui, c: char, result: &mut SymbolResult) {
+        let button = egui::Button::new(egui::RichText::new(button_text(c)).size(18.0)).min_size(egui::vec2(30.0, 30.0));
+        let response = ui.add(button).on_hover_ui(|ui| {
+            ui.label(format!("{}\nU+{:04X}", symbol_name(c), c as u32));
+        });
+        if response.clicked() {
+            *result = SymbolResult::Insert(c);
+        }
+    }
+
+    // Вставленный символ попадает в начало списка недавних в настройках
+    pub fn show_window(&mut self, ctx: &egui::Context, config: &mut Config) -> SymbolResult {
+        let mut result = SymbolResult::None;
+        if !self.show_window {
+            return result;
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Таблица символов"))
+            .open(&mut open)
+            .default_size([420.0, 360.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Поиск:"));
+                    ui.add(egui::TextEdit::singleline(&mut self.query).hint_text(tr!("тире, arrow, pi...")));
+                });
+
+                if !config.recent_symbols.is_empty() {
+                    ui.label(tr!("Недавние:"));
+                    ui.horizontal_wrapped(|ui| {
+                        for c in config.recent_symbols.chars() {
+                            Self::symbol_button(ui, c, &mut result);
+                        }
+                    });
+                }
+                ui.separator();
+
+                let query = self.query.trim().to_lowercase();
+                if query.is_empty() {
+                    egui::ComboBox::from_id_source("symbol_block")
+                        .selected_text(self.block.map_or(tr!("Типографика"), |index| tr!(BLOCKS[index].0)))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.block, None, tr!("Типографика"));
+                            for (index, (title, _, _)) in BLOCKS.iter().enumerate() {
+                                ui.selectable_value(&mut self.block, Some(index), tr!(title));
+                            }
+                        });
+                }
+
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        if !query.is_empty() {
+                            let found: Vec<char> = self
+                                .symbols
+                                .iter()
+                                .filter(|symbol| symbol.name.contains(&query))
+                                .map(|symbol| symbol.c)
+                                .take(MAX_SEARCH_RESULTS)
+                                .collect();
+                            if found.is_empty() {
+                                ui.label(tr!("Ничего не найдено"));
+                            }
+                            for c in found {
+                                Self::symbol_button(ui, c, &mut result);
+                            }
+                        } else if let Some(index) = self.block {
+                            let (_, first, last) = BLOCKS[index];
+                            let block = self.symbols[TYPOGRAPHY.len()..]
+                                .iter()
+                                .filter(|symbol| (first..=last).contains(&(symbol.c as u32)));
+                            for symbol in block {
+                                Self::symbol_button(ui, symbol.c, &mut result);
+                            }
+                        } else {
+                            for (c, _) in TYPOGRAPHY {
+                                Self::symbol_button(ui, c, &mut result);
+                            }
+                        }
+                    });
+                });
+            });
+
+        if let SymbolResult::Insert(c) = result {
+            let mut recent: String = config.recent_symbols.chars().filter(|&r| r != c).collect();
+            recent.insert(0, c);
+            config.recent_symbols = recent.chars().take(MAX_RECENT).collect();
+        }
+        if !open {
+            self.show_window = false;
+        }
+        result
+    }
+}
@@ -0,0 +1,242 @@
+// template_module.rs
+use eframe::egui;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::config_dir;
+use crate::i18n::tr;
+
+const TEMPLATES_DIR: &str = "templates";
+const TEMPLATE_EXTENSION: &str = "txt";
+
+// Шаблоны — обычные .txt в папке настроек, их можно править и снаружи редактора
+pub fn templates_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(TEMPLATES_DIR))
+}
+
+#[derive(Clone, Default)]
+pub struct Template {
+    pub name: String, // Имя файла без .txt
+    pub body: String,
+}
+
+// Значения {{переменных}} на момент создания документа
+pub struct TemplateVariables {
+    pub date: String, // В формате даты из настроек
+    pub author: String,
+}
+
+// {{date}}, {{time}}, {{author}} — подставляются, {{cursor}} — где окажется курсор (в символах)
+pub fn render(body: &str, variables: &TemplateVariables) -> (String, Option<usize>) {
+    let text = body
+        .replace("{{date}}", &variables.date)
+        .replace("{{time}}", &chrono::Local::now().format("%H:%M").to_string())
+        .replace("{{author}}", &variables.author);
+    match text.find("{{cursor}}") {
+        Some(index) => {
+            let cursor = text[..index].chars().count();
+            (text.replacen("{{cursor}}", "", 1), Some(cursor))
+        }
+        None => (text, None),
+    }
+}
+
+fn load_templates() -> Vec<Template> {
+    let Some(dir) = templates_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<Template> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == TEMPLATE_EXTENSION))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            let body = fs::read_to_string(&path).ok()?;
+            Some(Template { name, body })
+        })
+        .collect();
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    templates
+}
+
+fn template_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\', ':']) || name.starts_with('.') {
+        return Err(tr!("Недопустимое имя шаблона: {}", name));
+    }
+    let dir = templates_dir().ok_or_else(|| tr!("Не найдена папка настроек"))?;
+    Ok(dir.join(format!("{}.{}", name, TEMPLATE_EXTENSION)))
+}
+
+#[derive(Default)]
+pub struct TemplateModule {
+    pub show_window: bool,
+    templates: Option<Vec<Template>>, // Читаются с диска при первом обращении
+    selected: usize,
+    edit: Template,             // Правка выбранного шаблона до «Сохранить»
+    saved_name: Option<String>, // Под каким именем правка лежит на диске
+}
+
+pub enum TemplateResult {
+    None,
+    Create(String),
+    Error(String),
+}
+
+impl TemplateModule {
+    pub fn toggle_window(&mut self) {
+        self.show_window = !self.show_window;
+        if self.show_window {
+            self.reload();
+        }
+    }
+
+    pub fn templates(&mut self) -> &[Template] {
+        self.templates.get_or_insert_with(load_templates)
+    }
+
+    fn reload(&mut self) {
+        self.templates = Some(load_templates());
+        self.select(self.selected);
+    }
+
+    fn select(&mut self, index: usize) {
+        let template = self.templates().get(index).cloned();
+        self.selected = index;
+        self.saved_name = template.as_ref().map(|template| template.name.clone());
+        self.edit = template.unwrap_or_default();
+    }
+
+    // Записывает правку; при смене имени старый файл удаляется
+    fn save(&mut self) -> Result<(), String> {
+        let path = template_path(&self.edit.name)?;
+        let renamed = self.saved_name.as_deref() != Some(self.edit.name.trim());
+        if renamed && path.exists() {
+            return Err(tr!("Шаблон «{}» уже есть", self.edit.name.trim()));
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, &self.edit.body).map_err(|e| e.to_string())?;
+        if let Some(old) = self.saved_name.take().filter(|old| *old != self.edit.name.trim()) {
+            fs::remove_file(template_path(&old)?).map_err(|e| e.to_string())?;
+        }
+
+        let name = self.edit.name.trim().to_string();
+        self.templates = Some(load_templates());
+        let index = self.templates().iter().position(|template| template.name == name);
+        self.select(index.unwrap_or(0));
+        Ok(())
+    }
+
+    fn delete(&mut self) -> Result<(), String> {
+        if let Some(name) = self.saved_name.take() {
+            fs::remove_file(template_path(&name)?).map_err(|e| e.to_string())?;
+        }
+        self.templates = Some(load_templates());
+        self.select(self.selected.saturating_sub(1));
+        Ok(())
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context) -> TemplateResult {
+        if !self.show_window {
+            return TemplateResult::None;
+        }
+
+        let mut result = TemplateResult::None;
+        let mut open = self.show_window;
+        let mut select = None;
+        let mut add = false;
+        let mut save = false;
+        let mut delete = false;
+        let names: Vec<String> = self.templates().iter().map(|template| template.name.clone()).collect();
+
+        egui::Window::new(tr!("Шаблоны документов"))
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(150.0);
+                        egui::ScrollArea::vertical()
+                            .id_source("template_list")
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                for (index, name) in names.iter().enumerate() {
+                                    if ui.selectable_label(index == self.selected, name).clicked() {
+                                        select = Some(index);
+                                    }
+                                }
+                                if self.saved_name.is_none() {
+                                    let _ = ui.selectable_label(true, tr!("(новый)"));
+                                }
+                            });
+                        ui.horizontal(|ui| {
+                            add = ui.button(tr!("Добавить")).clicked();
+                            delete = ui
+                                .add_enabled(self.saved_name.is_some(), egui::Button::new(tr!("Удалить")))
+                                .clicked();
+                        });
+                    });
+
+                    ui.separator();
+
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(tr!("Название:"));
+                            ui.text_edit_singleline(&mut self.edit.name);
+                        });
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.edit.body)
+                                .desired_rows(12)
+                                .desired_width(f32::INFINITY),
+                        );
+                        ui.horizontal(|ui| {
+                            save = ui
+                                .add_enabled(!self.edit.name.trim().is_empty(), egui::Button::new(tr!("Сохранить")))
+                                .clicked();
+                            if ui.button(tr!("Создать документ")).clicked() {
+                                result = TemplateResult::Create(self.edit.body.clone());
+                            }
+                        });
+                    });
+                });
+
+                ui.separator();
+                ui.small(tr!(
+                    "{{date}}, {{time}}, {{author}} — дата, время, автор из настроек; {{cursor}} — где встанет курсор"
+                ));
+                if let Some(dir) = templates_dir() {
+                    ui.small(tr!("Папка шаблонов: {}", dir.display()));
+                }
+            });
+
+        if let Some(index) = select {
+            self.select(index);
+        }
+        if add {
+            self.selected = self.templates().len();
+            self.saved_name = None;
+            self.edit = Template::default();
+        }
+        let saved = if save {
+            self.save()
+        } else if delete {
+            self.delete()
+        } else {
+            Ok(())
+        };
+        if let Err(e) = saved {
+            result = TemplateResult::Error(tr!("Ошибка шаблона: {}", e));
+        }
+
+        if !open || matches!(result, TemplateResult::Create(_)) {
+            self.show_window = false;
+        }
+
+        result
+    }
+}
@@ -0,0 +1,217 @@
+// theme_module.rs
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+// Идентификаторы встроенных тем в config.toml
+pub const DARK_THEME: &str = "dark";
+pub const LIGHT_THEME: &str = "light";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Palette {
+    pub name: String,
+    pub dark: bool, // Основа: темные или светлые стандартные цвета egui
+    pub background: [u8; 3],
+    pub text: [u8; 3],
+    pub search_match: [u8; 4],
+    pub current_match: [u8; 4],
+    pub status_bar: [u8; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Palette {
+    pub fn dark() -> Self {
+        Self {
+            name: DARK_THEME.to_string(),
+            dark: true,
+            background: [10, 10, 10],
+            text: [180, 180, 180],
+            search_match: [255, 255, 100, 120],
+            current_match: [255, 100, 100, 180],
+            status_bar: [27, 27, 27],
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: LIGHT_THEME.to_string(),
+            dark: false,
+            background: [255, 255, 255],
+            text: [60, 60, 60],
+            search_match: [255, 230, 0, 120],
+            current_match: [255, 100, 100, 160],
+            status_bar: [248, 248, 248],
+        }
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.name == DARK_THEME || self.name == LIGHT_THEME
+    }
+
+    pub fn visuals(&self) -> egui::Visuals {
+        let mut visuals = if self.dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+        // extreme_bg_color — фон полей ввода, в том числе основного текста
+        visuals.extreme_bg_color = rgb(self.background);
+        visuals
+    }
+
+    pub fn text_color(&self) -> egui::Color32 {
+        rgb(self.text)
+    }
+
+    pub fn status_bar_color(&self) -> egui::Color32 {
+        rgb(self.status_bar)
+    }
+
+    pub fn match_color(&self, current: bool) -> egui::Color32 {
+        let [r, g, b, a] = if current { self.current_match } else { self.search_match };
+        egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+    }
+}
+
+fn rgb([r, g, b]: [u8; 3]) -> egui::Color32 {
+    egui::Color32::from_rgb(r, g, b)
+}
+
+pub fn theme_title(name: &str) -> &str {
+    match name {
+        DARK_THEME => tr!("Темная"),
+        LIGHT_THEME => tr!("Светлая"),
+        _ => name,
+    }
+}
+
+// Встроенные темы и темы пользователя: (идентификатор, название для меню)
+pub fn available_themes(config: &Config) -> Vec<(String, String)> {
+    [DARK_THEME, LIGHT_THEME]
+        .into_iter()
+        .map(str::to_string)
+        .chain(config.custom_themes.iter().map(|p| p.name.clone()))
+        .map(|name| {
+            let title = theme_title(&name).to_string();
+            (name, title)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct ThemeModule {
+    pub show_editor: bool,
+    draft: Palette,
+    error: Option<String>,
+}
+
+#[derive(PartialEq)]
+pub enum ThemeResult {
+    None,
+    Changed,
+}
+
+impl ThemeModule {
+    // Редактор открывается с копией текущей темы; встроенную тему сохраняем под новым именем
+    pub fn open_editor(&mut self, config: &Config) {
+        self.draft = config.palette();
+        if self.draft.is_builtin() {
+            self.draft.name = tr!("Моя тема").to_string();
+        }
+        self.error = None;
+        self.show_editor = true;
+    }
+
+    pub fn show_editor(&mut self, ctx: &egui::Context, config: &mut Config) -> ThemeResult {
+        if !self.show_editor {
+            return ThemeResult::None;
+        }
+
+        let mut result = ThemeResult::None;
+        let mut open = self.show_editor;
+
+        egui::Window::new(tr!("Редактор тем"))
+            .open(&mut open)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("theme_editor_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label(tr!("Название:"));
+                        ui.text_edit_singleline(&mut self.draft.name);
+                        ui.end_row();
+
+                        ui.label(tr!("Основа:"));
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut self.draft.dark, true, tr!("Темная"));
+                            ui.selectable_value(&mut self.draft.dark, false, tr!("Светлая"));
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("Фон текста:"));
+                        ui.color_edit_button_srgb(&mut self.draft.background);
+                        ui.end_row();
+
+                        ui.label(tr!("Текст:"));
+                        ui.color_edit_button_srgb(&mut self.draft.text);
+                        ui.end_row();
+
+                        ui.label(tr!("Совпадения поиска:"));
+                        ui.color_edit_button_srgba_unmultiplied(&mut self.draft.search_match);
+                        ui.end_row();
+
+                        ui.label(tr!("Текущее совпадение:"));
+                        ui.color_edit_button_srgba_unmultiplied(&mut self.draft.current_match);
+                        ui.end_row();
+
+                        ui.label(tr!("Строка состояния:"));
+                        ui.color_edit_button_srgb(&mut self.draft.status_bar);
+                        ui.end_row();
+                    });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Сохранить тему")).clicked() {
+                        self.draft.name = self.draft.name.trim().to_string();
+                        if self.draft.name.is_empty() || self.draft.is_builtin() {
+                            self.error = Some(tr!("Укажите другое название темы").to_string());
+                        } else {
+                            config.custom_themes.retain(|p| p.name != self.draft.name);
+                            config.custom_themes.push(self.draft.clone());
+                            config.theme = self.draft.name.clone();
+                            self.error = None;
+                            result = ThemeResult::Changed;
+                        }
+                    }
+
+                    let exists = config.custom_themes.iter().any(|p| p.name == self.draft.name);
+                    if ui.add_enabled(exists, egui::Button::new(tr!("Удалить тему"))).clicked() {
+                        config.custom_themes.retain(|p| p.name != self.draft.name);
+                        if config.theme == self.draft.name {
+                            config.theme = DARK_THEME.to_string();
+                        }
+                        result = ThemeResult::Changed;
+                    }
+                });
+            });
+
+        if !open {
+            self.show_editor = false;
+        }
+
+        result
+    }
+}
@@ -0,0 +1,367 @@
+// transform_module.rs
+use base64::Engine;
+use eframe::egui;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::cmp::Ordering;
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::translit::{self, SCHEMES};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Transform {
+    Upper,
+    Lower,
+    Title,
+    SortLexical,
+    SortNatural,
+    SortNumeric,
+    RemoveDuplicates,
+    Reverse,
+    Join,
+    Reflow,
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    ToLatin,
+    ToCyrillic,
+}
+
+pub enum TransformResult {
+    None,
+    ConfigChanged,
+    Apply(Transform),
+}
+
+impl Transform {
+    const CASE: [Transform; 3] = [Transform::Upper, Transform::Lower, Transform::Title];
+    const LINES: [Transform; 5] = [
+        Transform::SortLexical,
+        Transform::SortNatural,
+        Transform::SortNumeric,
+        Transform::RemoveDuplicates,
+        Transform::Reverse,
+    ];
+    const CODEC: [Transform; 4] = [
+        Transform::Base64Encode,
+        Transform::Base64Decode,
+        Transform::UrlEncode,
+        Transform::UrlDecode,
+    ];
+    const TRANSLIT: [Transform; 2] = [Transform::ToLatin, Transform::ToCyrillic];
+
+    fn title(self) -> &'static str {
+        match self {
+            Transform::Upper => tr!("ВЕРХНИЙ РЕГИСТР"),
+            Transform::Lower => tr!("нижний регистр"),
+            Transform::Title => tr!("Каждое Слово С Заглавной"),
+            Transform::SortLexical => tr!("Сортировать строки по алфавиту"),
+            Transform::SortNatural => tr!("Сортировать строки естественно (2 < 10)"),
+            Transform::SortNumeric => tr!("Сортировать строки по числам"),
+            Transform::RemoveDuplicates => tr!("Удалить повторяющиеся строки"),
+            Transform::Reverse => tr!("Обратный порядок строк"),
+            Transform::Join => tr!("Объединить строки через"),
+            Transform::Reflow => tr!("Переформатировать абзац"),
+            Transform::Base64Encode => tr!("Закодировать в Base64"),
+            Transform::Base64Decode => tr!("Раскодировать из Base64"),
+            Transform::UrlEncode => tr!("Закодировать для URL"),
+            Transform::UrlDecode => tr!("Раскодировать из URL"),
+            Transform::ToLatin => tr!("Кириллица → латиница"),
+            Transform::ToCyrillic => tr!("Латиница → кириллица"),
+        }
+    }
+
+    // Строковые преобразования берут строки целиком, даже если выделена их часть
+    pub fn works_on_lines(self) -> bool {
+        !Self::CASE.contains(&self) && !Self::CODEC.contains(&self) && !Self::TRANSLIT.contains(&self)
+    }
+
+    // Ошибка — только у раскодирования, если текст не в нужном формате
+    pub fn apply(self, text: &str, config: &Config) -> Result<String, String> {
+        match self {
+            Transform::Upper => Ok(text.to_uppercase()),
+            Transform::Lower => Ok(text.to_lowercase()),
+            Transform::Title => Ok(title_case(text)),
+            Transform::Reflow => Ok(reflow(text, config.reflow_column)),
+            Transform::Base64Encode => Ok(base64::engine::general_purpose::STANDARD.encode(text)),
+            Transform::Base64Decode => base64_decode(text),
+            Transform::UrlEncode => Ok(utf8_percent_encode(text, URL_ESCAPED).to_string()),
+            Transform::UrlDecode => percent_decode_str(text)
+                .decode_utf8()
+                .map(|decoded| decoded.to_string())
+                .map_err(|_| tr!("Результат не является текстом UTF-8").to_string()),
+            Transform::ToLatin => Ok(translit::to_latin(text, config.translit_scheme)),
+            Transform::ToCyrillic => Ok(translit::to_cyrillic(text, config.translit_scheme)),
+            _ => {
+                // Перевод строки в конце фрагмента остается на месте и в сортировке не участвует
+                let (body, newline) = match text.strip_suffix('\n') {
+                    Some(body) => (body, "\n"),
+                    None => (text, ""),
+                };
+                let mut lines: Vec<&str> = body.split('\n').collect();
+                match self {
+                    Transform::SortLexical => lines.sort_by(|a, b| lexical_cmp(a, b)),
+                    Transform::SortNatural => lines.sort_by(|a, b| natural_cmp(a, b)),
+                    Transform::SortNumeric => lines.sort_by(|a, b| numeric_cmp(a, b)),
+                    Transform::RemoveDuplicates => {
+                        let mut seen = std::collections::HashSet::new();
+                        lines.retain(|line| seen.insert(*line));
+                    }
+                    Transform::Reverse => lines.reverse(),
+                    _ => return Ok(lines.join(&config.join_separator) + newline),
+                }
+                Ok(lines.join("\n") + newline)
+            }
+        }
+    }
+}
+
+// Как encodeURIComponent: не трогаем только буквы, цифры и «-_.~»
+const URL_ESCAPED: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+// Переводы строк и пробелы внутри Base64 пропускаем: длинные блоки обычно разбиты на строки
+fn base64_decode(text: &str) -> Result<String, String> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .map_err(|e| tr!("Неверный Base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| tr!("Результат не является текстом UTF-8").to_string())
+}
+
+// Отступ с цитатой письма («> ») и маркер списка («- », «1. ») в начале строки
+fn split_prefix(line: &str) -> (&str, &str, &str) {
+    let content = line.trim_start_matches(|c: char| c.is_whitespace() || c == '>');
+    let indent = &line[..line.len() - content.len()];
+    let digits = content.len() - content.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker_end = if let Some(bullet) = content.chars().next().filter(|c| ['-', '*', '+', '•'].contains(c)) {
+        bullet.len_utf8()
+    } else if digits > 0 && content[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else {
+        0
+    };
+    let rest = &content[marker_end..];
+    let body = rest.trim_start();
+    // Маркер без пробела после него — просто начало текста: «-5 градусов», «3.14»
+    if marker_end > 0 && (body.len() == rest.len() && !body.is_empty()) {
+        return (indent, "", content);
+    }
+    (indent, &content[..content.len() - body.len()], body)
+}
+
+// Абзацы разделены пустыми строками, пункт списка начинает новый абзац.
+// Продолжение абзаца выравнивается по тексту первой строки, маркер не повторяется
+fn reflow(text: &str, width: usize) -> String {
+    let mut result: Vec<String> = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+    let mut first_prefix = String::new();
+    let mut next_prefix = String::new();
+
+    let flush = |result: &mut Vec<String>, words: &mut Vec<&str>, first: &str, next: &str| {
+        let mut line = first.to_string();
+        let mut line_has_words = false;
+        for word in words.drain(..) {
+            if line_has_words && line.chars().count() + 1 + word.chars().count() > width {
+                result.push(std::mem::replace(&mut line, next.to_string()));
+                line_has_words = false;
+            }
+            if line_has_words {
+                line.push(' ');
+            }
+            line.push_str(word);
+            line_has_words = true;
+        }
+        if line_has_words {
+            result.push(line);
+        }
+    };
+
+    let quote_depth = |prefix: &str| prefix.matches('>').count();
+    let mut block_lines = 0;
+    let mut has_marker = false;
+    for line in text.split('\n') {
+        let (indent, marker, body) = split_prefix(line);
+        if body.trim().is_empty() {
+            flush(&mut result, &mut words, &first_prefix, &next_prefix);
+            result.push(line.to_string());
+            continue;
+        }
+        let continues = !words.is_empty() && marker.is_empty() && quote_depth(indent) == quote_depth(&next_prefix);
+        if continues {
+            // Без маркера отступ продолжения берем со второй строки: красная строка остается только у первой
+            if block_lines == 1 && !has_marker {
+                next_prefix = indent.to_string();
+            }
+        } else {
+            flush(&mut result, &mut words, &first_prefix, &next_prefix);
+            first_prefix = format!("{}{}", indent, marker);
+            next_prefix = format!("{}{}", indent, " ".repeat(marker.chars().count()));
+            has_marker = !marker.is_empty();
+            block_lines = 0;
+        }
+        block_lines += 1;
+        words.extend(body.split_whitespace());
+    }
+    flush(&mut result, &mut words, &first_prefix, &next_prefix);
+    result.join("\n")
+}
+
+// Заглавная буква в начале каждого слова, остальные строчные; апостроф слово не разрывает
+fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_word = false;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if in_word {
+                result.extend(c.to_lowercase());
+            } else {
+                result.extend(c.to_uppercase());
+            }
+            in_word = true;
+        } else {
+            in_word = matches!(c, '\'' | '’') && in_word;
+            result.push(c);
+        }
+    }
+    result
+}
+
+// Без учета регистра, «ё» рядом с «е», а не после «я», как в таблице Unicode
+fn sort_key(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == 'ё' { 'е' } else { c })
+        .collect()
+}
+
+fn lexical_cmp(a: &str, b: &str) -> Ordering {
+    sort_key(a).cmp(&sort_key(b)).then_with(|| a.cmp(b))
+}
+
+// Числа внутри строк сравниваются по значению: «глава 2» раньше «глава 10»
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (a_key, b_key) = (sort_key(a), sort_key(b));
+    let mut a_chars = a_key.chars().peekable();
+    let mut b_chars = b_key.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(c);
+                    }
+                    digits.trim_start_matches('0').to_string()
+                };
+                let (x_number, y_number) = (take_number(&mut a_chars), take_number(&mut b_chars));
+                let ordering = x_number
+                    .len()
+                    .cmp(&y_number.len())
+                    .then_with(|| x_number.cmp(&y_number));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+// Число в начале строки; запятая считается десятичным разделителем
+fn leading_number(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    let end = line
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || c == ',' || (i == 0 && (c == '-' || c == '+'))))
+        .map(|(i, _)| i)
+        .unwrap_or(line.len());
+    let mut number = line[..end].replace(',', ".");
+    // «12.» или «3,5,» — отбрасываем хвост, пока не получится число
+    while !number.is_empty() {
+        if let Ok(value) = number.parse::<f64>() {
+            return Some(value);
+        }
+        number.pop();
+    }
+    None
+}
+
+// Строки без числа уходят в конец в прежнем порядке
+fn numeric_cmp(a: &str, b: &str) -> Ordering {
+    match (leading_number(a), leading_number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// Содержимое подменю «Преобразовать»; разделитель для объединения строк хранится в настройках
+pub fn show_menu(ui: &mut egui::Ui, config: &mut Config) -> TransformResult {
+    let mut result = TransformResult::None;
+    for transform in Transform::CASE {
+        if ui.button(transform.title()).clicked() {
+            result = TransformResult::Apply(transform);
+        }
+    }
+    ui.separator();
+    for transform in Transform::LINES {
+        if ui.button(transform.title()).clicked() {
+            result = TransformResult::Apply(transform);
+        }
+    }
+    ui.horizontal(|ui| {
+        if ui.button(Transform::Join.title()).clicked() {
+            result = TransformResult::Apply(Transform::Join);
+        }
+        let response = ui.add(egui::TextEdit::singleline(&mut config.join_separator).desired_width(50.0));
+        if response.changed() {
+            result = TransformResult::ConfigChanged;
+        }
+    });
+    ui.separator();
+    for transform in Transform::CODEC {
+        if ui.button(transform.title()).clicked() {
+            result = TransformResult::Apply(transform);
+        }
+    }
+    ui.separator();
+    ui.label(tr!("Транслитерация:"));
+    for scheme in SCHEMES {
+        if ui.radio_value(&mut config.translit_scheme, scheme, scheme.title()).clicked() {
+            result = TransformResult::ConfigChanged;
+        }
+    }
+    for transform in Transform::TRANSLIT {
+        if ui.button(transform.title()).clicked() {
+            result = TransformResult::Apply(transform);
+        }
+    }
+    if matches!(result, TransformResult::Apply(_)) {
+        ui.close_menu();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // «•» занимает в UTF-8 три байта; раньше строка с ним роняла переформатирование
+    #[test]
+    fn reflow_keeps_multibyte_bullet() {
+        assert_eq!(split_prefix("  • пункт"), ("  ", "• ", "пункт"));
+        assert_eq!(split_prefix("•слитно"), ("", "", "•слитно"));
+        assert_eq!(reflow("• один два три", 9), "• один\n  два три");
+    }
+}
@@ -0,0 +1,159 @@
+// translit.rs
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum TranslitScheme {
+    #[default]
+    Gost, // ГОСТ 7.79-2000, система Б: обратимая, для библиографии
+    Bgn,  // BGN/PCGN: географические названия, англоязычные ссылки
+    Icao, // Как в загранпаспорте: только латинские буквы, удобно для имен файлов
+}
+
+pub const SCHEMES: [TranslitScheme; 3] = [TranslitScheme::Gost, TranslitScheme::Bgn, TranslitScheme::Icao];
+
+const CYRILLIC: [char; 33] = [
+    'а', 'б', 'в', 'г', 'д', 'е', 'ё', 'ж', 'з', 'и', 'й', 'к', 'л', 'м', 'н', 'о', 'п', 'р', 'с', 'т', 'у', 'ф', 'х',
+    'ц', 'ч', 'ш', 'щ', 'ъ', 'ы', 'ь', 'э', 'ю', 'я',
+];
+
+const GOST: [&str; 33] = [
+    "a", "b", "v", "g", "d", "e", "yo", "zh", "z", "i", "j", "k", "l", "m", "n", "o", "p", "r", "s", "t", "u", "f",
+    "x", "cz", "ch", "sh", "shh", "``", "y`", "`", "e`", "yu", "ya",
+];
+const BGN: [&str; 33] = [
+    "a", "b", "v", "g", "d", "e", "ë", "zh", "z", "i", "y", "k", "l", "m", "n", "o", "p", "r", "s", "t", "u", "f",
+    "kh", "ts", "ch", "sh", "shch", "\u{2033}", "y", "\u{2032}", "e", "yu", "ya",
+];
+const ICAO: [&str; 33] = [
+    "a", "b", "v", "g", "d", "e", "e", "zh", "z", "i", "i", "k", "l", "m", "n", "o", "p", "r", "s", "t", "u", "f",
+    "kh", "ts", "ch", "sh", "shch", "ie", "y", "", "e", "iu", "ia",
+];
+
+impl TranslitScheme {
+    pub fn title(self) -> &'static str {
+        match self {
+            TranslitScheme::Gost => tr!("ГОСТ 7.79-2000 (Б)"),
+            TranslitScheme::Bgn => "BGN/PCGN",
+            TranslitScheme::Icao => tr!("Как в загранпаспорте"),
+        }
+    }
+
+    fn table(self) -> &'static [&'static str; 33] {
+        match self {
+            TranslitScheme::Gost => &GOST,
+            TranslitScheme::Bgn => &BGN,
+            TranslitScheme::Icao => &ICAO,
+        }
+    }
+}
+
+fn is_cyrillic_vowel(c: char) -> bool {
+    "аеёиоуыэюяъьйАЕЁИОУЫЭЮЯЪЬЙ".contains(c)
+}
+
+// Заглавная буква дает заглавную латиницу: «Щука» → «Shchuka», «ЩУКА» → «SHCHUKA»
+fn apply_case(latin: &str, upper: bool, whole_word_upper: bool) -> String {
+    if !upper {
+        return latin.to_string();
+    }
+    if whole_word_upper {
+        return latin.to_uppercase();
+    }
+    let mut chars = latin.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn to_latin(text: &str, scheme: TranslitScheme) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let table = scheme.table();
+    let mut result = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let Some(index) = CYRILLIC.iter().position(|&letter| letter == lower) else {
+            result.push(c);
+            continue;
+        };
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1).copied();
+
+        let mut latin = table[index];
+        match (scheme, lower) {
+            // В ГОСТе «ц» перед i, e, y, j пишется как «c»
+            (TranslitScheme::Gost, 'ц') if next.is_some_and(|n| "иеыйИЕЫЙ".contains(n)) => {
+                latin = "c";
+            }
+            // BGN: «е» и «ё» в начале слова и после гласных — «ye», «yë»
+            (TranslitScheme::Bgn, 'е' | 'ё') => {
+                let after_vowel = previous.is_none_or(|p| !p.is_alphabetic() || is_cyrillic_vowel(p));
+                if after_vowel {
+                    latin = if lower == 'е' { "ye" } else { "yë" };
+                }
+            }
+            _ => {}
+        }
+
+        let is_upper = |c: Option<char>| c.is_some_and(|c| c.is_uppercase());
+        let word_upper = is_upper(next) || (is_upper(previous) && !next.is_some_and(char::is_alphabetic));
+        result.push_str(&apply_case(latin, c.is_uppercase(), word_upper));
+    }
+    result
+}
+
+// Обратно — жадно, самыми длинными сочетаниями из той же таблицы: «shch» раньше «sh»
+pub fn to_cyrillic(text: &str, scheme: TranslitScheme) -> String {
+    let mut pairs: Vec<(String, char)> = scheme
+        .table()
+        .iter()
+        .zip(CYRILLIC)
+        .filter(|(latin, _)| !latin.is_empty())
+        .map(|(latin, cyrillic)| (latin.to_string(), cyrillic))
+        .collect();
+    // Неоднозначные записи: «e» — это «е», а не «э» или «ё»; «y» в BGN — «ы», «i» в ICAO — «и»
+    pairs.retain(|(latin, cyrillic)| {
+        !matches!(
+            (scheme, latin.as_str(), cyrillic),
+            (_, "e", 'э' | 'ё') | (TranslitScheme::Bgn, "y", 'й') | (TranslitScheme::Icao, "i", 'й')
+        )
+    });
+    match scheme {
+        TranslitScheme::Gost => pairs.push(("c".to_string(), 'ц')),
+        TranslitScheme::Bgn => pairs.extend([("ye".to_string(), 'е'), ("yë".to_string(), 'ё')]),
+        TranslitScheme::Icao => {}
+    }
+    pairs.sort_by_key(|(latin, _)| std::cmp::Reverse(latin.chars().count()));
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len() * 2);
+    let mut i = 0;
+    while i < chars.len() {
+        let found = pairs.iter().find(|(latin, _)| {
+            let length = latin.chars().count();
+            i + length <= chars.len()
+                && chars[i..i + length]
+                    .iter()
+                    .flat_map(|c| c.to_lowercase())
+                    .eq(latin.chars())
+        });
+        match found {
+            Some((latin, cyrillic)) => {
+                let length = latin.chars().count();
+                if chars[i].is_uppercase() {
+                    result.extend(cyrillic.to_uppercase());
+                } else {
+                    result.push(*cyrillic);
+                }
+                i += length;
+            }
+            None => {
+                result.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,154 @@
+// typing_sound_module.rs
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle, Source};
+use std::f32::consts::TAU;
+
+use crate::config::TypingSoundSet;
+
+const SAMPLE_RATE: u32 = 44100;
+// Несколько вариантов щелчка, чтобы одинаковые звуки подряд не резали слух
+const VARIANTS: u32 = 4;
+
+// Из чего складывается щелчок: короткий шум удара, звонкий тон и низкий «стук» корпуса
+struct ClickShape {
+    length: f32,     // Секунды
+    noise_tau: f32,  // Время затухания шума
+    smoothing: f32,  // 0..1, чем меньше — тем глуше шум
+    tone_freq: f32,
+    tone_tau: f32,
+    thump_freq: f32,
+    thump_tau: f32,
+    gain: f32,
+}
+
+impl ClickShape {
+    fn for_set(set: TypingSoundSet, enter: bool) -> Self {
+        match (set, enter) {
+            (TypingSoundSet::Mechanical, false) => Self {
+                length: 0.04,
+                noise_tau: 0.002,
+                smoothing: 0.9,
+                tone_freq: 4000.0,
+                tone_tau: 0.004,
+                thump_freq: 160.0,
+                thump_tau: 0.008,
+                gain: 0.6,
+            },
+            (TypingSoundSet::Mechanical, true) => Self {
+                length: 0.06,
+                noise_tau: 0.003,
+                smoothing: 0.8,
+                tone_freq: 3000.0,
+                tone_tau: 0.005,
+                thump_freq: 110.0,
+                thump_tau: 0.015,
+                gain: 0.8,
+            },
+            (TypingSoundSet::Typewriter, false) => Self {
+                length: 0.08,
+                noise_tau: 0.003,
+                smoothing: 1.0,
+                tone_freq: 1800.0,
+                tone_tau: 0.025,
+                thump_freq: 220.0,
+                thump_tau: 0.01,
+                gain: 0.6,
+            },
+            // Звонок каретки
+            (TypingSoundSet::Typewriter, true) => Self {
+                length: 0.5,
+                noise_tau: 0.004,
+                smoothing: 1.0,
+                tone_freq: 2100.0,
+                tone_tau: 0.15,
+                thump_freq: 2650.0,
+                thump_tau: 0.1,
+                gain: 0.5,
+            },
+            (TypingSoundSet::Soft, enter) => Self {
+                length: 0.03,
+                noise_tau: 0.004,
+                smoothing: 0.3,
+                tone_freq: 600.0,
+                tone_tau: 0.003,
+                thump_freq: if enter { 220.0 } else { 300.0 },
+                thump_tau: 0.006,
+                gain: 0.4,
+            },
+        }
+    }
+
+    fn render(&self, seed: u32) -> Vec<f32> {
+        let mut seed = seed.wrapping_mul(2654435761) | 1;
+        // Вариант слегка меняет высоту, как будто нажата другая клавиша
+        let detune = 1.0 + (seed % 7) as f32 * 0.015 - 0.045;
+        let count = (self.length * SAMPLE_RATE as f32) as usize;
+        let mut filtered = 0.0;
+
+        (0..count)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                let white = seed as f32 / u32::MAX as f32 * 2.0 - 1.0;
+                filtered += self.smoothing * (white - filtered);
+
+                let noise = filtered * (-t / self.noise_tau).exp();
+                let tone = (TAU * self.tone_freq * detune * t).sin() * (-t / self.tone_tau).exp() * 0.5;
+                let thump = (TAU * self.thump_freq * detune * t).sin() * (-t / self.thump_tau).exp() * 0.7;
+                (noise + tone + thump) * self.gain
+            })
+            .collect()
+    }
+}
+
+// Отдельный выход без очереди: щелчки смешиваются поверх музыки и не ждут друг друга
+#[derive(Default)]
+pub struct TypingSoundModule {
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    unavailable: bool, // Звуковое устройство не открылось — больше не пытаемся
+    rendered: Option<TypingSoundSet>,
+    clicks: Vec<Vec<f32>>,
+    enter: Vec<f32>,
+    next_variant: usize,
+}
+
+impl TypingSoundModule {
+    fn prepare(&mut self, set: TypingSoundSet) {
+        if self.rendered == Some(set) {
+            return;
+        }
+        let shape = ClickShape::for_set(set, false);
+        self.clicks = (0..VARIANTS).map(|seed| shape.render(seed + 1)).collect();
+        self.enter = ClickShape::for_set(set, true).render(VARIANTS + 1);
+        self.rendered = Some(set);
+    }
+
+    pub fn play(&mut self, set: TypingSoundSet, volume: f32, enter: bool) {
+        if self.unavailable {
+            return;
+        }
+        if self.output.is_none() {
+            match OutputStream::try_default() {
+                Ok(output) => self.output = Some(output),
+                Err(_) => {
+                    self.unavailable = true;
+                    return;
+                }
+            }
+        }
+        self.prepare(set);
+
+        let samples = if enter {
+            self.enter.clone()
+        } else {
+            self.next_variant = (self.next_variant + 1) % self.clicks.len();
+            self.clicks[self.next_variant].clone()
+        };
+        if let Some((_, handle)) = &self.output {
+            let source = SamplesBuffer::new(1, SAMPLE_RATE, samples).amplify(volume);
+            let _ = handle.play_raw(source.convert_samples());
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// welcome_module.rs
+use eframe::egui;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::i18n::tr;
+
+const LOGO_SIZE: f32 = 96.0;
+
+#[derive(Default)]
+pub struct WelcomeModule {
+    pub show: bool,
+    logo: Option<egui::TextureHandle>,
+}
+
+pub enum WelcomeResult {
+    None,
+    NewFile,
+    OpenFile,
+    OpenFolder,
+    OpenRecent(PathBuf),
+    ConfigChanged,
+}
+
+impl WelcomeModule {
+    fn logo(&mut self, ctx: &egui::Context) -> Option<egui::TextureHandle> {
+        if self.logo.is_none() {
+            let icon = eframe::icon_data::from_png_bytes(include_bytes!("../assets/logo.png")).ok()?;
+            let image = egui::ColorImage::from_rgba_unmultiplied([icon.width as usize, icon.height as usize], &icon.rgba);
+            self.logo = Some(ctx.load_texture("welcome_logo", image, egui::TextureOptions::LINEAR));
+        }
+        self.logo.clone()
+    }
+
+    // Стартовая страница вместо пустого документа; рисуется в центральной панели
+    pub fn show(&mut self, ui: &mut egui::Ui, config: &mut Config) -> WelcomeResult {
+        let mut result = WelcomeResult::None;
+        let logo = self.logo(ui.ctx());
+
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(24.0);
+                if let Some(logo) = &logo {
+                    ui.image((logo.id(), egui::vec2(LOGO_SIZE, LOGO_SIZE)));
+                }
+                ui.heading(tr!("Текстовый редактор Глеба"));
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    // Кнопки по центру: отступ на половину свободного места
+                    let buttons_width = 3.0 * 150.0;
+                    ui.add_space(((ui.available_width() - buttons_width) / 2.0).max(0.0));
+                    if ui.add_sized([140.0, 28.0], egui::Button::new(tr!("Новый файл"))).clicked() {
+                        result = WelcomeResult::NewFile;
+                    }
+                    if ui.add_sized([140.0, 28.0], egui::Button::new(tr!("Открыть..."))).clicked() {
+                        result = WelcomeResult::OpenFile;
+                    }
+                    if ui.add_sized([140.0, 28.0], egui::Button::new(tr!("Открыть папку..."))).clicked() {
+                        result = WelcomeResult::OpenFolder;
+                    }
+                });
+
+                ui.add_space(16.0);
+                ui.strong(tr!("Недавние файлы"));
+                if config.recent_files.is_empty() {
+                    ui.label(tr!("Пока ничего не открывалось"));
+                }
+                for path in &config.recent_files {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.display().to_string());
+                    let exists = path.exists();
+                    let response = ui
+                        .add_enabled(exists, egui::Link::new(name))
+                        .on_hover_text(path.display().to_string());
+                    if response.clicked() {
+                        result = WelcomeResult::OpenRecent(path.clone());
+                    }
+                }
+
+                ui.add_space(16.0);
+                egui::CollapsingHeader::new(tr!("Справка по терминальному режиму"))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.label(tr!("Ctrl-Q — выход, Ctrl-S — сохранить"));
+                        ui.label(tr!("Ctrl-F — поиск, Ctrl-R — замена, Ctrl-Z — отмена"));
+                    });
+
+                ui.add_space(16.0);
+                let mut skip = !config.show_welcome;
+                if ui.checkbox(&mut skip, tr!("Не показывать при запуске")).changed() {
+                    config.show_welcome = !skip;
+                    result = WelcomeResult::ConfigChanged;
+                }
+            });
+        });
+
+        result
+    }
+}
@@ -0,0 +1,236 @@
+// word_frequency_module.rs
+use eframe::egui;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::i18n::tr;
+
+// Служебные слова, которые почти всегда наверху списка и ничего не говорят о тексте
+const STOP_WORDS_RU: &[&str] = &[
+    "а", "без", "бы", "был", "была", "были", "было", "быть", "в", "вам", "вас", "весь", "во", "вот", "все", "всё",
+    "всех", "вы", "где", "да", "даже", "для", "до", "его", "ее", "её", "если", "есть", "еще", "ещё", "же", "за",
+    "здесь", "и", "из", "или", "им", "их", "к", "как", "ко", "когда", "кто", "ли", "либо", "мне", "может", "мы",
+    "на", "над", "надо", "наш", "не", "него", "нее", "неё", "нет", "ни", "них", "но", "ну", "о", "об", "один",
+    "он", "она", "они", "оно", "от", "по", "под", "при", "с", "со", "так", "также", "такой", "там", "те", "тем",
+    "то", "того", "тоже", "той", "только", "том", "ты", "у", "уже", "хотя", "чего", "чем", "что", "чтобы", "эта",
+    "эти", "это", "этого", "этой", "этот", "я",
+];
+const STOP_WORDS_EN: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been", "but", "by", "can",
+    "could", "did", "do", "does", "for", "from", "had", "has", "have", "he", "her", "his", "how", "i", "if", "in",
+    "into", "is", "it", "its", "just", "me", "my", "no", "not", "of", "on", "one", "or", "our", "out", "she", "so",
+    "than", "that", "the", "their", "them", "then", "there", "these", "they", "this", "to", "up", "was", "we",
+    "were", "what", "when", "which", "who", "will", "with", "would", "you", "your",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortColumn {
+    Word,
+    Count,
+}
+
+pub struct WordFrequencyModule {
+    pub show_window: bool,
+    pub error_message: Option<String>,
+    top_n: usize,
+    skip_stop_words: bool,
+    selection_only: bool,
+    sort: SortColumn,
+    descending: bool,
+    rows: Vec<(String, usize)>,
+    total_words: usize,
+    dirty: bool, // Пересчитать при следующей отрисовке
+}
+
+impl Default for WordFrequencyModule {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            error_message: None,
+            top_n: 30,
+            skip_stop_words: true,
+            selection_only: false,
+            sort: SortColumn::Count,
+            descending: true,
+            rows: Vec::new(),
+            total_words: 0,
+            dirty: true,
+        }
+    }
+}
+
+// Слово — буквы и цифры, дефис и апостроф внутри («кто-то», «don't»); числа не считаем
+fn split_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '\'' || c == '’'))
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .map(str::to_lowercase)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl WordFrequencyModule {
+    pub fn open(&mut self, has_selection: bool) {
+        self.show_window = true;
+        self.selection_only = has_selection;
+        self.dirty = true;
+    }
+
+    fn analyze(&mut self, text: &str) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        self.total_words = 0;
+        for word in split_words(text) {
+            self.total_words += 1;
+            if self.skip_stop_words && (STOP_WORDS_RU.contains(&word.as_str()) || STOP_WORDS_EN.contains(&word.as_str())) {
+                continue;
+            }
+            *counts.entry(word).or_insert(0) += 1;
+        }
+
+        // Top-N всегда по частоте; порядок в таблице меняется уже после отбора
+        let mut rows: Vec<(String, usize)> = counts.into_iter().collect();
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        rows.truncate(self.top_n);
+        self.rows = rows;
+        self.sort_rows();
+    }
+
+    fn sort_rows(&mut self) {
+        match self.sort {
+            SortColumn::Word => self.rows.sort_by(|a, b| a.0.cmp(&b.0)),
+            SortColumn::Count => self.rows.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0))),
+        }
+        if self.descending {
+            self.rows.reverse();
+        }
+    }
+
+    fn percent(&self, count: usize) -> f32 {
+        count as f32 * 100.0 / self.total_words.max(1) as f32
+    }
+
+    fn export_csv(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("words.csv")
+            .save_file()
+        else {
+            return;
+        };
+
+        let mut csv = String::from("word,count,percent\n");
+        for (word, count) in &self.rows {
+            csv.push_str(&format!("{},{},{:.2}\n", csv_field(word), count, self.percent(*count)));
+        }
+        self.error_message = fs::write(&path, csv)
+            .err()
+            .map(|e| tr!("Ошибка сохранения файла: {}", e));
+    }
+
+    fn header(&mut self, ui: &mut egui::Ui, title: &str, column: SortColumn) {
+        let arrow = match (self.sort == column, self.descending) {
+            (false, _) => "",
+            (true, true) => " ⏷",
+            (true, false) => " ⏶",
+        };
+        if ui.selectable_label(self.sort == column, format!("{}{}", title, arrow)).clicked() {
+            if self.sort == column {
+                self.descending = !self.descending;
+            } else {
+                self.sort = column;
+                self.descending = column == SortColumn::Count;
+            }
+            self.sort_rows();
+        }
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, text: &str, selection: Option<&str>) {
+        if !self.show_window {
+            return;
+        }
+        if self.selection_only && selection.is_none() {
+            self.selection_only = false;
+            self.dirty = true;
+        }
+        if self.dirty {
+            self.dirty = false;
+            let source = if self.selection_only { selection.unwrap_or(text) } else { text };
+            self.analyze(source);
+        }
+
+        let mut open = self.show_window;
+        egui::Window::new(tr!("Частота слов"))
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Показать:"));
+                    if ui.add(egui::DragValue::new(&mut self.top_n).clamp_range(5..=1000)).changed() {
+                        self.dirty = true;
+                    }
+                    if ui.checkbox(&mut self.skip_stop_words, tr!("Без служебных слов")).changed() {
+                        self.dirty = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.radio_value(&mut self.selection_only, false, tr!("Весь документ")).changed() {
+                        self.dirty = true;
+                    }
+                    let selection_radio = ui.add_enabled(
+                        selection.is_some(),
+                        egui::RadioButton::new(self.selection_only, tr!("Выделение")),
+                    );
+                    if selection_radio.clicked() {
+                        self.selection_only = true;
+                        self.dirty = true;
+                    }
+                    if ui.button(tr!("🔄 Обновить")).clicked() {
+                        self.dirty = true;
+                    }
+                });
+                ui.label(tr!("Всего слов: {}", self.total_words));
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(350.0).show(ui, |ui| {
+                    egui::Grid::new("word_frequency_grid")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("#");
+                            self.header(ui, tr!("Слово"), SortColumn::Word);
+                            self.header(ui, tr!("Количество"), SortColumn::Count);
+                            ui.label("%");
+                            ui.end_row();
+
+                            for (index, (word, count)) in self.rows.iter().enumerate() {
+                                ui.label((index + 1).to_string());
+                                ui.label(word);
+                                ui.monospace(count.to_string());
+                                ui.monospace(format!("{:.1}", self.percent(*count)));
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.rows.is_empty(), egui::Button::new(tr!("Экспорт в CSV..."))).clicked() {
+                        self.export_csv();
+                    }
+                    if let Some(error) = &self.error_message {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                });
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+    }
+}
@@ -0,0 +1,202 @@
+// writing_session_module.rs
+use eframe::egui;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::config::{config_dir, Config};
+use crate::i18n::tr;
+
+const WRITING_LOG_FILE: &str = "writing_log.txt";
+// Журнал пишется на диск не чаще, чем раз в полминуты
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+const CHART_DAYS: i64 = 30;
+const CHART_HEIGHT: f32 = 120.0;
+
+#[derive(Default)]
+pub struct WritingSessionModule {
+    pub show_window: bool,
+    session_words: i64,
+    days: BTreeMap<String, i64>, // "2026-10-16" → слов за день (правки считаются с минусом)
+    loaded: bool,
+    dirty: bool,
+    last_saved: Option<Instant>,
+}
+
+#[derive(PartialEq)]
+pub enum WritingSessionResult {
+    None,
+    ConfigChanged,
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+impl WritingSessionModule {
+    fn storage_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(WRITING_LOG_FILE))
+    }
+
+    // Формат файла: одна строка на день, "дата\tслов"
+    pub fn load(&mut self) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+
+        let Some(content) = Self::storage_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return;
+        };
+        for line in content.lines() {
+            if let Some((date, words)) = line.split_once('\t') {
+                if let Ok(words) = words.trim().parse() {
+                    self.days.insert(date.to_string(), words);
+                }
+            }
+        }
+    }
+
+    pub fn save(&mut self) -> Result<(), String> {
+        self.last_saved = Some(Instant::now());
+        if !self.dirty {
+            return Ok(());
+        }
+        let Some(path) = Self::storage_path() else {
+            return Ok(());
+        };
+
+        let content: String = self
+            .days
+            .iter()
+            .map(|(date, words)| format!("{}\t{}\n", date, words))
+            .collect();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| tr!("Ошибка сохранения журнала письма: {}", e))?;
+        }
+        fs::write(&path, content).map_err(|e| tr!("Ошибка сохранения журнала письма: {}", e))?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn autosave(&mut self) -> Result<(), String> {
+        let due = self.last_saved.is_none_or(|saved| saved.elapsed() >= SAVE_INTERVAL);
+        if self.dirty && due {
+            self.save()
+        } else {
+            Ok(())
+        }
+    }
+
+    // delta — изменение числа слов после правки пользователем
+    pub fn record(&mut self, delta: i64) {
+        if delta == 0 {
+            return;
+        }
+        self.load();
+        self.session_words += delta;
+        *self.days.entry(today()).or_insert(0) += delta;
+        self.dirty = true;
+    }
+
+    pub fn today_words(&self) -> i64 {
+        self.days.get(&today()).copied().unwrap_or(0).max(0)
+    }
+
+    pub fn show_window(&mut self, ctx: &egui::Context, config: &mut Config) -> WritingSessionResult {
+        if !self.show_window {
+            return WritingSessionResult::None;
+        }
+        self.load();
+
+        let mut result = WritingSessionResult::None;
+        let mut open = self.show_window;
+
+        egui::Window::new(tr!("Журнал письма"))
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("writing_session_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr!("За этот сеанс:"));
+                    ui.monospace(self.session_words.max(0).to_string());
+                    ui.end_row();
+
+                    ui.label(tr!("Сегодня:"));
+                    ui.monospace(self.today_words().to_string());
+                    ui.end_row();
+
+                    ui.label(tr!("Цель на день:"));
+                    if ui
+                        .add(egui::DragValue::new(&mut config.daily_word_goal).clamp_range(0..=100000).speed(10))
+                        .on_hover_text(tr!("0 — без цели"))
+                        .changed()
+                    {
+                        result = WritingSessionResult::ConfigChanged;
+                    }
+                    ui.end_row();
+                });
+
+                ui.separator();
+                ui.label(tr!("Последние {} дней", CHART_DAYS));
+                self.paint_chart(ui, config.daily_word_goal);
+            });
+
+        if !open {
+            self.show_window = false;
+        }
+
+        result
+    }
+
+    // Столбики по дням; дни, где цель выполнена, — зеленые, линия — цель
+    fn paint_chart(&self, ui: &mut egui::Ui, goal: u32) {
+        let today = chrono::Local::now().date_naive();
+        let days: Vec<(String, i64)> = (0..CHART_DAYS)
+            .rev()
+            .map(|ago| {
+                let date = (today - chrono::Duration::days(ago)).format("%Y-%m-%d").to_string();
+                let words = self.days.get(&date).copied().unwrap_or(0).max(0);
+                (date, words)
+            })
+            .collect();
+
+        let max = days.iter().map(|(_, w)| *w).max().unwrap_or(0).max(goal as i64).max(1) as f32;
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), CHART_HEIGHT),
+            egui::Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, egui::Rounding::same(2.0), ui.visuals().extreme_bg_color);
+
+        let bar_width = rect.width() / days.len() as f32;
+        let mut hovered = None;
+        for (index, (date, words)) in days.iter().enumerate() {
+            let height = *words as f32 / max * (rect.height() - 4.0);
+            let left = rect.left() + index as f32 * bar_width;
+            let bar = egui::Rect::from_min_max(
+                egui::pos2(left + 1.0, rect.bottom() - height),
+                egui::pos2(left + bar_width - 1.0, rect.bottom()),
+            );
+            let color = if goal > 0 && *words >= goal as i64 {
+                egui::Color32::from_rgb(100, 200, 100)
+            } else {
+                ui.visuals().selection.bg_fill
+            };
+            painter.rect_filled(bar, egui::Rounding::ZERO, color);
+
+            let column = egui::Rect::from_x_y_ranges(left..=left + bar_width, rect.y_range());
+            if response.hover_pos().is_some_and(|pos| column.contains(pos)) {
+                hovered = Some((date.clone(), *words));
+            }
+        }
+
+        if goal > 0 {
+            let y = rect.bottom() - goal as f32 / max * (rect.height() - 4.0);
+            painter.hline(rect.x_range(), y, egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 100, 100)));
+        }
+        if let Some((date, words)) = hovered {
+            response.on_hover_text(tr!("{}: {} слов", date, words));
+        }
+    }
+}